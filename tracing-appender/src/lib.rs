@@ -168,8 +168,12 @@ use std::io::Write;
 
 pub mod non_blocking;
 
+pub mod reconnect;
+
 pub mod rolling;
 
+pub mod socket;
+
 mod worker;
 
 pub(crate) mod sync;
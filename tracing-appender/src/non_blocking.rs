@@ -47,8 +47,10 @@
 //! # }
 //! ```
 use crate::worker::Worker;
+#[cfg(feature = "tokio")]
+use crate::worker::tokio::{AsyncIoWorker, TokioWorker};
 use crate::Msg;
-use crossbeam_channel::{bounded, SendTimeoutError, Sender};
+use crossbeam_channel::{bounded, Receiver, SendTimeoutError, Sender};
 use std::io;
 use std::io::Write;
 use std::sync::atomic::AtomicUsize;
@@ -69,6 +71,37 @@ use tracing_subscriber::fmt::MakeWriter;
 /// Recommended to be a power of 2.
 pub const DEFAULT_BUFFERED_LINES_LIMIT: usize = 128_000;
 
+/// Configures what a [`NonBlocking`] writer does with a log line when the
+/// buffer is full.
+///
+/// Set via [`NonBlockingBuilder::overflow_policy`].
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum OverflowPolicy {
+    /// Drop the incoming line, keeping everything already buffered.
+    ///
+    /// This is the default, and is equivalent to [`lossy(true)`][lossy].
+    ///
+    /// [lossy]: NonBlockingBuilder::lossy
+    DropNewest,
+    /// Drop the oldest buffered line to make room for the incoming one.
+    ///
+    /// On the tokio worker backend (see [`NonBlocking::new_on_tokio`]),
+    /// there's no way for the writer to reach into the worker's queue to
+    /// evict an entry, so this falls back to [`DropNewest`][Self::DropNewest]
+    /// behavior.
+    DropOldest,
+    /// Block the calling thread until there's capacity in the buffer.
+    ///
+    /// This is equivalent to [`lossy(false)`][lossy].
+    ///
+    /// [lossy]: NonBlockingBuilder::lossy
+    Block,
+    /// Block the calling thread for up to the given duration; if no capacity
+    /// becomes available in time, drop the incoming line.
+    BlockWithTimeout(Duration),
+}
+
 /// A guard that flushes spans/events associated to a [`NonBlocking`] on a drop
 ///
 /// Writing to a [`NonBlocking`] writer will **not** immediately write a span or event to the underlying
@@ -103,9 +136,80 @@ pub const DEFAULT_BUFFERED_LINES_LIMIT: usize = 128_000;
 #[must_use]
 #[derive(Debug)]
 pub struct WorkerGuard {
-    handle: Option<JoinHandle<()>>,
-    sender: Sender<Msg>,
-    shutdown: Sender<()>,
+    handle: GuardHandle,
+    sender: Channel<Msg>,
+    shutdown: Channel<()>,
+    error_counter: ErrorCounter,
+}
+
+/// The background worker driving a [`WorkerGuard`], either a dedicated OS
+/// thread or (with the `tokio` feature) a tokio task.
+#[derive(Debug)]
+enum GuardHandle {
+    Thread(Option<JoinHandle<()>>),
+    #[cfg(feature = "tokio")]
+    Tokio {
+        handle: Option<tokio::task::JoinHandle<()>>,
+        runtime: tokio::runtime::Handle,
+    },
+}
+
+/// A channel to the worker, abstracting over whether the worker is backed by
+/// a dedicated OS thread (using a [`crossbeam_channel`]) or a tokio task
+/// (using [`tokio::sync::mpsc`]).
+#[derive(Debug)]
+enum Channel<T> {
+    Sync(Sender<T>),
+    #[cfg(feature = "tokio")]
+    Tokio(tokio::sync::mpsc::Sender<T>),
+}
+
+impl<T> Clone for Channel<T> {
+    fn clone(&self) -> Self {
+        match self {
+            Channel::Sync(sender) => Channel::Sync(sender.clone()),
+            #[cfg(feature = "tokio")]
+            Channel::Tokio(sender) => Channel::Tokio(sender.clone()),
+        }
+    }
+}
+
+impl<T> Channel<T> {
+    /// Attempts to send `msg` without blocking, handing it back on failure so
+    /// the caller can decide what to do with it (e.g. retry after evicting
+    /// an older message).
+    fn try_send(&self, msg: T) -> Result<(), T> {
+        match self {
+            Channel::Sync(sender) => sender.try_send(msg).map_err(|e| e.into_inner()),
+            #[cfg(feature = "tokio")]
+            Channel::Tokio(sender) => sender.try_send(msg).map_err(|e| e.into_inner()),
+        }
+    }
+
+    fn send(&self, msg: T) -> Result<(), ()> {
+        match self {
+            Channel::Sync(sender) => sender.send(msg).map_err(|_| ()),
+            #[cfg(feature = "tokio")]
+            Channel::Tokio(sender) => sender.blocking_send(msg).map_err(|_| ()),
+        }
+    }
+
+    /// Sends `msg`, giving up after `timeout` if the worker hasn't consumed
+    /// it yet.
+    ///
+    /// The `tokio` variant ignores `timeout` and always sends synchronously:
+    /// `blocking_send` only blocks while the channel is full, and a full
+    /// bounded channel here means the worker task has stalled, which a
+    /// timeout can't fix.
+    fn send_timeout(&self, msg: T, timeout: Duration) -> Result<(), SendTimeoutError<T>> {
+        match self {
+            Channel::Sync(sender) => sender.send_timeout(msg, timeout),
+            #[cfg(feature = "tokio")]
+            Channel::Tokio(sender) => sender
+                .blocking_send(msg)
+                .map_err(|e| SendTimeoutError::Disconnected(e.0)),
+        }
+    }
 }
 
 /// A non-blocking writer.
@@ -122,19 +226,33 @@ pub struct WorkerGuard {
 ///
 /// [make_writer]: tracing_subscriber::fmt::MakeWriter
 /// [fmt]: mod@tracing_subscriber::fmt
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct NonBlocking {
     error_counter: ErrorCounter,
-    channel: Sender<Msg>,
-    is_lossy: bool,
+    channel: Channel<Msg>,
+    overflow_policy: OverflowPolicy,
+    evict: Option<Receiver<Msg>>,
+    on_dropped_lines: Option<Arc<dyn Fn(usize) + Send + Sync>>,
+}
+
+impl std::fmt::Debug for NonBlocking {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NonBlocking")
+            .field("error_counter", &self.error_counter)
+            .field("channel", &self.channel)
+            .field("overflow_policy", &self.overflow_policy)
+            .field("evict", &self.evict)
+            .field("on_dropped_lines", &self.on_dropped_lines.is_some())
+            .finish()
+    }
 }
 
 /// Tracks the number of times a log line was dropped by the background thread.
 ///
-/// If the non-blocking writer is not configured in [lossy mode], the error
-/// count should always be 0.
+/// If the non-blocking writer is configured with [`OverflowPolicy::Block`],
+/// the error count should always be 0.
 ///
-/// [lossy mode]: NonBlockingBuilder::lossy
+/// [`OverflowPolicy::Block`]: OverflowPolicy::Block
 #[derive(Clone, Debug)]
 pub struct ErrorCounter(Arc<AtomicUsize>);
 
@@ -153,47 +271,197 @@ impl NonBlocking {
     fn create<T: Write + Send + 'static>(
         writer: T,
         buffered_lines_limit: usize,
-        is_lossy: bool,
+        overflow_policy: OverflowPolicy,
+        on_dropped_lines: Option<Arc<dyn Fn(usize) + Send + Sync>>,
         thread_name: String,
     ) -> (NonBlocking, WorkerGuard) {
         let (sender, receiver) = bounded(buffered_lines_limit);
 
         let (shutdown_sender, shutdown_receiver) = bounded(0);
 
-        let worker = Worker::new(receiver, writer, shutdown_receiver);
+        let error_counter = ErrorCounter(Arc::new(AtomicUsize::new(0)));
+        let worker = Worker::new(receiver.clone(), writer, shutdown_receiver);
         let worker_guard = WorkerGuard::new(
-            worker.worker_thread(thread_name),
-            sender.clone(),
-            shutdown_sender,
+            GuardHandle::Thread(Some(worker.worker_thread(thread_name))),
+            Channel::Sync(sender.clone()),
+            Channel::Sync(shutdown_sender),
+            error_counter.clone(),
         );
 
         (
             Self {
-                channel: sender,
-                error_counter: ErrorCounter(Arc::new(AtomicUsize::new(0))),
-                is_lossy,
+                channel: Channel::Sync(sender),
+                error_counter,
+                evict: Some(receiver),
+                overflow_policy,
+                on_dropped_lines,
             },
             worker_guard,
         )
     }
 
     /// Returns a counter for the number of times logs where dropped. This will always return zero if
-    /// `NonBlocking` is not lossy.
+    /// `NonBlocking`'s [`OverflowPolicy`] is [`Block`](OverflowPolicy::Block).
     pub fn error_counter(&self) -> ErrorCounter {
         self.error_counter.clone()
     }
+
+    /// Returns a new `NonBlocking` writer wrapping the provided `writer`,
+    /// whose background worker runs as a task on the current [tokio
+    /// runtime] rather than a dedicated OS thread.
+    ///
+    /// This is intended for async-only environments --- such as WASI, or
+    /// other sandboxes that don't permit spawning OS threads --- where
+    /// [`NonBlocking::new`] cannot be used.
+    ///
+    /// The returned `NonBlocking` writer will have the [default
+    /// configuration][default] values. Other configurations can be
+    /// specified using the [builder] interface's
+    /// [`finish_on_tokio`][NonBlockingBuilder::finish_on_tokio].
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a tokio runtime.
+    ///
+    /// [tokio runtime]: tokio::runtime::Runtime
+    /// [default]: NonBlockingBuilder::default()
+    /// [builder]: NonBlockingBuilder
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub fn new_on_tokio<T: Write + Send + 'static>(writer: T) -> (NonBlocking, WorkerGuard) {
+        NonBlockingBuilder::default().finish_on_tokio(writer)
+    }
+
+    #[cfg(feature = "tokio")]
+    fn create_on_tokio<T: Write + Send + 'static>(
+        writer: T,
+        buffered_lines_limit: usize,
+        overflow_policy: OverflowPolicy,
+        on_dropped_lines: Option<Arc<dyn Fn(usize) + Send + Sync>>,
+    ) -> (NonBlocking, WorkerGuard) {
+        let runtime = tokio::runtime::Handle::current();
+        let (sender, receiver) = tokio::sync::mpsc::channel(buffered_lines_limit);
+        let (shutdown_sender, shutdown_receiver) = tokio::sync::mpsc::channel(1);
+
+        let error_counter = ErrorCounter(Arc::new(AtomicUsize::new(0)));
+        let worker = TokioWorker::new(receiver, writer, shutdown_receiver);
+        let worker_guard = WorkerGuard::new(
+            GuardHandle::Tokio {
+                handle: Some(worker.worker_task()),
+                runtime,
+            },
+            Channel::Tokio(sender.clone()),
+            Channel::Tokio(shutdown_sender),
+            error_counter.clone(),
+        );
+
+        (
+            Self {
+                channel: Channel::Tokio(sender),
+                error_counter,
+                // The tokio `mpsc::Receiver` isn't `Clone`, so there's no way
+                // to give the writer side access to it for eviction.
+                evict: None,
+                overflow_policy,
+                on_dropped_lines,
+            },
+            worker_guard,
+        )
+    }
+
+    /// Returns a new `NonBlocking` writer wrapping the provided async
+    /// `writer`, whose background worker runs as a task on the current
+    /// [tokio runtime] and writes using genuine async I/O, rather than
+    /// calling a blocking [`std::io::Write`] from within a task.
+    ///
+    /// This is the right choice when the destination itself is async, e.g. a
+    /// [`tokio::net::TcpStream`] shipping logs to a remote collector: unlike
+    /// [`NonBlocking::new_on_tokio`], a slow or stalled write won't block one
+    /// of the runtime's executor threads.
+    ///
+    /// The returned `NonBlocking` writer will have the [default
+    /// configuration][default] values. Other configurations can be
+    /// specified using the [builder] interface's
+    /// [`finish_on_tokio_with_async_writer`][NonBlockingBuilder::finish_on_tokio_with_async_writer].
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a tokio runtime.
+    ///
+    /// [tokio runtime]: tokio::runtime::Runtime
+    /// [default]: NonBlockingBuilder::default()
+    /// [builder]: NonBlockingBuilder
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub fn new_on_tokio_with_async_writer<W>(writer: W) -> (NonBlocking, WorkerGuard)
+    where
+        W: tokio::io::AsyncWrite + Send + Unpin + 'static,
+    {
+        NonBlockingBuilder::default().finish_on_tokio_with_async_writer(writer)
+    }
+
+    #[cfg(feature = "tokio")]
+    fn create_on_tokio_with_async_writer<W>(
+        writer: W,
+        buffered_lines_limit: usize,
+        overflow_policy: OverflowPolicy,
+        on_dropped_lines: Option<Arc<dyn Fn(usize) + Send + Sync>>,
+    ) -> (NonBlocking, WorkerGuard)
+    where
+        W: tokio::io::AsyncWrite + Send + Unpin + 'static,
+    {
+        let runtime = tokio::runtime::Handle::current();
+        let (sender, receiver) = tokio::sync::mpsc::channel(buffered_lines_limit);
+        let (shutdown_sender, shutdown_receiver) = tokio::sync::mpsc::channel(1);
+
+        let error_counter = ErrorCounter(Arc::new(AtomicUsize::new(0)));
+        let worker = AsyncIoWorker::new(receiver, writer, shutdown_receiver);
+        let worker_guard = WorkerGuard::new(
+            GuardHandle::Tokio {
+                handle: Some(worker.worker_task()),
+                runtime,
+            },
+            Channel::Tokio(sender.clone()),
+            Channel::Tokio(shutdown_sender),
+            error_counter.clone(),
+        );
+
+        (
+            Self {
+                channel: Channel::Tokio(sender),
+                error_counter,
+                // The tokio `mpsc::Receiver` isn't `Clone`, so there's no way
+                // to give the writer side access to it for eviction.
+                evict: None,
+                overflow_policy,
+                on_dropped_lines,
+            },
+            worker_guard,
+        )
+    }
 }
 
 /// A builder for [`NonBlocking`][non-blocking].
 ///
 /// [non-blocking]: NonBlocking
-#[derive(Debug)]
 pub struct NonBlockingBuilder {
     buffered_lines_limit: usize,
-    is_lossy: bool,
+    overflow_policy: OverflowPolicy,
+    on_dropped_lines: Option<Arc<dyn Fn(usize) + Send + Sync>>,
     thread_name: String,
 }
 
+impl std::fmt::Debug for NonBlockingBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NonBlockingBuilder")
+            .field("buffered_lines_limit", &self.buffered_lines_limit)
+            .field("overflow_policy", &self.overflow_policy)
+            .field("on_dropped_lines", &self.on_dropped_lines.is_some())
+            .field("thread_name", &self.thread_name)
+            .finish()
+    }
+}
+
 impl NonBlockingBuilder {
     /// Sets the number of lines to buffer before dropping logs or exerting backpressure on senders
     pub fn buffered_lines_limit(mut self, buffered_lines_limit: usize) -> NonBlockingBuilder {
@@ -207,8 +475,44 @@ impl NonBlockingBuilder {
     /// will be exerted on senders, blocking them until the buffer has capacity again.
     ///
     /// By default, the built `NonBlocking` will be lossy.
+    ///
+    /// This is shorthand for setting [`overflow_policy`] to
+    /// [`OverflowPolicy::DropNewest`] or [`OverflowPolicy::Block`]. Prefer
+    /// [`overflow_policy`] directly for the other policies.
+    ///
+    /// [`overflow_policy`]: Self::overflow_policy
     pub fn lossy(mut self, is_lossy: bool) -> NonBlockingBuilder {
-        self.is_lossy = is_lossy;
+        self.overflow_policy = if is_lossy {
+            OverflowPolicy::DropNewest
+        } else {
+            OverflowPolicy::Block
+        };
+        self
+    }
+
+    /// Sets the [`OverflowPolicy`] controlling what happens to a log line
+    /// when the buffer is full.
+    ///
+    /// By default, the built `NonBlocking` uses
+    /// [`OverflowPolicy::DropNewest`].
+    pub fn overflow_policy(mut self, policy: OverflowPolicy) -> NonBlockingBuilder {
+        self.overflow_policy = policy;
+        self
+    }
+
+    /// Sets a callback that's invoked, with the number of lines dropped, each
+    /// time the buffer's [`OverflowPolicy`] causes log lines to be dropped.
+    ///
+    /// This is meant for alerting or metrics --- e.g. incrementing a
+    /// `prometheus` counter --- rather than for recovering the dropped data,
+    /// which is gone by the time the callback runs. See
+    /// [`NonBlocking::error_counter`] and [`WorkerGuard::error_counter`] for
+    /// reading the cumulative drop count instead.
+    pub fn on_dropped_lines(
+        mut self,
+        callback: impl Fn(usize) + Send + Sync + 'static,
+    ) -> NonBlockingBuilder {
+        self.on_dropped_lines = Some(Arc::new(callback));
         self
     }
 
@@ -225,34 +529,128 @@ impl NonBlockingBuilder {
         NonBlocking::create(
             writer,
             self.buffered_lines_limit,
-            self.is_lossy,
+            self.overflow_policy,
+            self.on_dropped_lines,
             self.thread_name,
         )
     }
+
+    /// Completes the builder, returning the configured `NonBlocking` whose
+    /// worker runs as a tokio task rather than a dedicated thread.
+    ///
+    /// See [`NonBlocking::new_on_tokio`] for details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a tokio runtime.
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub fn finish_on_tokio<T: Write + Send + 'static>(
+        self,
+        writer: T,
+    ) -> (NonBlocking, WorkerGuard) {
+        NonBlocking::create_on_tokio(
+            writer,
+            self.buffered_lines_limit,
+            self.overflow_policy,
+            self.on_dropped_lines,
+        )
+    }
+
+    /// Completes the builder, returning the configured `NonBlocking` whose
+    /// worker runs as a tokio task and writes using genuine async I/O.
+    ///
+    /// See [`NonBlocking::new_on_tokio_with_async_writer`] for details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a tokio runtime.
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub fn finish_on_tokio_with_async_writer<W>(self, writer: W) -> (NonBlocking, WorkerGuard)
+    where
+        W: tokio::io::AsyncWrite + Send + Unpin + 'static,
+    {
+        NonBlocking::create_on_tokio_with_async_writer(
+            writer,
+            self.buffered_lines_limit,
+            self.overflow_policy,
+            self.on_dropped_lines,
+        )
+    }
 }
 
 impl Default for NonBlockingBuilder {
     fn default() -> Self {
         NonBlockingBuilder {
             buffered_lines_limit: DEFAULT_BUFFERED_LINES_LIMIT,
-            is_lossy: true,
+            overflow_policy: OverflowPolicy::DropNewest,
+            on_dropped_lines: None,
             thread_name: "tracing-appender".to_string(),
         }
     }
 }
 
+impl NonBlocking {
+    /// Records that `dropped` log lines were just discarded: increments the
+    /// [`ErrorCounter`] and invokes the
+    /// [`on_dropped_lines`](NonBlockingBuilder::on_dropped_lines) callback,
+    /// if one is set.
+    fn record_dropped(&self, dropped: usize) {
+        self.error_counter.incr_saturating();
+        if let Some(on_dropped_lines) = &self.on_dropped_lines {
+            on_dropped_lines(dropped);
+        }
+    }
+}
+
 impl std::io::Write for NonBlocking {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         let buf_size = buf.len();
-        if self.is_lossy {
-            if self.channel.try_send(Msg::Line(buf.to_vec())).is_err() {
-                self.error_counter.incr_saturating();
+        match self.overflow_policy {
+            OverflowPolicy::DropNewest => {
+                if self.channel.try_send(Msg::Line(buf.to_vec())).is_err() {
+                    self.record_dropped(1);
+                }
+            }
+            OverflowPolicy::DropOldest => {
+                let mut msg = Msg::Line(buf.to_vec());
+                loop {
+                    match self.channel.try_send(msg) {
+                        Ok(()) => break,
+                        Err(failed) => match &self.evict {
+                            // Evict the oldest buffered line, then retry the
+                            // send. If another send races us and empties the
+                            // buffer first, `try_recv` simply fails and we
+                            // retry the send, which will then succeed.
+                            Some(evict) if evict.try_recv().is_ok() => {
+                                self.record_dropped(1);
+                                msg = failed;
+                                continue;
+                            }
+                            _ => {
+                                self.record_dropped(1);
+                                break;
+                            }
+                        },
+                    }
+                }
+            }
+            OverflowPolicy::Block => {
+                return match self.channel.send(Msg::Line(buf.to_vec())) {
+                    Ok(_) => Ok(buf_size),
+                    Err(_) => Err(io::Error::from(io::ErrorKind::Other)),
+                };
+            }
+            OverflowPolicy::BlockWithTimeout(timeout) => {
+                match self.channel.send_timeout(Msg::Line(buf.to_vec()), timeout) {
+                    Ok(()) => {}
+                    Err(SendTimeoutError::Timeout(_)) => self.record_dropped(1),
+                    Err(SendTimeoutError::Disconnected(_)) => {
+                        return Err(io::Error::from(io::ErrorKind::Other))
+                    }
+                }
             }
-        } else {
-            return match self.channel.send(Msg::Line(buf.to_vec())) {
-                Ok(_) => Ok(buf_size),
-                Err(_) => Err(io::Error::from(io::ErrorKind::Other)),
-            };
         }
         Ok(buf_size)
     }
@@ -276,13 +674,30 @@ impl<'a> MakeWriter<'a> for NonBlocking {
 }
 
 impl WorkerGuard {
-    fn new(handle: JoinHandle<()>, sender: Sender<Msg>, shutdown: Sender<()>) -> Self {
+    fn new(
+        handle: GuardHandle,
+        sender: Channel<Msg>,
+        shutdown: Channel<()>,
+        error_counter: ErrorCounter,
+    ) -> Self {
         WorkerGuard {
-            handle: Some(handle),
+            handle,
             sender,
             shutdown,
+            error_counter,
         }
     }
+
+    /// Returns a counter for the number of times logs have been dropped by
+    /// the [`NonBlocking`] writer(s) associated with this guard.
+    ///
+    /// This is the same counter returned by [`NonBlocking::error_counter`],
+    /// exposed here so it can be read (e.g. on a metrics timer) from wherever
+    /// the guard is held, without needing to keep a `NonBlocking` handle
+    /// around for that purpose.
+    pub fn error_counter(&self) -> ErrorCounter {
+        self.error_counter.clone()
+    }
 }
 
 impl Drop for WorkerGuard {
@@ -303,12 +718,25 @@ impl Drop for WorkerGuard {
                         );
                     }
                     _ => {
-                        // At this point it is safe to wait for `Worker` destruction without blocking
-                        if let Some(handle) = self.handle.take() {
-                            if handle.join().is_err() {
-                                eprintln!("Logging worker thread panicked");
+                        // At this point it is safe to wait for the worker's destruction without
+                        // blocking indefinitely.
+                        match &mut self.handle {
+                            GuardHandle::Thread(handle) => {
+                                if let Some(handle) = handle.take() {
+                                    if handle.join().is_err() {
+                                        eprintln!("Logging worker thread panicked");
+                                    }
+                                }
                             }
-                        };
+                            #[cfg(feature = "tokio")]
+                            GuardHandle::Tokio { handle, runtime } => {
+                                if let Some(handle) = handle.take() {
+                                    if runtime.block_on(handle).is_err() {
+                                        eprintln!("Logging worker task panicked");
+                                    }
+                                }
+                            }
+                        }
                     }
                 }
             }
@@ -472,6 +900,92 @@ mod test {
         assert_eq!(1, error_count.dropped_lines());
     }
 
+    #[test]
+    fn drop_oldest_evicts_buffered_line_to_make_room() {
+        // A writer that never drains, so every write past the buffer's
+        // capacity has to be satisfied by evicting from the buffer itself.
+        let (mock_writer, _rx) = MockWriter::new(0);
+
+        let (mut non_blocking, _guard) = self::NonBlockingBuilder::default()
+            .overflow_policy(OverflowPolicy::DropOldest)
+            .buffered_lines_limit(1)
+            .finish(mock_writer);
+
+        let error_count = non_blocking.error_counter();
+
+        // The worker thread picks this up immediately and gets stuck forever
+        // on its downstream `write_all`, since nothing ever reads from the
+        // zero-capacity `MockWriter`.
+        non_blocking.write_all(b"first").expect("failed to write");
+        thread::sleep(Duration::from_millis(100));
+        assert_eq!(0, error_count.dropped_lines());
+
+        // The worker is stuck, so this fills the channel's one slot of
+        // capacity rather than being picked up.
+        non_blocking
+            .write_all(b"second")
+            .expect("failed to write");
+        assert_eq!(0, error_count.dropped_lines());
+
+        // The channel is now full; this should evict "second" rather than
+        // drop "third".
+        non_blocking.write_all(b"third").expect("failed to write");
+        assert_eq!(1, error_count.dropped_lines());
+    }
+
+    #[test]
+    fn on_dropped_lines_callback_is_invoked() {
+        let (mock_writer, _rx) = MockWriter::new(0);
+
+        let dropped = Arc::new(AtomicUsize::new(0));
+        let dropped2 = dropped.clone();
+
+        let (mut non_blocking, _guard) = self::NonBlockingBuilder::default()
+            .lossy(true)
+            .buffered_lines_limit(1)
+            .on_dropped_lines(move |n| {
+                dropped2.fetch_add(n, Ordering::SeqCst);
+            })
+            .finish(mock_writer);
+
+        non_blocking.write_all(b"first").expect("failed to write");
+        thread::sleep(Duration::from_millis(100));
+        non_blocking
+            .write_all(b"second")
+            .expect("failed to write");
+        assert_eq!(0, dropped.load(Ordering::SeqCst));
+
+        non_blocking.write_all(b"third").expect("failed to write");
+        assert_eq!(1, dropped.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn block_with_timeout_drops_after_deadline() {
+        let (mock_writer, _rx) = MockWriter::new(0);
+
+        let (mut non_blocking, _guard) = self::NonBlockingBuilder::default()
+            .overflow_policy(OverflowPolicy::BlockWithTimeout(Duration::from_millis(50)))
+            .buffered_lines_limit(1)
+            .finish(mock_writer);
+
+        let error_count = non_blocking.error_counter();
+
+        non_blocking.write_all(b"first").expect("failed to write");
+        thread::sleep(Duration::from_millis(100));
+        assert_eq!(0, error_count.dropped_lines());
+
+        // Fills the channel's one slot of capacity.
+        non_blocking
+            .write_all(b"second")
+            .expect("failed to write");
+        assert_eq!(0, error_count.dropped_lines());
+
+        // The buffer is full and nothing ever drains it, so this write
+        // should time out and be dropped rather than block forever.
+        non_blocking.write_all(b"third").expect("failed to write");
+        assert_eq!(1, error_count.dropped_lines());
+    }
+
     #[test]
     fn multi_threaded_writes() {
         let (mock_writer, rx) = MockWriter::new(DEFAULT_BUFFERED_LINES_LIMIT);
@@ -497,14 +1011,92 @@ mod test {
             handle.join().expect("Failed to join thread");
         }
 
-        let mut hello_count: u8 = 0;
+        let mut hello_count: usize = 0;
 
+        // The worker may coalesce several queued events into a single
+        // underlying write, so a `recv` here can yield more than one
+        // "Hello" at once; count occurrences rather than `recv` calls.
         while let Ok(event_str) = rx.recv_timeout(Duration::from_secs(5)) {
-            assert!(event_str.contains("Hello"));
-            hello_count += 1;
+            hello_count += event_str.matches("Hello").count();
         }
 
         assert_eq!(10, hello_count);
         assert_eq!(0, error_count.dropped_lines());
     }
+
+    // `WorkerGuard`'s `Drop` blocks on the tokio runtime to await the
+    // worker's shutdown, which panics if called from within a task running
+    // on that runtime. So (as in real usage, e.g. dropping a guard held by
+    // `main` after `#[tokio::main]`'s generated `block_on` call returns),
+    // the guard must be dropped from outside any task polled by the runtime.
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn tokio_worker_writes_and_flushes_on_drop() {
+        let (mock_writer, rx) = MockWriter::new(DEFAULT_BUFFERED_LINES_LIMIT);
+        let runtime = tokio::runtime::Runtime::new().expect("failed to start runtime");
+
+        let guard = runtime.block_on(async {
+            let (mut non_blocking, guard) =
+                self::NonBlockingBuilder::default().finish_on_tokio(mock_writer);
+            non_blocking.write_all(b"Hello").expect("Failed to write");
+            tokio::task::yield_now().await;
+            guard
+        });
+
+        drop(guard);
+
+        let line = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(line, "Hello");
+    }
+
+    // A minimal in-memory `AsyncWrite`, for testing the genuine-async-I/O
+    // worker without pulling in a real socket or file.
+    #[cfg(feature = "tokio")]
+    #[derive(Clone, Default)]
+    struct MockAsyncWriter(Arc<std::sync::Mutex<Vec<u8>>>);
+
+    #[cfg(feature = "tokio")]
+    impl tokio::io::AsyncWrite for MockAsyncWriter {
+        fn poll_write(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+            buf: &[u8],
+        ) -> std::task::Poll<io::Result<usize>> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            std::task::Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<io::Result<()>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<io::Result<()>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn async_io_worker_writes_and_flushes_on_drop() {
+        let writer = MockAsyncWriter::default();
+        let runtime = tokio::runtime::Runtime::new().expect("failed to start runtime");
+
+        let (writer, guard) = runtime.block_on(async {
+            let (mut non_blocking, guard) = self::NonBlockingBuilder::default()
+                .finish_on_tokio_with_async_writer(writer.clone());
+            non_blocking.write_all(b"Hello").expect("Failed to write");
+            tokio::task::yield_now().await;
+            (writer, guard)
+        });
+
+        drop(guard);
+
+        assert_eq!(b"Hello".to_vec(), *writer.0.lock().unwrap());
+    }
 }
@@ -0,0 +1,301 @@
+//! A generic, reconnecting [`Write`] adapter for arbitrary writer factories.
+//!
+//! [`ReconnectWriter`] wraps anything that can produce a writer on demand —
+//! not just sockets, but pipes, or any other destination a caller might
+//! want to reconnect to — and gives it buffering while disconnected,
+//! automatic reconnection with backoff, and a way to ask whether the
+//! current connection is healthy. [`socket::tcp`](crate::socket::tcp) and
+//! [`socket::unix`](crate::socket::unix) are built on top of this module;
+//! reach for [`from_factory`] directly when the destination isn't a
+//! socket.
+//!
+//! Pair a `ReconnectWriter` with [`non_blocking`](crate::non_blocking) to
+//! get a background worker thread and a `WorkerGuard` for shutdown, so
+//! resilience against a flaky destination doesn't require reimplementing
+//! the worker.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! # fn docs() -> std::io::Result<()> {
+//! let writer = tracing_appender::reconnect::from_factory(|| {
+//!     std::fs::OpenOptions::new().append(true).open("/dev/fifo/logs")
+//! });
+//! let (writer, _guard) = tracing_appender::non_blocking(writer);
+//! # Ok(())
+//! # }
+//! ```
+use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+/// Default cap, in bytes, on how much data a [`ReconnectWriter`] will
+/// buffer while disconnected before it starts dropping the oldest bytes.
+const DEFAULT_MAX_BACKLOG_BYTES: usize = 1024 * 1024;
+
+/// Default delay between reconnection attempts.
+const DEFAULT_RECONNECT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Default cap on how long a single connection attempt may block.
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Establishes new connections for a [`ReconnectWriter`].
+///
+/// This is implemented for any `Fn() -> io::Result<W>`, so a plain closure
+/// can be passed to [`from_factory`] to reconnect to an arbitrary
+/// destination; such factories ignore `timeout`, since there's no generic way
+/// to bound an arbitrary closure's blocking. It's also implemented for
+/// [`SocketAddr`](std::net::SocketAddr) (TCP) and, on Unix, for
+/// [`PathBuf`](std::path::PathBuf) (Unix-domain sockets) by the
+/// [`socket`](crate::socket) module, both of which do honor it.
+pub trait Connect {
+    /// The stream returned by a successful connection attempt.
+    type Stream: Write;
+
+    /// Attempts to establish a new connection, giving up once `timeout`
+    /// elapses.
+    fn connect(&self, timeout: Duration) -> io::Result<Self::Stream>;
+}
+
+impl<F, W> Connect for F
+where
+    F: Fn() -> io::Result<W>,
+    W: Write,
+{
+    type Stream = W;
+
+    fn connect(&self, _timeout: Duration) -> io::Result<W> {
+        self()
+    }
+}
+
+/// Returns a [`ReconnectWriter`] that calls `factory` to (re)connect to an
+/// arbitrary destination, such as a pipe or a user-defined transport.
+///
+/// For TCP or Unix-domain sockets, prefer [`socket::tcp`](crate::socket::tcp)
+/// or [`socket::unix`](crate::socket::unix), which are small wrappers
+/// around this same mechanism.
+pub fn from_factory<F, W>(factory: F) -> ReconnectWriter<F>
+where
+    F: Fn() -> io::Result<W>,
+    W: Write,
+{
+    ReconnectWriter::new(factory)
+}
+
+/// A [`Write`] implementation that buffers while disconnected and
+/// reconnects automatically via a [`Connect`] implementation.
+///
+/// Constructed via [`from_factory`] or, for sockets, via
+/// [`socket::tcp`](crate::socket::tcp)/[`socket::unix`](crate::socket::unix).
+/// `ReconnectWriter` never returns an [`io::Error`] from `write`: unsendable
+/// bytes are appended to the backlog instead, so a struggling or absent
+/// destination can't take down the thread driving it (typically the
+/// [`non_blocking`](crate::non_blocking) worker thread).
+pub struct ReconnectWriter<C: Connect> {
+    connector: C,
+    stream: Option<C::Stream>,
+    backlog: VecDeque<u8>,
+    max_backlog_bytes: usize,
+    reconnect_interval: Duration,
+    connect_timeout: Duration,
+    next_attempt: Option<Instant>,
+}
+
+impl<C: Connect> ReconnectWriter<C> {
+    pub(crate) fn new(connector: C) -> Self {
+        Self {
+            connector,
+            stream: None,
+            backlog: VecDeque::new(),
+            max_backlog_bytes: DEFAULT_MAX_BACKLOG_BYTES,
+            reconnect_interval: DEFAULT_RECONNECT_INTERVAL,
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            next_attempt: None,
+        }
+    }
+
+    /// Sets the maximum number of bytes buffered while disconnected.
+    ///
+    /// Once the backlog is full, the oldest buffered bytes are dropped to
+    /// make room for new ones. Defaults to 1 MiB.
+    pub fn with_max_backlog_bytes(mut self, max_backlog_bytes: usize) -> Self {
+        self.max_backlog_bytes = max_backlog_bytes;
+        self
+    }
+
+    /// Sets the delay between reconnection attempts. Defaults to 1 second.
+    pub fn with_reconnect_interval(mut self, reconnect_interval: Duration) -> Self {
+        self.reconnect_interval = reconnect_interval;
+        self
+    }
+
+    /// Sets how long a single connection attempt may block before it's
+    /// treated as a failure. Defaults to 10 seconds.
+    ///
+    /// Without a bound, a destination that accepts TCP connections but never
+    /// completes them (or a wedged Unix-domain socket) can stall whichever
+    /// thread calls [`write`](Write::write) --- typically the
+    /// [`non_blocking`](crate::non_blocking) worker thread --- for as long as
+    /// the OS's own connect timeout, backing up the bounded channel feeding
+    /// it in the meantime.
+    pub fn with_connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = connect_timeout;
+        self
+    }
+
+    /// Returns `true` if this writer currently holds a live connection.
+    ///
+    /// This performs no I/O of its own; it reports the outcome of the most
+    /// recent write, flush, or periodic reconnect attempt, so callers can
+    /// expose it as a health check (e.g. from a `/healthz` endpoint)
+    /// without driving the writer themselves.
+    pub fn is_healthy(&mut self) -> bool {
+        self.ensure_connected();
+        self.stream.is_some()
+    }
+
+    /// Appends `buf` to the backlog, dropping the oldest buffered bytes if
+    /// it would otherwise grow past `max_backlog_bytes`.
+    fn push_backlog(&mut self, buf: &[u8]) {
+        if buf.len() >= self.max_backlog_bytes {
+            self.backlog.clear();
+            self.backlog
+                .extend(&buf[buf.len() - self.max_backlog_bytes..]);
+            return;
+        }
+        let overflow = (self.backlog.len() + buf.len()).saturating_sub(self.max_backlog_bytes);
+        for _ in 0..overflow {
+            self.backlog.pop_front();
+        }
+        self.backlog.extend(buf);
+    }
+
+    /// Ensures `self.stream` is populated, respecting `reconnect_interval`
+    /// between attempts.
+    fn ensure_connected(&mut self) {
+        if self.stream.is_some() {
+            return;
+        }
+        if let Some(next_attempt) = self.next_attempt {
+            if Instant::now() < next_attempt {
+                return;
+            }
+        }
+        match self.connector.connect(self.connect_timeout) {
+            Ok(stream) => {
+                self.stream = Some(stream);
+                self.next_attempt = None;
+            }
+            Err(_) => {
+                self.next_attempt = Some(Instant::now() + self.reconnect_interval);
+            }
+        }
+    }
+
+    /// Attempts to drain the backlog into the current connection, dropping
+    /// the connection if a write fails partway through.
+    fn drain_backlog(&mut self) {
+        let stream = match &mut self.stream {
+            Some(stream) if !self.backlog.is_empty() => stream,
+            _ => return,
+        };
+        let (front, back) = self.backlog.as_slices();
+        let sent = stream.write_all(front).and_then(|()| stream.write_all(back));
+        if sent.is_ok() {
+            self.backlog.clear();
+        } else {
+            self.stream = None;
+        }
+    }
+}
+
+impl<C: Connect> Write for ReconnectWriter<C> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.ensure_connected();
+        self.drain_backlog();
+
+        if self.backlog.is_empty() {
+            if let Some(stream) = &mut self.stream {
+                match stream.write_all(buf) {
+                    Ok(()) => return Ok(buf.len()),
+                    Err(_) => self.stream = None,
+                }
+            }
+        }
+
+        self.push_backlog(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.ensure_connected();
+        self.drain_backlog();
+        match &mut self.stream {
+            Some(stream) if self.backlog.is_empty() => stream.flush(),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<C: Connect> std::fmt::Debug for ReconnectWriter<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReconnectWriter")
+            .field("connected", &self.stream.is_some())
+            .field("backlog_len", &self.backlog.len())
+            .field("max_backlog_bytes", &self.max_backlog_bytes)
+            .field("reconnect_interval", &self.reconnect_interval)
+            .field("connect_timeout", &self.connect_timeout)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct FlakyWriter;
+
+    impl Write for FlakyWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn from_factory_reconnects_after_transient_failures() {
+        let attempts = AtomicUsize::new(0);
+        let mut writer = from_factory(move || {
+            if attempts.fetch_add(1, Ordering::SeqCst) < 2 {
+                Err(io::Error::new(io::ErrorKind::Other, "not yet"))
+            } else {
+                Ok(FlakyWriter)
+            }
+        })
+        .with_reconnect_interval(Duration::from_millis(0));
+
+        assert!(!writer.is_healthy());
+        assert!(!writer.is_healthy());
+        assert!(writer.is_healthy());
+
+        writer.write_all(b"hello").unwrap();
+        writer.flush().unwrap();
+    }
+
+    #[test]
+    fn from_factory_buffers_while_the_factory_keeps_failing() {
+        let mut writer = from_factory(|| -> io::Result<FlakyWriter> {
+            Err(io::Error::new(io::ErrorKind::Other, "always fails"))
+        })
+        .with_reconnect_interval(Duration::from_millis(0));
+
+        writer.write_all(b"hello").unwrap();
+        assert!(!writer.is_healthy());
+        assert_eq!(writer.backlog, VecDeque::from(*b"hello"));
+    }
+}
@@ -17,6 +17,18 @@
 //!   will be created daily
 //! - [`Rotation::never()`][never()]: This will result in log file located at `some_directory/log_file_name`
 //!
+//! In addition to a time-based [`Rotation`], [`Builder::max_file_size`] can be used to also (or
+//! instead) rotate the log file once it exceeds a configured number of bytes. When both a
+//! [`Rotation`] and a max file size are configured, the file is rotated whenever either
+//! condition is reached first; log files created by a size-based rotation that occurs before the
+//! next time-based boundary are suffixed with an incrementing counter (e.g.
+//! `log_file_name_prefix.yyyy-MM-dd-HH.1`) so that they are not overwritten.
+//!
+//! With [`Rotation::never()`][never()], the appender never rotates the file itself, but an
+//! external tool such as `logrotate` can still be used to manage it: see
+//! [`RollingFileAppender::reopen`] for how the appender keeps up with files rotated out from
+//! under it.
+//!
 //!
 //! # Examples
 //!
@@ -108,6 +120,30 @@ struct Inner {
     rotation: Rotation,
     next_date: AtomicUsize,
     max_files: Option<usize>,
+    max_size: Option<u64>,
+    #[cfg_attr(
+        not(any(feature = "compression-gzip", feature = "compression-zstd")),
+        allow(dead_code)
+    )]
+    compression: Option<Compression>,
+    // The number of times the log file has been rotated for exceeding
+    // `max_size` since the last time-based rotation. Used to give each such
+    // file a distinct name, since they all share the same date.
+    rotation_count: AtomicUsize,
+}
+
+/// The reason [`Inner::should_rollover`] determined that the log file should
+/// be rotated, along with whatever state is needed to perform a
+/// `compare_exchange` when advancing past it.
+enum Rollover {
+    /// The current time has passed the next scheduled rotation time.
+    Date(usize),
+    /// The log file has exceeded `max_size`.
+    Size(usize),
+    /// The file at the appender's path is no longer the file we have open,
+    /// most likely because an external tool (such as `logrotate`) renamed or
+    /// replaced it out from under us.
+    Moved,
 }
 
 // === impl RollingFileAppender ===
@@ -190,6 +226,8 @@ impl RollingFileAppender {
             ref prefix,
             ref suffix,
             ref max_files,
+            ref max_size,
+            ref compression,
         } = builder;
         let directory = directory.as_ref().to_path_buf();
         let now = OffsetDateTime::now_utc();
@@ -200,6 +238,8 @@ impl RollingFileAppender {
             prefix.clone(),
             suffix.clone(),
             *max_files,
+            *max_size,
+            *compression,
         )?;
         Ok(Self {
             state,
@@ -217,16 +257,51 @@ impl RollingFileAppender {
         #[cfg(not(test))]
         OffsetDateTime::now_utc()
     }
+
+    /// Reopens the log file at the appender's current path, discarding the
+    /// currently open file handle.
+    ///
+    /// On Unix, the appender already detects when the file at its path has
+    /// been renamed or replaced out from under it (for instance, by a
+    /// `logrotate` job) and reopens the path automatically on the next
+    /// write. This method is provided for the case where that isn't enough:
+    /// `logrotate`'s `copytruncate` mode, for example, truncates the
+    /// existing file in place rather than replacing it, which leaves the
+    /// currently open handle pointing at the (now-empty) file but with its
+    /// write position unchanged, so subsequent writes still land at the old
+    /// offset, padding the file with NUL bytes.
+    ///
+    /// Call this from your own `SIGHUP` handling code after such a rotation
+    /// (e.g. the one registered with the [`signal-hook`] crate) to force the
+    /// path to be reopened.
+    ///
+    /// [`signal-hook`]: https://docs.rs/signal-hook
+    pub fn reopen(&self) {
+        let now = self.now();
+        self.state.refresh_writer(now, &mut self.writer.write(), None);
+    }
 }
 
 impl io::Write for RollingFileAppender {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         let now = self.now();
         let writer = self.writer.get_mut();
-        if let Some(current_time) = self.state.should_rollover(now) {
-            let _did_cas = self.state.advance_date(now, current_time);
+        if let Some(reason) = self.state.should_rollover(now, writer) {
+            // Only a rotation we performed ourselves (as opposed to one noticed after an
+            // external tool moved the file out from under us) has a well-defined "previous
+            // file" to compress.
+            let rotated_from = match reason {
+                Rollover::Date(_) | Rollover::Size(_) => Some(self.state.filename(&now)),
+                Rollover::Moved => None,
+            };
+            let _did_cas = match reason {
+                Rollover::Date(current_time) => self.state.advance_date(now, current_time),
+                Rollover::Size(current_count) => self.state.advance_size(current_count),
+                Rollover::Moved => true,
+            };
             debug_assert!(_did_cas, "if we have &mut access to the appender, no other thread can have advanced the timestamp...");
-            self.state.refresh_writer(now, writer);
+            self.state
+                .refresh_writer(now, writer, rotated_from.as_deref());
         }
         writer.write(buf)
     }
@@ -242,11 +317,25 @@ impl<'a> tracing_subscriber::fmt::writer::MakeWriter<'a> for RollingFileAppender
         let now = self.now();
 
         // Should we try to roll over the log file?
-        if let Some(current_time) = self.state.should_rollover(now) {
+        let rollover = self.state.should_rollover(now, &self.writer.read());
+        if let Some(reason) = rollover {
+            // Only a rotation we performed ourselves (as opposed to one noticed after an
+            // external tool moved the file out from under us) has a well-defined "previous
+            // file" to compress.
+            let rotated_from = match reason {
+                Rollover::Date(_) | Rollover::Size(_) => Some(self.state.filename(&now)),
+                Rollover::Moved => None,
+            };
             // Did we get the right to lock the file? If not, another thread
             // did it and we can just make a writer.
-            if self.state.advance_date(now, current_time) {
-                self.state.refresh_writer(now, &mut self.writer.write());
+            let did_advance = match reason {
+                Rollover::Date(current_time) => self.state.advance_date(now, current_time),
+                Rollover::Size(current_count) => self.state.advance_size(current_count),
+                Rollover::Moved => true,
+            };
+            if did_advance {
+                self.state
+                    .refresh_writer(now, &mut self.writer.write(), rotated_from.as_deref());
             }
         }
         RollingWriter(self.writer.read())
@@ -503,6 +592,70 @@ impl Rotation {
     }
 }
 
+/// A compression algorithm that can be applied to a log file once it has been rotated out, as
+/// configured with [`Builder::compression`].
+///
+/// Each variant is only available when the corresponding Cargo feature is enabled. If no
+/// compression feature is enabled, this type has no variants and cannot be constructed.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[non_exhaustive]
+pub enum Compression {
+    /// Compresses rotated log files with gzip, appending a `.gz` extension to the file name.
+    ///
+    /// Requires the `compression-gzip` feature.
+    #[cfg(feature = "compression-gzip")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "compression-gzip")))]
+    Gzip,
+    /// Compresses rotated log files with zstd, appending a `.zst` extension to the file name.
+    ///
+    /// Requires the `compression-zstd` feature.
+    #[cfg(feature = "compression-zstd")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "compression-zstd")))]
+    Zstd,
+}
+
+#[cfg(any(feature = "compression-gzip", feature = "compression-zstd"))]
+impl Compression {
+    fn extension(self) -> &'static str {
+        match self {
+            #[cfg(feature = "compression-gzip")]
+            Compression::Gzip => "gz",
+            #[cfg(feature = "compression-zstd")]
+            Compression::Zstd => "zst",
+        }
+    }
+
+    /// Compresses the file at `path`, writing the result alongside it with this compression's
+    /// extension appended to the file name, and then removes the uncompressed original.
+    fn compress_file(self, path: &Path) -> io::Result<()> {
+        let mut compressed_path = path.as_os_str().to_os_string();
+        compressed_path.push(".");
+        compressed_path.push(self.extension());
+        let compressed_path = PathBuf::from(compressed_path);
+
+        let mut input = File::open(path)?;
+        let output = File::create(&compressed_path)?;
+
+        match self {
+            #[cfg(feature = "compression-gzip")]
+            Compression::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(output, flate2::Compression::default());
+                io::copy(&mut input, &mut encoder)?;
+                encoder.finish()?;
+            }
+            #[cfg(feature = "compression-zstd")]
+            Compression::Zstd => {
+                let mut encoder = zstd::Encoder::new(output, 0)?;
+                io::copy(&mut input, &mut encoder)?;
+                encoder.finish()?;
+            }
+        }
+
+        fs::remove_file(path)
+    }
+}
+
 // === impl RollingWriter ===
 
 impl io::Write for RollingWriter<'_> {
@@ -525,6 +678,8 @@ impl Inner {
         log_filename_prefix: Option<String>,
         log_filename_suffix: Option<String>,
         max_files: Option<usize>,
+        max_size: Option<u64>,
+        compression: Option<Compression>,
     ) -> Result<(Self, RwLock<File>), builder::InitError> {
         let log_directory = directory.as_ref().to_path_buf();
         let date_format = rotation.date_format();
@@ -542,12 +697,31 @@ impl Inner {
             ),
             rotation,
             max_files,
+            max_size,
+            compression,
+            rotation_count: AtomicUsize::new(0),
         };
-        let filename = inner.join_date(&now);
+        let filename = inner.filename(&now);
         let writer = RwLock::new(create_writer(inner.log_directory.as_ref(), &filename)?);
         Ok((inner, writer))
     }
 
+    /// Returns the name of the log file that should currently be written to,
+    /// including a `max_size`-driven rotation count suffix (e.g. `.1`, `.2`)
+    /// if this file isn't the first one created within the current
+    /// [`Rotation`] period.
+    fn filename(&self, date: &OffsetDateTime) -> String {
+        let base = self.join_date(date);
+        if self.max_size.is_none() {
+            return base;
+        }
+
+        match self.rotation_count.load(Ordering::Acquire) {
+            0 => base,
+            n => format!("{}.{}", base, n),
+        }
+    }
+
     pub(crate) fn join_date(&self, date: &OffsetDateTime) -> String {
         let date = date
             .format(&self.date_format)
@@ -583,6 +757,12 @@ impl Inner {
                 let filename = entry.file_name();
                 // if the filename is not a UTF-8 string, skip it.
                 let filename = filename.to_str()?;
+                // A compressed file has a trailing extension (e.g. `.gz`) and a file
+                // that was rotated because it hit `max_file_size` has a trailing `.N`
+                // counter (e.g. `app.log.2`); neither is part of the prefix/suffix/date,
+                // so strip them before matching, so that those files are still
+                // recognized as ours and subject to retention.
+                let filename = strip_rotation_count(strip_compression_extension(filename));
                 if let Some(prefix) = &self.log_filename_prefix {
                     if !filename.starts_with(prefix) {
                         return None;
@@ -634,8 +814,13 @@ impl Inner {
         }
     }
 
-    fn refresh_writer(&self, now: OffsetDateTime, file: &mut File) {
-        let filename = self.join_date(&now);
+    /// Switches `file` over to the log file for `now`.
+    ///
+    /// `rotated_from` is the name of the file that's being rotated away from, if any, so that it
+    /// can be compressed once the new file is in place; it should be `None` when `file` is simply
+    /// being reopened (as with [`RollingFileAppender::reopen`]) rather than rotated.
+    fn refresh_writer(&self, now: OffsetDateTime, file: &mut File, rotated_from: Option<&str>) {
+        let filename = self.filename(&now);
 
         if let Some(max_files) = self.max_files {
             self.prune_old_logs(max_files);
@@ -647,45 +832,159 @@ impl Inner {
                     eprintln!("Couldn't flush previous writer: {}", err);
                 }
                 *file = new_file;
+                self.compress_rotated_file(rotated_from, &filename);
             }
             Err(err) => eprintln!("Couldn't create writer for logs: {}", err),
         }
     }
 
+    /// Compresses the file named `rotated_from`, if one is given, a compression algorithm is
+    /// configured, and it isn't the same file we just rotated *to* (as can happen when
+    /// `rotated_from` is recomputed around an externally-moved file).
+    #[cfg(any(feature = "compression-gzip", feature = "compression-zstd"))]
+    fn compress_rotated_file(&self, rotated_from: Option<&str>, new_filename: &str) {
+        let (compression, old_filename) = match (self.compression, rotated_from) {
+            (Some(compression), Some(old_filename)) if old_filename != new_filename => {
+                (compression, old_filename)
+            }
+            _ => return,
+        };
+
+        let path = self.log_directory.join(old_filename);
+        if let Err(err) = compression.compress_file(&path) {
+            eprintln!(
+                "Couldn't compress rotated log file {}: {}",
+                path.display(),
+                err
+            );
+        }
+    }
+
+    #[cfg(not(any(feature = "compression-gzip", feature = "compression-zstd")))]
+    fn compress_rotated_file(&self, _rotated_from: Option<&str>, _new_filename: &str) {}
+
     /// Checks whether or not it's time to roll over the log file.
     ///
-    /// Rather than returning a `bool`, this returns the current value of
-    /// `next_date` so that we can perform a `compare_exchange` operation with
-    /// that value when setting the next rollover time.
+    /// Rather than returning a `bool`, this returns a [`Rollover`] describing
+    /// *why* the file should be rotated, carrying whatever state is needed to
+    /// perform a `compare_exchange` operation when advancing past it.
     ///
     /// If this method returns `Some`, we should roll to a new log file.
-    /// Otherwise, if this returns we should not rotate the log file.
-    fn should_rollover(&self, date: OffsetDateTime) -> Option<usize> {
+    /// Otherwise, if this returns `None` we should not rotate the log file.
+    fn should_rollover(&self, date: OffsetDateTime, file: &File) -> Option<Rollover> {
         let next_date = self.next_date.load(Ordering::Acquire);
-        // if the next date is 0, this appender *never* rotates log files.
-        if next_date == 0 {
-            return None;
+        // if the next date is not 0, this appender rotates log files on a
+        // fixed schedule.
+        if next_date != 0 && date.unix_timestamp() as usize >= next_date {
+            return Some(Rollover::Date(next_date));
         }
 
-        if date.unix_timestamp() as usize >= next_date {
-            return Some(next_date);
+        if let Some(max_size) = self.max_size {
+            if let Ok(metadata) = file.metadata() {
+                if metadata.len() >= max_size {
+                    return Some(Rollover::Size(self.rotation_count.load(Ordering::Acquire)));
+                }
+            }
+        }
+
+        if self.file_was_moved(&date, file) {
+            return Some(Rollover::Moved);
         }
 
         None
     }
 
+    /// Returns whether the file at the appender's current path is no longer
+    /// the same file as `file`, as determined by comparing inodes.
+    ///
+    /// This is how an external rotation tool (`logrotate`'s default
+    /// rename-and-recreate behavior) is detected: the path now refers to a
+    /// freshly created file, while `file` is still the old, renamed-away
+    /// one.
+    #[cfg(unix)]
+    fn file_was_moved(&self, date: &OffsetDateTime, file: &File) -> bool {
+        use std::os::unix::fs::MetadataExt;
+
+        let open_ino = match file.metadata() {
+            Ok(metadata) => metadata.ino(),
+            Err(_) => return false,
+        };
+        let path = self.log_directory.join(self.filename(date));
+        match fs::metadata(&path) {
+            // if the path can't be stat'd (e.g. it was removed by
+            // `logrotate`'s `copytruncate` mode and hasn't been recreated
+            // yet), leave the current file open rather than treating this
+            // as a rotation.
+            Err(_) => false,
+            Ok(metadata) => metadata.ino() != open_ino,
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn file_was_moved(&self, _date: &OffsetDateTime, _file: &File) -> bool {
+        // Detecting an external rename/replace requires comparing inodes,
+        // which isn't available outside Unix; use `RollingFileAppender::reopen`
+        // to recover from an external rotation on other platforms.
+        false
+    }
+
     fn advance_date(&self, now: OffsetDateTime, current: usize) -> bool {
         let next_date = self
             .rotation
             .next_date(&now)
             .map(|date| date.unix_timestamp() as usize)
             .unwrap_or(0);
-        self.next_date
+        let did_cas = self
+            .next_date
             .compare_exchange(current, next_date, Ordering::AcqRel, Ordering::Acquire)
+            .is_ok();
+        if did_cas {
+            // a new rotation period has begun; forget about any files we've
+            // created in this one due to exceeding `max_size`.
+            self.rotation_count.store(0, Ordering::Release);
+        }
+        did_cas
+    }
+
+    fn advance_size(&self, current_count: usize) -> bool {
+        self.rotation_count
+            .compare_exchange(
+                current_count,
+                current_count + 1,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            )
             .is_ok()
     }
 }
 
+/// Strips a trailing compression extension (as appended by [`Compression::compress_file`]) from
+/// `filename`, if present.
+fn strip_compression_extension(filename: &str) -> &str {
+    #[cfg(feature = "compression-gzip")]
+    if let Some(stripped) = filename.strip_suffix(".gz") {
+        return stripped;
+    }
+    #[cfg(feature = "compression-zstd")]
+    if let Some(stripped) = filename.strip_suffix(".zst") {
+        return stripped;
+    }
+    filename
+}
+
+/// Strips a trailing `.N` size-rotation counter (as appended by [`Inner::filename`]) from
+/// `filename`, if present.
+fn strip_rotation_count(filename: &str) -> &str {
+    match filename.rfind('.') {
+        Some(idx)
+            if idx + 1 < filename.len() && filename[idx + 1..].bytes().all(|b| b.is_ascii_digit()) =>
+        {
+            &filename[..idx]
+        }
+        _ => filename,
+    }
+}
+
 fn create_writer(directory: &Path, filename: &str) -> Result<File, InitError> {
     let path = directory.join(filename);
     let mut open_options = OpenOptions::new();
@@ -708,6 +1007,8 @@ fn create_writer(directory: &Path, filename: &str) -> Result<File, InitError> {
 mod test {
     use super::*;
     use std::fs;
+    #[cfg(feature = "compression-gzip")]
+    use std::io::Read;
     use std::io::Write;
 
     fn find_str_in_log(dir_path: &Path, expected_value: &str) -> bool {
@@ -829,6 +1130,8 @@ mod test {
                 prefix.map(ToString::to_string),
                 suffix.map(ToString::to_string),
                 None,
+                None,
+                None,
             )
             .unwrap();
             let path = inner.join_date(&now);
@@ -941,6 +1244,8 @@ mod test {
             Some("test_make_writer".to_string()),
             None,
             None,
+            None,
+            None,
         )
         .unwrap();
 
@@ -1023,6 +1328,8 @@ mod test {
             Some("test_max_log_files".to_string()),
             None,
             Some(2),
+            None,
+            None,
         )
         .unwrap();
 
@@ -1106,4 +1413,221 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn test_max_file_size() {
+        let directory = tempfile::tempdir().expect("failed to create tempdir");
+        let mut appender = RollingFileAppender::builder()
+            .rotation(Rotation::NEVER)
+            .filename_prefix("app")
+            .filename_suffix("log")
+            .max_file_size(10)
+            .build(directory.path())
+            .expect("failed to build appender");
+
+        // the file starts out empty, so this write doesn't trigger a rotation.
+        write_to_log(&mut appender, "0123456789");
+        // this write sees that the file is already at `max_file_size`, so it
+        // rotates to a new, distinctly-named file before writing.
+        write_to_log(&mut appender, "hello world");
+
+        let mut contents = fs::read_dir(directory.path())
+            .expect("failed to read directory")
+            .map(|entry| {
+                let path = entry.expect("expected dir entry").path();
+                let file = fs::read_to_string(&path).expect("failed to read file");
+                (
+                    path.file_name()
+                        .expect("expected a file name")
+                        .to_str()
+                        .expect("file name should be UTF8")
+                        .to_string(),
+                    file,
+                )
+            })
+            .collect::<Vec<_>>();
+        contents.sort();
+
+        assert_eq!(
+            contents,
+            vec![
+                ("app.log".to_string(), "0123456789".to_string()),
+                ("app.log.1".to_string(), "hello world".to_string()),
+            ]
+        );
+
+        directory
+            .close()
+            .expect("Failed to explicitly close TempDir. TempDir should delete once out of scope.")
+    }
+
+    #[test]
+    #[cfg(feature = "compression-gzip")]
+    fn test_compresses_rotated_file() {
+        let directory = tempfile::tempdir().expect("failed to create tempdir");
+        let mut appender = RollingFileAppender::builder()
+            .rotation(Rotation::NEVER)
+            .filename_prefix("app")
+            .filename_suffix("log")
+            .max_file_size(10)
+            .compression(Compression::Gzip)
+            .build(directory.path())
+            .expect("failed to build appender");
+
+        // the file starts out empty, so this write doesn't trigger a rotation.
+        write_to_log(&mut appender, "0123456789");
+        // this write sees that the file is already at `max_file_size`, so it
+        // rotates to a new file, compressing the one we just rotated out of.
+        write_to_log(&mut appender, "hello world");
+
+        let mut file_names = fs::read_dir(directory.path())
+            .expect("failed to read directory")
+            .map(|entry| {
+                entry
+                    .expect("expected dir entry")
+                    .file_name()
+                    .to_str()
+                    .expect("file name should be UTF8")
+                    .to_string()
+            })
+            .collect::<Vec<_>>();
+        file_names.sort();
+
+        assert_eq!(
+            file_names,
+            vec!["app.log.1".to_string(), "app.log.gz".to_string()],
+        );
+
+        assert_eq!(
+            fs::read_to_string(directory.path().join("app.log.1")).expect("failed to read file"),
+            "hello world",
+        );
+
+        let compressed =
+            fs::read(directory.path().join("app.log.gz")).expect("failed to read gzip file");
+        let mut decoder = flate2::read::GzDecoder::new(&compressed[..]);
+        let mut decompressed = String::new();
+        decoder
+            .read_to_string(&mut decompressed)
+            .expect("failed to decompress gzip file");
+        assert_eq!(decompressed, "0123456789");
+
+        directory
+            .close()
+            .expect("Failed to explicitly close TempDir. TempDir should delete once out of scope.")
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_reopens_on_external_rename() {
+        let directory = tempfile::tempdir().expect("failed to create tempdir");
+        let mut appender = RollingFileAppender::new(Rotation::NEVER, directory.path(), "app.log");
+
+        write_to_log(&mut appender, "before rotation\n");
+
+        // simulate `logrotate`'s default behavior: rename the current log
+        // file out of the way, so that the appender's path now refers to
+        // nothing.
+        fs::rename(
+            directory.path().join("app.log"),
+            directory.path().join("app.log.1"),
+        )
+        .expect("failed to rename log file");
+        // ...then recreate the file at the original path, as `logrotate`
+        // would with the `create` directive (or as the next process to open
+        // it for append would).
+        fs::File::create(directory.path().join("app.log")).expect("failed to recreate log file");
+
+        write_to_log(&mut appender, "after rotation\n");
+
+        let rotated = fs::read_to_string(directory.path().join("app.log.1"))
+            .expect("failed to read rotated log file");
+        assert_eq!(rotated, "before rotation\n");
+
+        let current = fs::read_to_string(directory.path().join("app.log"))
+            .expect("failed to read current log file");
+        assert_eq!(current, "after rotation\n");
+
+        directory
+            .close()
+            .expect("Failed to explicitly close TempDir. TempDir should delete once out of scope.")
+    }
+
+    #[test]
+    fn test_reopen() {
+        let directory = tempfile::tempdir().expect("failed to create tempdir");
+        let mut appender = RollingFileAppender::new(Rotation::NEVER, directory.path(), "app.log");
+
+        write_to_log(&mut appender, "before reopen\n");
+
+        // truncate the file in place, simulating `logrotate`'s `copytruncate`
+        // mode, which a platform without inode-based detection would miss.
+        fs::File::create(directory.path().join("app.log")).expect("failed to truncate log file");
+        appender.reopen();
+
+        write_to_log(&mut appender, "after reopen\n");
+
+        let current = fs::read_to_string(directory.path().join("app.log"))
+            .expect("failed to read current log file");
+        assert_eq!(current, "after reopen\n");
+
+        directory
+            .close()
+            .expect("Failed to explicitly close TempDir. TempDir should delete once out of scope.")
+    }
+
+    #[test]
+    fn test_max_log_files_with_max_file_size() {
+        let directory = tempfile::tempdir().expect("failed to create tempdir");
+        let mut appender = RollingFileAppender::builder()
+            .rotation(Rotation::NEVER)
+            .filename_prefix("app")
+            .filename_suffix("log")
+            .max_file_size(10)
+            .max_log_files(2)
+            .build(directory.path())
+            .expect("failed to build appender");
+
+        write_to_log(&mut appender, "0123456789");
+
+        // depending on the filesystem, the creation timestamp's resolution may
+        // be as coarse as one second, so we need to wait a bit here to ensure
+        // that the next rotated file actually is newer than the old one.
+        std::thread::sleep(std::time::Duration::from_secs(1));
+        write_to_log(&mut appender, "hello world");
+
+        std::thread::sleep(std::time::Duration::from_secs(1));
+        write_to_log(&mut appender, "third");
+
+        let mut contents = fs::read_dir(directory.path())
+            .expect("failed to read directory")
+            .map(|entry| {
+                let path = entry.expect("expected dir entry").path();
+                let file = fs::read_to_string(&path).expect("failed to read file");
+                (
+                    path.file_name()
+                        .expect("expected a file name")
+                        .to_str()
+                        .expect("file name should be UTF8")
+                        .to_string(),
+                    file,
+                )
+            })
+            .collect::<Vec<_>>();
+        contents.sort();
+
+        // `app.log`, the oldest of the three size-rotated files, should have
+        // been pruned, leaving only the two most recent.
+        assert_eq!(
+            contents,
+            vec![
+                ("app.log.1".to_string(), "hello world".to_string()),
+                ("app.log.2".to_string(), "third".to_string()),
+            ]
+        );
+
+        directory
+            .close()
+            .expect("Failed to explicitly close TempDir. TempDir should delete once out of scope.")
+    }
 }
@@ -1,4 +1,4 @@
-use super::{RollingFileAppender, Rotation};
+use super::{Compression, RollingFileAppender, Rotation};
 use std::{io, path::Path};
 use thiserror::Error;
 
@@ -11,6 +11,8 @@ pub struct Builder {
     pub(super) prefix: Option<String>,
     pub(super) suffix: Option<String>,
     pub(super) max_files: Option<usize>,
+    pub(super) max_size: Option<u64>,
+    pub(super) compression: Option<Compression>,
 }
 
 /// Errors returned by [`Builder::build`].
@@ -42,11 +44,15 @@ impl Builder {
     /// | [`filename_prefix`] | `""` | By default, log file names will not have a prefix. |
     /// | [`filename_suffix`] | `""` | By default, log file names will not have a suffix. |
     /// | [`max_log_files`] | `None` | By default, there is no limit for maximum log file count. |
+    /// | [`max_file_size`] | `None` | By default, log files are not rotated based on size. |
+    /// | [`compression`] | `None` | By default, rotated log files are not compressed. |
     ///
     /// [`rotation`]: Self::rotation
     /// [`filename_prefix`]: Self::filename_prefix
     /// [`filename_suffix`]: Self::filename_suffix
     /// [`max_log_files`]: Self::max_log_files
+    /// [`max_file_size`]: Self::max_file_size
+    /// [`compression`]: Self::compression
     #[must_use]
     pub const fn new() -> Self {
         Self {
@@ -54,6 +60,8 @@ impl Builder {
             prefix: None,
             suffix: None,
             max_files: None,
+            max_size: None,
+            compression: None,
         }
     }
 
@@ -233,6 +241,79 @@ impl Builder {
         }
     }
 
+    /// Rotates the log file once it has grown to at least `max_bytes`.
+    ///
+    /// This can be combined with [`rotation`] to rotate on whichever
+    /// condition — elapsed time or file size — is reached first. Log files
+    /// created by a size-based rotation that occurs before the next
+    /// time-based boundary are suffixed with an incrementing counter (e.g.
+    /// `myapp.log.2019-01-01.1`, `myapp.log.2019-01-01.2`) so that they are
+    /// not overwritten; the counter resets whenever a time-based rotation
+    /// occurs.
+    ///
+    /// By default, log files are not rotated based on size.
+    ///
+    /// [`rotation`]: Self::rotation
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tracing_appender::rolling::{Rotation, RollingFileAppender};
+    ///
+    /// # fn docs() {
+    /// let appender = RollingFileAppender::builder()
+    ///     .rotation(Rotation::DAILY) // rotate at least once per day
+    ///     .max_file_size(10 * 1024 * 1024) // ...but also rotate once a file hits 10 MB
+    ///     // ...
+    ///     .build("/var/log")
+    ///     .expect("failed to initialize rolling file appender");
+    /// # drop(appender)
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn max_file_size(self, max_bytes: u64) -> Self {
+        Self {
+            max_size: Some(max_bytes),
+            ..self
+        }
+    }
+
+    /// Compresses each log file with `compression` once it has been rotated out.
+    ///
+    /// The compressed file is written alongside the original, with the compression's file
+    /// extension (e.g. `.gz`) appended to the name, and the uncompressed file is then removed.
+    /// Compression happens as part of rotating to the next log file, so if the appender is
+    /// wrapped in a [`NonBlocking`] writer (as is typical), it runs on the non-blocking worker
+    /// thread rather than blocking whichever thread triggered the rotation.
+    ///
+    /// By default, rotated log files are not compressed.
+    ///
+    /// [`NonBlocking`]: crate::non_blocking::NonBlocking
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "compression-gzip")]
+    /// # fn docs() {
+    /// use tracing_appender::rolling::{Compression, Rotation, RollingFileAppender};
+    ///
+    /// let appender = RollingFileAppender::builder()
+    ///     .rotation(Rotation::DAILY)
+    ///     .compression(Compression::Gzip) // gzip-compress each log file once it's rotated out
+    ///     // ...
+    ///     .build("/var/log")
+    ///     .expect("failed to initialize rolling file appender");
+    /// # drop(appender)
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn compression(self, compression: Compression) -> Self {
+        Self {
+            compression: Some(compression),
+            ..self
+        }
+    }
+
     /// Builds a new [`RollingFileAppender`] with the configured parameters,
     /// emitting log files to the provided directory.
     ///
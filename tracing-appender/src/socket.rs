@@ -0,0 +1,155 @@
+//! Writers that stream bytes to a TCP or Unix-domain socket, reconnecting
+//! automatically when the connection drops.
+//!
+//! This is a thin, socket-specific wrapper around the generic
+//! [`reconnect`](crate::reconnect) module; see there for the buffering and
+//! reconnection behavior, and for [`reconnect::from_factory`] if the
+//! destination isn't a socket.
+//!
+//! Pair a [`ReconnectWriter`] with [`non_blocking`](crate::non_blocking) to
+//! get a background worker thread and a `WorkerGuard` for shutdown, and with
+//! a `tracing-serde`-based formatter (such as
+//! [`tracing_subscriber::fmt().json()`][fmt-json]) to encode events before
+//! they reach the writer. Everyone standing up a sidecar log collector ends
+//! up wiring this by hand, so `tracing-appender` provides it once.
+//!
+//! [fmt-json]: https://docs.rs/tracing-subscriber/latest/tracing_subscriber/fmt/struct.SubscriberBuilder.html#method.json
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! # fn docs() -> std::io::Result<()> {
+//! let addr = "127.0.0.1:4317".parse().unwrap();
+//! let (writer, _guard) = tracing_appender::non_blocking(tracing_appender::socket::tcp(addr));
+//! # Ok(())
+//! # }
+//! ```
+use crate::reconnect::{Connect, ReconnectWriter};
+use std::io;
+use std::net::{SocketAddr, TcpStream};
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+#[cfg(unix)]
+use std::path::{Path, PathBuf};
+#[cfg(unix)]
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+impl Connect for SocketAddr {
+    type Stream = TcpStream;
+
+    fn connect(&self, timeout: Duration) -> io::Result<TcpStream> {
+        TcpStream::connect_timeout(self, timeout)
+    }
+}
+
+/// How many detached helper threads (see `impl Connect for PathBuf`) may be
+/// blocked in `UnixStream::connect` at once.
+///
+/// Without this cap, a permanently wedged Unix-domain socket --- exactly the
+/// scenario the per-attempt timeout below exists to survive --- would leak
+/// one OS thread per reconnect attempt forever, since a thread blocked in
+/// `connect` can't be cancelled from the outside.
+#[cfg(unix)]
+const MAX_PENDING_UNIX_CONNECTS: usize = 8;
+
+#[cfg(unix)]
+static PENDING_UNIX_CONNECTS: AtomicUsize = AtomicUsize::new(0);
+
+#[cfg(unix)]
+impl Connect for PathBuf {
+    type Stream = UnixStream;
+
+    fn connect(&self, timeout: Duration) -> io::Result<UnixStream> {
+        // `UnixStream` has no `connect_timeout`: connecting to a Unix-domain
+        // socket is normally near-instant, since it never leaves the kernel,
+        // but a peer that accepts the connection and then never reads (or a
+        // stale socket file wedged in `accept`) can still block indefinitely.
+        // Bound it by connecting on a short-lived helper thread instead ---
+        // capped at `MAX_PENDING_UNIX_CONNECTS`, so a wedged socket leaks a
+        // bounded number of threads rather than one per attempt forever.
+        let reserved = PENDING_UNIX_CONNECTS
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |pending| {
+                (pending < MAX_PENDING_UNIX_CONNECTS).then_some(pending + 1)
+            })
+            .is_ok();
+        if !reserved {
+            return Err(io::Error::new(
+                io::ErrorKind::WouldBlock,
+                "too many Unix-domain socket connect attempts already in flight",
+            ));
+        }
+
+        let path = self.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let result = UnixStream::connect(&path);
+            PENDING_UNIX_CONNECTS.fetch_sub(1, Ordering::SeqCst);
+            let _ = tx.send(result);
+        });
+        rx.recv_timeout(timeout)
+            .unwrap_or_else(|_| Err(io::Error::new(io::ErrorKind::TimedOut, "connect timed out")))
+    }
+}
+
+/// Returns a [`ReconnectWriter`] that streams to the TCP socket at `addr`,
+/// reconnecting automatically if the connection is lost.
+pub fn tcp(addr: SocketAddr) -> ReconnectWriter<SocketAddr> {
+    ReconnectWriter::new(addr)
+}
+
+/// Returns a [`ReconnectWriter`] that streams to the Unix-domain socket at
+/// `path`, reconnecting automatically if the connection is lost.
+#[cfg(unix)]
+pub fn unix(path: impl AsRef<Path>) -> ReconnectWriter<PathBuf> {
+    ReconnectWriter::new(path.as_ref().to_path_buf())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn buffers_while_disconnected_and_flushes_on_reconnect() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        // Nothing is listening yet at this point in the sense that no one
+        // has called `accept`; writes below must not block or error out.
+        drop(listener);
+
+        let mut writer = tcp(addr).with_reconnect_interval(Duration::from_millis(0));
+        writer.write_all(b"hello ").unwrap();
+        writer.write_all(b"world").unwrap();
+        assert!(!writer.is_healthy());
+
+        let listener = TcpListener::bind(addr).unwrap();
+        let accepted = thread::spawn(move || {
+            let (mut socket, _) = listener.accept().unwrap();
+            let mut buf = Vec::new();
+            socket.read_to_end(&mut buf).unwrap();
+            buf
+        });
+
+        // The next write reconnects and drains the backlog first.
+        writer.write_all(b"!").unwrap();
+        writer.flush().unwrap();
+        drop(writer);
+
+        assert_eq!(accepted.join().unwrap(), b"hello world!");
+    }
+
+    #[test]
+    fn drops_oldest_bytes_once_backlog_is_full() {
+        let addr: SocketAddr = "127.0.0.1:0".parse().unwrap();
+        let mut writer = tcp(addr)
+            .with_max_backlog_bytes(4)
+            .with_reconnect_interval(Duration::from_secs(3600));
+        writer.write_all(b"abcd").unwrap();
+        writer.write_all(b"ef").unwrap();
+        assert!(format!("{:?}", writer).contains("backlog_len: 4"));
+    }
+}
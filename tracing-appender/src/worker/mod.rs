@@ -4,6 +4,9 @@ use std::fmt::Debug;
 use std::io::Write;
 use std::{io, thread};
 
+#[cfg(feature = "tokio")]
+pub(crate) mod tokio;
+
 pub(crate) struct Worker<T: Write + Send + 'static> {
     writer: T,
     receiver: Receiver<Msg>,
@@ -27,40 +30,50 @@ impl<T: Write + Send + 'static> Worker<T> {
         }
     }
 
-    fn handle_recv(&mut self, result: &Result<Msg, RecvError>) -> io::Result<WorkerState> {
+    fn handle_recv(&mut self, result: &Result<Msg, RecvError>, batch: &mut Vec<u8>) -> WorkerState {
         match result {
             Ok(Msg::Line(msg)) => {
-                self.writer.write_all(msg)?;
-                Ok(WorkerState::Continue)
+                batch.extend_from_slice(msg);
+                WorkerState::Continue
             }
-            Ok(Msg::Shutdown) => Ok(WorkerState::Shutdown),
-            Err(_) => Ok(WorkerState::Disconnected),
+            Ok(Msg::Shutdown) => WorkerState::Shutdown,
+            Err(_) => WorkerState::Disconnected,
         }
     }
 
-    fn handle_try_recv(&mut self, result: &Result<Msg, TryRecvError>) -> io::Result<WorkerState> {
+    fn handle_try_recv(&mut self, result: &Result<Msg, TryRecvError>, batch: &mut Vec<u8>) -> WorkerState {
         match result {
             Ok(Msg::Line(msg)) => {
-                self.writer.write_all(msg)?;
-                Ok(WorkerState::Continue)
+                batch.extend_from_slice(msg);
+                WorkerState::Continue
             }
-            Ok(Msg::Shutdown) => Ok(WorkerState::Shutdown),
-            Err(TryRecvError::Empty) => Ok(WorkerState::Empty),
-            Err(TryRecvError::Disconnected) => Ok(WorkerState::Disconnected),
+            Ok(Msg::Shutdown) => WorkerState::Shutdown,
+            Err(TryRecvError::Empty) => WorkerState::Empty,
+            Err(TryRecvError::Disconnected) => WorkerState::Disconnected,
         }
     }
 
     /// Blocks on the first recv of each batch of logs, unless the
     /// channel is disconnected. Afterwards, grabs as many logs as
-    /// it can off the channel, buffers them and attempts a flush.
+    /// it can off the channel, concatenates them into a single buffer, and
+    /// writes that buffer out with one `write_all` call before flushing.
+    ///
+    /// Coalescing the batch into a single write matters under load: when a
+    /// burst of events arrives in quick succession (e.g. several spans
+    /// closing as a deep scope unwinds), it means one lock acquisition on
+    /// the underlying writer instead of one per message.
     pub(crate) fn work(&mut self) -> io::Result<WorkerState> {
+        let mut batch = Vec::new();
         // Worker thread yields here if receive buffer is empty
-        let mut worker_state = self.handle_recv(&self.receiver.recv())?;
+        let mut worker_state = self.handle_recv(&self.receiver.recv(), &mut batch);
 
         while worker_state == WorkerState::Continue {
             let try_recv_result = self.receiver.try_recv();
-            let handle_result = self.handle_try_recv(&try_recv_result);
-            worker_state = handle_result?;
+            worker_state = self.handle_try_recv(&try_recv_result, &mut batch);
+        }
+
+        if !batch.is_empty() {
+            self.writer.write_all(&batch)?;
         }
         self.writer.flush()?;
         Ok(worker_state)
@@ -0,0 +1,184 @@
+//! A worker whose loop is driven by a [tokio task][task], rather than a
+//! dedicated OS thread.
+//!
+//! This lets [`NonBlocking`][crate::non_blocking::NonBlocking] be used in
+//! async-only environments --- such as WASI or other sandboxes that don't
+//! permit spawning OS threads --- at the cost of requiring a tokio runtime
+//! to drive the worker.
+//!
+//! [task]: tokio::task
+use super::WorkerState;
+use crate::Msg;
+use std::io;
+use std::io::Write;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::sync::mpsc::error::TryRecvError;
+use tokio::sync::mpsc::Receiver;
+use tokio::task::JoinHandle;
+
+pub(crate) struct TokioWorker<T: Write + Send + 'static> {
+    writer: T,
+    receiver: Receiver<Msg>,
+    shutdown: Receiver<()>,
+}
+
+impl<T: Write + Send + 'static> TokioWorker<T> {
+    pub(crate) fn new(receiver: Receiver<Msg>, writer: T, shutdown: Receiver<()>) -> Self {
+        Self {
+            writer,
+            receiver,
+            shutdown,
+        }
+    }
+
+    fn handle_msg(&mut self, msg: Msg, batch: &mut Vec<u8>) -> WorkerState {
+        match msg {
+            Msg::Line(msg) => {
+                batch.extend_from_slice(&msg);
+                WorkerState::Continue
+            }
+            Msg::Shutdown => WorkerState::Shutdown,
+        }
+    }
+
+    /// Awaits the first message of each batch of logs, unless the channel is
+    /// disconnected. Afterwards, drains as many logs as are already
+    /// available without yielding, concatenates them into a single buffer,
+    /// and writes that buffer out with one `write_all` call before flushing.
+    ///
+    /// Coalescing the batch into a single write matters under load: when a
+    /// burst of events arrives in quick succession (e.g. several spans
+    /// closing as a deep scope unwinds), it means one lock acquisition on
+    /// the underlying writer instead of one per message.
+    async fn work(&mut self) -> io::Result<WorkerState> {
+        let mut batch = Vec::new();
+        let mut worker_state = match self.receiver.recv().await {
+            Some(msg) => self.handle_msg(msg, &mut batch),
+            None => WorkerState::Disconnected,
+        };
+
+        while worker_state == WorkerState::Continue {
+            worker_state = match self.receiver.try_recv() {
+                Ok(msg) => self.handle_msg(msg, &mut batch),
+                Err(TryRecvError::Empty) => WorkerState::Empty,
+                Err(TryRecvError::Disconnected) => WorkerState::Disconnected,
+            };
+        }
+
+        if !batch.is_empty() {
+            self.writer.write_all(&batch)?;
+        }
+        self.writer.flush()?;
+        Ok(worker_state)
+    }
+
+    /// Spawns a tokio task that drives this worker's loop until its channel
+    /// is disconnected or a shutdown message is received.
+    pub(crate) fn worker_task(mut self) -> JoinHandle<()> {
+        tokio::task::spawn(async move {
+            loop {
+                match self.work().await {
+                    Ok(WorkerState::Continue) | Ok(WorkerState::Empty) => {}
+                    Ok(WorkerState::Shutdown) | Ok(WorkerState::Disconnected) => {
+                        drop(self.writer); // drop now in case it blocks
+                        let _ = self.shutdown.recv().await;
+                        return;
+                    }
+                    Err(_) => {
+                        // TODO: Expose a metric for IO Errors, or print to stderr
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// A worker whose writer is driven with genuine async I/O (e.g. a tokio
+/// [`File`][tokio::fs::File] or [`TcpStream`][tokio::net::TcpStream]),
+/// rather than a [`std::io::Write`] implementation called from within a
+/// tokio task.
+///
+/// Unlike [`TokioWorker`], this never calls a blocking `write`, so it won't
+/// stall the runtime's executor threads if the underlying writer is slow
+/// (e.g. a socket to a remote log collector).
+pub(crate) struct AsyncIoWorker<W: AsyncWrite + Send + Unpin + 'static> {
+    writer: W,
+    receiver: Receiver<Msg>,
+    shutdown: Receiver<()>,
+}
+
+impl<W: AsyncWrite + Send + Unpin + 'static> AsyncIoWorker<W> {
+    pub(crate) fn new(receiver: Receiver<Msg>, writer: W, shutdown: Receiver<()>) -> Self {
+        Self {
+            writer,
+            receiver,
+            shutdown,
+        }
+    }
+
+    fn handle_msg(&mut self, msg: Msg, batch: &mut Vec<u8>) -> WorkerState {
+        match msg {
+            Msg::Line(msg) => {
+                batch.extend_from_slice(&msg);
+                WorkerState::Continue
+            }
+            Msg::Shutdown => WorkerState::Shutdown,
+        }
+    }
+
+    /// Awaits the first message of each batch of logs, unless the channel is
+    /// disconnected. Afterwards, drains as many logs as are already
+    /// available without yielding, concatenates them into a single buffer,
+    /// and writes that buffer out with one `write_all` call before flushing.
+    ///
+    /// Coalescing the batch into a single write matters under load: when a
+    /// burst of events arrives in quick succession (e.g. several spans
+    /// closing as a deep scope unwinds), it means one write call on the
+    /// underlying writer instead of one per message.
+    async fn work(&mut self) -> io::Result<WorkerState> {
+        let mut batch = Vec::new();
+        let mut worker_state = match self.receiver.recv().await {
+            Some(msg) => self.handle_msg(msg, &mut batch),
+            None => WorkerState::Disconnected,
+        };
+
+        while worker_state == WorkerState::Continue {
+            worker_state = match self.receiver.try_recv() {
+                Ok(msg) => self.handle_msg(msg, &mut batch),
+                Err(TryRecvError::Empty) => WorkerState::Empty,
+                Err(TryRecvError::Disconnected) => WorkerState::Disconnected,
+            };
+        }
+
+        if !batch.is_empty() {
+            self.writer.write_all(&batch).await?;
+        }
+        self.writer.flush().await?;
+        Ok(worker_state)
+    }
+
+    /// Spawns a tokio task that drives this worker's loop until its channel
+    /// is disconnected or a shutdown message is received.
+    ///
+    /// On shutdown, the writer is flushed and then asked to
+    /// [`shutdown`][AsyncWriteExt::shutdown] before the task exits, so that
+    /// e.g. a buffered `TcpStream` writer sends its final bytes instead of
+    /// silently dropping them.
+    pub(crate) fn worker_task(mut self) -> JoinHandle<()> {
+        tokio::task::spawn(async move {
+            loop {
+                match self.work().await {
+                    Ok(WorkerState::Continue) | Ok(WorkerState::Empty) => {}
+                    Ok(WorkerState::Shutdown) | Ok(WorkerState::Disconnected) => {
+                        let _ = self.writer.shutdown().await;
+                        let _ = self.shutdown.recv().await;
+                        return;
+                    }
+                    Err(_) => {
+                        // TODO: Expose a metric for IO Errors, or print to stderr
+                    }
+                }
+            }
+        })
+    }
+}
@@ -19,12 +19,16 @@ pub(crate) struct InstrumentArgs {
     level: Option<Level>,
     pub(crate) name: Option<LitStrOrIdent>,
     target: Option<LitStrOrIdent>,
+    krate: Option<Path>,
     pub(crate) parent: Option<Expr>,
     pub(crate) follows_from: Option<Expr>,
     pub(crate) skips: HashSet<Ident>,
+    pub(crate) skip_all: bool,
+    pub(crate) renames: Vec<Rename>,
     pub(crate) fields: Option<Fields>,
     pub(crate) err_args: Option<EventArgs>,
     pub(crate) ret_args: Option<EventArgs>,
+    pub(crate) sample: Option<Expr>,
     /// Errors describing any unrecognized parse inputs that we skipped.
     parse_warnings: Vec<syn::Error>,
 }
@@ -42,6 +46,18 @@ impl InstrumentArgs {
         }
     }
 
+    /// The path generated code should use to refer to the `tracing` crate.
+    ///
+    /// Defaults to the literal `tracing`, which resolves correctly as long as
+    /// `tracing` is a direct dependency of the instrumented crate under its
+    /// usual name. A `crate = "..."` argument overrides this for crates that
+    /// depend on `tracing` under another name.
+    pub(crate) fn krate(&self) -> Path {
+        self.krate
+            .clone()
+            .unwrap_or_else(|| syn::parse_quote!(tracing))
+    }
+
     /// Generate "deprecation" warnings for any unrecognized attribute inputs
     /// that we skipped.
     ///
@@ -69,6 +85,35 @@ impl InstrumentArgs {
             { #(#warnings)* }
         }
     }
+
+    /// Forces `self` into the skip list, regardless of whether the user's
+    /// `skip(...)` already mentions it.
+    ///
+    /// Used by `#[instrument_drop]`, which records the type name rather than
+    /// `Debug`-formatting `self` by default.
+    pub(crate) fn skip_self(&mut self) {
+        self.skips
+            .insert(Ident::new("self", proc_macro2::Span::call_site()));
+    }
+
+    /// Adds a `name = value` field, unless the user already provided a field
+    /// with that name via `fields(...)`.
+    pub(crate) fn add_default_field(&mut self, name: Ident, value: Expr) {
+        let fields = self.fields.get_or_insert_with(|| Fields(Punctuated::new()));
+        let already_present = fields
+            .0
+            .iter()
+            .any(|field| field.name.len() == 1 && field.name.first() == Some(&name));
+        if !already_present {
+            let mut field_name = Punctuated::new();
+            field_name.push(name);
+            fields.0.push(Field {
+                name: field_name,
+                value: Some(value),
+                kind: FieldKind::Value,
+            });
+        }
+    }
 }
 
 impl Parse for InstrumentArgs {
@@ -98,6 +143,13 @@ impl Parse for InstrumentArgs {
                 }
                 let target = input.parse::<StrArg<kw::target>>()?.value;
                 args.target = Some(target);
+            } else if lookahead.peek(Token![crate]) {
+                if args.krate.is_some() {
+                    return Err(input.error("expected only a single `crate` argument"));
+                }
+                let _ = input.parse::<Token![crate]>()?;
+                let _ = input.parse::<Token![=]>()?;
+                args.krate = Some(input.parse()?);
             } else if lookahead.peek(kw::parent) {
                 if args.target.is_some() {
                     return Err(input.error("expected only a single `parent` argument"));
@@ -115,12 +167,43 @@ impl Parse for InstrumentArgs {
                     return Err(input.error("expected only a single `level` argument"));
                 }
                 args.level = Some(input.parse()?);
+            } else if lookahead.peek(kw::skip_all) {
+                let skip_all = input.parse::<kw::skip_all>()?;
+                if args.skip_all {
+                    return Err(syn::Error::new(
+                        skip_all.span,
+                        "expected only a single `skip_all` argument",
+                    ));
+                }
+                if !args.skips.is_empty() {
+                    return Err(syn::Error::new(
+                        skip_all.span,
+                        "`skip_all` and `skip(...)` are mutually exclusive",
+                    ));
+                }
+                args.skip_all = true;
             } else if lookahead.peek(kw::skip) {
                 if !args.skips.is_empty() {
                     return Err(input.error("expected only a single `skip` argument"));
                 }
                 let Skips(skips) = input.parse()?;
+                if args.skip_all {
+                    return Err(syn::Error::new(
+                        skips
+                            .iter()
+                            .next()
+                            .map(|ident| ident.span())
+                            .unwrap_or_else(proc_macro2::Span::call_site),
+                        "`skip_all` and `skip(...)` are mutually exclusive",
+                    ));
+                }
                 args.skips = skips;
+            } else if lookahead.peek(kw::rename) {
+                if !args.renames.is_empty() {
+                    return Err(input.error("expected only a single `rename` argument"));
+                }
+                let Renames(renames) = input.parse()?;
+                args.renames = renames;
             } else if lookahead.peek(kw::fields) {
                 if args.fields.is_some() {
                     return Err(input.error("expected only a single `fields` argument"));
@@ -133,7 +216,18 @@ impl Parse for InstrumentArgs {
             } else if lookahead.peek(kw::ret) {
                 let _ = input.parse::<kw::ret>()?;
                 let ret_args = EventArgs::parse(input)?;
+                if ret_args.mode == FormatMode::Otel {
+                    return Err(
+                        input.error("`otel` formatting is only supported for `err`, not `ret`")
+                    );
+                }
                 args.ret_args = Some(ret_args);
+            } else if lookahead.peek(kw::sample) {
+                if args.sample.is_some() {
+                    return Err(input.error("expected only a single `sample` argument"));
+                }
+                let sample = input.parse::<ExprArg<kw::sample>>()?;
+                args.sample = Some(sample.value);
             } else if lookahead.peek(Token![,]) {
                 let _ = input.parse::<Token![,]>()?;
             } else {
@@ -179,9 +273,10 @@ impl Parse for EventArgs {
                     match ident.to_string().as_str() {
                         "Debug" => result.mode = FormatMode::Debug,
                         "Display" => result.mode = FormatMode::Display,
+                        "otel" => result.mode = FormatMode::Otel,
                         _ => return Err(syn::Error::new(
                             ident.span(),
-                            "unknown event formatting mode, expected either `Debug` or `Display`",
+                            "unknown event formatting mode, expected `Debug`, `Display`, or `otel`",
                         )),
                     }
                 }
@@ -281,12 +376,67 @@ impl Parse for Skips {
     }
 }
 
+/// A single `arg as "field_name"` entry inside `rename(...)`, recording that a
+/// parameter should be recorded under a field name other than its own.
+#[derive(Clone, Debug)]
+pub(crate) struct Rename {
+    pub(crate) ident: Ident,
+    pub(crate) name: LitStr,
+}
+
+impl Parse for Rename {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let ident = input.call(Ident::parse_any)?;
+        let _ = input.parse::<Token![as]>()?;
+        let name = input.parse::<LitStr>()?;
+        Ok(Self { ident, name })
+    }
+}
+
+struct Renames(Vec<Rename>);
+
+impl Parse for Renames {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let _ = input.parse::<kw::rename>();
+        let content;
+        let _ = syn::parenthesized!(content in input);
+        let renames = content.parse_terminated(Rename::parse, Token![,])?;
+        let mut seen_params = HashSet::new();
+        let mut seen_names = HashSet::new();
+        let mut result = Vec::new();
+        for rename in renames {
+            if !seen_params.insert(rename.ident.to_string()) {
+                return Err(syn::Error::new(
+                    rename.ident.span(),
+                    "tried to rename the same parameter twice",
+                ));
+            }
+            if !seen_names.insert(rename.name.value()) {
+                return Err(syn::Error::new(
+                    rename.name.span(),
+                    "multiple parameters renamed to the same field name",
+                ));
+            }
+            result.push(rename);
+        }
+        Ok(Self(result))
+    }
+}
+
 #[derive(Clone, Debug, Hash, PartialEq, Eq, Default)]
 pub(crate) enum FormatMode {
     #[default]
     Default,
     Display,
     Debug,
+    /// Emit an `err`-event using [OpenTelemetry semantic conventions for
+    /// exceptions][otel] (`exception.message`, `exception.type`, and
+    /// `exception.escaped`) rather than a single `error` field.
+    ///
+    /// This is only meaningful for `err`; it is rejected for `ret`.
+    ///
+    /// [otel]: https://opentelemetry.io/docs/specs/semconv/exceptions/exceptions-spans/
+    Otel,
 }
 
 #[derive(Clone, Debug)]
@@ -322,6 +472,16 @@ impl ToTokens for Fields {
     }
 }
 
+impl Fields {
+    /// Like [`ToTokens`], but resolves against `krate` rather than a
+    /// hardcoded `tracing` path, so the generated code keeps working when
+    /// the caller's `tracing` dependency is renamed.
+    pub(crate) fn tokens_with_crate(&self, krate: &Path) -> TokenStream {
+        let fields = self.0.iter().map(|field| field.tokens_with_crate(krate));
+        quote!(#(#fields),*)
+    }
+}
+
 impl Parse for Field {
     fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
         let mut kind = FieldKind::Value;
@@ -373,6 +533,31 @@ impl ToTokens for Field {
     }
 }
 
+impl Field {
+    /// Like [`ToTokens`], but resolves against `krate` rather than a
+    /// hardcoded `tracing` path, so the generated code keeps working when
+    /// the caller's `tracing` dependency is renamed.
+    fn tokens_with_crate(&self, krate: &Path) -> TokenStream {
+        if let Some(ref value) = self.value {
+            let name = &self.name;
+            let kind = &self.kind;
+            quote! {
+                #name = #kind #value
+            }
+        } else if self.kind == FieldKind::Value {
+            // XXX(eliza): see the matching comment on `Field`'s `ToTokens`
+            // impl; this has to keep producing empty fields for the same
+            // backwards-compatibility reason.
+            let name = &self.name;
+            quote!(#name = #krate::field::Empty)
+        } else {
+            let kind = &self.kind;
+            let name = &self.name;
+            quote!(#kind #name)
+        }
+    }
+}
+
 impl ToTokens for FieldKind {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         match self {
@@ -451,9 +636,27 @@ impl ToTokens for Level {
     }
 }
 
+impl Level {
+    /// Like [`ToTokens`], but resolves the named levels against `krate`
+    /// rather than a hardcoded `tracing` path, so the generated code keeps
+    /// working when the caller's `tracing` dependency is renamed.
+    pub(crate) fn tokens_with_crate(&self, krate: &Path) -> TokenStream {
+        match self {
+            Level::Trace => quote!(#krate::Level::TRACE),
+            Level::Debug => quote!(#krate::Level::DEBUG),
+            Level::Info => quote!(#krate::Level::INFO),
+            Level::Warn => quote!(#krate::Level::WARN),
+            Level::Error => quote!(#krate::Level::ERROR),
+            Level::Path(ref pat) => quote!(#pat),
+        }
+    }
+}
+
 mod kw {
     syn::custom_keyword!(fields);
     syn::custom_keyword!(skip);
+    syn::custom_keyword!(skip_all);
+    syn::custom_keyword!(rename);
     syn::custom_keyword!(level);
     syn::custom_keyword!(target);
     syn::custom_keyword!(parent);
@@ -461,4 +664,5 @@ mod kw {
     syn::custom_keyword!(name);
     syn::custom_keyword!(err);
     syn::custom_keyword!(ret);
+    syn::custom_keyword!(sample);
 }
@@ -10,8 +10,8 @@ use syn::{
 };
 
 use crate::{
-    attr::{Field, Fields, FormatMode, InstrumentArgs, Level},
-    MaybeItemFn, MaybeItemFnRef,
+    attr::{Field, Fields, FormatMode, InstrumentArgs, Level, LitStrOrIdent, Rename},
+    naming, MaybeItemFn, MaybeItemFnRef,
 };
 
 /// Given an existing function, generate an instrumented version of that function
@@ -111,6 +111,34 @@ fn gen_block<B: ToTokens>(
     instrumented_function_name: &str,
     self_type: Option<&TypePath>,
 ) -> proc_macro2::TokenStream {
+    // if a naming convention is configured (see `naming`), enforce it on the
+    // span's name and any custom field names before generating anything else
+    let (span_name_str, span_name_span) = match &args.name {
+        Some(LitStrOrIdent::LitStr(lit)) => (lit.value(), lit.span()),
+        Some(LitStrOrIdent::Ident(ident)) => (ident.to_string(), ident.span()),
+        None => (
+            instrumented_function_name.to_string(),
+            proc_macro2::Span::call_site(),
+        ),
+    };
+    if let Some(err) = naming::check("span", &span_name_str, span_name_span) {
+        return err.to_compile_error();
+    }
+    if let Some(Fields(ref fields)) = args.fields {
+        for field in fields {
+            if let Some(name) = field.name.last() {
+                if let Some(err) = naming::check("field", &name.to_string(), name.span()) {
+                    return err.to_compile_error();
+                }
+            }
+        }
+    }
+    for Rename { name, .. } in &args.renames {
+        if let Some(err) = naming::check("field", &name.value(), name.span()) {
+            return err.to_compile_error();
+        }
+    }
+
     // generate the span's name
     let span_name = args
         // did the user override the span's name?
@@ -119,8 +147,9 @@ fn gen_block<B: ToTokens>(
         .map(|name| quote!(#name))
         .unwrap_or_else(|| quote!(#instrumented_function_name));
 
+    let krate = args.krate();
     let args_level = args.level();
-    let level = args_level.clone();
+    let level = args_level.tokens_with_crate(&krate);
 
     let follows_from = args.follows_from.iter();
     let follows_from = quote! {
@@ -174,15 +203,45 @@ fn gen_block<B: ToTokens>(
             }
         }
 
+        for Rename { ident, name } in &args.renames {
+            if !param_names.iter().map(|(user, _)| user).any(|y| y == ident) {
+                return quote_spanned! {ident.span()=>
+                    compile_error!("attempting to rename non-existent parameter")
+                };
+            }
+            if args.skips.contains(ident) {
+                return quote_spanned! {ident.span()=>
+                    compile_error!("parameter cannot be both renamed and skipped")
+                };
+            }
+            // A field explicitly defined in `fields(...)` under the same name
+            // as a `rename(...)` target would silently shadow one or the
+            // other, depending on the order `span!` happens to see them in;
+            // catch that at compile time instead.
+            if let Some(ref fields) = args.fields {
+                if let Some(field) = fields.0.iter().find(|field| {
+                    field.name.len() == 1 && field.name.first().unwrap() == name.value().as_str()
+                }) {
+                    let field_name = field.name.first().unwrap();
+                    return quote_spanned! {field_name.span()=>
+                        compile_error!("field name conflicts with a `rename(...)` target; remove the duplicate `fields(...)` entry")
+                    };
+                }
+            }
+        }
+
         let target = args.target();
 
         let parent = args.parent.iter();
 
-        // filter out skipped fields
+        // filter out skipped and renamed fields
         let quoted_fields: Vec<_> = param_names
             .iter()
             .filter(|(param, _)| {
-                if args.skips.contains(param) {
+                if args.skip_all
+                    || args.skips.contains(param)
+                    || args.renames.iter().any(|rename| &rename.ident == param)
+                {
                     return false;
                 }
 
@@ -199,8 +258,16 @@ fn gen_block<B: ToTokens>(
             })
             .map(|(user_name, (real_name, record_type))| match record_type {
                 RecordType::Value => quote!(#user_name = #real_name),
-                RecordType::Debug => quote!(#user_name = tracing::field::debug(&#real_name)),
+                RecordType::Debug => quote!(#user_name = #krate::field::debug(&#real_name)),
             })
+            .chain(param_names.iter().filter_map(|(param, (real_name, record_type))| {
+                let rename = args.renames.iter().find(|rename| &rename.ident == param)?;
+                let field_name = &rename.name;
+                Some(match record_type {
+                    RecordType::Value => quote!(#field_name = #real_name),
+                    RecordType::Debug => quote!(#field_name = #krate::field::debug(&#real_name)),
+                })
+            }))
             .collect();
 
         // replace every use of a variable with its original name
@@ -221,9 +288,9 @@ fn gen_block<B: ToTokens>(
             }
         }
 
-        let custom_fields = &args.fields;
+        let custom_fields = args.fields.as_ref().map(|fields| fields.tokens_with_crate(&krate));
 
-        quote!(tracing::span!(
+        quote!(#krate::span!(
             target: #target,
             #(parent: #parent,)*
             #level,
@@ -238,14 +305,26 @@ fn gen_block<B: ToTokens>(
 
     let err_event = match args.err_args {
         Some(event_args) => {
-            let level_tokens = event_args.level(Level::Error);
+            let level_tokens = event_args.level(Level::Error).tokens_with_crate(&krate);
             match event_args.mode {
                 FormatMode::Default | FormatMode::Display => Some(quote!(
-                    tracing::event!(target: #target, #level_tokens, error = %e)
+                    #krate::event!(target: #target, #level_tokens, error = %e)
                 )),
                 FormatMode::Debug => Some(quote!(
-                    tracing::event!(target: #target, #level_tokens, error = ?e)
+                    #krate::event!(target: #target, #level_tokens, error = ?e)
                 )),
+                FormatMode::Otel => Some(quote!({
+                    fn __tracing_attr_exception_type<T>(_: &T) -> &'static str {
+                        std::any::type_name::<T>()
+                    }
+                    #krate::event!(
+                        target: #target,
+                        #level_tokens,
+                        exception.message = %e,
+                        exception.type = __tracing_attr_exception_type(&e),
+                        exception.escaped = true
+                    )
+                })),
             }
         }
         _ => None,
@@ -253,14 +332,16 @@ fn gen_block<B: ToTokens>(
 
     let ret_event = match args.ret_args {
         Some(event_args) => {
-            let level_tokens = event_args.level(args_level);
+            let level_tokens = event_args.level(args_level).tokens_with_crate(&krate);
             match event_args.mode {
                 FormatMode::Display => Some(quote!(
-                    tracing::event!(target: #target, #level_tokens, return = %x)
+                    #krate::event!(target: #target, #level_tokens, return = %x)
                 )),
                 FormatMode::Default | FormatMode::Debug => Some(quote!(
-                    tracing::event!(target: #target, #level_tokens, return = ?x)
+                    #krate::event!(target: #target, #level_tokens, return = ?x)
                 )),
+                // Rejected by `InstrumentArgs::parse`; `ret` has no OTel mode.
+                FormatMode::Otel => unreachable!("`otel` is not a valid `ret` format mode"),
             }
         }
         _ => None,
@@ -315,12 +396,23 @@ fn gen_block<B: ToTokens>(
             ),
         };
 
+        let span_binding = match &args.sample {
+            Some(rate) => quote!(
+                let (__tracing_attr_sampled, __tracing_attr_sample_guard) =
+                    #krate::__macro_support::sample(#rate);
+                let __tracing_attr_span = if __tracing_attr_sampled { #span } else { #krate::Span::none() };
+            ),
+            None => quote!(
+                let __tracing_attr_span = #span;
+            ),
+        };
+
         return quote!(
-            let __tracing_attr_span = #span;
+            #span_binding
             let __tracing_instrument_future = #mk_fut;
             if !__tracing_attr_span.is_disabled() {
                 #follows_from
-                tracing::Instrument::instrument(
+                #krate::Instrument::instrument(
                     __tracing_instrument_future,
                     __tracing_attr_span
                 )
@@ -331,6 +423,18 @@ fn gen_block<B: ToTokens>(
         );
     }
 
+    let sample_gate = args.sample.as_ref().map(|rate| {
+        quote!(
+            let (__tracing_attr_sampled, __tracing_attr_sample_guard) =
+                #krate::__macro_support::sample(#rate);
+        )
+    });
+    let sample_cond = if args.sample.is_some() {
+        quote!(__tracing_attr_sampled &&)
+    } else {
+        quote!()
+    };
+
     let span = quote!(
         // These variables are left uninitialized and initialized only
         // if the tracing level is statically enabled at this point.
@@ -342,9 +446,10 @@ fn gen_block<B: ToTokens>(
         // is very straightforward for LLVM to optimize out if the tracing
         // level is statically disabled, while not causing any performance
         // regression in case the level is enabled.
+        #sample_gate
         let __tracing_attr_span;
         let __tracing_attr_guard;
-        if tracing::level_enabled!(#level) || tracing::if_log_enabled!(#level, {true} else {false}) {
+        if #sample_cond (#krate::level_enabled!(#level) || #krate::if_log_enabled!(#level, {true} else {false})) {
             __tracing_attr_span = #span;
             #follows_from
             __tracing_attr_guard = __tracing_attr_span.enter();
@@ -82,10 +82,11 @@
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
 use syn::parse::{Parse, ParseStream};
-use syn::{Attribute, ItemFn, Signature, Visibility};
+use syn::{Attribute, FnArg, Ident, ItemFn, ReturnType, Signature, Visibility};
 
 mod attr;
 mod expand;
+mod naming;
 /// Instruments a function to create and enter a `tracing` [span] every time
 /// the function is called.
 ///
@@ -156,6 +157,44 @@ mod expand;
 ///     // ...
 /// }
 /// ```
+/// If a crate re-exports `tracing` under another name, or depends on it via
+/// a renamed Cargo dependency, the `crate` argument tells the generated code
+/// where to find it:
+/// ```
+/// # use tracing_attributes::instrument;
+/// use tracing as my_tracing;
+/// #[instrument(crate = my_tracing)]
+/// pub fn my_function() {
+///     // ...
+/// }
+/// ```
+///
+/// For functions hot enough that recording a span (and its arguments) on
+/// every call is too expensive, `sample` makes only a fraction of calls pay
+/// that cost, while still emitting all-or-nothing subtrees: an
+/// `#[instrument(sample = ...)]`d function called from inside another one
+/// reuses its caller's decision rather than rolling its own, so a sampled-out
+/// call doesn't produce a subtree with some spans and not others.
+/// ```
+/// # use tracing_attributes::instrument;
+/// // Only one call in a hundred builds and records this span.
+/// #[instrument(sample = 0.01)]
+/// pub fn hot_path() {
+///     // ...
+/// }
+/// ```
+/// `sample` requires the `std` feature of `tracing` (the decision is tracked
+/// per-thread), and, for `async fn`s, is only guaranteed to be inherited by
+/// nested `#[instrument(sample = ...)]`d calls made before the first
+/// `.await`. Beyond that first `.await`, a multi-threaded executor may
+/// resume the function on a different OS thread than the one it started on;
+/// since the decision lives in a thread-local, inheritance simply stops
+/// working across that boundary (nested calls on the new thread roll their
+/// own decision instead of seeing a stale one). Prefer keeping `sample` on
+/// `async fn`s that either don't `.await` or are only ever driven on a
+/// single-threaded executor, if inheritance past the first `.await` matters
+/// to you.
+///
 /// Overriding the generated span's parent:
 /// ```
 /// # use tracing_attributes::instrument;
@@ -210,6 +249,19 @@ mod expand;
 /// }
 /// ```
 ///
+/// To skip every argument, without having to name each one, pass `skip_all`:
+///
+/// ```
+/// # use tracing_attributes::instrument;
+/// # struct NonDebug;
+/// #[instrument(skip_all)]
+/// fn my_function(arg: usize, non_debug: NonDebug) {
+///     // ...
+/// }
+/// ```
+///
+/// `skip_all` and `skip(...)` may not be used together.
+///
 /// To add additional context to the span, pass key-value pairs to `fields`:
 ///
 /// ```
@@ -220,6 +272,24 @@ mod expand;
 /// }
 /// ```
 ///
+/// To record an argument under a field name other than its own, pass
+/// `arg as "field_name"` to `rename`:
+///
+/// ```
+/// # use tracing_attributes::instrument;
+/// #[instrument(rename(user_id as "userId"))]
+/// fn my_function(user_id: u64) {
+///     // ...
+/// }
+/// ```
+///
+/// This is equivalent to skipping the argument and adding it back as a custom
+/// field (`#[instrument(skip(user_id), fields(userId = user_id))]`), but
+/// without the risk of the two falling out of sync as the function changes.
+/// A parameter may not be both `skip`ped and `rename`d, and a `rename`
+/// target may not collide with an explicit `fields(...)` name; both are
+/// compile errors.
+///
 /// Adding the `ret` argument to `#[instrument]` will emit an event with the function's
 /// return value when the function returns:
 ///
@@ -296,6 +366,21 @@ mod expand;
 /// }
 /// ```
 ///
+/// Writing `err(otel)` emits the error event using [OpenTelemetry semantic conventions for
+/// exceptions][otel-exceptions], recording the error's `Display` output as `exception.message`,
+/// its type name as `exception.type`, and `exception.escaped = true`, instead of a single
+/// `error` field:
+///
+/// ```
+/// # use tracing_attributes::instrument;
+/// #[instrument(err(otel))]
+/// fn my_function(arg: usize) -> Result<(), std::io::Error> {
+///     Ok(())
+/// }
+/// ```
+///
+/// [otel-exceptions]: https://opentelemetry.io/docs/specs/semconv/exceptions/exceptions-spans/
+///
 /// If a `target` is specified, both the `ret` and `err` arguments will emit outputs to
 /// the declared target (or the default channel if `target` is not specified).
 ///
@@ -420,6 +505,113 @@ fn instrument_precise(
     .into())
 }
 
+/// Instruments a `Drop::drop` implementation, recording the type name (and
+/// any fields selected with `fields(...)`) in the generated span.
+///
+/// This is a variant of [`#[instrument]`][macro@instrument] tailored to
+/// `fn drop(&mut self)`. Debug-formatting `self` while it's being torn down
+/// is often unsafe or simply unhelpful, so unlike `#[instrument]`,
+/// `#[instrument_drop]` never records `self`, and instead records the
+/// dropped type's name via [`std::any::type_name`]:
+///
+/// ```
+/// # use tracing_attributes::instrument_drop;
+/// struct Connection {
+///     id: u64,
+/// }
+///
+/// impl Drop for Connection {
+///     #[instrument_drop(fields(id = self.id))]
+///     fn drop(&mut self) {
+///         // ...
+///     }
+/// }
+/// ```
+///
+/// All of `#[instrument]`'s other arguments (`level`, `name`, `target`,
+/// `err`, `ret`, and so on) are supported here too. Applying
+/// `#[instrument_drop]` to anything other than `fn drop(&mut self)` is a
+/// compile error.
+#[proc_macro_attribute]
+pub fn instrument_drop(
+    args: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let mut args = syn::parse_macro_input!(args as attr::InstrumentArgs);
+    args.skip_self();
+    args.add_default_field(
+        Ident::new("type_name", proc_macro2::Span::call_site()),
+        syn::parse_quote!(std::any::type_name::<Self>()),
+    );
+    // Cloning a `TokenStream` is cheap since it's reference counted internally.
+    instrument_drop_precise(args.clone(), item.clone())
+        .unwrap_or_else(|_err| instrument_drop_speculative(args, item))
+}
+
+/// Instrument `fn drop(&mut self)`, without parsing the function body
+/// (instead using the raw tokens).
+fn instrument_drop_speculative(
+    args: attr::InstrumentArgs,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(item as MaybeItemFn);
+    if let Some(err) = reject_if_not_drop(&input.sig) {
+        return err;
+    }
+    let instrumented_function_name = input.sig.ident.to_string();
+    expand::gen_function(
+        input.as_ref(),
+        args,
+        instrumented_function_name.as_str(),
+        None,
+    )
+    .into()
+}
+
+/// Instrument `fn drop(&mut self)`, by fully parsing the function body.
+fn instrument_drop_precise(
+    args: attr::InstrumentArgs,
+    item: proc_macro::TokenStream,
+) -> Result<proc_macro::TokenStream, syn::Error> {
+    let input = syn::parse::<ItemFn>(item)?;
+    if let Some(err) = reject_if_not_drop(&input.sig) {
+        return Ok(err);
+    }
+    let instrumented_function_name = input.sig.ident.to_string();
+    let input = MaybeItemFn::from(input);
+
+    Ok(expand::gen_function(
+        input.as_ref(),
+        args,
+        instrumented_function_name.as_str(),
+        None,
+    )
+    .into())
+}
+
+/// Returns a `compile_error!` token stream if `sig` is not `fn drop(&mut self)`.
+fn reject_if_not_drop(sig: &Signature) -> Option<proc_macro::TokenStream> {
+    let is_drop_shaped = sig.ident == "drop"
+        && sig.inputs.len() == 1
+        && matches!(
+            sig.inputs.first(),
+            Some(FnArg::Receiver(receiver))
+                if receiver.reference.is_some() && receiver.mutability.is_some()
+        )
+        && matches!(sig.output, ReturnType::Default);
+
+    if is_drop_shaped {
+        None
+    } else {
+        Some(
+            quote! {
+                compile_error!("`#[instrument_drop]` may only be used on `fn drop(&mut self)`")
+            }
+            .into(),
+        )
+    }
+}
+
 /// This is a more flexible/imprecise `ItemFn` type,
 /// which's block is just a `TokenStream` (it may contain invalid code).
 #[derive(Debug, Clone)]
@@ -0,0 +1,69 @@
+//! Compile-time enforcement of naming conventions for span and field names.
+//!
+//! This is opt-in along two axes: it has no effect unless this crate is
+//! built with the `naming-lint` feature *and* the
+//! `TRACING_ATTRIBUTES_NAME_PATTERN` environment variable is set (to a regex
+//! that span and field names must match). Neither is the case by default, so
+//! existing `#[instrument]` usage is unaffected.
+use proc_macro2::Span;
+
+const ENV_VAR: &str = "TRACING_ATTRIBUTES_NAME_PATTERN";
+
+/// Checks a span or field name against the naming convention configured via
+/// [`ENV_VAR`], if any is configured.
+///
+/// `kind` describes what `name` is (`"span"` or `"field"`) for the resulting
+/// error message. Returns `None` if no convention is configured, or if
+/// `name` satisfies it.
+pub(crate) fn check(kind: &str, name: &str, span: Span) -> Option<syn::Error> {
+    let pattern = std::env::var(ENV_VAR).ok()?;
+    imp::check(kind, name, &pattern, span)
+}
+
+#[cfg(feature = "naming-lint")]
+mod imp {
+    use super::{Span, ENV_VAR};
+
+    pub(super) fn check(kind: &str, name: &str, pattern: &str, span: Span) -> Option<syn::Error> {
+        let re = match regex::Regex::new(pattern) {
+            Ok(re) => re,
+            Err(e) => {
+                return Some(syn::Error::new(
+                    span,
+                    format!("`{ENV_VAR}` is not a valid regex (\"{pattern}\"): {e}"),
+                ))
+            }
+        };
+        if re.is_match(name) {
+            None
+        } else {
+            Some(syn::Error::new(
+                span,
+                format!(
+                    "{kind} name `{name}` does not match the naming convention configured by \
+                     `{ENV_VAR}` (`{pattern}`)",
+                ),
+            ))
+        }
+    }
+}
+
+#[cfg(not(feature = "naming-lint"))]
+mod imp {
+    use super::{Span, ENV_VAR};
+
+    pub(super) fn check(
+        _kind: &str,
+        _name: &str,
+        _pattern: &str,
+        span: Span,
+    ) -> Option<syn::Error> {
+        Some(syn::Error::new(
+            span,
+            format!(
+                "`{ENV_VAR}` is set, but checking it requires building `tracing-attributes` \
+                 with the `naming-lint` feature enabled",
+            ),
+        ))
+    }
+}
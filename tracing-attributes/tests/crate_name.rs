@@ -0,0 +1,67 @@
+use tracing as my_tracing;
+use tracing::{collect::with_default, Level};
+use tracing_attributes::instrument;
+use tracing_mock::{collector, expect};
+
+#[instrument(crate = my_tracing)]
+fn renamed_crate() {}
+
+#[instrument(crate = my_tracing, err)]
+fn renamed_crate_err() -> Result<u8, &'static str> {
+    Err("oops")
+}
+
+#[instrument(crate = my_tracing, ret)]
+fn renamed_crate_ret() -> u8 {
+    42
+}
+
+#[test]
+fn generates_span() {
+    let span = expect::span().named("renamed_crate");
+    let (collector, handle) = collector::mock()
+        .new_span(span.clone())
+        .enter(span.clone())
+        .exit(span)
+        .only()
+        .run_with_handle();
+
+    with_default(collector, renamed_crate);
+    handle.assert_finished();
+}
+
+#[test]
+fn supports_err() {
+    let span = expect::span().named("renamed_crate_err");
+    let (collector, handle) = collector::mock()
+        .new_span(span.clone())
+        .enter(span.clone())
+        .event(expect::event().at_level(Level::ERROR))
+        .exit(span.clone())
+        .drop_span(span)
+        .only()
+        .run_with_handle();
+
+    with_default(collector, || renamed_crate_err().ok());
+    handle.assert_finished();
+}
+
+#[test]
+fn supports_ret() {
+    let span = expect::span().named("renamed_crate_ret");
+    let (collector, handle) = collector::mock()
+        .new_span(span.clone())
+        .enter(span.clone())
+        .event(
+            expect::event()
+                .with_fields(expect::field("return").with_value(&tracing::field::debug(42)))
+                .at_level(Level::INFO),
+        )
+        .exit(span.clone())
+        .drop_span(span)
+        .only()
+        .run_with_handle();
+
+    with_default(collector, renamed_crate_ret);
+    handle.assert_finished();
+}
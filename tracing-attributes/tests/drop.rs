@@ -0,0 +1,91 @@
+use tracing::collect::with_default;
+use tracing::Level;
+use tracing_attributes::instrument_drop;
+use tracing_mock::{collector, expect};
+
+#[test]
+fn records_the_type_name() {
+    struct Connection;
+
+    impl Drop for Connection {
+        #[instrument_drop]
+        fn drop(&mut self) {}
+    }
+
+    let new_span = expect::span()
+        .named("drop")
+        .with_fields(expect::field("type_name").only());
+    let (collector, handle) = collector::mock()
+        .new_span(new_span)
+        .enter(expect::span().named("drop"))
+        .exit(expect::span().named("drop"))
+        .drop_span(expect::span().named("drop"))
+        .only()
+        .run_with_handle();
+
+    with_default(collector, || {
+        drop(Connection);
+    });
+
+    handle.assert_finished();
+}
+
+#[test]
+fn does_not_require_debug() {
+    // `Connection` deliberately does not implement `Debug`; `#[instrument]`
+    // would fail to compile here, since it records `self` via
+    // `tracing::field::debug`.
+    struct Connection {
+        id: u64,
+    }
+
+    impl Drop for Connection {
+        #[instrument_drop(fields(id = self.id))]
+        fn drop(&mut self) {}
+    }
+
+    let new_span = expect::span().with_fields(
+        expect::field("id")
+            .with_value(&42u64)
+            .and(expect::field("type_name"))
+            .only(),
+    );
+    let (collector, handle) = collector::mock()
+        .new_span(new_span)
+        .enter(expect::span().named("drop"))
+        .exit(expect::span().named("drop"))
+        .drop_span(expect::span().named("drop"))
+        .only()
+        .run_with_handle();
+
+    with_default(collector, || {
+        drop(Connection { id: 42 });
+    });
+
+    handle.assert_finished();
+}
+
+#[test]
+fn overrides_name_and_level() {
+    struct Guard;
+
+    impl Drop for Guard {
+        #[instrument_drop(name = "guard_drop", level = "debug")]
+        fn drop(&mut self) {}
+    }
+
+    let new_span = expect::span().named("guard_drop").at_level(Level::DEBUG);
+    let (collector, handle) = collector::mock()
+        .new_span(new_span)
+        .enter(expect::span().named("guard_drop"))
+        .exit(expect::span().named("guard_drop"))
+        .drop_span(expect::span().named("guard_drop"))
+        .only()
+        .run_with_handle();
+
+    with_default(collector, || {
+        drop(Guard);
+    });
+
+    handle.assert_finished();
+}
@@ -307,6 +307,34 @@ fn test_err_dbg_info() {
     handle.assert_finished();
 }
 
+#[instrument(err(otel))]
+fn err_otel() -> Result<u8, TryFromIntError> {
+    u8::try_from(1234)
+}
+
+#[test]
+fn test_err_otel() {
+    let span = expect::span().named("err_otel");
+    let (collector, handle) = collector::mock()
+        .new_span(span.clone())
+        .enter(span.clone())
+        .event(
+            expect::event().at_level(Level::ERROR).with_fields(
+                expect::field("exception.message")
+                    .with_value(&tracing::field::display(
+                        u8::try_from(1234).unwrap_err(),
+                    ))
+                    .and(expect::field("exception.escaped").with_value(&true)),
+            ),
+        )
+        .exit(span.clone())
+        .drop_span(span)
+        .only()
+        .run_with_handle();
+    with_default(collector, || err_otel().ok());
+    handle.assert_finished();
+}
+
 #[instrument(level = "warn", err(level = "info"))]
 fn err_warn_info() -> Result<u8, TryFromIntError> {
     u8::try_from(1234)
@@ -37,6 +37,11 @@ fn fn_string(s: String) {
 #[instrument(fields(keywords.impl.type.fn = _arg), skip(_arg))]
 fn fn_keyword_ident_in_field(_arg: &str) {}
 
+#[instrument(rename(user_id as "userId"))]
+fn fn_renamed_param(user_id: u64, other: &str) {
+    let _ = other;
+}
+
 #[derive(Debug)]
 struct HasField {
     my_field: &'static str,
@@ -159,6 +164,19 @@ fn keyword_ident_in_field_name() {
     run_test(span, || fn_keyword_ident_in_field("test"));
 }
 
+#[test]
+fn renamed_param() {
+    let span = expect::span().with_fields(
+        expect::field("userId")
+            .with_value(&1u64)
+            .and(expect::field("other").with_value(&"hi"))
+            .only(),
+    );
+    run_test(span, || {
+        fn_renamed_param(1, "hi");
+    });
+}
+
 fn run_test<F: FnOnce() -> T, T>(span: NewSpan, fun: F) {
     let (collector, handle) = collector::mock()
         .new_span(span)
@@ -141,6 +141,34 @@ fn skip() {
     handle.assert_finished();
 }
 
+#[test]
+fn skip_all() {
+    struct UnDebug();
+
+    #[instrument(target = "my_target", level = "debug", skip_all)]
+    fn my_fn(_arg1: usize, _arg2: UnDebug, _arg3: UnDebug) {}
+
+    // `UnDebug` doesn't implement `Debug`, so this only compiles because
+    // `skip_all` skips every argument.
+    let span = expect::span()
+        .named("my_fn")
+        .at_level(Level::DEBUG)
+        .with_target("my_target");
+    let (collector, handle) = collector::mock()
+        .new_span(span.clone())
+        .enter(span.clone())
+        .exit(span.clone())
+        .drop_span(span)
+        .only()
+        .run_with_handle();
+
+    with_default(collector, || {
+        my_fn(2, UnDebug(), UnDebug());
+    });
+
+    handle.assert_finished();
+}
+
 #[test]
 fn generics() {
     #[derive(Debug)]
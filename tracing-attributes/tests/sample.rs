@@ -0,0 +1,46 @@
+use tracing::collect::with_default;
+use tracing_attributes::instrument;
+use tracing_mock::{collector, expect};
+
+#[instrument(sample = 1.0)]
+fn always_sampled() {}
+
+#[instrument(sample = 0.0)]
+fn never_sampled() {}
+
+#[instrument(sample = 0.0)]
+fn never_sampled_parent() {
+    // Nested calls inherit their caller's decision rather than rolling their
+    // own, so this doesn't produce a span even though its own rate is 1.0.
+    always_sampled();
+}
+
+#[test]
+fn rate_one_always_creates_a_span() {
+    let span = expect::span().named("always_sampled");
+    let (collector, handle) = collector::mock()
+        .new_span(span.clone())
+        .enter(span.clone())
+        .exit(span)
+        .only()
+        .run_with_handle();
+
+    with_default(collector, always_sampled);
+    handle.assert_finished();
+}
+
+#[test]
+fn rate_zero_never_creates_a_span() {
+    let (collector, handle) = collector::mock().only().run_with_handle();
+
+    with_default(collector, never_sampled);
+    handle.assert_finished();
+}
+
+#[test]
+fn sampled_out_parent_suppresses_child() {
+    let (collector, handle) = collector::mock().only().run_with_handle();
+
+    with_default(collector, never_sampled_parent);
+    handle.assert_finished();
+}
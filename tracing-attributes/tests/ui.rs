@@ -12,3 +12,17 @@ fn const_instrument() {
     let t = trybuild::TestCases::new();
     t.compile_fail("tests/ui/const_instrument.rs");
 }
+
+#[rustversion::stable]
+#[test]
+fn skip_all_and_skip() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/skip_all_and_skip.rs");
+}
+
+#[rustversion::stable]
+#[test]
+fn rename_conflicts() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/rename_conflicts.rs");
+}
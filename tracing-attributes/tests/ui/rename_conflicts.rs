@@ -0,0 +1,7 @@
+#[tracing::instrument(rename(arg as "id"), skip(arg))]
+fn renamed_and_skipped(arg: usize) {}
+
+#[tracing::instrument(rename(arg as "id"), fields(id = "literal"))]
+fn renamed_and_fielded(arg: usize) {}
+
+fn main() {}
@@ -0,0 +1,4 @@
+#[tracing::instrument(skip_all, skip(arg))]
+fn my_fn(arg: usize) {}
+
+fn main() {}
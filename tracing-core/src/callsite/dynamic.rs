@@ -0,0 +1,309 @@
+//! Constructing [`Metadata`] at runtime, for bridges that don't have
+//! `'static` strings available.
+//!
+//! Most callsites in `tracing` are macro-generated `static`s: the name,
+//! target, and field names baked into their [`Metadata`] are string literals
+//! that live for the lifetime of the program. FFI bindings and scripting
+//! language bridges don't have that luxury --- a span's name might be a
+//! string that only exists as long as a Python or Lua value does --- but
+//! [`Callsite`] still requires a `&'static dyn Callsite`, and [`FieldSet`]
+//! still requires `&'static [&'static str]` field names.
+//!
+//! [`Interner`] bridges that gap: it deduplicates the strings and field sets
+//! it's given, leaking each *distinct* one at most once, and reuses the same
+//! callsite for any later request describing the same name, target, level,
+//! location, and fields. A bridge that repeatedly constructs metadata for
+//! the same underlying source location --- the common case, since a given
+//! line of script code is typically logged many times --- ends up leaking a
+//! single small allocation for it rather than one per call.
+//!
+//! [`Metadata`]: crate::metadata::Metadata
+//! [`Callsite`]: crate::callsite::Callsite
+//! [`FieldSet`]: crate::field::FieldSet
+use crate::{
+    callsite::{self, Callsite, Identifier, Registration},
+    collect::Interest,
+    field::FieldSet,
+    metadata::{Kind, Level, Metadata},
+};
+use once_cell::sync::OnceCell;
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::atomic::{AtomicUsize, Ordering},
+    sync::Mutex,
+};
+
+/// A builder for [`Metadata`] whose strings need not be `'static`.
+///
+/// Build one up with the setter methods, then hand it to [`Interner::intern`]
+/// to obtain a [`callsite::Identifier`] for the (possibly newly created,
+/// possibly reused) callsite describing it.
+#[derive(Clone, Debug)]
+pub struct MetadataBuilder<'a> {
+    name: &'a str,
+    target: &'a str,
+    level: Level,
+    module_path: Option<&'a str>,
+    file: Option<&'a str>,
+    line: Option<u32>,
+    fields: Vec<&'a str>,
+    kind: Kind,
+}
+
+impl<'a> MetadataBuilder<'a> {
+    /// Returns a new builder for an event with the given `name` and `level`.
+    ///
+    /// The target defaults to `name`; use [`target`](Self::target) to
+    /// override it.
+    pub fn event(name: &'a str, level: Level) -> Self {
+        Self::new(name, level, Kind::EVENT)
+    }
+
+    /// Returns a new builder for a span with the given `name` and `level`.
+    ///
+    /// The target defaults to `name`; use [`target`](Self::target) to
+    /// override it.
+    pub fn span(name: &'a str, level: Level) -> Self {
+        Self::new(name, level, Kind::SPAN)
+    }
+
+    fn new(name: &'a str, level: Level, kind: Kind) -> Self {
+        Self {
+            name,
+            target: name,
+            level,
+            module_path: None,
+            file: None,
+            line: None,
+            fields: Vec::new(),
+            kind,
+        }
+    }
+
+    /// Sets the target of the described span or event.
+    pub fn target(mut self, target: &'a str) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// Sets the module path of the described span or event.
+    pub fn module_path(mut self, module_path: &'a str) -> Self {
+        self.module_path = Some(module_path);
+        self
+    }
+
+    /// Sets the source file of the described span or event.
+    pub fn file(mut self, file: &'a str) -> Self {
+        self.file = Some(file);
+        self
+    }
+
+    /// Sets the source line number of the described span or event.
+    pub fn line(mut self, line: u32) -> Self {
+        self.line = Some(line);
+        self
+    }
+
+    /// Adds a field name to the described span or event.
+    pub fn field(mut self, name: &'a str) -> Self {
+        self.fields.push(name);
+        self
+    }
+
+    /// Sets the field names of the described span or event, replacing any
+    /// previously added with [`field`](Self::field).
+    pub fn fields(mut self, names: impl IntoIterator<Item = &'a str>) -> Self {
+        self.fields = names.into_iter().collect();
+        self
+    }
+}
+
+/// A cache of dynamically-constructed callsites, keyed by their metadata's
+/// content.
+///
+/// See the [module-level documentation](self) for why this exists. An
+/// `Interner` is typically constructed once (e.g. as a `static`, behind a
+/// [`once_cell::sync::Lazy`]) and shared by every call into an FFI or
+/// scripting bridge.
+#[derive(Default)]
+pub struct Interner {
+    callsites: Mutex<HashMap<Key, &'static DynamicCallsite>>,
+}
+
+impl fmt::Debug for Interner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Interner").finish_non_exhaustive()
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct Key {
+    name: String,
+    target: String,
+    level: Level,
+    module_path: Option<String>,
+    file: Option<String>,
+    line: Option<u32>,
+    fields: Vec<String>,
+    kind: Kind,
+}
+
+impl Interner {
+    /// Returns a new, empty `Interner`.
+    pub fn new() -> Self {
+        Self {
+            callsites: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Interns `builder`'s metadata, returning the [`Identifier`] of a
+    /// callsite describing it.
+    ///
+    /// If this `Interner` has already interned an equivalent
+    /// [`MetadataBuilder`] (same name, target, level, location, and fields),
+    /// the existing callsite is reused and nothing is leaked. Otherwise, a
+    /// new callsite is allocated, leaked (so that it can satisfy
+    /// [`Callsite`]'s `'static` requirement), registered with the global
+    /// callsite registry, and cached for future calls.
+    pub fn intern(&self, builder: MetadataBuilder<'_>) -> Identifier {
+        let key = Key {
+            name: builder.name.to_owned(),
+            target: builder.target.to_owned(),
+            level: builder.level,
+            module_path: builder.module_path.map(ToOwned::to_owned),
+            file: builder.file.map(ToOwned::to_owned),
+            line: builder.line,
+            fields: builder.fields.iter().map(|&s| s.to_owned()).collect(),
+            kind: builder.kind,
+        };
+
+        let mut callsites = self.callsites.lock().unwrap();
+        if let Some(callsite) = callsites.get(&key) {
+            return callsite.id();
+        }
+
+        let callsite = DynamicCallsite::leak(&key);
+        callsites.insert(key, callsite);
+        callsite.id()
+    }
+}
+
+/// A leaked, dynamically-constructed [`Callsite`].
+///
+/// Its `meta` and `registration` are filled in lazily, exactly once, right
+/// after the `DynamicCallsite` itself is leaked: only at that point does a
+/// `&'static` reference to `self` exist for them to embed.
+struct DynamicCallsite {
+    interest: AtomicUsize,
+    meta: OnceCell<Metadata<'static>>,
+    registration: OnceCell<Registration>,
+}
+
+impl DynamicCallsite {
+    const INTEREST_NEVER: usize = 0;
+    const INTEREST_SOMETIMES: usize = 1;
+    const INTEREST_ALWAYS: usize = 2;
+
+    /// Builds a `DynamicCallsite` from `key`, leaks it to obtain a `&'static
+    /// dyn Callsite`, and registers it with the global callsite registry.
+    fn leak(key: &Key) -> &'static Self {
+        let name: &'static str = Box::leak(key.name.clone().into_boxed_str());
+        let target: &'static str = Box::leak(key.target.clone().into_boxed_str());
+        let module_path: Option<&'static str> = key
+            .module_path
+            .clone()
+            .map(|s| &*Box::leak(s.into_boxed_str()));
+        let file: Option<&'static str> = key.file.clone().map(|s| &*Box::leak(s.into_boxed_str()));
+        let field_names: &'static [&'static str] = Box::leak(
+            key.fields
+                .iter()
+                .map(|s| &*Box::leak(s.clone().into_boxed_str()))
+                .collect::<Vec<_>>()
+                .into_boxed_slice(),
+        );
+
+        let this: &'static Self = Box::leak(Box::new(Self {
+            interest: AtomicUsize::new(Self::INTEREST_SOMETIMES),
+            meta: OnceCell::new(),
+            registration: OnceCell::new(),
+        }));
+
+        // `this` is now a stable, permanent address, so `Identifier(this)`
+        // can finally be built. `OnceCell::set` only needs `&self`, so this
+        // never requires a `&mut Self` that would conflict with `this` also
+        // appearing inside the value being stored.
+        this.meta
+            .set(Metadata::new(
+                name,
+                target,
+                key.level,
+                file,
+                key.line,
+                module_path,
+                FieldSet::new(field_names, Identifier(this)),
+                key.kind.clone(),
+            ))
+            .map_err(|_| ())
+            .expect("DynamicCallsite metadata was already initialized");
+        this.registration
+            .set(Registration::new(this))
+            .map_err(|_| ())
+            .expect("DynamicCallsite registration was already initialized");
+
+        callsite::register(this.registration.get().expect("just initialized above"));
+        this
+    }
+
+    fn id(&'static self) -> Identifier {
+        self.metadata().callsite()
+    }
+}
+
+impl Callsite for DynamicCallsite {
+    fn set_interest(&self, interest: Interest) {
+        let interest = if interest.is_never() {
+            Self::INTEREST_NEVER
+        } else if interest.is_always() {
+            Self::INTEREST_ALWAYS
+        } else {
+            Self::INTEREST_SOMETIMES
+        };
+        self.interest.store(interest, Ordering::SeqCst);
+    }
+
+    fn metadata(&self) -> &Metadata<'_> {
+        self.meta
+            .get()
+            .expect("DynamicCallsite metadata not yet initialized")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interns_identical_metadata() {
+        let interner = Interner::new();
+        let a = interner.intern(
+            MetadataBuilder::event("py_log", Level::INFO)
+                .target("bridge.python")
+                .field("message"),
+        );
+        let b = interner.intern(
+            MetadataBuilder::event("py_log", Level::INFO)
+                .target("bridge.python")
+                .field("message"),
+        );
+        assert_eq!(a, b, "identical metadata should reuse the same callsite");
+    }
+
+    #[test]
+    fn distinguishes_different_metadata() {
+        let interner = Interner::new();
+        let a = interner.intern(MetadataBuilder::event("py_log", Level::INFO));
+        let b = interner.intern(MetadataBuilder::event("py_log", Level::WARN));
+        assert_ne!(a, b, "different levels should not share a callsite");
+    }
+}
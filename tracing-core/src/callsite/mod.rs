@@ -458,6 +458,10 @@ impl LinkedList {
     }
 }
 
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod dynamic;
+
 #[cfg(test)]
 mod tests {
     use super::*;
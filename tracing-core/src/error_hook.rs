@@ -0,0 +1,120 @@
+//! A global hook for internal errors encountered by `tracing` implementations.
+//!
+//! `Subscribe`, `Collect`, and `MakeWriter` implementations sometimes run
+//! into problems that have nothing to do with the application being traced:
+//! a lock guarding internal state was poisoned by a panic on another thread,
+//! a configured writer failed to flush its buffer, or a span that should be
+//! present in a collector's storage could not be found. These failures can't
+//! be surfaced through the trait methods that triggered them --- `Subscribe`
+//! and `Collect` methods don't return a `Result` --- so implementations have
+//! historically either panicked (tearing down an unrelated thread) or
+//! silently dropped the data.
+//!
+//! This module provides [`set_error_hook`], which lets an application
+//! install a callback invoked with an [`InternalError`] whenever such a
+//! failure occurs, so it can be routed to the application's own alerting
+//! rather than discarded.
+use once_cell::sync::OnceCell;
+use std::fmt;
+
+static ERROR_HOOK: OnceCell<Box<dyn Fn(InternalError) + Send + Sync>> = OnceCell::new();
+
+/// Describes an internal failure encountered by a `tracing` implementation,
+/// as reported to the hook installed by [`set_error_hook`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum InternalError {
+    /// A lock (such as a `Mutex` or `RwLock`) guarding a `Subscribe` or
+    /// `Collect` implementation's internal state was poisoned by a panic on
+    /// another thread.
+    LockPoisoned {
+        /// A short, human-readable description of what the poisoned lock
+        /// was protecting (e.g. `"span storage"`).
+        context: &'static str,
+    },
+    /// A configured writer (such as a file or non-blocking appender) failed
+    /// while a `Subscribe` or `Collect` implementation attempted to write
+    /// formatted output to it.
+    WriteFailed {
+        /// A human-readable description of the write failure.
+        message: String,
+    },
+    /// A span that a `Subscribe` or `Collect` implementation expected to
+    /// find in its span storage (for instance, a `Registry`) was missing,
+    /// most likely because it had already been closed and removed.
+    SpanNotFound {
+        /// The numeric id of the missing span.
+        id: u64,
+    },
+}
+
+impl fmt::Display for InternalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InternalError::LockPoisoned { context } => {
+                write!(f, "a lock guarding {} was poisoned", context)
+            }
+            InternalError::WriteFailed { message } => {
+                write!(f, "failed to write trace output: {}", message)
+            }
+            InternalError::SpanNotFound { id } => {
+                write!(f, "span {} was not found in the collector's storage", id)
+            }
+        }
+    }
+}
+
+/// Sets the global hook invoked when a `tracing` implementation encounters
+/// an internal error it cannot otherwise report.
+///
+/// Returns an error if a hook has already been set.
+///
+/// As with [`set_global_default`], this is meant to be called once, by the
+/// application, near the start of `main`; libraries should not call this
+/// themselves.
+///
+/// [`set_global_default`]: crate::dispatch::set_global_default
+pub fn set_error_hook(
+    hook: impl Fn(InternalError) + Send + Sync + 'static,
+) -> Result<(), SetErrorHookError> {
+    ERROR_HOOK
+        .set(Box::new(hook))
+        .map_err(|_| SetErrorHookError { _no_construct: () })
+}
+
+/// Reports `error` to the hook installed by [`set_error_hook`], if any.
+///
+/// `Subscribe`, `Collect`, and `MakeWriter` implementations should call this
+/// instead of panicking or silently ignoring internal failures that aren't
+/// caused by the application being traced.
+pub fn report_error(error: InternalError) {
+    if let Some(hook) = ERROR_HOOK.get() {
+        hook(error);
+    }
+}
+
+/// Returned if setting the global error hook fails because one has already
+/// been set.
+pub struct SetErrorHookError {
+    _no_construct: (),
+}
+
+impl fmt::Debug for SetErrorHookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SetErrorHookError")
+            .field(&Self::MESSAGE)
+            .finish()
+    }
+}
+
+impl fmt::Display for SetErrorHookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(Self::MESSAGE)
+    }
+}
+
+impl std::error::Error for SetErrorHookError {}
+
+impl SetErrorHookError {
+    const MESSAGE: &'static str = "a global error hook has already been set";
+}
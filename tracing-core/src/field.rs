@@ -225,10 +225,64 @@ pub trait Visit {
     }
 
     /// Visit a byte slice.
+    ///
+    /// The default implementation formats `value` as a hex string via
+    /// [`record_debug`]. Visitors that want a different encoding -- base64,
+    /// or the raw bytes themselves -- should override this method rather
+    /// than relying on the default.
+    ///
+    /// [`record_debug`]: Visit::record_debug
     fn record_bytes(&mut self, field: &Field, value: &[u8]) {
         self.record_debug(field, &HexBytes(value))
     }
 
+    /// Visit a sequence of values, such as the elements of an array, slice,
+    /// or `Vec`.
+    ///
+    /// The default implementation formats `debug` (the sequence as a whole)
+    /// via [`record_debug`]. Visitors that want the individual elements --
+    /// to serialize them as a JSON array, for instance -- should call
+    /// [`Seq::for_each`] on `seq` instead of relying on the default.
+    ///
+    /// [`record_debug`]: Visit::record_debug
+    fn record_seq(&mut self, field: &Field, debug: &dyn fmt::Debug, seq: &dyn Seq) {
+        let _ = seq;
+        self.record_debug(field, debug)
+    }
+
+    /// Visit a map of string keys to values, such as the entries of a
+    /// `BTreeMap`.
+    ///
+    /// The default implementation formats `debug` (the map as a whole) via
+    /// [`record_debug`]. Visitors that want the individual entries -- to
+    /// serialize them as a JSON object, for instance -- should call
+    /// [`Map::for_each`] on `map` instead of relying on the default.
+    ///
+    /// [`record_debug`]: Visit::record_debug
+    fn record_map(&mut self, field: &Field, debug: &dyn fmt::Debug, map: &dyn Map) {
+        let _ = map;
+        self.record_debug(field, debug)
+    }
+
+    /// Visit a `Duration` value.
+    fn record_duration(&mut self, field: &Field, value: core::time::Duration) {
+        self.record_debug(field, &value)
+    }
+
+    /// Visit a `SystemTime` value.
+    ///
+    /// <div class="example-wrap" style="display:inline-block">
+    /// <pre class="ignore" style="white-space:normal;font:inherit;">
+    /// <strong>Note</strong>: This is only enabled when the Rust standard library is
+    /// present.
+    /// </pre>
+    /// </div>
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    fn record_system_time(&mut self, field: &Field, value: std::time::SystemTime) {
+        self.record_debug(field, &value)
+    }
+
     /// Records a type implementing `Error`.
     ///
     /// <div class="example-wrap" style="display:inline-block">
@@ -257,6 +311,48 @@ pub trait Visit {
 pub trait Value: crate::sealed::Sealed {
     /// Visits this value with the given `Visitor`.
     fn record(&self, key: &Field, visitor: &mut dyn Visit);
+
+    /// Returns this value as a [`PrimitiveValue`], if it is one of the
+    /// primitive types `tracing-core` knows how to record directly --
+    /// a number, a `bool`, or a `str` -- without needing to dispatch through
+    /// a [`Visit`] implementation.
+    ///
+    /// Returns `None` for any value recorded via [`record_debug`] or
+    /// [`record_error`] (structs, enums, errors, `Duration`, byte slices,
+    /// and so on), since those have no primitive representation.
+    ///
+    /// See [`ValueSet::as_primitives`] for why this is useful.
+    ///
+    /// [`record_debug`]: Visit::record_debug
+    /// [`record_error`]: Visit::record_error
+    fn as_primitive(&self) -> Option<PrimitiveValue<'_>> {
+        None
+    }
+}
+
+/// A primitive field value: a number, a `bool`, or a `str`.
+///
+/// Returned by [`Value::as_primitive`] and [`ValueSet::as_primitives`],
+/// letting a subscriber that only cares about these common, cheaply-copied
+/// types read them directly, without implementing the full [`Visit`] trait
+/// and dispatching through it one call at a time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub enum PrimitiveValue<'a> {
+    /// A signed 64-bit integer.
+    I64(i64),
+    /// An unsigned 64-bit integer.
+    U64(u64),
+    /// A signed 128-bit integer.
+    I128(i128),
+    /// An unsigned 128-bit integer.
+    U128(u128),
+    /// A 64-bit floating point number.
+    F64(f64),
+    /// A boolean.
+    Bool(bool),
+    /// A string slice.
+    Str(&'a str),
 }
 
 /// A `Value` which serializes using `fmt::Display`.
@@ -288,6 +384,58 @@ where
     DebugValue(t)
 }
 
+/// A sequence of field values, such as the elements of an array, slice, or
+/// `Vec`, whose elements a [`Visit`] implementation may record one-by-one
+/// rather than formatting the whole sequence with `fmt::Debug`.
+///
+/// See [`seq`] for wrapping a type implementing `Seq` as a [`Value`].
+pub trait Seq {
+    /// Calls `f` once for each value in this sequence, in order.
+    fn for_each(&self, f: &mut dyn FnMut(&dyn Value));
+}
+
+/// A map of string keys to field values, such as the entries of a
+/// `BTreeMap`, whose entries a [`Visit`] implementation may record
+/// one-by-one rather than formatting the whole map with `fmt::Debug`.
+///
+/// See [`map`] for wrapping a type implementing `Map` as a [`Value`].
+pub trait Map {
+    /// Calls `f` once for each entry in this map, in order.
+    fn for_each(&self, f: &mut dyn FnMut(&str, &dyn Value));
+}
+
+/// A `Value` which records its elements individually via [`Seq::for_each`],
+/// falling back to `fmt::Debug` for visitors that don't care.
+#[derive(Clone)]
+pub struct SeqValue<T>(T);
+
+/// A `Value` which records its entries individually via [`Map::for_each`],
+/// falling back to `fmt::Debug` for visitors that don't care.
+#[derive(Clone)]
+pub struct MapValue<T>(T);
+
+/// Wraps a type implementing [`Seq`] (and `fmt::Debug`, for the fallback
+/// case) as a `Value` that a [`Visit`] implementation may record
+/// element-by-element -- as a JSON array, say -- instead of as a single
+/// `Debug`-formatted string.
+pub fn seq<T>(t: T) -> SeqValue<T>
+where
+    T: Seq + fmt::Debug,
+{
+    SeqValue(t)
+}
+
+/// Wraps a type implementing [`Map`] (and `fmt::Debug`, for the fallback
+/// case) as a `Value` that a [`Visit`] implementation may record
+/// entry-by-entry -- as a JSON object, say -- instead of as a single
+/// `Debug`-formatted string.
+pub fn map<T>(t: T) -> MapValue<T>
+where
+    T: Map + fmt::Debug,
+{
+    MapValue(t)
+}
+
 struct HexBytes<'a>(&'a [u8]);
 
 impl fmt::Debug for HexBytes<'_> {
@@ -404,6 +552,11 @@ macro_rules! impl_one_value {
                 #[allow(clippy::redundant_closure_call)]
                 visitor.$record(key, $op(*self))
             }
+
+            fn as_primitive(&self) -> Option<$crate::field::PrimitiveValue<'_>> {
+                #[allow(clippy::redundant_closure_call)]
+                Some(primitive_value!($record, $op(*self)))
+            }
         }
     };
     (nonzero, $value_ty:tt, $op:expr, $record:ident) => {
@@ -423,10 +576,38 @@ macro_rules! impl_one_value {
                 #[allow(clippy::redundant_closure_call)]
                 visitor.$record(key, $op(self.get()))
             }
+
+            fn as_primitive(&self) -> Option<$crate::field::PrimitiveValue<'_>> {
+                #[allow(clippy::redundant_closure_call)]
+                Some(primitive_value!($record, $op(self.get())))
+            }
         }
     };
 }
 
+/// Maps a `record_*` visitor method name to the matching [`PrimitiveValue`]
+/// variant, for the primitive [`Value`] impls generated by [`impl_value!`].
+macro_rules! primitive_value {
+    (record_i64, $val:expr) => {
+        $crate::field::PrimitiveValue::I64($val)
+    };
+    (record_u64, $val:expr) => {
+        $crate::field::PrimitiveValue::U64($val)
+    };
+    (record_i128, $val:expr) => {
+        $crate::field::PrimitiveValue::I128($val)
+    };
+    (record_u128, $val:expr) => {
+        $crate::field::PrimitiveValue::U128($val)
+    };
+    (record_f64, $val:expr) => {
+        $crate::field::PrimitiveValue::F64($val)
+    };
+    (record_bool, $val:expr) => {
+        $crate::field::PrimitiveValue::Bool($val)
+    };
+}
+
 macro_rules! impl_value {
     ( $record:ident( $( $value_ty:tt ),+ ) ) => {
         $(
@@ -466,10 +647,39 @@ impl Value for str {
     fn record(&self, key: &Field, visitor: &mut dyn Visit) {
         visitor.record_str(key, self)
     }
+
+    fn as_primitive(&self) -> Option<PrimitiveValue<'_>> {
+        Some(PrimitiveValue::Str(self))
+    }
+}
+
+impl crate::sealed::Sealed for core::time::Duration {}
+
+impl Value for core::time::Duration {
+    fn record(&self, key: &Field, visitor: &mut dyn Visit) {
+        visitor.record_duration(key, *self)
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl crate::sealed::Sealed for std::time::SystemTime {}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl Value for std::time::SystemTime {
+    fn record(&self, key: &Field, visitor: &mut dyn Visit) {
+        visitor.record_system_time(key, *self)
+    }
 }
 
 impl crate::sealed::Sealed for [u8] {}
 
+/// Records a byte slice, such as a binary payload fragment, hash, or ID,
+/// without requiring it to be formatted to a string first.
+///
+/// See [`Visit::record_bytes`] for how the bytes are encoded by default, and
+/// how to customize that.
 impl Value for [u8] {
     fn record(&self, key: &Field, visitor: &mut dyn Visit) {
         visitor.record_bytes(key, self)
@@ -529,6 +739,10 @@ where
     fn record(&self, key: &Field, visitor: &mut dyn Visit) {
         (*self).record(key, visitor)
     }
+
+    fn as_primitive(&self) -> Option<PrimitiveValue<'_>> {
+        (*self).as_primitive()
+    }
 }
 
 impl<'a, T: ?Sized> crate::sealed::Sealed for &'a mut T where T: Value + crate::sealed::Sealed + 'a {}
@@ -542,6 +756,12 @@ where
         // cause stack overflow due to `unconditional_recursion`.
         T::record(self, key, visitor)
     }
+
+    fn as_primitive(&self) -> Option<PrimitiveValue<'_>> {
+        // Don't use `(*self).as_primitive()`, for the same
+        // unconditional-recursion reason as `record` above.
+        T::as_primitive(self)
+    }
 }
 
 impl crate::sealed::Sealed for fmt::Arguments<'_> {}
@@ -579,6 +799,80 @@ impl Value for alloc::string::String {
     }
 }
 
+impl<T> crate::sealed::Sealed for SeqValue<T> where T: Seq + fmt::Debug {}
+
+impl<T> Value for SeqValue<T>
+where
+    T: Seq + fmt::Debug,
+{
+    fn record(&self, key: &Field, visitor: &mut dyn Visit) {
+        visitor.record_seq(key, &self.0, &self.0)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SeqValue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T> crate::sealed::Sealed for MapValue<T> where T: Map + fmt::Debug {}
+
+impl<T> Value for MapValue<T>
+where
+    T: Map + fmt::Debug,
+{
+    fn record(&self, key: &Field, visitor: &mut dyn Visit) {
+        visitor.record_map(key, &self.0, &self.0)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for MapValue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T: Value> Seq for &[T] {
+    fn for_each(&self, f: &mut dyn FnMut(&dyn Value)) {
+        for value in self.iter() {
+            f(value)
+        }
+    }
+}
+
+impl<T: Value, const N: usize> Seq for &[T; N] {
+    fn for_each(&self, f: &mut dyn FnMut(&dyn Value)) {
+        self.as_slice().for_each(f)
+    }
+}
+
+impl<'a, V: Value> Map for &'a [(&'a str, V)] {
+    fn for_each(&self, f: &mut dyn FnMut(&str, &dyn Value)) {
+        for (k, v) in self.iter() {
+            f(k, v)
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+impl<T: Value> Seq for &alloc::vec::Vec<T> {
+    fn for_each(&self, f: &mut dyn FnMut(&dyn Value)) {
+        self.as_slice().for_each(f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+impl<V: Value> Map for &alloc::collections::BTreeMap<alloc::string::String, V> {
+    fn for_each(&self, f: &mut dyn FnMut(&str, &dyn Value)) {
+        for (k, v) in self.iter() {
+            f(k.as_str(), v)
+        }
+    }
+}
+
 impl fmt::Debug for dyn Value {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // We are only going to be recording the field value, so we don't
@@ -923,6 +1217,37 @@ impl ValueSet<'_> {
         }
     }
 
+    /// Returns this `ValueSet`'s fields and values as [`PrimitiveValue`]s, if
+    /// every field in the set is a primitive (a number, `bool`, or `str`)
+    /// with a value -- letting a subscriber read them directly, without
+    /// implementing the full [`Visit`] trait and dispatching through it one
+    /// field at a time.
+    ///
+    /// Returns `None` if any field in this set is [`Empty`] or holds a
+    /// non-primitive value (anything recorded via `record_debug` or
+    /// `record_error`, a byte slice, a `Duration`, and so on); callers that
+    /// get `None` back should fall back to [`ValueSet::record`] with a full
+    /// [`Visit`] implementation.
+    ///
+    /// [visitor]: Visit
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    pub fn as_primitives(&self) -> Option<alloc::vec::Vec<(&'static str, PrimitiveValue<'_>)>> {
+        let my_callsite = self.callsite();
+        let mut primitives = alloc::vec::Vec::with_capacity(self.values.len());
+        for (field, value) in self.values {
+            if field.callsite() != my_callsite {
+                continue;
+            }
+            let value = match value {
+                Some(value) => value,
+                None => return None,
+            };
+            primitives.push((field.name(), value.as_primitive()?));
+        }
+        Some(primitives)
+    }
+
     /// Returns the number of fields in this `ValueSet` that would be visited
     /// by a given [visitor] to the [`ValueSet::record()`] method.
     ///
@@ -1141,6 +1466,45 @@ mod test {
         valueset.record(&mut MyVisitor);
     }
 
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn as_primitives_returns_primitive_fields() {
+        let fields = TEST_META_1.fields();
+        let values = &[
+            (&fields.field("foo").unwrap(), Some(&1i64 as &dyn Value)),
+            (&fields.field("bar").unwrap(), Some(&true as &dyn Value)),
+            (&fields.field("baz").unwrap(), Some(&"hi" as &dyn Value)),
+        ];
+        let valueset = fields.value_set(values);
+        let primitives = valueset
+            .as_primitives()
+            .expect("all fields in this set are primitives");
+        assert_eq!(
+            primitives,
+            vec![
+                ("foo", PrimitiveValue::I64(1)),
+                ("bar", PrimitiveValue::Bool(true)),
+                ("baz", PrimitiveValue::Str("hi")),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn as_primitives_is_none_for_non_primitive_fields() {
+        let fields = TEST_META_1.fields();
+        let values = &[
+            (&fields.field("foo").unwrap(), Some(&1i64 as &dyn Value)),
+            (
+                &fields.field("bar").unwrap(),
+                Some(&core::time::Duration::from_secs(1) as &dyn Value),
+            ),
+            (&fields.field("baz").unwrap(), None),
+        ];
+        let valueset = fields.value_set(values);
+        assert!(valueset.as_primitives().is_none());
+    }
+
     #[test]
     #[cfg(feature = "std")]
     fn record_debug_fn() {
@@ -1197,4 +1561,55 @@ mod test {
         });
         assert_eq!(result, format!("{}", r#"[61 62 63]" "[c0 ff ee]"#));
     }
+
+    #[test]
+    fn record_duration() {
+        let fields = TEST_META_1.fields();
+        let duration = core::time::Duration::from_millis(1500);
+        let values = &[
+            (&fields.field("foo").unwrap(), Some(&duration as &dyn Value)),
+            (&fields.field("bar").unwrap(), Some(&Empty as &dyn Value)),
+            (&fields.field("baz").unwrap(), Some(&Empty as &dyn Value)),
+        ];
+
+        struct MyVisitor;
+        impl Visit for MyVisitor {
+            fn record_duration(&mut self, field: &Field, value: core::time::Duration) {
+                assert_eq!(field.name(), "foo");
+                assert_eq!(value, core::time::Duration::from_millis(1500));
+            }
+
+            fn record_debug(&mut self, _field: &Field, _value: &dyn fmt::Debug) {
+                panic!("a Duration should be recorded via record_duration, not record_debug");
+            }
+        }
+        let valueset = fields.value_set(values);
+        valueset.record(&mut MyVisitor);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn record_system_time() {
+        let fields = TEST_META_1.fields();
+        let time = std::time::UNIX_EPOCH + core::time::Duration::from_secs(1);
+        let values = &[
+            (&fields.field("foo").unwrap(), Some(&time as &dyn Value)),
+            (&fields.field("bar").unwrap(), Some(&Empty as &dyn Value)),
+            (&fields.field("baz").unwrap(), Some(&Empty as &dyn Value)),
+        ];
+
+        struct MyVisitor;
+        impl Visit for MyVisitor {
+            fn record_system_time(&mut self, field: &Field, value: std::time::SystemTime) {
+                assert_eq!(field.name(), "foo");
+                assert_eq!(value, std::time::UNIX_EPOCH + core::time::Duration::from_secs(1));
+            }
+
+            fn record_debug(&mut self, _field: &Field, _value: &dyn fmt::Debug) {
+                panic!("a SystemTime should be recorded via record_system_time, not record_debug");
+            }
+        }
+        let valueset = fields.value_set(values);
+        valueset.record(&mut MyVisitor);
+    }
 }
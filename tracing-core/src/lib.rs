@@ -298,6 +298,9 @@ pub(crate) mod spin;
 pub mod callsite;
 pub mod collect;
 pub mod dispatch;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod error_hook;
 pub mod event;
 pub mod field;
 pub mod metadata;
@@ -81,12 +81,22 @@ pub struct Metadata<'a> {
     /// event.
     fields: field::FieldSet,
 
+    /// The name of the crate that instrumented this span or event, if the
+    /// `crate-origin` feature is enabled.
+    #[cfg(feature = "crate-origin")]
+    crate_name: Option<&'static str>,
+
+    /// The version of the crate that instrumented this span or event, if the
+    /// `crate-origin` feature is enabled.
+    #[cfg(feature = "crate-origin")]
+    crate_version: Option<&'static str>,
+
     /// The kind of the callsite.
     kind: Kind,
 }
 
 /// Indicates whether the callsite is a span or event.
-#[derive(Clone, Eq, PartialEq)]
+#[derive(Clone, Eq, PartialEq, Hash)]
 pub struct Kind(u8);
 
 /// Describes the level of verbosity of a span or event.
@@ -268,10 +278,34 @@ impl<'a> Metadata<'a> {
             file,
             line,
             fields,
+            #[cfg(feature = "crate-origin")]
+            crate_name: None,
+            #[cfg(feature = "crate-origin")]
+            crate_version: None,
             kind,
         }
     }
 
+    /// Returns a copy of this `Metadata` with the name and version of the
+    /// crate that instrumented the described span or event attached.
+    ///
+    /// This is used by the `tracing` macros to stamp callsite metadata with
+    /// the `CARGO_PKG_NAME`/`CARGO_PKG_VERSION` of the crate in which the
+    /// `span!`/`event!` invocation is expanded, so that collectors in
+    /// multi-crate workspaces can attribute telemetry to the exact
+    /// dependency version that emitted it.
+    #[cfg(feature = "crate-origin")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "crate-origin")))]
+    pub const fn with_crate_origin(
+        mut self,
+        crate_name: &'static str,
+        crate_version: &'static str,
+    ) -> Self {
+        self.crate_name = Some(crate_name);
+        self.crate_version = Some(crate_version);
+        self
+    }
+
     /// Returns the names of the fields on the described span or event.
     #[inline]
     pub fn fields(&self) -> &field::FieldSet {
@@ -331,6 +365,30 @@ impl<'a> Metadata<'a> {
     pub fn is_span(&self) -> bool {
         self.kind.is_span()
     }
+
+    /// Returns the name of the crate that instrumented this span or event,
+    /// or `None` if it was not stamped with a crate origin.
+    ///
+    /// This is only populated when the emitting crate's `span!`/`event!`
+    /// invocations were compiled with `tracing`'s `crate-origin` feature
+    /// enabled.
+    #[cfg(feature = "crate-origin")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "crate-origin")))]
+    pub fn crate_name(&self) -> Option<&'static str> {
+        self.crate_name
+    }
+
+    /// Returns the version of the crate that instrumented this span or
+    /// event, or `None` if it was not stamped with a crate origin.
+    ///
+    /// This is only populated when the emitting crate's `span!`/`event!`
+    /// invocations were compiled with `tracing`'s `crate-origin` feature
+    /// enabled.
+    #[cfg(feature = "crate-origin")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "crate-origin")))]
+    pub fn crate_version(&self) -> Option<&'static str> {
+        self.crate_version
+    }
 }
 
 impl fmt::Debug for Metadata<'_> {
@@ -359,6 +417,16 @@ impl fmt::Debug for Metadata<'_> {
             (None, None) => {}
         };
 
+        #[cfg(feature = "crate-origin")]
+        {
+            if let Some(name) = self.crate_name() {
+                meta.field("crate_name", &name);
+            }
+            if let Some(version) = self.crate_version() {
+                meta.field("crate_version", &version);
+            }
+        }
+
         meta.field("fields", &format_args!("{}", self.fields))
             .field("callsite", &self.callsite())
             .field("kind", &self.kind)
@@ -467,6 +535,10 @@ impl PartialEq for Metadata<'_> {
                 file: lhs_file,
                 line: lhs_line,
                 fields: lhs_fields,
+                #[cfg(feature = "crate-origin")]
+                    crate_name: lhs_crate_name,
+                #[cfg(feature = "crate-origin")]
+                    crate_version: lhs_crate_version,
                 kind: lhs_kind,
             } = self;
 
@@ -478,6 +550,10 @@ impl PartialEq for Metadata<'_> {
                 file: rhs_file,
                 line: rhs_line,
                 fields: rhs_fields,
+                #[cfg(feature = "crate-origin")]
+                    crate_name: rhs_crate_name,
+                #[cfg(feature = "crate-origin")]
+                    crate_version: rhs_crate_version,
                 kind: rhs_kind,
             } = &other;
 
@@ -493,6 +569,16 @@ impl PartialEq for Metadata<'_> {
                 && lhs_line == rhs_line
                 && lhs_fields == rhs_fields
                 && lhs_kind == rhs_kind
+                && {
+                    #[cfg(feature = "crate-origin")]
+                    {
+                        lhs_crate_name == rhs_crate_name && lhs_crate_version == rhs_crate_version
+                    }
+                    #[cfg(not(feature = "crate-origin"))]
+                    {
+                        true
+                    }
+                }
         }
     }
 }
@@ -1113,4 +1199,36 @@ mod tests {
             assert_eq!(expected, repr, "repr changed for {:?}", filter)
         }
     }
+
+    #[cfg(feature = "crate-origin")]
+    #[test]
+    fn with_crate_origin_sets_name_and_version() {
+        let fields = field::FieldSet::new(&[], callsite::Identifier(&TEST_CALLSITE));
+        let meta = Metadata::new(
+            "test",
+            "test",
+            Level::TRACE,
+            None,
+            None,
+            None,
+            fields,
+            Kind::EVENT,
+        )
+        .with_crate_origin("my_crate", "1.2.3");
+
+        assert_eq!(meta.crate_name(), Some("my_crate"));
+        assert_eq!(meta.crate_version(), Some("1.2.3"));
+    }
+
+    #[cfg(feature = "crate-origin")]
+    struct TestCallsite;
+    #[cfg(feature = "crate-origin")]
+    impl callsite::Callsite for TestCallsite {
+        fn set_interest(&self, _: crate::collect::Interest) {}
+        fn metadata(&self) -> &Metadata<'_> {
+            unimplemented!()
+        }
+    }
+    #[cfg(feature = "crate-origin")]
+    static TEST_CALLSITE: TestCallsite = TestCallsite;
 }
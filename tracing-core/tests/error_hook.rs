@@ -0,0 +1,24 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+use tracing_core::error_hook::{set_error_hook, InternalError};
+
+#[test]
+fn error_hook() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let calls2 = calls.clone();
+    set_error_hook(move |error| {
+        calls2.fetch_add(1, Ordering::SeqCst);
+        match error {
+            InternalError::SpanNotFound { id } => assert_eq!(id, 42),
+            other => panic!("unexpected error: {}", other),
+        }
+    })
+    .expect("error hook set failed");
+
+    tracing_core::error_hook::report_error(InternalError::SpanNotFound { id: 42 });
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    set_error_hook(|_| {}).expect_err("double error hook set succeeded");
+}
@@ -0,0 +1,278 @@
+//! A `no_std`, allocation-free [`tracing`] collector that formats events as
+//! text into a caller-supplied [`core::fmt::Write`] sink.
+//!
+//! [`TextCollector`] is meant for bare-metal and other `no_std` targets that
+//! have no allocator and no `std::sync` primitives, but do have some kind of
+//! byte or character sink reachable through [`core::fmt::Write`] — an RTT
+//! channel, a semihosting console, a UART. It fills the same niche that a
+//! `tracing-subscriber` formatting [`Subscribe`] fills on hosted targets,
+//! without depending on `alloc` or `std`.
+//!
+//! Because it has nowhere to store per-span state without an allocator,
+//! `TextCollector` does not track span context: entering and exiting a span
+//! is a no-op, and span field values are not recorded. Only events are
+//! formatted and written out. Each event is filtered against a [`LevelFilter`]
+//! fixed at construction time, via [`Collect::max_level_hint`], so that
+//! spans and events more verbose than the configured level are skipped by
+//! the callsite [`Interest`] cache rather than re-checked on every call.
+//!
+//! [`tracing`]: https://crates.io/crates/tracing
+//! [`Subscribe`]: https://docs.rs/tracing-subscriber/latest/tracing_subscriber/subscribe/trait.Subscribe.html
+//! [`Interest`]: tracing_core::Interest
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use core::fmt;
+//! use tracing_core::LevelFilter;
+//! use tracing_embedded::TextCollector;
+//!
+//! struct Uart;
+//!
+//! impl fmt::Write for Uart {
+//!     fn write_str(&mut self, s: &str) -> fmt::Result {
+//!         // ...write `s` out over the wire...
+//!         Ok(())
+//!     }
+//! }
+//!
+//! static COLLECTOR: TextCollector<Uart> = TextCollector::new(Uart, LevelFilter::INFO);
+//!
+//! tracing_core::dispatch::set_global_default(tracing_core::Dispatch::from_static(&COLLECTOR))
+//!     .expect("failed to set the global default collector");
+//! ```
+#![no_std]
+#![warn(missing_docs)]
+
+#[cfg(test)]
+extern crate std;
+
+use core::fmt::{self, Write};
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use tracing_core::field::{Field, Visit};
+use tracing_core::span;
+use tracing_core::{Collect, Event, Interest, LevelFilter, Metadata};
+
+/// A [`Collect`] that formats events as text into a `W: fmt::Write` sink.
+///
+/// See the [crate-level documentation](crate) for details.
+pub struct TextCollector<W> {
+    writer: Spinlock<W>,
+    max_level: LevelFilter,
+    next_id: AtomicU64,
+}
+
+impl<W> TextCollector<W> {
+    /// Returns a new `TextCollector` that writes formatted events into
+    /// `writer`, filtering out any span or event more verbose than
+    /// `max_level`.
+    pub const fn new(writer: W, max_level: LevelFilter) -> Self {
+        Self {
+            writer: Spinlock::new(writer),
+            max_level,
+            next_id: AtomicU64::new(1),
+        }
+    }
+}
+
+impl<W: Write + Send + 'static> Collect for TextCollector<W> {
+    fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+        if self.enabled(metadata) {
+            Interest::always()
+        } else {
+            Interest::never()
+        }
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        *metadata.level() <= self.max_level
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        Some(self.max_level)
+    }
+
+    fn new_span(&self, _span: &span::Attributes<'_>) -> span::Id {
+        // We don't track span state, so every span just gets a fresh,
+        // unique ID; we never need to look it up again.
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        span::Id::from_u64(id)
+    }
+
+    fn record(&self, _span: &span::Id, _values: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut writer = self.writer.lock();
+        let metadata = event.metadata();
+        let _ = write!(writer, "{} {}: ", metadata.level(), metadata.target());
+        let mut visitor = TextVisitor {
+            writer: &mut *writer,
+            seen_field: false,
+        };
+        event.record(&mut visitor);
+        let _ = writer.write_char('\n');
+    }
+
+    fn enter(&self, _span: &span::Id) {}
+
+    fn exit(&self, _span: &span::Id) {}
+
+    fn current_span(&self) -> span::Current {
+        span::Current::unknown()
+    }
+}
+
+struct TextVisitor<'writer, W> {
+    writer: &'writer mut W,
+    seen_field: bool,
+}
+
+impl<W: Write> TextVisitor<'_, W> {
+    fn write_padding(&mut self) {
+        let _ = self.writer.write_str(if self.seen_field { " " } else { "" });
+        self.seen_field = true;
+    }
+}
+
+impl<W: Write> Visit for TextVisitor<'_, W> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.write_padding();
+        let _ = write!(self.writer, "{}={:?}", field.name(), value);
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.write_padding();
+        let _ = write!(self.writer, "{}={:?}", field.name(), value);
+    }
+}
+
+/// A minimal, dependency-free busy-wait mutex.
+///
+/// `no_std` targets without `alloc` generally can't use `std::sync::Mutex`,
+/// and pulling in an external spinlock or critical-section crate would
+/// contradict the point of a "tiny" collector. A spinlock is good enough
+/// here: events are written one at a time and held only for the duration of
+/// formatting a single record.
+struct Spinlock<T> {
+    locked: AtomicBool,
+    value: core::cell::UnsafeCell<T>,
+}
+
+// SAFETY: access to `value` is only ever granted while `locked` is held
+// exclusively, by `lock`, which hands out the one `SpinlockGuard` for this
+// spinlock at a time.
+unsafe impl<T: Send> Sync for Spinlock<T> {}
+
+impl<T> Spinlock<T> {
+    const fn new(value: T) -> Self {
+        Self {
+            locked: AtomicBool::new(false),
+            value: core::cell::UnsafeCell::new(value),
+        }
+    }
+
+    fn lock(&self) -> SpinlockGuard<'_, T> {
+        while self
+            .locked
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            core::hint::spin_loop();
+        }
+        SpinlockGuard { lock: self }
+    }
+}
+
+struct SpinlockGuard<'a, T> {
+    lock: &'a Spinlock<T>,
+}
+
+impl<T> core::ops::Deref for SpinlockGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: holding a `SpinlockGuard` means we hold the lock.
+        unsafe { &*self.lock.value.get() }
+    }
+}
+
+impl<T> core::ops::DerefMut for SpinlockGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: holding a `SpinlockGuard` means we hold the lock.
+        unsafe { &mut *self.lock.value.get() }
+    }
+}
+
+impl<T> Drop for SpinlockGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.locked.store(false, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_core::{metadata, Kind, Level};
+
+    struct TestCallsite;
+    impl tracing_core::Callsite for TestCallsite {
+        fn set_interest(&self, _interest: Interest) {}
+        fn metadata(&self) -> &Metadata<'_> {
+            &WARN_META
+        }
+    }
+    static TEST_CALLSITE: TestCallsite = TestCallsite;
+    static WARN_META: Metadata<'static> = metadata! {
+        name: "test",
+        target: "test",
+        level: Level::WARN,
+        fields: &[],
+        callsite: &TEST_CALLSITE,
+        kind: Kind::EVENT
+    };
+    static DEBUG_META: Metadata<'static> = metadata! {
+        name: "test",
+        target: "test",
+        level: Level::DEBUG,
+        fields: &[],
+        callsite: &TEST_CALLSITE,
+        kind: Kind::EVENT
+    };
+
+    #[test]
+    fn filters_by_max_level() {
+        struct DiscardWriter;
+        impl Write for DiscardWriter {
+            fn write_str(&mut self, _s: &str) -> fmt::Result {
+                Ok(())
+            }
+        }
+
+        let collector = TextCollector::new(DiscardWriter, LevelFilter::WARN);
+        assert_eq!(collector.max_level_hint(), Some(LevelFilter::WARN));
+        assert!(collector.enabled(&WARN_META));
+        assert!(!collector.enabled(&DEBUG_META));
+    }
+
+    #[test]
+    fn formats_event_into_writer() {
+        use std::string::String;
+
+        struct BufWriter(String);
+        impl Write for BufWriter {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                self.0.push_str(s);
+                Ok(())
+            }
+        }
+
+        let collector = TextCollector::new(BufWriter(String::new()), LevelFilter::WARN);
+        let values = WARN_META.fields().value_set(&[]);
+        let event = Event::new(&WARN_META, &values);
+        collector.event(&event);
+        assert_eq!(collector.writer.lock().0, "WARN test: \n");
+    }
+}
@@ -264,6 +264,25 @@ impl fmt::Debug for SpanTrace {
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for SpanTrace {
+    /// Serializes this `SpanTrace` as a sequence of [`Frame`](crate::Frame)s,
+    /// innermost first, so it can be attached to an error reported over a
+    /// JSON API rather than only rendered via `Display`.
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeSeq;
+        let frames = self.frames();
+        let mut seq = serializer.serialize_seq(Some(frames.len()))?;
+        for frame in &frames {
+            seq.serialize_element(frame)?;
+        }
+        seq.end()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -301,6 +320,49 @@ mod tests {
         });
     }
 
+    #[test]
+    fn capture_includes_fields_recorded_after_span_creation() {
+        let collector = Registry::default().with(ErrorSubscriber::default());
+
+        with_default(collector, || {
+            let span = span!(Level::ERROR, "test span", request_id = tracing::field::Empty);
+            let _guard = span.enter();
+            span.record("request_id", 42);
+
+            let span_trace = SpanTrace::capture();
+
+            let mut visited = String::new();
+            span_trace.with_spans(|_metadata, fields| {
+                visited.push_str(fields);
+                true
+            });
+
+            assert!(
+                visited.contains("request_id=42"),
+                "expected captured fields {:?} to contain the value recorded after the span was created",
+                visited
+            );
+        });
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serializes_as_a_sequence_of_frames() {
+        let collector = Registry::default().with(ErrorSubscriber::default());
+
+        with_default(collector, || {
+            let span = span!(Level::ERROR, "test span", answer = 42);
+            let _guard = span.enter();
+
+            let json = serde_json::to_value(SpanTrace::capture()).unwrap();
+            let frames = json.as_array().unwrap();
+
+            assert_eq!(1, frames.len());
+            assert_eq!(frames[0]["function"], "test span");
+            assert_eq!(frames[0]["vars"][0][0], "answer");
+        });
+    }
+
     #[test]
     fn capture_unsupported() {
         let collector = Registry::default();
@@ -270,6 +270,26 @@ where
     }
 }
 
+#[cfg(feature = "serde")]
+impl<E> serde::Serialize for TracedError<E>
+where
+    E: std::error::Error,
+{
+    /// Serializes this error as its [`Display`] message alongside its
+    /// [`SpanTrace`], rather than requiring the wrapped error type itself to
+    /// implement `Serialize`, since most `E: Error` types don't.
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("TracedError", 2)?;
+        state.serialize_field("error", &self.inner.error.to_string())?;
+        state.serialize_field("span_trace", &self.inner.span_trace)?;
+        state.end()
+    }
+}
+
 impl Error for ErrorImpl<Erased> {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         self.error().source()
@@ -387,3 +407,182 @@ impl ExtractSpanTrace for dyn Error + 'static {
             .map(|inner| &inner.span_trace)
     }
 }
+
+/// Returns an iterator over the [`SpanTrace`]s attached anywhere in `error`'s
+/// [`source`] chain, in the order the errors were wrapped (outermost first).
+///
+/// Only the links in the chain with an attached `SpanTrace` (i.e. those
+/// wrapped in a [`TracedError`], directly or via a library like `anyhow` or
+/// `eyre` whose error type ultimately boxes one) contribute an item; other
+/// links are skipped. This makes it possible to recover every trace captured
+/// along a chain that was wrapped several times over, not just the
+/// outermost one.
+///
+/// [`source`]: Error::source
+///
+/// # Examples
+///
+/// ```rust
+/// use std::fmt;
+/// use tracing_error::{ErrorSubscriber, InstrumentError};
+/// use tracing_subscriber::prelude::*;
+///
+/// #[derive(Debug)]
+/// struct Inner;
+/// impl fmt::Display for Inner {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         write!(f, "inner error")
+///     }
+/// }
+/// impl std::error::Error for Inner {}
+///
+/// #[derive(Debug)]
+/// struct Outer(Box<dyn std::error::Error + Send + Sync + 'static>);
+/// impl fmt::Display for Outer {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         write!(f, "outer error")
+///     }
+/// }
+/// impl std::error::Error for Outer {
+///     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+///         Some(&*self.0)
+///     }
+/// }
+///
+/// let collector = tracing_subscriber::registry().with(ErrorSubscriber::default());
+/// tracing::collect::with_default(collector, || {
+///     let inner = tracing::info_span!("inner_span").in_scope(|| Inner.in_current_span());
+///     let outer = tracing::info_span!("outer_span")
+///         .in_scope(|| Outer(Box::new(inner)).in_current_span());
+///
+///     let traces: Vec<_> = tracing_error::span_traces(&outer).collect();
+///     assert_eq!(traces.len(), 2);
+/// });
+/// ```
+pub fn span_traces<'a>(error: &'a (dyn Error + 'static)) -> SpanTraceChain<'a> {
+    SpanTraceChain { next: Some(error) }
+}
+
+/// An iterator over the [`SpanTrace`]s attached along an error's `source`
+/// chain. See [`span_traces`].
+///
+/// This type also implements [`Display`], formatting each captured trace in
+/// order, separated by blank lines.
+#[derive(Debug, Clone)]
+pub struct SpanTraceChain<'a> {
+    next: Option<&'a (dyn Error + 'static)>,
+}
+
+impl<'a> Iterator for SpanTraceChain<'a> {
+    type Item = &'a SpanTrace;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(error) = self.next.take() {
+            self.next = error.source();
+            if let Some(span_trace) = error.span_trace() {
+                return Some(span_trace);
+            }
+        }
+        None
+    }
+}
+
+impl Display for SpanTraceChain<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, span_trace) in self.clone().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+                writeln!(f)?;
+            }
+            write!(f, "{span_trace}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorSubscriber;
+    use tracing::collect::with_default;
+    use tracing_subscriber::{prelude::*, registry::Registry};
+
+    #[derive(Debug)]
+    struct MyError;
+
+    impl Display for MyError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "something went wrong")
+        }
+    }
+
+    impl Error for MyError {}
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serializes_display_message_and_span_trace() {
+        let collector = Registry::default().with(ErrorSubscriber::default());
+
+        with_default(collector, || {
+            let span = tracing::info_span!("my_span", answer = 42);
+            let _guard = span.enter();
+
+            let err = TracedError::from(MyError);
+            let json = serde_json::to_value(&err).unwrap();
+
+            assert_eq!(json["error"], "something went wrong");
+            assert_eq!(json["span_trace"][0]["function"], "my_span");
+        });
+    }
+
+    #[derive(Debug)]
+    struct Wrapper(Box<dyn Error + Send + Sync + 'static>);
+
+    impl Display for Wrapper {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "wrapped error")
+        }
+    }
+
+    impl Error for Wrapper {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&*self.0)
+        }
+    }
+
+    #[test]
+    fn span_traces_collects_every_trace_in_the_source_chain() {
+        let collector = Registry::default().with(ErrorSubscriber::default());
+
+        with_default(collector, || {
+            let inner = tracing::info_span!("inner_span").in_scope(|| MyError.in_current_span());
+            let outer =
+                tracing::info_span!("outer_span").in_scope(|| Wrapper(Box::new(inner)).in_current_span());
+
+            let traces: Vec<_> = span_traces(&outer).collect();
+            assert_eq!(traces.len(), 2);
+
+            let mut inner_spans = String::new();
+            traces[1].with_spans(|metadata, _| {
+                inner_spans.push_str(metadata.name());
+                true
+            });
+            assert_eq!(inner_spans, "inner_span");
+        });
+    }
+
+    #[test]
+    fn span_traces_display_joins_traces_with_blank_lines() {
+        let collector = Registry::default().with(ErrorSubscriber::default());
+
+        with_default(collector, || {
+            let inner = tracing::info_span!("inner_span").in_scope(|| MyError.in_current_span());
+            let outer =
+                tracing::info_span!("outer_span").in_scope(|| Wrapper(Box::new(inner)).in_current_span());
+
+            let rendered = span_traces(&outer).to_string();
+            assert_eq!(rendered.matches("outer_span").count(), 1);
+            assert!(rendered.contains("\n\n"));
+        });
+    }
+}
@@ -0,0 +1,196 @@
+use crate::SpanTrace;
+
+/// A single frame of a [`SpanTrace`], shaped to match the stack-frame schema
+/// used by error-reporting services such as Sentry (see Sentry's
+/// [stack trace interface]).
+///
+/// Use [`SpanTrace::frames`] to convert a captured trace into a list of
+/// `Frame`s suitable for attaching to an error report.
+///
+/// [stack trace interface]: https://develop.sentry.dev/sdk/event-payloads/stacktrace/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    /// The name of the span, analogous to a stack frame's function name.
+    pub function: Option<String>,
+
+    /// The path of the source file the span was entered in.
+    pub filename: Option<String>,
+
+    /// The line number the span was entered at.
+    pub lineno: Option<u32>,
+
+    /// Whether this frame belongs to "app" code, as opposed to a library or
+    /// runtime dependency.
+    ///
+    /// `SpanTrace`s only ever contain spans that the application explicitly
+    /// instrumented, so every frame is considered an app frame.
+    pub in_app: bool,
+
+    /// The fields recorded on the span, as `(name, value)` pairs, analogous
+    /// to a stack frame's local variables.
+    ///
+    /// Values are recovered on a best-effort basis from the span's formatted
+    /// fields, so values containing unescaped spaces or `=` characters may
+    /// not round-trip exactly.
+    pub vars: Vec<(String, String)>,
+}
+
+impl SpanTrace {
+    /// Converts this `SpanTrace` into a list of [`Frame`]s, formatted to
+    /// match the stack-frame schema used by error-reporting services such as
+    /// Sentry.
+    ///
+    /// Frames are returned innermost-first, matching the order spans are
+    /// visited in by [`SpanTrace::with_spans`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tracing_error::{ErrorSubscriber, SpanTrace};
+    /// use tracing_subscriber::prelude::*;
+    ///
+    /// tracing::collect::with_default(
+    ///     tracing_subscriber::registry().with(ErrorSubscriber::default()),
+    ///     || {
+    ///         let _span = tracing::info_span!("my_span", answer = 42).entered();
+    ///         let trace = SpanTrace::capture();
+    ///         for frame in trace.frames() {
+    ///             println!("{:?}", frame);
+    ///         }
+    ///     },
+    /// );
+    /// ```
+    pub fn frames(&self) -> Vec<Frame> {
+        let mut frames = Vec::new();
+        self.with_spans(|metadata, fields| {
+            frames.push(Frame {
+                function: Some(metadata.name().to_string()),
+                filename: metadata.file().map(String::from),
+                lineno: metadata.line(),
+                in_app: true,
+                vars: parse_fields(fields),
+            });
+            true
+        });
+        frames
+    }
+}
+
+/// Best-effort parser for the `key=value key2=value2 ...` strings produced by
+/// [`tracing_subscriber::fmt::format::DefaultFields`], respecting
+/// double-quoted values so that `=` and spaces inside a quoted value aren't
+/// mistaken for field separators.
+fn parse_fields(fields: &str) -> Vec<(String, String)> {
+    let mut vars = Vec::new();
+
+    for token in split_unquoted_whitespace(fields) {
+        if let Some((name, value)) = token.split_once('=') {
+            let value = value.strip_prefix('"').unwrap_or(value);
+            let value = value.strip_suffix('"').unwrap_or(value);
+            vars.push((name.to_string(), value.to_string()));
+        }
+    }
+
+    vars
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Frame {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("Frame", 5)?;
+        state.serialize_field("function", &self.function)?;
+        state.serialize_field("filename", &self.filename)?;
+        state.serialize_field("lineno", &self.lineno)?;
+        state.serialize_field("in_app", &self.in_app)?;
+        state.serialize_field("vars", &self.vars)?;
+        state.end()
+    }
+}
+
+fn split_unquoted_whitespace(s: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut in_quotes = false;
+    let mut start = 0;
+
+    for (i, c) in s.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            c if c.is_whitespace() && !in_quotes => {
+                if start < i {
+                    tokens.push(&s[start..i]);
+                }
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+
+    if start < s.len() {
+        tokens.push(&s[start..]);
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorSubscriber;
+    use tracing::collect::with_default;
+    use tracing_subscriber::{prelude::*, registry::Registry};
+
+    #[test]
+    fn frames_captures_span_fields_as_vars() {
+        let collector = Registry::default().with(ErrorSubscriber::default());
+
+        with_default(collector, || {
+            let span = tracing::info_span!("my_span", answer = 42, greeting = "hello world");
+            let _guard = span.enter();
+
+            let trace = SpanTrace::capture();
+            let frames = trace.frames();
+
+            assert_eq!(1, frames.len());
+            let frame = &frames[0];
+            assert_eq!(Some("my_span".to_string()), frame.function);
+            assert!(frame.in_app);
+            assert!(frame.vars.contains(&("answer".to_string(), "42".to_string())));
+            assert!(frame
+                .vars
+                .contains(&("greeting".to_string(), "hello world".to_string())));
+        });
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn frame_serializes_to_the_expected_shape() {
+        let collector = Registry::default().with(ErrorSubscriber::default());
+
+        with_default(collector, || {
+            let span = tracing::info_span!("my_span", answer = 42);
+            let _guard = span.enter();
+
+            let frame = &SpanTrace::capture().frames()[0];
+            let json = serde_json::to_value(frame).unwrap();
+
+            assert_eq!(json["function"], "my_span");
+            assert_eq!(json["in_app"], true);
+            assert_eq!(json["vars"][0][0], "answer");
+            assert_eq!(json["vars"][0][1], "42");
+        });
+    }
+
+    #[test]
+    fn frames_empty_when_no_current_span() {
+        let collector = Registry::default().with(ErrorSubscriber::default());
+
+        with_default(collector, || {
+            let trace = SpanTrace::capture();
+            assert!(trace.frames().is_empty());
+        });
+    }
+}
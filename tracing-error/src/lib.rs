@@ -16,6 +16,9 @@
 //!
 //! * [`ErrorSubscriber`], a [subscriber] which enables capturing `SpanTrace`s
 //!
+//! * [`Frame`], a representation of a single `SpanTrace` frame shaped to
+//!   match the stack-frame schema used by error-reporting services
+//!
 //! **Note**: This crate is currently experimental.
 //!
 //! *Compiler support: [requires `rustc` 1.63+][msrv]*
@@ -30,6 +33,12 @@
 //!       [`SpanTrace`].
 //!     - [`ExtractSpanTrace`] extension trait, for extracting `SpanTrace`s from
 //!       behind `dyn Error` trait objects.
+//!     - [`span_traces()`], for walking an error's `source` chain (such as
+//!       one built with `anyhow` or `eyre`) and collecting every `SpanTrace`
+//!       attached anywhere along it, not just the outermost one.
+//! - `serde` - Implements `serde::Serialize` for [`SpanTrace`] and
+//!   [`TracedError`], so they can be reported over JSON APIs and other
+//!   structured error reporters, not just formatted with `Display`.
 //!
 //! ## Usage
 //!
@@ -209,11 +218,15 @@
 mod backtrace;
 #[cfg(feature = "traced-error")]
 mod error;
+mod frame;
 mod subscriber;
 
 pub use self::backtrace::{SpanTrace, SpanTraceStatus};
 #[cfg(feature = "traced-error")]
-pub use self::error::{ExtractSpanTrace, InstrumentError, InstrumentResult, TracedError};
+pub use self::error::{
+    span_traces, ExtractSpanTrace, InstrumentError, InstrumentResult, SpanTraceChain, TracedError,
+};
+pub use self::frame::Frame;
 pub use self::subscriber::ErrorSubscriber;
 
 #[cfg(feature = "traced-error")]
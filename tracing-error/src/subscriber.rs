@@ -59,6 +59,24 @@ where
         }
     }
 
+    /// Notifies this subscriber that a span with the given `Id` recorded the
+    /// given values, so that fields set after a span was created (such as a
+    /// request ID recorded partway through handling a request) are reflected
+    /// in any `SpanTrace` captured afterwards.
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, ctx: subscribe::Context<'_, C>) {
+        let span = ctx.span(id).expect("span must already exist!");
+        let mut extensions = span.extensions_mut();
+        if let Some(fields) = extensions.get_mut::<FormattedFields<F>>() {
+            let _ = self.format.add_fields(fields, values);
+            return;
+        }
+
+        let mut fields = FormattedFields::<F>::new(String::new());
+        if self.format.format_fields(fields.as_writer(), values).is_ok() {
+            extensions.insert(fields);
+        }
+    }
+
     unsafe fn downcast_raw(&self, id: TypeId) -> Option<NonNull<()>> {
         match id {
             id if id == TypeId::of::<Self>() => Some(NonNull::from(self).cast()),
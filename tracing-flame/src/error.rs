@@ -32,6 +32,12 @@ impl std::error::Error for Error {
         match &self.0 {
             Kind::CreateFile { ref source, .. } => Some(source),
             Kind::FlushFile(ref source) => Some(source),
+            #[cfg(feature = "inferno")]
+            Kind::OpenFoldedFile { ref source, .. } => Some(source),
+            #[cfg(feature = "inferno")]
+            Kind::CreateSvgFile { ref source, .. } => Some(source),
+            #[cfg(feature = "inferno")]
+            Kind::RenderSvg(ref source) => Some(source.as_ref()),
         }
     }
 }
@@ -43,6 +49,18 @@ pub(crate) enum Kind {
         path: PathBuf,
     },
     FlushFile(std::io::Error),
+    #[cfg(feature = "inferno")]
+    OpenFoldedFile {
+        source: std::io::Error,
+        path: PathBuf,
+    },
+    #[cfg(feature = "inferno")]
+    CreateSvgFile {
+        source: std::io::Error,
+        path: PathBuf,
+    },
+    #[cfg(feature = "inferno")]
+    RenderSvg(Box<dyn std::error::Error + Send + Sync>),
 }
 
 impl fmt::Display for Kind {
@@ -52,6 +70,16 @@ impl fmt::Display for Kind {
                 write!(f, "cannot create output file. path={}", path.display())
             }
             Self::FlushFile { .. } => write!(f, "cannot flush output buffer"),
+            #[cfg(feature = "inferno")]
+            Self::OpenFoldedFile { path, .. } => {
+                write!(f, "cannot open folded stack file. path={}", path.display())
+            }
+            #[cfg(feature = "inferno")]
+            Self::CreateSvgFile { path, .. } => {
+                write!(f, "cannot create svg output file. path={}", path.display())
+            }
+            #[cfg(feature = "inferno")]
+            Self::RenderSvg { .. } => write!(f, "cannot render svg flamegraph"),
         }
     }
 }
@@ -73,6 +73,9 @@
 //! cat tracing.folded | inferno-flamegraph --flamechart > tracing-flamechart.svg
 //! ```
 //!
+//! Alternatively, with the `inferno` feature enabled, [`FlushGuard::with_svg`] renders the SVG
+//! directly whenever the guard flushes, without a separate `inferno-flamegraph` invocation.
+//!
 //! ## Differences between `flamegraph`s and `flamechart`s
 //!
 //! By default, `inferno-flamegraph` creates flamegraphs. Flamegraphs operate by
@@ -148,14 +151,18 @@ use std::path::Path;
 use std::sync::Arc;
 use std::sync::Mutex;
 use std::time::{Duration, Instant};
+use tracing::field::{Field, Visit};
 use tracing::span;
 use tracing::Collect;
+use tracing::Event;
 use tracing_subscriber::registry::LookupSpan;
 use tracing_subscriber::registry::SpanRef;
 use tracing_subscriber::subscribe::Context;
 use tracing_subscriber::Subscribe;
 
 mod error;
+#[cfg(feature = "inferno")]
+mod svg;
 
 static START: Lazy<Instant> = Lazy::new(Instant::now);
 
@@ -192,6 +199,14 @@ thread_local! {
 /// Instead, the numbers on each line are the number of nanoseconds since the
 /// last event in the same thread.
 ///
+/// With [`with_events`] enabled, `tracing` events are also recorded, each as
+/// its own zero-width sample (a sample count of `0`) whose last frame is the
+/// event's message. These don't widen the rendered flamegraph, so they show
+/// up as thin markers annotating where they occurred relative to the spans
+/// around them.
+///
+/// [`with_events`]: FlameSubscriber::with_events
+///
 /// # Dropping and Flushing
 ///
 /// If you use a global collector the drop implementations on your various
@@ -226,6 +241,12 @@ struct Config {
 
     /// Don't display file and line
     file_and_line: bool,
+
+    /// What to key each folded stack's lane by.
+    task_key: TaskKey,
+
+    /// Record events as zero-width marker frames.
+    events: bool,
 }
 
 impl Default for Config {
@@ -235,6 +256,59 @@ impl Default for Config {
             threads_collapsed: false,
             module_path: true,
             file_and_line: false,
+            task_key: TaskKey::Thread,
+            events: false,
+        }
+    }
+}
+
+/// What identifies a "lane" --- the first segment of each folded stack line ---
+/// in the recorded output.
+#[derive(Debug, Clone)]
+enum TaskKey {
+    /// Key by OS thread, via [`THREAD_NAME`]. This is the default, and is
+    /// what `perf`-style folded stacks normally use, but it's a poor fit for
+    /// async workloads: a task's spans are split across whichever threads
+    /// happened to be polling it, rather than staying together in one lane.
+    Thread,
+
+    /// Key by the root span of each stack --- the outermost span entered ---
+    /// so every sample belonging to one logical task stays in the same lane
+    /// no matter which OS thread records it.
+    RootSpan,
+
+    /// Key by the value of a field recorded on some span in the current
+    /// scope, falling back to [`TaskKey::Thread`] for samples recorded
+    /// while no span in scope has recorded it.
+    Field(&'static str),
+}
+
+/// The value of a [`TaskKey::Field`] field, recorded in a span's extensions
+/// by [`FlameSubscriber::on_new_span`].
+struct TaskId(String);
+
+struct TaskIdVisitor<'a> {
+    field: &'a str,
+    value: Option<String>,
+}
+
+impl Visit for TaskIdVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == self.field {
+            self.value = Some(format!("{:?}", value));
+        }
+    }
+}
+
+#[derive(Default)]
+struct MessageVisitor {
+    message: Option<String>,
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = Some(format!("{:?}", value));
         }
     }
 }
@@ -245,6 +319,12 @@ impl Default for Config {
 /// This type is only needed when using
 /// `tracing::subscriber::set_global_default`, which prevents the drop
 /// implementation of layers from running when the program exits.
+///
+/// With the `inferno` feature enabled, a guard can additionally be configured with
+/// [`with_svg`] to render the folded stack data directly to an SVG flamegraph or flamechart
+/// every time it flushes.
+///
+/// [`with_svg`]: FlushGuard::with_svg
 #[must_use]
 #[derive(Debug)]
 pub struct FlushGuard<W>
@@ -252,6 +332,8 @@ where
     W: Write + 'static,
 {
     out: Arc<Mutex<W>>,
+    #[cfg(feature = "inferno")]
+    svg: Option<Mutex<svg::SvgOutput>>,
 }
 
 impl<C, W> FlameSubscriber<C, W>
@@ -277,6 +359,8 @@ where
     pub fn flush_on_drop(&self) -> FlushGuard<W> {
         FlushGuard {
             out: self.out.clone(),
+            #[cfg(feature = "inferno")]
+            svg: None,
         }
     }
 
@@ -324,6 +408,75 @@ where
         self.config.file_and_line = enabled;
         self
     }
+
+    /// Configures whether folded stack samples are keyed by each trace's root
+    /// span instead of by OS thread.
+    ///
+    /// Defaults to `false`, keying by OS thread (optionally collapsed with
+    /// [`with_threads_collapsed`]).
+    ///
+    /// An async task's spans are typically polled on whichever thread an
+    /// executor happens to schedule it onto next, so keying by OS thread
+    /// splits one logical task's samples across as many lanes as threads it
+    /// was polled on, producing a flamegraph that's hard to read. Keying by
+    /// root span instead keeps every sample belonging to one task in the
+    /// same lane, regardless of which thread recorded it.
+    ///
+    /// This takes precedence over [`with_threads_collapsed`] and
+    /// [`with_task_id_field`]; only the most recently called of the three
+    /// has an effect.
+    ///
+    /// [`with_threads_collapsed`]: FlameSubscriber::with_threads_collapsed
+    /// [`with_task_id_field`]: FlameSubscriber::with_task_id_field
+    pub fn with_root_span_as_task(mut self, enabled: bool) -> Self {
+        self.config.task_key = if enabled {
+            TaskKey::RootSpan
+        } else {
+            TaskKey::Thread
+        };
+        self
+    }
+
+    /// Configures folded stack samples to be keyed by the value of `field`
+    /// instead of by OS thread, for samples recorded while some span in
+    /// scope has recorded a field by that name.
+    ///
+    /// This is meant for applications that already tag their spans with a
+    /// task or request identifier, such as `task_id = %id`; passing
+    /// `"task_id"` here keys each sample by that value instead of by
+    /// whichever OS thread happened to record it, the same way
+    /// [`with_root_span_as_task`] does based on the root span. Samples
+    /// recorded while no span in scope has recorded `field` fall back to the
+    /// OS thread, as if this had not been called.
+    ///
+    /// This takes precedence over [`with_threads_collapsed`] and
+    /// [`with_root_span_as_task`]; only the most recently called of the
+    /// three has an effect.
+    ///
+    /// [`with_threads_collapsed`]: FlameSubscriber::with_threads_collapsed
+    /// [`with_root_span_as_task`]: FlameSubscriber::with_root_span_as_task
+    pub fn with_task_id_field(mut self, field: &'static str) -> Self {
+        self.config.task_key = TaskKey::Field(field);
+        self
+    }
+
+    /// Configures whether events (not just span enter/exit) are recorded as
+    /// zero-width marker frames in the folded stack output.
+    ///
+    /// Defaults to `false`.
+    ///
+    /// A flamegraph is normally built purely out of span enter/exit samples,
+    /// so there's no way to see where a one-off event --- such as the start
+    /// of a GC pause, or a cache eviction --- falls relative to the spans
+    /// around it. Enabling this records each event as an additional sample
+    /// whose last frame is the event's message (or, if it didn't record a
+    /// `message` field, its callsite name), with a sample count of `0`. This
+    /// gives the event zero width in the rendered flamegraph/flamechart, so
+    /// it shows up as a marker rather than skewing the timing of any span.
+    pub fn with_events(mut self, enabled: bool) -> Self {
+        self.config.events = enabled;
+        self
+    }
 }
 
 impl<W> FlushGuard<W>
@@ -332,6 +485,11 @@ where
 {
     /// Flush the internal writer of the `FlameSubscriber`, ensuring that all
     /// intermediately buffered contents reach their destination.
+    ///
+    /// If this guard has been configured with [`with_svg`], this also re-renders the SVG
+    /// flamegraph/flamechart from the folded stack data.
+    ///
+    /// [`with_svg`]: FlushGuard::with_svg
     pub fn flush(&self) -> Result<(), Error> {
         let mut guard = match self.out.lock() {
             Ok(guard) => guard,
@@ -344,7 +502,62 @@ where
             }
         };
 
-        guard.flush().map_err(Kind::FlushFile).map_err(Error)
+        guard.flush().map_err(Kind::FlushFile).map_err(Error)?;
+        drop(guard);
+
+        #[cfg(feature = "inferno")]
+        self.render_svg()?;
+
+        Ok(())
+    }
+
+    /// Configures this guard to additionally render an SVG flamegraph (or, with `flame_chart`
+    /// set on `options`, a flamechart) every time it flushes, including when it is dropped.
+    ///
+    /// `folded_path` must be the path the `FlameSubscriber` that returned this guard is writing
+    /// its folded stack samples to, such as the path passed to [`FlameSubscriber::with_file`];
+    /// rendering works by re-reading the folded data back from that file, so it reflects
+    /// whatever has been flushed to it so far rather than only samples recorded since this guard
+    /// was created. The rendered SVG is written to `svg_path`, which is created or truncated on
+    /// every render.
+    ///
+    /// `options` are passed through to [`inferno::flamegraph::from_reader`] unchanged, so any
+    /// flamegraph option `inferno` supports --- title, color scheme, `flame_chart`, and so on ---
+    /// can be set on it.
+    #[cfg(feature = "inferno")]
+    pub fn with_svg(
+        mut self,
+        folded_path: impl AsRef<Path>,
+        svg_path: impl AsRef<Path>,
+        options: inferno::flamegraph::Options<'static>,
+    ) -> Self {
+        self.svg = Some(Mutex::new(svg::SvgOutput {
+            folded_path: folded_path.as_ref().to_path_buf(),
+            svg_path: svg_path.as_ref().to_path_buf(),
+            options,
+        }));
+        self
+    }
+
+    #[cfg(feature = "inferno")]
+    fn render_svg(&self) -> Result<(), Error> {
+        let svg = match &self.svg {
+            Some(svg) => svg,
+            None => return Ok(()),
+        };
+
+        let mut svg = match svg.lock() {
+            Ok(svg) => svg,
+            Err(e) => {
+                if !std::thread::panicking() {
+                    panic!("{}", e);
+                } else {
+                    return Ok(());
+                }
+            }
+        };
+
+        svg.render()
     }
 }
 
@@ -386,6 +599,61 @@ where
     C: Collect + for<'span> LookupSpan<'span>,
     W: Write + 'static,
 {
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, C>) {
+        let field = match &self.config.task_key {
+            TaskKey::Field(field) => field,
+            _ => return,
+        };
+
+        let mut visitor = TaskIdVisitor {
+            field,
+            value: None,
+        };
+        attrs.record(&mut visitor);
+
+        if let (Some(value), Some(span)) = (visitor.value, ctx.span(id)) {
+            span.extensions_mut().insert(TaskId(value));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, C>) {
+        if !self.config.events {
+            return;
+        }
+
+        // Advance the clock so the gap between this event and whatever span
+        // is entered or exited next doesn't also get credited to this event's
+        // (always zero) sample count.
+        let _ = self.time_since_last_event();
+
+        let mut stack = String::new();
+        match ctx.event_scope(event).and_then(|mut scope| scope.next()) {
+            Some(first) => {
+                stack += &lane(&first, &self.config);
+                for parent in first.scope().from_root() {
+                    stack += ";";
+                    write(&mut stack, parent, &self.config)
+                        .expect("expected: write to String never fails");
+                }
+            }
+            None => stack += &thread_lane(&self.config),
+        }
+
+        stack += ";";
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        stack += &visitor
+            .message
+            .unwrap_or_else(|| event.metadata().name().to_string());
+
+        // Events are zero-width markers: they never advance the flamegraph's
+        // sense of elapsed time, they only annotate the point they occurred
+        // at, so they're always recorded with a sample count of `0`.
+        write!(&mut stack, " 0").expect("expected: write to String never fails");
+
+        let _ = writeln!(*self.out.lock().unwrap(), "{}", stack);
+    }
+
     fn on_enter(&self, id: &span::Id, ctx: Context<'_, C>) {
         let samples = self.time_since_last_event();
 
@@ -396,12 +664,7 @@ where
         }
 
         let mut stack = String::new();
-
-        if !self.config.threads_collapsed {
-            THREAD_NAME.with(|name| stack += name.as_str());
-        } else {
-            stack += "all-threads";
-        }
+        stack += &lane(&first, &self.config);
 
         if let Some(second) = first.parent() {
             for parent in second.scope().from_root() {
@@ -440,11 +703,7 @@ where
         let first = expect!(ctx.span(id), "expected: span id exists in registry");
 
         let mut stack = String::new();
-        if !self.config.threads_collapsed {
-            THREAD_NAME.with(|name| stack += name.as_str());
-        } else {
-            stack += "all-threads";
-        }
+        stack += &lane(&first, &self.config);
 
         for parent in first.scope().from_root() {
             stack += ";";
@@ -505,3 +764,35 @@ where
 
     Ok(())
 }
+
+/// Returns the lane --- the first segment of a folded stack line --- that a
+/// sample recorded while `first` is entered or exited should be attributed
+/// to, according to `config.task_key`.
+fn lane<C>(first: &SpanRef<'_, C>, config: &Config) -> String
+where
+    C: Collect + for<'span> LookupSpan<'span>,
+{
+    match &config.task_key {
+        TaskKey::Thread => thread_lane(config),
+        TaskKey::RootSpan => {
+            let root = first
+                .scope()
+                .from_root()
+                .next()
+                .expect("a span's own scope always contains at least itself");
+            format!("{:?}-{}", root.id(), root.name())
+        }
+        TaskKey::Field(_) => first
+            .scope()
+            .find_map(|span| span.extensions().get::<TaskId>().map(|task_id| task_id.0.clone()))
+            .unwrap_or_else(|| thread_lane(config)),
+    }
+}
+
+fn thread_lane(config: &Config) -> String {
+    if config.threads_collapsed {
+        "all-threads".to_string()
+    } else {
+        THREAD_NAME.with(|name| name.clone())
+    }
+}
@@ -0,0 +1,42 @@
+//! Rendering folded stack data directly to an SVG flamegraph/flamechart via [`inferno`].
+use crate::error::{Error, Kind};
+use inferno::flamegraph::{self, Options};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::PathBuf;
+
+/// Where, and with what [`inferno`] options, to render an SVG flamegraph/flamechart, used by
+/// [`FlushGuard::with_svg`].
+///
+/// [`FlushGuard::with_svg`]: crate::FlushGuard::with_svg
+#[derive(Debug)]
+pub(crate) struct SvgOutput {
+    pub(crate) folded_path: PathBuf,
+    pub(crate) svg_path: PathBuf,
+    pub(crate) options: Options<'static>,
+}
+
+impl SvgOutput {
+    /// Re-reads the folded stack data from `self.folded_path` and renders it to an SVG file at
+    /// `self.svg_path`, using `self.options`.
+    pub(crate) fn render(&mut self) -> Result<(), Error> {
+        let folded = File::open(&self.folded_path)
+            .map_err(|source| {
+                Kind::OpenFoldedFile {
+                    source,
+                    path: self.folded_path.clone(),
+                }
+            })
+            .map_err(Error)?;
+        let svg = File::create(&self.svg_path)
+            .map_err(|source| {
+                Kind::CreateSvgFile {
+                    source,
+                    path: self.svg_path.clone(),
+                }
+            })
+            .map_err(Error)?;
+        flamegraph::from_reader(&mut self.options, BufReader::new(folded), svg)
+            .map_err(|source| Error(Kind::RenderSvg(Box::new(source))))
+    }
+}
@@ -0,0 +1,62 @@
+use std::fs;
+use tracing::{event, span, Level};
+use tracing_flame::FlameSubscriber;
+use tracing_subscriber::{prelude::*, registry::Registry};
+
+#[test]
+fn events_are_recorded_as_zero_width_markers() {
+    let tmp_dir = tempfile::Builder::new()
+        .prefix("tracing-flame-events-test-")
+        .tempdir()
+        .expect("failed to create tempdir");
+    let folded_path = tmp_dir.path().join("tracing.folded");
+
+    let (flame_layer, guard) = FlameSubscriber::with_file(&folded_path).unwrap();
+    let flame_layer = flame_layer.with_events(true);
+
+    tracing::collect::with_default(Registry::default().with(flame_layer), || {
+        span!(Level::ERROR, "outer").in_scope(|| {
+            event!(Level::INFO, "GC start");
+        });
+    });
+    guard.flush().unwrap();
+
+    let folded = fs::read_to_string(&folded_path).expect("folded stack file should exist");
+    let lines: Vec<&str> = folded.lines().collect();
+
+    let marker = lines
+        .iter()
+        .find(|line| line.contains("GC start"))
+        .unwrap_or_else(|| panic!("no line recorded the event, got: {:?}", lines));
+
+    assert!(
+        marker.ends_with("outer;GC start 0"),
+        "event should be recorded under its enclosing span with a sample count of 0, got: {}",
+        marker
+    );
+
+    tmp_dir.close().expect("failed to delete tempdir");
+}
+
+#[test]
+fn events_are_not_recorded_by_default() {
+    let tmp_dir = tempfile::Builder::new()
+        .prefix("tracing-flame-events-test-")
+        .tempdir()
+        .expect("failed to create tempdir");
+    let folded_path = tmp_dir.path().join("tracing.folded");
+
+    let (flame_layer, guard) = FlameSubscriber::with_file(&folded_path).unwrap();
+
+    tracing::collect::with_default(Registry::default().with(flame_layer), || {
+        span!(Level::ERROR, "outer").in_scope(|| {
+            event!(Level::INFO, "GC start");
+        });
+    });
+    guard.flush().unwrap();
+
+    let folded = fs::read_to_string(&folded_path).expect("folded stack file should exist");
+    assert!(!folded.contains("GC start"));
+
+    tmp_dir.close().expect("failed to delete tempdir");
+}
@@ -0,0 +1,40 @@
+#![cfg(feature = "inferno")]
+
+use std::fs;
+use std::thread::sleep;
+use std::time::Duration;
+use tracing::{span, Level};
+use tracing_flame::FlameSubscriber;
+use tracing_subscriber::{prelude::*, registry::Registry};
+
+#[test]
+fn renders_svg_on_drop() {
+    let tmp_dir = tempfile::Builder::new()
+        .prefix("tracing-flame-svg-test-")
+        .tempdir()
+        .expect("failed to create tempdir");
+    let folded_path = tmp_dir.path().join("tracing.folded");
+    let svg_path = tmp_dir.path().join("tracing-flamegraph.svg");
+
+    {
+        let (flame_layer, guard) = FlameSubscriber::with_file(&folded_path).unwrap();
+        let guard = guard.with_svg(&folded_path, &svg_path, inferno::flamegraph::Options::default());
+
+        let subscriber = Registry::default().with(flame_layer);
+        tracing::collect::set_global_default(subscriber).expect("Could not set global default");
+
+        {
+            let span = span!(Level::ERROR, "outer");
+            let _guard = span.enter();
+            sleep(Duration::from_millis(10));
+        }
+
+        drop(guard);
+    }
+
+    let svg = fs::read_to_string(&svg_path).expect("svg file should have been written");
+    assert!(svg.contains("<svg"), "output should be a rendered SVG");
+    assert!(svg.contains("outer"), "svg should contain the span's name");
+
+    tmp_dir.close().expect("failed to delete tempdir");
+}
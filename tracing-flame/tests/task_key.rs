@@ -0,0 +1,81 @@
+use std::fs;
+use tracing::{span, Level};
+use tracing_flame::FlameSubscriber;
+use tracing_subscriber::{prelude::*, registry::Registry};
+
+/// The lane each folded stack line starts with, i.e. everything before the
+/// first `;`.
+fn lanes_of(folded: &str) -> Vec<&str> {
+    folded
+        .lines()
+        .map(|line| line.split(';').next().unwrap_or(line))
+        .collect()
+}
+
+#[test]
+fn root_span_as_task_keys_by_root_span_not_thread() {
+    let tmp_dir = tempfile::Builder::new()
+        .prefix("tracing-flame-task-key-test-")
+        .tempdir()
+        .expect("failed to create tempdir");
+    let folded_path = tmp_dir.path().join("tracing.folded");
+
+    let (flame_layer, guard) = FlameSubscriber::with_file(&folded_path).unwrap();
+    let flame_layer = flame_layer.with_root_span_as_task(true);
+
+    tracing::collect::with_default(Registry::default().with(flame_layer), || {
+        span!(Level::ERROR, "first_task").in_scope(|| {
+            span!(Level::ERROR, "child").in_scope(|| {});
+        });
+        span!(Level::ERROR, "second_task").in_scope(|| {
+            span!(Level::ERROR, "child").in_scope(|| {});
+        });
+    });
+    guard.flush().unwrap();
+
+    let folded = fs::read_to_string(&folded_path).expect("folded stack file should exist");
+    let lanes = lanes_of(&folded);
+
+    // Both root spans ran on this same OS thread, but each gets its own
+    // lane rather than being collapsed under a shared thread name.
+    assert!(lanes.iter().any(|lane| lane.ends_with("first_task")));
+    assert!(lanes.iter().any(|lane| lane.ends_with("second_task")));
+    assert_ne!(
+        lanes.iter().find(|lane| lane.ends_with("first_task")),
+        lanes.iter().find(|lane| lane.ends_with("second_task")),
+    );
+
+    tmp_dir.close().expect("failed to delete tempdir");
+}
+
+#[test]
+fn task_id_field_keys_by_field_and_falls_back_to_thread() {
+    let tmp_dir = tempfile::Builder::new()
+        .prefix("tracing-flame-task-key-test-")
+        .tempdir()
+        .expect("failed to create tempdir");
+    let folded_path = tmp_dir.path().join("tracing.folded");
+
+    let (flame_layer, guard) = FlameSubscriber::with_file(&folded_path).unwrap();
+    let flame_layer = flame_layer.with_task_id_field("task_id");
+
+    tracing::collect::with_default(Registry::default().with(flame_layer), || {
+        span!(Level::ERROR, "tagged", task_id = "abc").in_scope(|| {
+            span!(Level::ERROR, "child").in_scope(|| {});
+        });
+        // No span in scope records `task_id`, so this falls back to
+        // keying by OS thread.
+        span!(Level::ERROR, "untagged").in_scope(|| {});
+    });
+    guard.flush().unwrap();
+
+    let folded = fs::read_to_string(&folded_path).expect("folded stack file should exist");
+    let lanes = lanes_of(&folded);
+
+    assert!(lanes.iter().any(|lane| lane == &"\"abc\""));
+    assert!(lanes
+        .iter()
+        .any(|lane| lane.starts_with("ThreadId(") && !lane.contains("abc")));
+
+    tmp_dir.close().expect("failed to delete tempdir");
+}
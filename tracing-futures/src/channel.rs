@@ -0,0 +1,131 @@
+//! Propagating spans across a channel, so a message's producer and its
+//! consumer are linked in the trace even though they run on different
+//! threads.
+//!
+//! Sending a message over an [`std::sync::mpsc`] channel loses whatever span
+//! was current at the time: by the time the receiver's thread picks the
+//! message up, [`Span::current()`] just returns the receiver's own,
+//! unrelated span. [`SpanCarrier`] captures the sender's current span
+//! alongside the message, and [`SenderExt`]/[`ReceiverExt`] wrap sending and
+//! receiving so that capturing and re-entering it don't have to be done by
+//! hand at every call site.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::sync::mpsc;
+//! use tracing_futures::channel::{ReceiverExt, SenderExt};
+//!
+//! let (tx, rx) = mpsc::channel();
+//!
+//! tracing::info_span!("producer").in_scope(|| {
+//!     tx.send_with_span("a message").unwrap();
+//! });
+//!
+//! // The closure passed to `recv_with_span` runs with the "producer" span
+//! // entered, even though it's the consumer's thread doing the receiving.
+//! rx.recv_with_span(|message| {
+//!     assert_eq!(message, "a message");
+//! })
+//! .unwrap();
+//! ```
+use std::sync::mpsc::{Receiver, RecvError, SendError, Sender};
+use tracing::Span;
+
+/// A message wrapped together with the [`Span`] that was current when it
+/// was sent.
+///
+/// Constructed by [`SenderExt::send_with_span`]; see the [module-level
+/// documentation](self) for why this exists.
+#[derive(Debug, Clone)]
+pub struct SpanCarrier<T> {
+    span: Span,
+    message: T,
+}
+
+impl<T> SpanCarrier<T> {
+    /// Wraps `message` together with [`Span::current()`].
+    pub fn new(message: T) -> Self {
+        Self {
+            span: Span::current(),
+            message,
+        }
+    }
+
+    /// Consumes the carrier, returning the captured span and the message
+    /// separately.
+    pub fn into_parts(self) -> (Span, T) {
+        (self.span, self.message)
+    }
+}
+
+/// Extension trait for [`Sender`]s of [`SpanCarrier`]s, capturing the
+/// current span at send time.
+pub trait SenderExt<T> {
+    /// Sends `message`, wrapped in a [`SpanCarrier`] capturing
+    /// [`Span::current()`].
+    ///
+    /// On failure, the returned error contains the carrier (span and
+    /// message together), matching [`Sender::send`]'s own behavior of
+    /// handing the un-sent value back.
+    fn send_with_span(&self, message: T) -> Result<(), SendError<SpanCarrier<T>>>;
+}
+
+impl<T> SenderExt<T> for Sender<SpanCarrier<T>> {
+    fn send_with_span(&self, message: T) -> Result<(), SendError<SpanCarrier<T>>> {
+        self.send(SpanCarrier::new(message))
+    }
+}
+
+/// Extension trait for [`Receiver`]s of [`SpanCarrier`]s, re-entering the
+/// sender's span while the received message is processed.
+pub trait ReceiverExt<T> {
+    /// Receives a message, entering the span it was sent with for the
+    /// duration of `f`, and returns `f`'s result.
+    ///
+    /// Blocks as [`Receiver::recv`] does if no message is available yet.
+    fn recv_with_span<R>(&self, f: impl FnOnce(T) -> R) -> Result<R, RecvError>;
+}
+
+impl<T> ReceiverExt<T> for Receiver<SpanCarrier<T>> {
+    fn recv_with_span<R>(&self, f: impl FnOnce(T) -> R) -> Result<R, RecvError> {
+        let (span, message) = self.recv()?.into_parts();
+        let _enter = span.enter();
+        Ok(f(message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn recv_with_span_enters_the_sent_span() {
+        let (tx, rx) = mpsc::channel();
+
+        let span = tracing::info_span!("producer");
+        span.in_scope(|| {
+            tx.send_with_span(1).unwrap();
+        });
+
+        let entered = rx
+            .recv_with_span(|message| {
+                assert_eq!(message, 1);
+                Span::current().id()
+            })
+            .unwrap();
+
+        assert_eq!(entered, span.id());
+    }
+
+    #[test]
+    fn send_error_returns_the_carrier() {
+        let (tx, rx) = mpsc::channel();
+        drop(rx);
+
+        let err = tx.send_with_span("never received").unwrap_err();
+        let (_span, message) = err.0.into_parts();
+        assert_eq!(message, "never received");
+    }
+}
@@ -114,6 +114,11 @@ use tracing::{dispatch, Dispatch};
 
 use tracing::Span;
 
+/// Propagating spans across a channel boundary.
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod channel;
+
 /// Implementations for `Instrument`ed future executors.
 pub mod executor;
 
@@ -0,0 +1,113 @@
+use crate::{FieldValue, GeneratedField, GeneratedRecord, RecordKind, MAX_FIELDS};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+/// Characters chosen to make generated strings "weird UTF-8": combining
+/// marks, right-to-left text, emoji with variation selectors, and other code
+/// points that have historically tripped up naive string handling.
+const WEIRD_CHARS: &[char] = &[
+    '\u{0301}',  // combining acute accent
+    '\u{200B}',  // zero-width space
+    '\u{202E}',  // right-to-left override
+    '\u{FEFF}',  // byte order mark / zero-width no-break space
+    '🧵',        // emoji outside the BMP
+    '\u{1F3FB}', // emoji skin tone modifier
+    '\u{0645}',  // arabic letter meem, for right-to-left scripts
+    '\0',
+];
+
+/// Generates [`GeneratedRecord`]s made of adversarial field values, for use
+/// with [`run`](crate::run).
+///
+/// A `Generator` is deterministic given its seed, so a fuzz run that finds a
+/// panic can be reproduced by constructing a new `Generator` with the same
+/// seed.
+pub struct Generator {
+    rng: StdRng,
+}
+
+impl std::fmt::Debug for Generator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Generator").finish_non_exhaustive()
+    }
+}
+
+impl Generator {
+    /// Returns a new `Generator` seeded with `seed`.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Generates `count` events, each with a random number of fields from 0
+    /// up to [`MAX_FIELDS`].
+    pub fn generate_events(&mut self, count: usize) -> Vec<GeneratedRecord> {
+        (0..count)
+            .map(|i| self.generate(RecordKind::Event, i))
+            .collect()
+    }
+
+    /// Generates `count` spans, each with a random number of fields from 0
+    /// up to [`MAX_FIELDS`].
+    pub fn generate_spans(&mut self, count: usize) -> Vec<GeneratedRecord> {
+        (0..count)
+            .map(|i| self.generate(RecordKind::Span, i))
+            .collect()
+    }
+
+    fn generate(&mut self, kind: RecordKind, index: usize) -> GeneratedRecord {
+        let field_count = self.rng.gen_range(0..=MAX_FIELDS);
+        let fields = (0..field_count)
+            .map(|i| GeneratedField {
+                name: format!("field_{}", i),
+                value: self.generate_value(),
+            })
+            .collect();
+
+        GeneratedRecord {
+            kind,
+            name: format!("fuzzed_{}", index),
+            fields,
+        }
+    }
+
+    /// Generates a single adversarial field value, chosen uniformly from
+    /// every [`FieldValue`] variant.
+    pub fn generate_value(&mut self) -> FieldValue {
+        match self.rng.gen_range(0..6) {
+            0 => FieldValue::Str(self.generate_string()),
+            1 => FieldValue::I64(
+                *[i64::MIN, i64::MAX, 0, -1]
+                    .choose(&mut self.rng)
+                    .unwrap_or(&0),
+            ),
+            2 => FieldValue::U64(*[0, 1, u64::MAX].choose(&mut self.rng).unwrap_or(&0)),
+            3 => FieldValue::F64(
+                *[f64::NAN, f64::INFINITY, f64::NEG_INFINITY, 0.0]
+                    .choose(&mut self.rng)
+                    .unwrap_or(&0.0),
+            ),
+            4 => FieldValue::Bool(self.rng.gen()),
+            _ => FieldValue::PanicsOnDebug,
+        }
+    }
+
+    /// Generates a string that is either very large or full of characters
+    /// that have historically been mishandled by naive UTF-8 code.
+    fn generate_string(&mut self) -> String {
+        if self.rng.gen_bool(0.5) {
+            // A huge value: long enough to catch buffer size assumptions.
+            let len = self.rng.gen_range(1..=1 << 16);
+            "a".repeat(len)
+        } else {
+            let len = self.rng.gen_range(0..=64);
+            (0..len)
+                .map(|_| {
+                    *WEIRD_CHARS
+                        .choose(&mut self.rng)
+                        .expect("WEIRD_CHARS is non-empty")
+                })
+                .collect()
+        }
+    }
+}
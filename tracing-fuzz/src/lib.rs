@@ -0,0 +1,348 @@
+#![doc = include_str!("../README.md")]
+#![warn(
+    missing_debug_implementations,
+    missing_docs,
+    rust_2018_idioms,
+    unreachable_pub,
+    bad_style,
+    dead_code,
+    improper_ctypes,
+    non_shorthand_field_patterns,
+    no_mangle_generic_items,
+    overflowing_literals,
+    path_statements,
+    patterns_in_fns_without_body,
+    unconditional_recursion,
+    unused,
+    unused_allocation,
+    unused_comparisons,
+    unused_parens,
+    while_true
+)]
+
+//! Generators for adversarial spans and events, and a harness for driving
+//! them through a [`Collect`] under [`catch_unwind`], for fuzzing
+//! `tracing_subscriber::Subscribe` and `FormatEvent` implementations.
+//!
+//! [`Collect`]: tracing_core::Collect
+//! [`catch_unwind`]: std::panic::catch_unwind
+
+use std::{convert::TryFrom, fmt};
+use tracing_core::{
+    callsite::dynamic::{Interner, MetadataBuilder},
+    field::{Field, FieldSet, Value},
+    span, Event, Level,
+};
+
+mod generator;
+
+pub use generator::Generator;
+
+/// The maximum number of fields a [`Generator`] will put on a single span or
+/// event, and the largest field count [`run`] can dispatch a `ValueSet` for.
+pub const MAX_FIELDS: usize = 32;
+
+/// A single generated field: a name, paired with a value that is by
+/// construction adversarial in some way (an unusually large number, a string
+/// full of combining marks and emoji, or a value whose `Debug` impl panics).
+#[derive(Debug)]
+pub struct GeneratedField {
+    /// The field's name.
+    pub name: String,
+    /// The field's value.
+    pub value: FieldValue,
+}
+
+/// A generated field value.
+///
+/// This deliberately mirrors the shape of values `tracing`'s own macros
+/// record --- primitives recorded via [`Value`]'s built-in implementations,
+/// and a `Debug`-recorded case --- so that fuzzed events exercise the same
+/// code paths real instrumentation does.
+pub enum FieldValue {
+    /// A string, which may contain unusual UTF-8 (combining marks,
+    /// emoji, right-to-left text) or be very large.
+    Str(String),
+    /// A signed integer, which may be an extreme value such as
+    /// [`i64::MIN`] or [`i64::MAX`].
+    I64(i64),
+    /// An unsigned integer, which may be an extreme value such as
+    /// [`u64::MAX`].
+    U64(u64),
+    /// A floating point number, which may be `NAN`, `INFINITY`, or
+    /// `-INFINITY`.
+    F64(f64),
+    /// A boolean.
+    Bool(bool),
+    /// A value whose `Debug` implementation panics.
+    ///
+    /// Recorded with [`tracing_core::field::debug`], this exercises how a
+    /// subscriber or formatter handles a field visitor that unwinds while
+    /// formatting a value.
+    PanicsOnDebug,
+}
+
+impl fmt::Debug for FieldValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Str(s) => f.debug_tuple("Str").field(s).finish(),
+            Self::I64(n) => f.debug_tuple("I64").field(n).finish(),
+            Self::U64(n) => f.debug_tuple("U64").field(n).finish(),
+            Self::F64(n) => f.debug_tuple("F64").field(n).finish(),
+            Self::Bool(b) => f.debug_tuple("Bool").field(b).finish(),
+            Self::PanicsOnDebug => f.write_str("PanicsOnDebug"),
+        }
+    }
+}
+
+/// A value whose [`fmt::Debug`] implementation panics, for constructing
+/// [`FieldValue::PanicsOnDebug`] fields.
+struct PanicsOnDebug;
+
+impl fmt::Debug for PanicsOnDebug {
+    fn fmt(&self, _: &mut fmt::Formatter<'_>) -> fmt::Result {
+        panic!("tracing-fuzz: this value's Debug implementation always panics")
+    }
+}
+
+/// A generated span or event, ready to be dispatched with [`run`].
+#[derive(Debug)]
+pub struct GeneratedRecord {
+    /// Whether this record is a span or an event.
+    pub kind: RecordKind,
+    /// The record's name.
+    pub name: String,
+    /// The record's fields.
+    pub fields: Vec<GeneratedField>,
+}
+
+/// Whether a [`GeneratedRecord`] represents a span or an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordKind {
+    /// The record is a span: [`run`] will open and immediately close it.
+    Span,
+    /// The record is an event.
+    Event,
+}
+
+/// The outcome of dispatching one [`GeneratedRecord`] with [`run`].
+#[derive(Debug)]
+pub struct FuzzResult {
+    /// The record that was dispatched.
+    pub record: GeneratedRecord,
+    /// The panic payload, downcast to a message, if dispatching the record
+    /// caused a panic that unwound out of the collector.
+    pub panic: Option<String>,
+}
+
+impl FuzzResult {
+    /// Returns `true` if dispatching this record panicked.
+    pub fn panicked(&self) -> bool {
+        self.panic.is_some()
+    }
+}
+
+/// Dispatches every record in `records` to the current default collector,
+/// catching any panic that unwinds out of it.
+///
+/// This is the entry point layer and formatter authors should call from a
+/// test: set a collector under test as the default (for example with
+/// `tracing::collect::with_default`), generate some records with a
+/// [`Generator`], and pass them here. The returned [`FuzzResult`]s report
+/// which records, if any, caused a panic.
+pub fn run(records: Vec<GeneratedRecord>) -> Vec<FuzzResult> {
+    records
+        .into_iter()
+        .map(|record| {
+            let panic =
+                std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| dispatch(&record)))
+                    .err()
+                    .map(|payload| {
+                        payload
+                            .downcast_ref::<&str>()
+                            .map(|s| s.to_string())
+                            .or_else(|| payload.downcast_ref::<String>().cloned())
+                            .unwrap_or_else(|| "non-string panic payload".to_string())
+                    });
+            FuzzResult { record, panic }
+        })
+        .collect()
+}
+
+/// Interns metadata for `record` and dispatches it as a span or event to the
+/// current default collector.
+fn dispatch(record: &GeneratedRecord) {
+    // A fresh `Interner` per call forgoes the callsite deduplication it's
+    // meant for, but the fuzz harness doesn't rely on collectors caching
+    // per-callsite interest, so the simplicity is worth it.
+    let interner = Interner::new();
+
+    let builder = match record.kind {
+        RecordKind::Span => MetadataBuilder::span(&record.name, Level::TRACE),
+        RecordKind::Event => MetadataBuilder::event(&record.name, Level::TRACE),
+    }
+    .target("tracing_fuzz")
+    .fields(record.fields.iter().map(|f| f.name.as_str()));
+
+    let id = interner.intern(builder);
+    let metadata = id.0.metadata();
+    let field_set = metadata.fields();
+
+    let fields: Vec<(Field, &FieldValue)> = record
+        .fields
+        .iter()
+        .map(|f| {
+            let field = field_set
+                .field(&f.name)
+                .expect("interned field must be present in its own FieldSet");
+            (field, &f.value)
+        })
+        .collect();
+
+    with_value_set(field_set, &fields, |value_set| match record.kind {
+        RecordKind::Event => Event::dispatch(metadata, value_set),
+        RecordKind::Span => tracing_core::dispatch::get_default(|dispatch| {
+            let attrs = span::Attributes::new_root(metadata, value_set);
+            let id = dispatch.new_span(&attrs);
+            dispatch.enter(&id);
+            dispatch.exit(&id);
+        }),
+    });
+}
+
+/// Builds a [`tracing_core::field::ValueSet`] out of `fields` and passes it
+/// to `f`.
+///
+/// [`FieldSet::value_set`] only accepts fixed-size arrays --- its bound is a
+/// sealed trait implemented for `[(&Field, Option<&dyn Value>); N]` --- so a
+/// runtime-length slice of fields has to be dispatched to one of a fixed set
+/// of array sizes. `f` is invoked from inside the matching arm, since the
+/// array, and the `ValueSet` borrowing it, cannot outlive that arm.
+fn with_value_set(
+    field_set: &FieldSet,
+    fields: &[(Field, &FieldValue)],
+    f: impl FnOnce(&tracing_core::field::ValueSet<'_>),
+) {
+    let leaked: Vec<&dyn Value> = fields
+        .iter()
+        .map(|(_, value)| -> &dyn Value {
+            match value {
+                FieldValue::Str(s) => Box::leak(Box::new(s.clone())),
+                FieldValue::I64(n) => Box::leak(Box::new(*n)),
+                FieldValue::U64(n) => Box::leak(Box::new(*n)),
+                FieldValue::F64(n) => Box::leak(Box::new(*n)),
+                FieldValue::Bool(b) => Box::leak(Box::new(*b)),
+                // `Value` is only implemented for `Debug`/`Display` types via
+                // the `debug`/`display` wrappers, and generating a fuzz
+                // report is not meant to be allocation-free, so this is
+                // boxed and leaked like every other case above.
+                FieldValue::PanicsOnDebug => {
+                    Box::leak(Box::new(tracing_core::field::debug(PanicsOnDebug)))
+                }
+            }
+        })
+        .collect();
+
+    let pairs: Vec<(&Field, Option<&dyn Value>)> = fields
+        .iter()
+        .zip(&leaked)
+        .map(|((field, _), value)| (field, Some(*value)))
+        .collect();
+
+    macro_rules! dispatch_by_len {
+        ($pairs:expr, [$($n:literal),*]) => {
+            match $pairs.len() {
+                $($n => {
+                    let array: [(&Field, Option<&dyn Value>); $n] =
+                        <[(&Field, Option<&dyn Value>); $n]>::try_from($pairs).unwrap();
+                    let value_set = field_set.value_set(&array);
+                    f(&value_set)
+                })*
+                n => panic!(
+                    "tracing-fuzz: {} fields exceeds the maximum of {} (see `MAX_FIELDS`)",
+                    n, MAX_FIELDS,
+                ),
+            }
+        };
+    }
+
+    dispatch_by_len!(
+        pairs,
+        [
+            0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23,
+            24, 25, 26, 27, 28, 29, 30, 31, 32
+        ]
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dispatches_generated_events_without_a_collector() {
+        // With no collector set, dispatching just falls through to the
+        // no-op default `Dispatch`; this only checks that building the
+        // metadata and value set doesn't panic on its own.
+        let mut generator = Generator::new(0);
+        let records = generator.generate_events(MAX_FIELDS + 1);
+        let results = run(records);
+        assert!(results.iter().all(|r| !r.panicked()));
+    }
+
+    #[test]
+    fn panics_on_debug_field_is_caught() {
+        let record = GeneratedRecord {
+            kind: RecordKind::Event,
+            name: "fuzzed".to_string(),
+            fields: vec![GeneratedField {
+                name: "boom".to_string(),
+                value: FieldValue::PanicsOnDebug,
+            }],
+        };
+
+        tracing_core::dispatch::with_default(
+            &tracing_core::Dispatch::new(PanicOnRecordCollector),
+            || {
+                let results = run(vec![record]);
+                assert_eq!(results.len(), 1);
+                assert!(results[0].panicked());
+            },
+        );
+    }
+
+    /// A collector that formats every field it's given with `{:?}`, so that
+    /// a [`FieldValue::PanicsOnDebug`] field actually panics when recorded.
+    struct PanicOnRecordCollector;
+
+    impl tracing_core::Collect for PanicOnRecordCollector {
+        fn enabled(&self, _: &tracing_core::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+            span::Id::from_u64(1)
+        }
+
+        fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+        fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            struct Formats;
+            impl tracing_core::field::Visit for Formats {
+                fn record_debug(&mut self, _: &Field, value: &dyn fmt::Debug) {
+                    let _ = format!("{:?}", value);
+                }
+            }
+            event.record(&mut Formats);
+        }
+
+        fn enter(&self, _: &span::Id) {}
+        fn exit(&self, _: &span::Id) {}
+
+        fn current_span(&self) -> span::Current {
+            span::Current::none()
+        }
+    }
+}
@@ -40,7 +40,12 @@
 
 #[cfg(unix)]
 use std::os::unix::net::UnixDatagram;
-use std::{fmt, io, io::Write};
+use std::{
+    convert::TryFrom,
+    fmt, io,
+    io::Write,
+    time::{Duration, Instant},
+};
 
 use tracing_core::{
     event::Event,
@@ -48,12 +53,20 @@ use tracing_core::{
     span::{Attributes, Id, Record},
     Collect, Field, Level, Metadata,
 };
-use tracing_subscriber::{registry::LookupSpan, subscribe::Context};
+use tracing_subscriber::{
+    registry::{LookupSpan, SpanRef},
+    subscribe::Context,
+};
 
 #[cfg(target_os = "linux")]
 mod memfd;
 #[cfg(target_os = "linux")]
 mod socket;
+#[cfg(unix)]
+mod worker;
+
+#[cfg(unix)]
+pub use worker::FlushGuard;
 
 /// Sends events and their fields to journald
 ///
@@ -72,6 +85,19 @@ mod socket;
 ///
 /// These mappings can be changed with [`Subscriber::with_priority_mappings`].
 ///
+/// An event with a `priority` field overrides its `PRIORITY` entirely, instead of deriving it
+/// from the event's level: `tracing::error!(priority = 4, "rate limited, but recovering")` is
+/// sent to journald as priority 4 (Warning) even though the level is `ERROR`. The field must be
+/// an integer between 0 and 7, per the [syslog severity scale][syslog]; out-of-range values are
+/// ignored and the level-derived priority is used instead. Either way, the `priority` field
+/// itself is never forwarded as a regular field.
+///
+/// An event with a `message_id` field is treated as an audit-class event, in the sense used by
+/// [systemd's message catalog][catalog]: the field is validated as a 128-bit ID, formatted as 32
+/// lowercase hex digits, and emitted as the well-known `MESSAGE_ID` field if it passes validation.
+/// A malformed `message_id` is not forwarded as `MESSAGE_ID` --- since a bad ID would silently
+/// break catalog lookups for the message --- and is instead recorded as a regular, prefixed field.
+///
 /// The standard journald `CODE_LINE` and `CODE_FILE` fields are automatically emitted. A `TARGET`
 /// field is emitted containing the event's target.
 ///
@@ -81,16 +107,34 @@ mod socket;
 /// User-defined fields other than the event `message` field have a prefix applied by default to
 /// prevent collision with standard fields.
 ///
+/// Span lifecycles --- a span being created and, later, closed --- are not logged by default,
+/// since most applications only care about the point events recorded within spans. Enable
+/// [`Subscriber::with_span_events`] to additionally emit a journal entry each time a span is
+/// created and closed, tagged with a `SPAN_EVENT` field of `create` or `close`; the closing entry
+/// also carries a `SPAN_DURATION_US` field with the span's total lifetime in microseconds.
+///
 /// [journald conventions]: https://www.freedesktop.org/software/systemd/man/systemd.journal-fields.html
+/// [syslog]: https://www.rfc-editor.org/rfc/rfc5424#section-6.2.1
+/// [catalog]: https://www.freedesktop.org/software/systemd/man/systemd.journal-fields.html#MESSAGE_ID=
 pub struct Subscriber {
     #[cfg(unix)]
     socket: UnixDatagram,
+    #[cfg(unix)]
+    async_sender: Option<std::sync::mpsc::SyncSender<worker::Msg>>,
     field_prefix: Option<String>,
     syslog_identifier: String,
     additional_fields: Vec<u8>,
     priority_mappings: PriorityMappings,
+    correlation_provider: Option<Box<CorrelationProvider>>,
+    log_spans: bool,
 }
 
+/// A callback that produces correlation fields --- such as `CONTAINER_ID` or `UNIT` --- to
+/// attach to every event.
+///
+/// See [`Subscriber::with_correlation_fields`].
+pub type CorrelationProvider = dyn Fn() -> Vec<(String, String)> + Send + Sync;
+
 #[cfg(unix)]
 const JOURNALD_PATH: &str = "/run/systemd/journal/socket";
 
@@ -105,6 +149,7 @@ impl Subscriber {
             let socket = UnixDatagram::unbound()?;
             let sub = Self {
                 socket,
+                async_sender: None,
                 field_prefix: Some("F".into()),
                 syslog_identifier: std::env::current_exe()
                     .ok()
@@ -115,6 +160,8 @@ impl Subscriber {
                     .unwrap_or_default(),
                 additional_fields: Vec::new(),
                 priority_mappings: PriorityMappings::new(),
+                correlation_provider: None,
+                log_spans: false,
             };
             // Check that we can talk to journald, by sending empty payload which journald discards.
             // However if the socket didn't exist or if none listened we'd get an error here.
@@ -225,11 +272,96 @@ impl Subscriber {
         self
     }
 
+    /// Sets a callback that's invoked once per event to produce additional fields, such as
+    /// `CONTAINER_ID` or `UNIT`, that correlate this process's journal entries with container
+    /// or service runtime metadata when journals from many sources are centralized.
+    ///
+    /// Unlike [`with_custom_fields`](Self::with_custom_fields), which records fields once when
+    /// the subscriber is built, `provider` is called again for every event, so it can report
+    /// values --- like the current container ID --- that aren't known until runtime or that
+    /// change over the process's lifetime.
+    ///
+    /// ```no_run
+    /// # use tracing_journald::Subscriber;
+    /// # fn read_container_id() -> String { String::new() }
+    /// let sub = Subscriber::new()
+    ///     .unwrap()
+    ///     .with_correlation_fields(|| {
+    ///         vec![("CONTAINER_ID".to_string(), read_container_id())]
+    ///     });
+    /// ```
+    pub fn with_correlation_fields<F>(mut self, provider: F) -> Self
+    where
+        F: Fn() -> Vec<(String, String)> + Send + Sync + 'static,
+    {
+        self.correlation_provider = Some(Box::new(provider));
+        self
+    }
+
+    /// Enables or disables logging a journal entry when a span is created and another when it is
+    /// closed.
+    ///
+    /// Each entry carries the fields of the span and its ancestors, exactly as a point event
+    /// recorded in the same span would, along with a `SPAN_EVENT` field of `create` or `close` so
+    /// `journalctl` can distinguish them from ordinary events. The closing entry additionally
+    /// carries a `SPAN_DURATION_US` field with the span's total lifetime in microseconds.
+    ///
+    /// Defaults to `false`, since most applications only care about the point events recorded
+    /// inside spans, not the spans' lifecycles themselves.
+    pub fn with_span_events(mut self, enabled: bool) -> Self {
+        self.log_spans = enabled;
+        self
+    }
+
     /// Returns the syslog identifier in use.
     pub fn syslog_identifier(&self) -> &str {
         &self.syslog_identifier
     }
 
+    /// Moves sending to journald onto a dedicated background thread.
+    ///
+    /// By default, every span and event this subscriber handles is sent to journald
+    /// synchronously, with a single blocking `sendto(2)` call on the thread that produced it.
+    /// Under heavy load, a slow or backed-up journald can make that call stall, adding latency to
+    /// every traced operation in the process. `with_async_buffering` instead hands the formatted
+    /// payload off to a bounded in-memory queue of `capacity` entries and returns immediately; a
+    /// dedicated worker thread drains the queue and performs the actual sends.
+    ///
+    /// If the queue is full --- because the worker can't keep up with the rate of incoming spans
+    /// and events --- the entry that doesn't fit is dropped rather than blocking the caller.
+    ///
+    /// Returns the reconfigured subscriber along with a [`FlushGuard`]. The guard should be kept
+    /// alive for as long as journald logging is needed, typically by binding it in `main`:
+    /// dropping it flushes any entries still queued and joins the worker thread, so logs aren't
+    /// silently lost if the process exits right after a burst of activity.
+    ///
+    /// ```
+    /// # fn docs() -> std::io::Result<()> {
+    /// let (subscriber, _flush_guard) = tracing_journald::Subscriber::new()?.with_async_buffering(1024);
+    /// tracing_subscriber::registry().with(subscriber);
+    /// # Ok(())
+    /// # }
+    /// # use tracing_subscriber::prelude::*;
+    /// ```
+    #[cfg(unix)]
+    pub fn with_async_buffering(mut self, capacity: usize) -> (Self, FlushGuard) {
+        let (sender, receiver) = std::sync::mpsc::sync_channel(capacity);
+        let worker_socket = self
+            .socket
+            .try_clone()
+            .expect("failed to clone journald socket for the async worker thread");
+        let handle = std::thread::Builder::new()
+            .name("tracing-journald-worker".to_string())
+            .spawn(move || worker::run(receiver, worker_socket))
+            .expect("failed to spawn tracing-journald worker thread");
+        let guard = FlushGuard {
+            sender: Some(sender.clone()),
+            handle: Some(handle),
+        };
+        self.async_sender = Some(sender);
+        (self, guard)
+    }
+
     #[cfg(not(unix))]
     fn send_payload(&self, _opayload: &[u8]) -> io::Result<()> {
         Err(io::Error::new(
@@ -240,19 +372,40 @@ impl Subscriber {
 
     #[cfg(unix)]
     fn send_payload(&self, payload: &[u8]) -> io::Result<usize> {
-        self.socket
-            .send_to(payload, JOURNALD_PATH)
-            .or_else(|error| {
-                if Some(libc::EMSGSIZE) == error.raw_os_error() {
-                    self.send_large_payload(payload)
-                } else {
-                    Err(error)
-                }
-            })
+        if let Some(sender) = &self.async_sender {
+            let len = payload.len();
+            return match sender.try_send(worker::Msg::Payload(payload.to_vec())) {
+                Ok(()) => Ok(len),
+                Err(std::sync::mpsc::TrySendError::Full(_)) => Err(io::Error::new(
+                    io::ErrorKind::WouldBlock,
+                    "tracing-journald worker queue is full, dropping entry",
+                )),
+                Err(std::sync::mpsc::TrySendError::Disconnected(_)) => Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    "tracing-journald worker thread has shut down",
+                )),
+            };
+        }
+        Self::send_payload_via(&self.socket, payload)
+    }
+
+    /// Sends `payload` to journald over `socket`, without going through any async buffering.
+    ///
+    /// Shared between the synchronous path and the async worker thread, which owns its own
+    /// clone of the socket.
+    #[cfg(unix)]
+    fn send_payload_via(socket: &UnixDatagram, payload: &[u8]) -> io::Result<usize> {
+        socket.send_to(payload, JOURNALD_PATH).or_else(|error| {
+            if Some(libc::EMSGSIZE) == error.raw_os_error() {
+                Self::send_large_payload_via(socket, payload)
+            } else {
+                Err(error)
+            }
+        })
     }
 
     #[cfg(all(unix, not(target_os = "linux")))]
-    fn send_large_payload(&self, _payload: &[u8]) -> io::Result<usize> {
+    fn send_large_payload_via(_socket: &UnixDatagram, _payload: &[u8]) -> io::Result<usize> {
         Err(io::Error::new(
             io::ErrorKind::Other,
             "Large payloads not supported on non-Linux OS",
@@ -261,7 +414,7 @@ impl Subscriber {
 
     /// Send large payloads to journald via a memfd.
     #[cfg(target_os = "linux")]
-    fn send_large_payload(&self, payload: &[u8]) -> io::Result<usize> {
+    fn send_large_payload_via(socket: &UnixDatagram, payload: &[u8]) -> io::Result<usize> {
         // If the payload's too large for a single datagram, send it through a memfd, see
         // https://systemd.io/JOURNAL_NATIVE_PROTOCOL/
         use std::os::unix::prelude::AsRawFd;
@@ -271,21 +424,75 @@ impl Subscriber {
         // Fully seal the memfd to signal journald that its backing data won't resize anymore
         // and so is safe to mmap.
         memfd::seal_fully(mem.as_raw_fd())?;
-        socket::send_one_fd_to(&self.socket, mem.as_raw_fd(), JOURNALD_PATH)
+        socket::send_one_fd_to(socket, mem.as_raw_fd(), JOURNALD_PATH)
     }
 
-    fn put_priority(&self, buf: &mut Vec<u8>, meta: &Metadata) {
+    fn put_priority(&self, buf: &mut Vec<u8>, meta: &Metadata, override_priority: Option<Priority>) {
+        let priority = override_priority.unwrap_or(match *meta.level() {
+            Level::ERROR => self.priority_mappings.error,
+            Level::WARN => self.priority_mappings.warn,
+            Level::INFO => self.priority_mappings.info,
+            Level::DEBUG => self.priority_mappings.debug,
+            Level::TRACE => self.priority_mappings.trace,
+        });
+        put_field_wellformed(buf, "PRIORITY", &[priority as u8]);
+    }
+
+    /// Sends a journal entry recording that `span` was just created or has just been closed.
+    ///
+    /// `duration`, if given, is the span's total lifetime, and marks this as the closing entry
+    /// rather than the creating one.
+    fn send_span_lifecycle_event<C>(&self, span: &SpanRef<C>, duration: Option<Duration>)
+    where
+        C: for<'lookup> LookupSpan<'lookup>,
+    {
+        let mut buf = Vec::with_capacity(256);
+
+        for ancestor in span.scope().from_root() {
+            let exts = ancestor.extensions();
+            let fields = exts.get::<SpanFields>().expect("missing fields");
+            buf.extend_from_slice(&fields.0);
+        }
+
+        self.put_priority(&mut buf, span.metadata(), None);
+        put_metadata(&mut buf, span.metadata(), None);
         put_field_wellformed(
-            buf,
-            "PRIORITY",
-            &[match *meta.level() {
-                Level::ERROR => self.priority_mappings.error as u8,
-                Level::WARN => self.priority_mappings.warn as u8,
-                Level::INFO => self.priority_mappings.info as u8,
-                Level::DEBUG => self.priority_mappings.debug as u8,
-                Level::TRACE => self.priority_mappings.trace as u8,
-            }],
+            &mut buf,
+            "SPAN_EVENT",
+            if duration.is_some() { b"close" } else { b"create" },
         );
+        put_field_length_encoded(&mut buf, "SYSLOG_IDENTIFIER", |buf| {
+            write!(buf, "{}", self.syslog_identifier).unwrap()
+        });
+        buf.extend_from_slice(&self.additional_fields);
+
+        if let Some(provider) = &self.correlation_provider {
+            for (name, value) in provider() {
+                put_field_length_encoded(&mut buf, &name, |buf| {
+                    buf.extend_from_slice(value.as_bytes())
+                });
+            }
+        }
+
+        put_field_length_encoded(&mut buf, "MESSAGE", |buf| match duration {
+            Some(duration) => write!(
+                buf,
+                "{} span closed after {:.6}s",
+                span.name(),
+                duration.as_secs_f64()
+            )
+            .unwrap(),
+            None => write!(buf, "{} span created", span.name()).unwrap(),
+        });
+
+        if let Some(duration) = duration {
+            put_field_length_encoded(&mut buf, "SPAN_DURATION_US", |buf| {
+                write!(buf, "{}", duration.as_micros()).unwrap()
+            });
+        }
+
+        // At this point we can't handle the error anymore so just ignore it.
+        let _ = self.send_payload(&buf);
     }
 }
 
@@ -314,6 +521,23 @@ where
         });
 
         span.extensions_mut().insert(SpanFields(buf));
+
+        if self.log_spans {
+            span.extensions_mut().insert(SpanCreated(Instant::now()));
+            self.send_span_lifecycle_event(&span, None);
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<C>) {
+        if !self.log_spans {
+            return;
+        }
+        let span = ctx.span(&id).expect("unknown span");
+        let duration = span
+            .extensions()
+            .get::<SpanCreated>()
+            .map(|created| created.0.elapsed());
+        self.send_span_lifecycle_event(&span, duration);
     }
 
     fn on_record(&self, id: &Id, values: &Record, ctx: Context<C>) {
@@ -340,17 +564,35 @@ where
             buf.extend_from_slice(&fields.0);
         }
 
+        // Look for the conventional `priority` and `message_id` override fields before recording
+        // the rest of the event's fields, so we know whether to forward them as `PRIORITY` /
+        // `MESSAGE_ID` or as ordinary, prefixed fields.
+        let mut overrides = SpecialFields::default();
+        event.record(&mut overrides);
+
         // Record event fields
-        self.put_priority(&mut buf, event.metadata());
+        self.put_priority(&mut buf, event.metadata(), overrides.priority);
+        if let Some(message_id) = &overrides.message_id {
+            put_field_wellformed(&mut buf, "MESSAGE_ID", message_id.as_bytes());
+        }
         put_metadata(&mut buf, event.metadata(), None);
         put_field_length_encoded(&mut buf, "SYSLOG_IDENTIFIER", |buf| {
             write!(buf, "{}", self.syslog_identifier).unwrap()
         });
         buf.extend_from_slice(&self.additional_fields);
 
+        if let Some(provider) = &self.correlation_provider {
+            for (name, value) in provider() {
+                put_field_length_encoded(&mut buf, &name, |buf| {
+                    buf.extend_from_slice(value.as_bytes())
+                });
+            }
+        }
+
         event.record(&mut EventVisitor::new(
             &mut buf,
             self.field_prefix.as_deref(),
+            overrides.message_id.is_some(),
         ));
 
         // At this point we can't handle the error anymore so just ignore it.
@@ -360,6 +602,10 @@ where
 
 struct SpanFields(Vec<u8>);
 
+/// The instant a span was created, recorded so [`Subscriber::on_close`] can compute the span's
+/// total lifetime when [`Subscriber::with_span_events`] is enabled.
+struct SpanCreated(Instant);
+
 struct SpanVisitor<'a> {
     buf: &'a mut Vec<u8>,
     field_prefix: Option<&'a str>,
@@ -395,11 +641,16 @@ impl Visit for SpanVisitor<'_> {
 struct EventVisitor<'a> {
     buf: &'a mut Vec<u8>,
     prefix: Option<&'a str>,
+    skip_message_id: bool,
 }
 
 impl<'a> EventVisitor<'a> {
-    fn new(buf: &'a mut Vec<u8>, prefix: Option<&'a str>) -> Self {
-        Self { buf, prefix }
+    fn new(buf: &'a mut Vec<u8>, prefix: Option<&'a str>, skip_message_id: bool) -> Self {
+        Self {
+            buf,
+            prefix,
+            skip_message_id,
+        }
     }
 
     fn put_prefix(&mut self, field: &Field) {
@@ -411,17 +662,53 @@ impl<'a> EventVisitor<'a> {
             }
         }
     }
+
+    /// Whether `field` is a reserved control field that's handled separately in `on_event`, and
+    /// so must not also be forwarded here as a regular field.
+    fn is_handled_elsewhere(&self, field: &Field) -> bool {
+        field.name() == "priority" || (self.skip_message_id && field.name() == "message_id")
+    }
 }
 
 impl Visit for EventVisitor<'_> {
     fn record_str(&mut self, field: &Field, value: &str) {
+        if self.is_handled_elsewhere(field) {
+            return;
+        }
         self.put_prefix(field);
         put_field_length_encoded(self.buf, field.name(), |buf| {
             buf.extend_from_slice(value.as_bytes())
         });
     }
 
+    fn record_duration(&mut self, field: &Field, value: std::time::Duration) {
+        if self.is_handled_elsewhere(field) {
+            return;
+        }
+        self.put_prefix(field);
+        put_field_length_encoded(self.buf, field.name(), |buf| {
+            write!(buf, "{}", value.as_secs_f64()).unwrap()
+        });
+    }
+
+    fn record_system_time(&mut self, field: &Field, value: std::time::SystemTime) {
+        if self.is_handled_elsewhere(field) {
+            return;
+        }
+        self.put_prefix(field);
+        let secs = value
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs_f64())
+            .unwrap_or_else(|e| -e.duration().as_secs_f64());
+        put_field_length_encoded(self.buf, field.name(), |buf| {
+            write!(buf, "{}", secs).unwrap()
+        });
+    }
+
     fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if self.is_handled_elsewhere(field) {
+            return;
+        }
         self.put_prefix(field);
         put_field_length_encoded(self.buf, field.name(), |buf| {
             write!(buf, "{:?}", value).unwrap()
@@ -429,6 +716,54 @@ impl Visit for EventVisitor<'_> {
     }
 }
 
+/// Captures the conventional `priority` and `message_id` override fields from an event, before
+/// its fields are recorded for real by [`EventVisitor`].
+///
+/// `priority`, if present and a valid [syslog severity][Priority] (0-7), overrides the `PRIORITY`
+/// field that would otherwise be derived from the event's level. `message_id`, if present and a
+/// well-formed 128-bit ID (32 lowercase hex digits), is forwarded as the journal's `MESSAGE_ID`
+/// field, marking the event as an audit-class entry associated with a catalog message.
+#[derive(Default)]
+struct SpecialFields {
+    priority: Option<Priority>,
+    message_id: Option<String>,
+}
+
+impl Visit for SpecialFields {
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        if field.name() == "priority" {
+            if let Ok(value) = u64::try_from(value) {
+                self.priority = Priority::from_syslog_severity(value);
+            }
+        }
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        if field.name() == "priority" {
+            self.priority = Priority::from_syslog_severity(value);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message_id" && is_well_formed_message_id(value) {
+            self.message_id = Some(value.to_owned());
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn fmt::Debug) {
+        // `priority` and `message_id` are only recognized when recorded as an integer or a
+        // string, respectively; any other type is left for `EventVisitor` to record normally.
+    }
+}
+
+/// Whether `id` is a well-formed systemd catalog message ID: exactly 32 lowercase hex digits
+/// (a 128-bit ID with no separators), as required by `MESSAGE_ID`'s [field documentation].
+///
+/// [field documentation]: https://www.freedesktop.org/software/systemd/man/systemd.journal-fields.html#MESSAGE_ID=
+fn is_well_formed_message_id(id: &str) -> bool {
+    id.len() == 32 && id.bytes().all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+}
+
 /// A priority (called "severity code" by syslog) is used to mark the
 /// importance of a message.
 ///
@@ -497,6 +832,28 @@ pub enum Priority {
     Debug = b'7',
 }
 
+impl Priority {
+    /// Converts a [syslog severity code][syslog] (0 through 7) into the matching `Priority`,
+    /// or `None` if `severity` is out of range.
+    ///
+    /// Used to interpret an event's `priority` field override; see the [`Subscriber`] docs.
+    ///
+    /// [syslog]: https://www.rfc-editor.org/rfc/rfc5424#section-6.2.1
+    fn from_syslog_severity(severity: u64) -> Option<Priority> {
+        match severity {
+            0 => Some(Priority::Emergency),
+            1 => Some(Priority::Alert),
+            2 => Some(Priority::Critical),
+            3 => Some(Priority::Error),
+            4 => Some(Priority::Warning),
+            5 => Some(Priority::Notice),
+            6 => Some(Priority::Informational),
+            7 => Some(Priority::Debug),
+            _ => None,
+        }
+    }
+}
+
 /// Mappings from tracing [`Level`]s to journald [priorities].
 ///
 /// [priorities]: Priority
@@ -0,0 +1,58 @@
+//! The background thread driving [`Subscriber::with_async_buffering`].
+//!
+//! [`Subscriber::with_async_buffering`]: crate::Subscriber::with_async_buffering
+use std::os::unix::net::UnixDatagram;
+use std::sync::mpsc::{Receiver, SyncSender};
+use std::thread::JoinHandle;
+
+use crate::Subscriber;
+
+/// A message sent from a [`Subscriber`] to its async worker thread.
+pub(crate) enum Msg {
+    /// A formatted payload, ready to be sent to journald as-is.
+    Payload(Vec<u8>),
+    /// Finish draining the queue and exit the worker's `recv` loop.
+    Shutdown,
+}
+
+/// Drains `receiver`, sending each payload to journald over `socket`, until a [`Msg::Shutdown`]
+/// is received or every sender has been dropped.
+pub(crate) fn run(receiver: Receiver<Msg>, socket: UnixDatagram) {
+    for msg in receiver {
+        match msg {
+            Msg::Payload(payload) => {
+                // At this point there's no one left to report the error to; the caller that
+                // produced this payload got back `Ok` as soon as it was queued. Silently
+                // dropping a failed send here matches the synchronous path, which also discards
+                // `send_payload`'s error once a span or event has been fully formatted.
+                let _ = Subscriber::send_payload_via(&socket, &payload);
+            }
+            Msg::Shutdown => break,
+        }
+    }
+}
+
+/// A guard that flushes buffered journal entries and shuts down the background worker thread
+/// spawned by [`Subscriber::with_async_buffering`] when dropped.
+///
+/// This must be assigned to a binding that isn't `_` --- binding it to `_` drops it immediately,
+/// which defeats the point of holding it until the process is ready to exit.
+#[must_use]
+pub struct FlushGuard {
+    pub(crate) sender: Option<SyncSender<Msg>>,
+    pub(crate) handle: Option<JoinHandle<()>>,
+}
+
+impl Drop for FlushGuard {
+    fn drop(&mut self) {
+        // The subscriber this guard was returned alongside holds its own clone of `sender`, so
+        // sending `Shutdown` --- rather than simply dropping our half --- is what actually tells
+        // the worker to stop once it's drained everything queued ahead of this message.
+        if let Some(sender) = self.sender.take() {
+            let _ = sender.send(Msg::Shutdown);
+        }
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
@@ -211,6 +211,105 @@ fn custom_priorities() {
     with_journald_subscriber(subscriber, test);
 }
 
+#[test]
+fn priority_field_override() {
+    with_journald(|| {
+        error!(
+            test.name = "priority_field_override",
+            priority = 4,
+            "rate limited, but recovering"
+        );
+
+        let message = retry_read_one_line_from_journal("priority_field_override");
+        assert_eq!(message["MESSAGE"], "rate limited, but recovering");
+        // if the `priority` field were also forwarded as a regular field, journald would see
+        // two `PRIORITY` entries and this would deserialize as an array instead of a single value
+        assert_eq!(message["PRIORITY"], "4");
+    });
+}
+
+#[test]
+fn priority_field_out_of_range_falls_back_to_level() {
+    with_journald(|| {
+        error!(
+            test.name = "priority_field_out_of_range_falls_back_to_level",
+            priority = 99,
+            "out-of-range priority is ignored"
+        );
+
+        let message =
+            retry_read_one_line_from_journal("priority_field_out_of_range_falls_back_to_level");
+        assert_eq!(message["MESSAGE"], "out-of-range priority is ignored");
+        assert_eq!(message["PRIORITY"], "3");
+    });
+}
+
+#[test]
+fn async_buffering_flushes_on_guard_drop() {
+    match journalctl_version() {
+        Ok(_) => {
+            let (subscriber, guard) = Subscriber::new()
+                .unwrap()
+                .with_field_prefix(None)
+                .with_async_buffering(16);
+            let sub = Registry::default().with(subscriber);
+            tracing::collect::with_default(sub, || {
+                info!(
+                    test.name = "async_buffering_flushes_on_guard_drop",
+                    "sent via the async worker thread"
+                );
+            });
+            // Dropping the guard joins the worker thread after it drains the queue, so the
+            // entry above is guaranteed to have reached journald by the time we read it back.
+            drop(guard);
+
+            let message =
+                retry_read_one_line_from_journal("async_buffering_flushes_on_guard_drop");
+            assert_eq!(message["MESSAGE"], "sent via the async worker thread");
+        }
+        Err(error) => eprintln!(
+            "SKIPPING TEST: journalctl --version failed with error: {}",
+            error
+        ),
+    }
+}
+
+#[test]
+fn message_id_audit_field() {
+    with_journald(|| {
+        let id = "167a0359bf62417e9a447ca2d6e0fdf5";
+        info!(
+            test.name = "message_id_audit_field",
+            message_id = id,
+            "user logged in"
+        );
+
+        let message = retry_read_one_line_from_journal("message_id_audit_field");
+        assert_eq!(message["MESSAGE"], "user logged in");
+        assert_eq!(message["MESSAGE_ID"], id);
+    });
+}
+
+#[test]
+fn malformed_message_id_is_not_forwarded() {
+    // Use the default field prefix here (unlike `with_journald`) so that a `message_id` which
+    // falls back to being treated as an ordinary field is distinguishable from a well-formed one:
+    // it's prefixed, rather than sent as the bare `MESSAGE_ID` field.
+    let sub = Subscriber::new().unwrap();
+    with_journald_subscriber(sub, || {
+        info!(
+            test.name = "malformed_message_id_is_not_forwarded",
+            message_id = "not-a-valid-id",
+            "user logged in"
+        );
+
+        let message = retry_read_one_line_from_journal("malformed_message_id_is_not_forwarded");
+        assert_eq!(message["MESSAGE"], "user logged in");
+        assert!(message.get("MESSAGE_ID").is_none());
+        assert_eq!(message["F_MESSAGE_ID"], "not-a-valid-id");
+    });
+}
+
 #[test]
 fn multiline_message() {
     with_journald(|| {
@@ -374,3 +473,40 @@ fn spans_field_collision() {
         assert_eq!(message["SPAN_FIELD"], vec!["foo1", "foo2", "foo3"]);
     });
 }
+
+#[test]
+fn span_events() {
+    let sub = Subscriber::new()
+        .unwrap()
+        .with_field_prefix(None)
+        .with_span_events(true);
+    with_journald_subscriber(sub, || {
+        {
+            let _span = info_span!("my_span", test.name = "span_events").entered();
+        }
+
+        let messages = retry(|| {
+            let messages = read_from_journal("span_events");
+            if messages.len() == 2 {
+                Ok(messages)
+            } else {
+                Err(format!("two messages expected, got {}", messages.len()))
+            }
+        })
+        .unwrap();
+
+        let created = messages
+            .iter()
+            .find(|m| m["SPAN_EVENT"] == "create")
+            .expect("no span creation entry");
+        assert_eq!(created["SPAN_NAME"].as_text(), Some("my_span"));
+        assert!(!created.contains_key("SPAN_DURATION_US"));
+
+        let closed = messages
+            .iter()
+            .find(|m| m["SPAN_EVENT"] == "close")
+            .expect("no span close entry");
+        assert_eq!(closed["SPAN_NAME"].as_text(), Some("my_span"));
+        assert!(closed["SPAN_DURATION_US"].as_text().is_some());
+    });
+}
@@ -0,0 +1,173 @@
+//! Forwarding `log`'s structured key-values onto the bridged `tracing`
+//! event as real fields.
+//!
+//! Without this module, a `log::Record`'s [`log::kv`] pairs are discarded
+//! entirely by the bridge in [`dispatch_record`]: only `record.args()` (the
+//! formatted message) is ever recorded. That flattens away any structure
+//! the `log` caller attached with e.g. `log::info!(key = value; "message")`.
+//!
+//! `tracing`'s field system normally requires a `Metadata`'s field names to
+//! be known at compile time, tied to a single `'static` callsite. Since the
+//! set of key names on a `log::Record` is only known once the record
+//! arrives, we lean on [`tracing_core::callsite::dynamic`] to intern one
+//! callsite per distinct *shape* of key-value fields seen (the same name,
+//! target, level, location, and key names reuse the same callsite, just
+//! like a real `tracing` callsite would).
+//!
+//! [`dispatch_record`]: crate::dispatch_record
+use crate::{loglevel_to_cs, AsTrace};
+use log::kv::{self, VisitSource};
+use once_cell::sync::Lazy;
+use tracing_core::{
+    callsite::dynamic::{Interner, MetadataBuilder},
+    dispatch::Dispatch,
+    field::{self, Field},
+    Event,
+};
+
+static INTERNER: Lazy<Interner> = Lazy::new(Interner::new);
+
+/// The maximum number of `log::kv` pairs forwarded as `tracing` fields on a
+/// single record. Key-values beyond this are dropped, rather than growing
+/// the interned callsite (and the `ValueSet` array backing it) without
+/// bound.
+const MAX_KEY_VALUES: usize = 16;
+
+#[derive(Default)]
+struct Visitor<'kvs> {
+    keys: Vec<String>,
+    values: Vec<kv::Value<'kvs>>,
+}
+
+impl<'kvs> VisitSource<'kvs> for Visitor<'kvs> {
+    fn visit_pair(&mut self, key: kv::Key<'kvs>, value: kv::Value<'kvs>) -> Result<(), kv::Error> {
+        if self.keys.len() == MAX_KEY_VALUES {
+            return Ok(());
+        }
+        self.keys.push(key.as_str().to_owned());
+        self.values.push(value);
+        Ok(())
+    }
+}
+
+/// If `record` carries any `log::kv` pairs, dispatches it (and its
+/// key-values, as real fields) to `dispatch` and returns `true`.
+///
+/// Returns `false` (dispatching nothing) if `record` has no key-values, so
+/// that the caller can fall back to its normal, static-callsite dispatch
+/// path for the common case.
+pub(crate) fn dispatch_with_key_values(dispatch: &Dispatch, record: &log::Record<'_>) -> bool {
+    let mut visitor = Visitor::default();
+    if record.key_values().visit(&mut visitor).is_err() || visitor.keys.is_empty() {
+        return false;
+    }
+
+    let (_, keys, static_meta) = loglevel_to_cs(record.level());
+
+    let module = record.module_path();
+    let file = record.file();
+    let line = record.line();
+
+    let mut builder = MetadataBuilder::event(static_meta.name(), record.level().as_trace())
+        .target(static_meta.target())
+        .field(keys.message.name())
+        .field(keys.target.name())
+        .field(keys.module.name())
+        .field(keys.file.name())
+        .field(keys.line.name());
+    if let Some(module) = module {
+        builder = builder.module_path(module);
+    }
+    if let Some(file) = file {
+        builder = builder.file(file);
+    }
+    if let Some(line) = line {
+        builder = builder.line(line);
+    }
+    for key in &visitor.keys {
+        builder = builder.field(key);
+    }
+
+    let id = INTERNER.intern(builder);
+    let meta = id.0.metadata();
+    let fields = meta.fields();
+
+    let module: Option<&dyn field::Value> = module.as_ref().map(|s| s as &dyn field::Value);
+    let file: Option<&dyn field::Value> = file.as_ref().map(|s| s as &dyn field::Value);
+    let line: Option<&dyn field::Value> = line.as_ref().map(|l| l as &dyn field::Value);
+    let target = record.target();
+
+    let message_field = fields.field("message").expect("field was just interned");
+    let target_field = fields.field("log.target").expect("field was just interned");
+    let module_field = fields.field("log.module_path").expect("field was just interned");
+    let file_field = fields.field("log.file").expect("field was just interned");
+    let line_field = fields.field("log.line").expect("field was just interned");
+
+    let base: [(&Field, Option<&dyn field::Value>); 5] = [
+        (&message_field, Some(record.args() as &dyn field::Value)),
+        (&target_field, Some(&target as &dyn field::Value)),
+        (&module_field, module),
+        (&file_field, file),
+        (&line_field, line),
+    ];
+
+    let kv_fields: Vec<Field> = visitor
+        .keys
+        .iter()
+        .map(|key| fields.field(key).expect("field was just interned"))
+        .collect();
+
+    dispatch_by_kv_len(dispatch, meta, &base, &kv_fields, &visitor.values);
+
+    true
+}
+
+/// Dispatches an `Event` combining `base`'s fixed fields with `kv_fields`'
+/// key-value fields, whose count is only known at runtime.
+///
+/// `tracing_core::field::ValueSet` requires a fixed-size array (its length
+/// is part of the type), so this matches on the (capped) key-value count to
+/// pick a concrete array size, rather than allocating a `Vec` that could
+/// satisfy `ValueSet`'s `ValidLen` bound.
+fn dispatch_by_kv_len<'a>(
+    dispatch: &Dispatch,
+    meta: &'static tracing_core::Metadata<'static>,
+    base: &[(&'a Field, Option<&'a dyn field::Value>); 5],
+    kv_fields: &'a [Field],
+    kv_values: &'a [kv::Value<'a>],
+) {
+    macro_rules! dispatch_arm {
+        ($n:literal) => {{
+            let displayed: [field::DisplayValue<&kv::Value<'a>>; $n] =
+                std::array::from_fn(|i| field::display(&kv_values[i]));
+            let mut values: [(&Field, Option<&dyn field::Value>); 5 + $n] =
+                std::array::from_fn(|_| (base[0].0, None));
+            values[..5].copy_from_slice(base);
+            for (i, slot) in values[5..].iter_mut().enumerate() {
+                *slot = (&kv_fields[i], Some(&displayed[i] as &dyn field::Value));
+            }
+            dispatch.event(&Event::new(meta, &meta.fields().value_set(&values)));
+        }};
+    }
+
+    match kv_fields.len() {
+        0 => dispatch.event(&Event::new(meta, &meta.fields().value_set(base))),
+        1 => dispatch_arm!(1),
+        2 => dispatch_arm!(2),
+        3 => dispatch_arm!(3),
+        4 => dispatch_arm!(4),
+        5 => dispatch_arm!(5),
+        6 => dispatch_arm!(6),
+        7 => dispatch_arm!(7),
+        8 => dispatch_arm!(8),
+        9 => dispatch_arm!(9),
+        10 => dispatch_arm!(10),
+        11 => dispatch_arm!(11),
+        12 => dispatch_arm!(12),
+        13 => dispatch_arm!(13),
+        14 => dispatch_arm!(14),
+        15 => dispatch_arm!(15),
+        16 => dispatch_arm!(16),
+        _ => unreachable!("Visitor caps key-values at MAX_KEY_VALUES"),
+    }
+}
@@ -72,6 +72,10 @@
 //! * `log-tracer`: enables the `LogTracer` type (on by default)
 //! * `env_logger`: enables the `env_logger` module, with helpers for working
 //!   with the [`env_logger` crate].
+//! * `kv`: forwards a bridged [`log::Record`]'s structured key-values (the
+//!   `log` crate's `kv` feature) onto the `tracing::Event` as real fields,
+//!   instead of losing their structure by flattening everything into the
+//!   event's `message`.
 //!
 //! ## Supported Rust Versions
 //!
@@ -150,6 +154,9 @@ pub use self::log_tracer::LogTracer;
 #[cfg_attr(docsrs, doc(cfg(feature = "env_logger")))]
 pub mod env_logger;
 
+#[cfg(feature = "kv")]
+mod kv;
+
 pub use log;
 
 /// Format a log record as a trace event in the current span.
@@ -168,6 +175,13 @@ pub(crate) fn dispatch_record(record: &log::Record<'_>) {
             return;
         }
 
+        #[cfg(feature = "kv")]
+        {
+            if kv::dispatch_with_key_values(dispatch, record) {
+                return;
+            }
+        }
+
         let (_, keys, meta) = loglevel_to_cs(record.level());
 
         let log_module = record.module_path();
@@ -467,7 +481,7 @@ impl<'a> NormalizeEvent<'a> for Event<'a> {
     fn normalized_metadata(&'a self) -> Option<Metadata<'a>> {
         let original = self.metadata();
         if self.is_log() {
-            let mut fields = LogVisitor::new_for(self, level_to_cs(*original.level()).1);
+            let mut fields = LogVisitor::new_for(self);
             self.record(&mut fields);
 
             Some(Metadata::new(
@@ -486,7 +500,18 @@ impl<'a> NormalizeEvent<'a> for Event<'a> {
     }
 
     fn is_log(&self) -> bool {
-        self.metadata().callsite() == identify_callsite!(level_to_cs(*self.metadata().level()).0)
+        let meta = self.metadata();
+        // Fast path: this is one of our own statically-allocated per-level
+        // callsites (the common case, since `kv`-carrying records are rare).
+        if meta.callsite() == identify_callsite!(level_to_cs(*meta.level()).0) {
+            return true;
+        }
+        // Slow path: a record with `log::kv` fields is bridged through a
+        // dynamically interned callsite (see the `kv` module) so that its
+        // key-values can be recorded as extra fields, which gives it its own
+        // distinct callsite identity. Recognize it structurally instead, by
+        // its name and the presence of the well-known bridge fields.
+        meta.name() == "log event" && meta.fields().field("log.target").is_some()
     }
 }
 
@@ -495,20 +520,18 @@ struct LogVisitor<'a> {
     module_path: Option<&'a str>,
     file: Option<&'a str>,
     line: Option<u64>,
-    fields: &'static Fields,
 }
 
 impl<'a> LogVisitor<'a> {
     // We don't actually _use_ the provided event argument; it is simply to
     // ensure that the `LogVisitor` does not outlive the event whose fields it
     // is visiting, so that the reference casts in `record_str` are safe.
-    fn new_for(_event: &'a Event<'a>, fields: &'static Fields) -> Self {
+    fn new_for(_event: &'a Event<'a>) -> Self {
         Self {
             target: None,
             module_path: None,
             file: None,
             line: None,
-            fields,
         }
     }
 }
@@ -517,7 +540,7 @@ impl Visit for LogVisitor<'_> {
     fn record_debug(&mut self, _field: &Field, _value: &dyn fmt::Debug) {}
 
     fn record_u64(&mut self, field: &Field, value: u64) {
-        if field == &self.fields.line {
+        if field.name() == "log.line" {
             self.line = Some(value);
         }
     }
@@ -529,12 +552,11 @@ impl Visit for LogVisitor<'_> {
             // (and only if!) this `LogVisitor` was constructed with the same
             // lifetime parameter `'a` as the event in question, it's safe to
             // cast these string slices to the `'a` lifetime.
-            if field == &self.fields.file {
-                self.file = Some(&*(value as *const _));
-            } else if field == &self.fields.target {
-                self.target = Some(&*(value as *const _));
-            } else if field == &self.fields.module {
-                self.module_path = Some(&*(value as *const _));
+            match field.name() {
+                "log.file" => self.file = Some(&*(value as *const _)),
+                "log.target" => self.target = Some(&*(value as *const _)),
+                "log.module_path" => self.module_path = Some(&*(value as *const _)),
+                _ => {}
             }
         }
     }
@@ -32,6 +32,7 @@ use tracing_core::dispatch;
 #[derive(Debug)]
 pub struct LogTracer {
     ignore_crates: Box<[String]>,
+    target_filters: Box<[(String, log::LevelFilter)]>,
 }
 
 /// Configures a new `LogTracer`.
@@ -39,6 +40,7 @@ pub struct LogTracer {
 pub struct Builder {
     ignore_crates: Vec<String>,
     filter: log::LevelFilter,
+    target_filters: Vec<(String, log::LevelFilter)>,
 }
 
 // ===== impl LogTracer =====
@@ -96,6 +98,7 @@ impl LogTracer {
     pub fn new() -> Self {
         Self {
             ignore_crates: Vec::new().into_boxed_slice(),
+            target_filters: Vec::new().into_boxed_slice(),
         }
     }
 
@@ -178,6 +181,14 @@ impl log::Log for LogTracer {
             }
         }
 
+        // Do we have a per-target filter that's more specific than the global
+        // max level?
+        if let Some(target_level) = self.target_level(metadata.target()) {
+            if metadata.level() > target_level {
+                return false;
+            }
+        }
+
         // Finally, check if the current `tracing` dispatcher cares about this.
         dispatch::get_default(|dispatch| dispatch.enabled(&metadata.as_trace()))
     }
@@ -191,6 +202,22 @@ impl log::Log for LogTracer {
     fn flush(&self) {}
 }
 
+impl LogTracer {
+    /// Returns the configured max level for `target`, if one of the
+    /// per-target directives configured with [`Builder::with_target`]
+    /// matches it.
+    ///
+    /// When more than one configured target is a prefix of `target`, the
+    /// most specific (longest) one wins; `self.target_filters` is kept
+    /// sorted by descending prefix length to make this the first match.
+    fn target_level(&self, target: &str) -> Option<log::LevelFilter> {
+        self.target_filters
+            .iter()
+            .find(|(prefix, _)| target.starts_with(prefix.as_str()))
+            .map(|(_, level)| *level)
+    }
+}
+
 // ===== impl Builder =====
 
 impl Builder {
@@ -235,6 +262,53 @@ impl Builder {
         crates.into_iter().fold(self, Self::ignore_crate)
     }
 
+    /// Configures the `LogTracer` to enable, at most, `level` for log
+    /// records whose target starts with `target`.
+    ///
+    /// This is useful for turning down particularly chatty dependencies
+    /// that use `log` without having to construct a full
+    /// `tracing_subscriber::EnvFilter` (or similar) downstream of the
+    /// `LogTracer`. When more than one configured target matches a given
+    /// record, the most specific (longest) one takes precedence, mirroring
+    /// `tracing_subscriber::filter::Targets`.
+    ///
+    /// This filter is applied in addition to, not instead of, the global
+    /// maximum level configured with [`with_max_level`] and whatever the
+    /// currently active `tracing` `Collector` chooses to enable.
+    ///
+    /// ```rust
+    /// use tracing_log::LogTracer;
+    ///
+    /// LogTracer::builder()
+    ///     // Only forward `WARN` and above from this chatty dependency.
+    ///     .with_target("noisy_dependency", log::LevelFilter::Warn)
+    ///     .init()
+    ///     .expect("failed to set logger");
+    /// ```
+    ///
+    /// [`with_max_level`]: Builder::with_max_level
+    pub fn with_target(mut self, target: impl Into<String>, level: log::LevelFilter) -> Self {
+        self.target_filters.push((target.into(), level));
+        self
+    }
+
+    /// Configures the `LogTracer` with per-target max levels from an
+    /// iterator of target-[`LevelFilter`] pairs. See [`with_target`] for
+    /// details.
+    ///
+    /// [`LevelFilter`]: log::LevelFilter
+    /// [`with_target`]: Builder::with_target
+    pub fn with_targets<T>(self, targets: impl IntoIterator<Item = (T, log::LevelFilter)>) -> Self
+    where
+        T: Into<String>,
+    {
+        targets
+            .into_iter()
+            .fold(self, |builder, (target, level)| {
+                builder.with_target(target, level)
+            })
+    }
+
     /// Constructs a new `LogTracer` with the provided configuration and sets it
     /// as the default logger.
     ///
@@ -243,7 +317,14 @@ impl Builder {
     #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
     pub fn init(self) -> Result<(), SetLoggerError> {
         let ignore_crates = self.ignore_crates.into_boxed_slice();
-        let logger = Box::new(LogTracer { ignore_crates });
+        let mut target_filters = self.target_filters;
+        // Sort by descending prefix length, so `target_level` finds the
+        // most specific match first.
+        target_filters.sort_by_key(|(target, _)| std::cmp::Reverse(target.len()));
+        let logger = Box::new(LogTracer {
+            ignore_crates,
+            target_filters: target_filters.into_boxed_slice(),
+        });
         log::set_boxed_logger(logger)?;
         log::set_max_level(self.filter);
         Ok(())
@@ -255,6 +336,42 @@ impl Default for Builder {
         Self {
             ignore_crates: Vec::new(),
             filter: log::LevelFilter::max(),
+            target_filters: Vec::new(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn logger(target_filters: &[(&str, log::LevelFilter)]) -> LogTracer {
+        let mut target_filters: Vec<_> = target_filters
+            .iter()
+            .map(|(target, level)| (target.to_string(), *level))
+            .collect();
+        target_filters.sort_by_key(|(target, _)| std::cmp::Reverse(target.len()));
+        LogTracer {
+            ignore_crates: Vec::new().into_boxed_slice(),
+            target_filters: target_filters.into_boxed_slice(),
+        }
+    }
+
+    #[test]
+    fn target_level_prefers_the_most_specific_match() {
+        let logger = logger(&[
+            ("my_crate", log::LevelFilter::Info),
+            ("my_crate::noisy_module", log::LevelFilter::Warn),
+        ]);
+
+        assert_eq!(
+            logger.target_level("my_crate::noisy_module::deeper"),
+            Some(log::LevelFilter::Warn)
+        );
+        assert_eq!(
+            logger.target_level("my_crate::other_module"),
+            Some(log::LevelFilter::Info)
+        );
+        assert_eq!(logger.target_level("unrelated_crate"), None);
+    }
+}
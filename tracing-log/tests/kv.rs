@@ -0,0 +1,85 @@
+#![cfg(feature = "kv")]
+
+use std::sync::{Arc, Mutex};
+use tracing::collect::with_default;
+use tracing_core::field::{Field, Visit};
+use tracing_core::span::{Attributes, Record};
+use tracing_core::{span, Collect, Event, Level, LevelFilter, Metadata};
+use tracing_log::LogTracer;
+
+#[derive(Default)]
+struct RecordedFields {
+    message: Option<String>,
+    kvs: Vec<(String, String)>,
+}
+
+impl Visit for RecordedFields {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        let value = format!("{:?}", value);
+        if field.name() == "message" {
+            self.message = Some(value);
+        } else {
+            self.kvs.push((field.name().to_string(), value));
+        }
+    }
+}
+
+struct State {
+    last_event: Mutex<Option<RecordedFields>>,
+}
+
+struct TestSubscriber(Arc<State>);
+
+impl Collect for TestSubscriber {
+    fn enabled(&self, _meta: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        Some(LevelFilter::from_level(Level::INFO))
+    }
+
+    fn new_span(&self, _span: &Attributes<'_>) -> span::Id {
+        span::Id::from_u64(42)
+    }
+
+    fn record(&self, _span: &span::Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut recorded = RecordedFields::default();
+        event.record(&mut recorded);
+        *self.0.last_event.lock().unwrap() = Some(recorded);
+    }
+
+    fn enter(&self, _span: &span::Id) {}
+
+    fn exit(&self, _span: &span::Id) {}
+
+    fn current_span(&self) -> span::Current {
+        span::Current::unknown()
+    }
+}
+
+#[test]
+fn forwards_log_key_values_as_fields() {
+    LogTracer::init().unwrap();
+    let me = Arc::new(State {
+        last_event: Mutex::new(None),
+    });
+    let state = me.clone();
+
+    with_default(TestSubscriber(me), || {
+        log::info!(request_id = 42, path = "/health"; "handled request");
+
+        let lock = state.last_event.lock().unwrap();
+        let recorded = lock.as_ref().expect("an event was recorded");
+        assert_eq!(recorded.message.as_deref(), Some("handled request"));
+        assert!(recorded
+            .kvs
+            .iter()
+            .any(|(k, v)| k == "request_id" && v == "42"));
+        assert!(recorded.kvs.iter().any(|(k, v)| k == "path" && v == "/health"));
+    })
+}
@@ -43,3 +43,119 @@ macro_rules! dbg {
         $crate::dbg!(level: $crate::tracing::Level::DEBUG, $ex)
     };
 }
+
+/// Logs the `Err` variant of `$ex` as a tracing event, annotated with the
+/// call site's file and line number, then evaluates to `$ex` unchanged so it
+/// can still be propagated with `?`.
+///
+/// This formalizes the common pattern of logging an error immediately before
+/// giving up on it:
+///
+/// ```rust,ignore
+/// let value = match some_fallible_call() {
+///     Ok(value) => value,
+///     Err(error) => {
+///         tracing::error!(%error, "some_fallible_call failed");
+///         return Err(error.into());
+///     }
+/// };
+/// ```
+///
+/// becomes:
+///
+/// ```rust,ignore
+/// let value = trace_catch!(some_fallible_call())?;
+/// ```
+///
+/// By default, the verbosity level for the generated event is `ERROR`, but
+/// this can be customized. See [`ResultExt::trace_err`] for a method-call
+/// form of the same behavior that doesn't require the macro, at the cost of
+/// not recording the call site's file and line number.
+#[macro_export]
+macro_rules! trace_catch {
+    (target: $target:expr, level: $level:expr, $ex:expr) => {{
+        match $ex {
+            result => {
+                if let ::std::result::Result::Err(ref error) = result {
+                    $crate::tracing::dyn_event!(
+                        target: $target,
+                        $level,
+                        %error,
+                        file = file!(),
+                        line = line!(),
+                        "error"
+                    );
+                }
+                result
+            }
+        }
+    }};
+    (level: $level:expr, $ex:expr) => {
+        $crate::trace_catch!(target: module_path!(), level: $level, $ex)
+    };
+    (target: $target:expr, $ex:expr) => {
+        $crate::trace_catch!(target: $target, level: $crate::tracing::Level::ERROR, $ex)
+    };
+    ($ex:expr) => {
+        $crate::trace_catch!(level: $crate::tracing::Level::ERROR, $ex)
+    };
+}
+
+/// Extension trait that adds [`trace_err`](ResultExt::trace_err) to
+/// [`Result`].
+pub trait ResultExt<T, E> {
+    /// If `self` is `Err`, logs the error as a tracing event at `level`,
+    /// then returns `self` unchanged so it can still be propagated with `?`.
+    ///
+    /// Unlike [`trace_catch!`], this doesn't record the call site's file and
+    /// line number, since those aren't available from inside a trait method;
+    /// reach for the macro instead when that's useful.
+    fn trace_err(self, level: tracing::Level) -> Self;
+}
+
+impl<T, E> ResultExt<T, E> for Result<T, E>
+where
+    E: std::fmt::Display,
+{
+    fn trace_err(self, level: tracing::Level) -> Self {
+        if let Err(ref error) = self {
+            tracing::dyn_event!(level, %error, "error");
+        }
+        self
+    }
+}
+
+/// Like [`tracing::event!`], but additionally records the literal message
+/// template (the format string itself, before its arguments are
+/// interpolated) as a `message.template` field.
+///
+/// Log backends that group or translate by template rather than by the
+/// fully-rendered message --- the way structured logging frameworks in
+/// other ecosystems do --- need the template kept separate from the
+/// values that were formatted into it, since the rendered message alone
+/// throws that information away. Doing this by hand means repeating the
+/// format string:
+///
+/// ```rust,ignore
+/// tracing::info!(message.template = "user {} logged in", user_id, "user {} logged in", user_id);
+/// ```
+///
+/// `event_template!` takes it once:
+///
+/// ```rust,ignore
+/// tracing_macros::event_template!(Level::INFO, "user {} logged in", user_id);
+/// ```
+///
+/// Note this only captures the template at call sites that use this macro;
+/// making every `tracing::event!`-family macro do so unconditionally would
+/// require changing `tracing`'s core macros themselves, which is out of
+/// scope here.
+#[macro_export]
+macro_rules! event_template {
+    (target: $target:expr, $lvl:expr, $msg:literal $(, $($arg:tt)*)?) => {
+        $crate::tracing::event!(target: $target, $lvl, message.template = $msg, $msg $(, $($arg)*)?)
+    };
+    ($lvl:expr, $msg:literal $(, $($arg:tt)*)?) => {
+        $crate::event_template!(target: module_path!(), $lvl, $msg $(, $($arg)*)?)
+    };
+}
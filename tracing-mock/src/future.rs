@@ -0,0 +1,262 @@
+//! Deterministic, dependency-free helpers for driving instrumented futures
+//! and streams against a [`MockCollector`].
+//!
+//! Driving a future on a full async runtime (such as a multi-threaded
+//! `tokio` executor) makes tests that assert the exact sequence of span
+//! enters and exits flaky: the runtime is free to poll the future on
+//! whichever worker thread it likes, whenever it likes, so the order
+//! [`MockCollector`] observes enter/exit calls in can vary from run to run.
+//! The helpers in this module instead poll a future or stream on the
+//! current thread, exactly once per yield point, with nothing else able to
+//! interleave -- so the recorded sequence is reproducible.
+//!
+//! [`block_on_timeout`] and [`collect_stream_timeout`] additionally bound
+//! how long a future or stream is allowed to keep yielding `Poll::Pending`
+//! before the test fails, so a bug that causes a future to never complete
+//! (and thus never close its span) shows up as a clear panic rather than a
+//! hung test process.
+//!
+//! [`MockCollector`]: crate::collector::MockCollector
+use std::{
+    future::Future,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    time::{Duration, Instant},
+};
+
+use tokio_stream::Stream;
+
+struct NoopWaker;
+
+impl Wake for NoopWaker {
+    fn wake(self: Arc<Self>) {}
+
+    fn wake_by_ref(self: &Arc<Self>) {}
+}
+
+fn noop_waker() -> Waker {
+    Waker::from(Arc::new(NoopWaker))
+}
+
+/// Polls `future` to completion on the current thread, returning its
+/// output.
+///
+/// Each time `future` returns [`Poll::Pending`], it's immediately polled
+/// again, so this never actually parks waiting on a waker -- it only
+/// terminates once `future` returns [`Poll::Ready`]. If `future` might, as
+/// the result of a bug, never become ready, use [`block_on_timeout`]
+/// instead so the test fails rather than hanging.
+///
+/// # Examples
+///
+/// ```
+/// use tracing::Instrument;
+/// use tracing_mock::{collector, expect, future::block_on};
+///
+/// # struct PollTwice(u8);
+/// # impl std::future::Future for PollTwice {
+/// #     type Output = ();
+/// #     fn poll(
+/// #         mut self: std::pin::Pin<&mut Self>,
+/// #         cx: &mut std::task::Context<'_>,
+/// #     ) -> std::task::Poll<()> {
+/// #         if self.0 == 0 {
+/// #             std::task::Poll::Ready(())
+/// #         } else {
+/// #             self.0 -= 1;
+/// #             cx.waker().wake_by_ref();
+/// #             std::task::Poll::Pending
+/// #         }
+/// #     }
+/// # }
+/// // Two real polls (the first returns `Pending`, the second `Ready`),
+/// // plus one more enter/exit pair and a `drop_span` when the
+/// // `Instrumented` future itself is dropped.
+/// let span = expect::span().named("my_span");
+/// let (collector, handle) = collector::mock()
+///     .enter(&span)
+///     .exit(&span)
+///     .enter(&span)
+///     .exit(&span)
+///     .enter(&span)
+///     .exit(&span)
+///     .drop_span(span)
+///     .only()
+///     .run_with_handle();
+///
+/// tracing::collect::with_default(collector, || {
+///     let future = PollTwice(1).instrument(tracing::info_span!("my_span"));
+///     block_on(future);
+/// });
+///
+/// handle.assert_finished();
+/// ```
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = Box::pin(future);
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(value) = future.as_mut().poll(&mut cx) {
+            return value;
+        }
+    }
+}
+
+/// Like [`block_on`], but panics naming `what` if `future` hasn't become
+/// ready within `timeout`, rather than polling forever.
+pub fn block_on_timeout<F: Future>(future: F, timeout: Duration, what: &str) -> F::Output {
+    let mut future = Box::pin(future);
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    let start = Instant::now();
+    loop {
+        if let Poll::Ready(value) = future.as_mut().poll(&mut cx) {
+            return value;
+        }
+        assert!(
+            start.elapsed() < timeout,
+            "timed out after {:?} waiting for {} to complete",
+            timeout,
+            what
+        );
+    }
+}
+
+/// Polls `stream` on the current thread until it ends, returning every item
+/// it yielded, in order.
+///
+/// As with [`block_on`], each [`Poll::Pending`] is immediately followed by
+/// another poll, so this never parks on a waker. If `stream` might never
+/// end, use [`collect_stream_timeout`] instead.
+pub fn collect_stream<S: Stream + Unpin>(stream: S) -> Vec<S::Item> {
+    let mut stream = Box::pin(stream);
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    let mut items = Vec::new();
+    loop {
+        match stream.as_mut().poll_next(&mut cx) {
+            Poll::Ready(Some(item)) => items.push(item),
+            Poll::Ready(None) => return items,
+            Poll::Pending => {}
+        }
+    }
+}
+
+/// Like [`collect_stream`], but panics naming `what` if `stream` hasn't
+/// ended within `timeout`.
+pub fn collect_stream_timeout<S: Stream + Unpin>(
+    stream: S,
+    timeout: Duration,
+    what: &str,
+) -> Vec<S::Item> {
+    let mut stream = Box::pin(stream);
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    let mut items = Vec::new();
+    let start = Instant::now();
+    loop {
+        match stream.as_mut().poll_next(&mut cx) {
+            Poll::Ready(Some(item)) => items.push(item),
+            Poll::Ready(None) => return items,
+            Poll::Pending => assert!(
+                start.elapsed() < timeout,
+                "timed out after {:?} waiting for {} to finish yielding items",
+                timeout,
+                what
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{collector, expect};
+    use std::{
+        pin::Pin,
+        task::{Context as TaskContext, Poll as TaskPoll},
+    };
+    use tracing::Instrument;
+
+    struct PollN {
+        remaining: usize,
+    }
+
+    impl Future for PollN {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> TaskPoll<()> {
+            if self.remaining == 0 {
+                TaskPoll::Ready(())
+            } else {
+                self.remaining -= 1;
+                cx.waker().wake_by_ref();
+                TaskPoll::Pending
+            }
+        }
+    }
+
+    #[test]
+    fn block_on_enters_and_exits_span_once_per_poll() {
+        // Two real polls (the first returns `Pending`, the second
+        // `Ready`), plus one more enter/exit pair and a `drop_span` when
+        // the `Instrumented` future itself is dropped.
+        let span = expect::span().named("my_span");
+        let (collector, handle) = collector::mock()
+            .enter(&span)
+            .exit(&span)
+            .enter(&span)
+            .exit(&span)
+            .enter(&span)
+            .exit(&span)
+            .drop_span(span)
+            .only()
+            .run_with_handle();
+
+        tracing::collect::with_default(collector, || {
+            let future = PollN { remaining: 1 }.instrument(tracing::info_span!("my_span"));
+            block_on(future);
+        });
+
+        handle.assert_finished();
+    }
+
+    #[test]
+    #[should_panic(expected = "timed out")]
+    fn block_on_timeout_panics_if_never_ready() {
+        struct Never;
+        impl Future for Never {
+            type Output = ();
+            fn poll(self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> TaskPoll<()> {
+                cx.waker().wake_by_ref();
+                TaskPoll::Pending
+            }
+        }
+
+        block_on_timeout(Never, Duration::from_millis(10), "a future that never completes");
+    }
+
+    #[test]
+    fn collect_stream_collects_every_item() {
+        let stream = tokio_stream::iter(vec![1, 2, 3]);
+        assert_eq!(collect_stream(stream), vec![1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "timed out")]
+    fn collect_stream_timeout_panics_if_never_ends() {
+        struct Never;
+        impl Stream for Never {
+            type Item = ();
+            fn poll_next(
+                self: Pin<&mut Self>,
+                cx: &mut TaskContext<'_>,
+            ) -> TaskPoll<Option<()>> {
+                cx.waker().wake_by_ref();
+                TaskPoll::Pending
+            }
+        }
+
+        collect_stream_timeout(Never, Duration::from_millis(10), "a stream that never ends");
+    }
+}
@@ -39,6 +39,7 @@ pub mod collector;
 pub mod event;
 pub mod expect;
 pub mod field;
+pub mod future;
 mod metadata;
 pub mod span;
 
@@ -0,0 +1,274 @@
+//! An alternative, indexed serialization mode for high-frequency events.
+//!
+//! [`SerializeEvent`](crate::SerializeEvent) re-serializes an event's field
+//! names on every single event, which is wasteful when a callsite fires
+//! often: the field names never change between occurrences of the same
+//! callsite. This module instead serializes a callsite's field names exactly
+//! once, in a [`SerializeCallsiteSchema`], identified by a [`CallsiteId`].
+//! Subsequent events from that callsite are serialized as an
+//! [`SerializeIndexedEvent`], which carries only the callsite id and the
+//! positional field values, omitting the names entirely.
+//!
+//! Consumers are expected to serialize a [`SerializeCallsiteSchema`] the
+//! first time they observe a given callsite, then [`SerializeIndexedEvent`]
+//! for every occurrence (including the first). [`IndexedEventDecoder`]
+//! implements the other half of this protocol: it collects
+//! [`CallsiteSchema`]s as they arrive and uses them to reassociate field
+//! names with the positional values of a decoded [`IndexedEvent`].
+use std::{collections::HashMap, fmt};
+
+use serde::{
+    ser::{SerializeSeq, SerializeStruct},
+    Deserialize, Serialize, Serializer,
+};
+use tracing_core::{
+    callsite::{Callsite, Identifier},
+    field::{Field, Visit},
+    Event, Level, Metadata,
+};
+
+fn level_str(level: &Level) -> &'static str {
+    if level == &Level::ERROR {
+        "ERROR"
+    } else if level == &Level::WARN {
+        "WARN"
+    } else if level == &Level::INFO {
+        "INFO"
+    } else if level == &Level::DEBUG {
+        "DEBUG"
+    } else {
+        "TRACE"
+    }
+}
+
+/// A stable identifier for a callsite, suitable for serialization.
+///
+/// This is derived from the callsite's `'static` address, so it is only
+/// stable for the lifetime of the process; it should not be persisted across
+/// runs.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct CallsiteId(pub u64);
+
+impl From<&Identifier> for CallsiteId {
+    fn from(id: &Identifier) -> Self {
+        // `Identifier`'s inner pointer is the callsite's own `'static`
+        // registration, so its address is stable for the life of the
+        // process; this mirrors `Identifier`'s own `PartialEq`/`Hash` impls.
+        CallsiteId(id.0 as *const dyn Callsite as *const () as u64)
+    }
+}
+
+/// Serializes the field schema for a callsite: its name, target, level, and
+/// the names of its fields, in declaration order.
+///
+/// This should be serialized once per callsite, before or alongside the
+/// first [`SerializeIndexedEvent`] referencing it.
+#[derive(Debug)]
+pub struct SerializeCallsiteSchema<'a>(pub &'a Metadata<'a>);
+
+impl Serialize for SerializeCallsiteSchema<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("CallsiteSchema", 5)?;
+        state.serialize_field("callsite", &CallsiteId::from(&self.0.callsite()))?;
+        state.serialize_field("name", self.0.name())?;
+        state.serialize_field("target", self.0.target())?;
+        state.serialize_field("level", level_str(self.0.level()))?;
+        let fields: Vec<&str> = self.0.fields().iter().map(|f| f.name()).collect();
+        state.serialize_field("fields", &fields)?;
+        state.end()
+    }
+}
+
+/// Serializes an event as its callsite id plus the positional values of its
+/// fields, omitting field names.
+///
+/// Decoding an `SerializeIndexedEvent` requires already having observed the
+/// matching [`SerializeCallsiteSchema`] for its callsite; see
+/// [`IndexedEventDecoder`].
+#[derive(Debug)]
+pub struct SerializeIndexedEvent<'a>(pub &'a Event<'a>);
+
+impl Serialize for SerializeIndexedEvent<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("IndexedEvent", 2)?;
+        state.serialize_field("callsite", &CallsiteId::from(&self.0.metadata().callsite()))?;
+        // `serialize_field` can't take a value we haven't fully computed
+        // yet, so the positional values are collected through a `Visit`
+        // into a temporary seq first.
+        state.serialize_field("values", &IndexedValues(self.0))?;
+        state.end()
+    }
+}
+
+struct IndexedValues<'a>(&'a Event<'a>);
+
+impl Serialize for IndexedValues<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let seq = serializer.serialize_seq(None)?;
+        let mut visitor = SerdeSeqVisitor {
+            serializer: seq,
+            state: Ok(()),
+        };
+        self.0.record(&mut visitor);
+        visitor.finish()
+    }
+}
+
+/// Implements `tracing_core::field::Visit` for some `serde::ser::SerializeSeq`,
+/// recording only field *values* (in visitation order), and discarding field
+/// names.
+struct SerdeSeqVisitor<S: SerializeSeq> {
+    serializer: S,
+    state: Result<(), S::Error>,
+}
+
+impl<S: SerializeSeq> SerdeSeqVisitor<S> {
+    fn finish(self) -> Result<S::Ok, S::Error> {
+        self.state?;
+        self.serializer.end()
+    }
+}
+
+impl<S: SerializeSeq> Visit for SerdeSeqVisitor<S> {
+    fn record_bool(&mut self, _field: &Field, value: bool) {
+        if self.state.is_ok() {
+            self.state = self.serializer.serialize_element(&value);
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, value: &dyn fmt::Debug) {
+        if self.state.is_ok() {
+            self.state = self
+                .serializer
+                .serialize_element(&format_args!("{:?}", value));
+        }
+    }
+
+    fn record_u64(&mut self, _field: &Field, value: u64) {
+        if self.state.is_ok() {
+            self.state = self.serializer.serialize_element(&value);
+        }
+    }
+
+    fn record_i64(&mut self, _field: &Field, value: i64) {
+        if self.state.is_ok() {
+            self.state = self.serializer.serialize_element(&value);
+        }
+    }
+
+    fn record_f64(&mut self, _field: &Field, value: f64) {
+        if self.state.is_ok() {
+            self.state = self.serializer.serialize_element(&value);
+        }
+    }
+
+    fn record_str(&mut self, _field: &Field, value: &str) {
+        if self.state.is_ok() {
+            self.state = self.serializer.serialize_element(&value);
+        }
+    }
+}
+
+/// An owned field value, as reconstructed from a decoded [`IndexedEvent`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum OwnedValue {
+    /// A boolean value.
+    Bool(bool),
+    /// A signed integer value.
+    I64(i64),
+    /// An unsigned integer value.
+    U64(u64),
+    /// A floating-point value.
+    F64(f64),
+    /// A string, or the `Debug`/`Display` representation of a value that
+    /// wasn't one of the other primitive kinds.
+    Str(String),
+}
+
+/// The deserialized form of a [`SerializeCallsiteSchema`].
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CallsiteSchema {
+    /// The callsite this schema describes.
+    pub callsite: CallsiteId,
+    /// The span or event's name.
+    pub name: String,
+    /// The span or event's target.
+    pub target: String,
+    /// The span or event's level, as a string (e.g. `"INFO"`).
+    pub level: String,
+    /// The names of the callsite's fields, in declaration order.
+    pub fields: Vec<String>,
+}
+
+/// The deserialized form of a [`SerializeIndexedEvent`].
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct IndexedEvent {
+    /// The callsite this event was recorded at.
+    pub callsite: CallsiteId,
+    /// The event's field values, in the same order as the matching
+    /// [`CallsiteSchema`]'s `fields`.
+    pub values: Vec<OwnedValue>,
+}
+
+/// A decoded event, with field names reassociated with their values.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedEvent {
+    /// The event's name.
+    pub name: String,
+    /// The event's target.
+    pub target: String,
+    /// The event's level, as a string (e.g. `"INFO"`).
+    pub level: String,
+    /// The event's fields, paired with their values.
+    pub fields: Vec<(String, OwnedValue)>,
+}
+
+/// Reassembles [`IndexedEvent`]s into [`DecodedEvent`]s by remembering the
+/// [`CallsiteSchema`] observed for each callsite.
+///
+/// A decoder must see the schema for a callsite (via [`register`]) before it
+/// can decode events from that callsite; this mirrors the serialization side
+/// only sending the schema once, the first time a callsite is seen.
+///
+/// [`register`]: IndexedEventDecoder::register
+#[derive(Clone, Debug, Default)]
+pub struct IndexedEventDecoder {
+    schemas: HashMap<CallsiteId, CallsiteSchema>,
+}
+
+impl IndexedEventDecoder {
+    /// Returns a new, empty decoder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a callsite's schema, so that events from it can later be
+    /// decoded.
+    pub fn register(&mut self, schema: CallsiteSchema) {
+        self.schemas.insert(schema.callsite, schema);
+    }
+
+    /// Decodes an [`IndexedEvent`] into a [`DecodedEvent`], returning `None`
+    /// if the event's callsite schema has not been [registered][register].
+    ///
+    /// [register]: IndexedEventDecoder::register
+    pub fn decode(&self, event: IndexedEvent) -> Option<DecodedEvent> {
+        let schema = self.schemas.get(&event.callsite)?;
+        let fields = schema.fields.iter().cloned().zip(event.values).collect();
+        Some(DecodedEvent {
+            name: schema.name.clone(),
+            target: schema.target.clone(),
+            level: schema.level.clone(),
+            fields,
+        })
+    }
+}
@@ -131,6 +131,12 @@
 //
 //!   **Note**:`tracing-serde`'s `no_std` support requires `liballoc`.
 //!
+//! * `postcard`: Enables the [`wire`] module, a compact, length-delimited
+//!   binary encoding of events and metadata built on [`postcard`][postcard-crate]'s
+//!   COBS framing. Unlike the `std`-only [`owned`] and [`replay`] modules,
+//!   this works with `default-features = false`, making it suitable for
+//!   embedded and other `no_std` targets where JSON is too heavy.
+//!
 //! ## Supported Rust Versions
 //!
 //! Tracing is built against the latest stable release. The minimum supported
@@ -147,6 +153,7 @@
 //!
 //! [`tracing`]: https://crates.io/crates/tracing
 //! [`serde`]: https://crates.io/crates/serde
+//! [postcard-crate]: https://crates.io/crates/postcard
 #![doc(
     html_logo_url = "https://raw.githubusercontent.com/tokio-rs/tracing/master/assets/logo-type.png",
     html_favicon_url = "https://raw.githubusercontent.com/tokio-rs/tracing/master/assets/favicon.ico",
@@ -186,13 +193,29 @@ use serde::{
 
 use tracing_core::{
     event::Event,
-    field::{Field, FieldSet, Visit},
+    field::{self, Field, FieldSet, Value, Visit},
     metadata::{Level, Metadata},
     span::{Attributes, Id, Record},
 };
 
 pub mod fields;
 
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod indexed;
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod owned;
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod replay;
+
+#[cfg(feature = "postcard")]
+#[cfg_attr(docsrs, doc(cfg(feature = "postcard")))]
+pub mod wire;
+
 #[derive(Debug)]
 pub struct SerializeField<'a>(&'a Field);
 
@@ -221,6 +244,158 @@ impl Serialize for SerializeFieldSet<'_> {
     }
 }
 
+/// Serializes the elements of a recorded [`field::Seq`] as a sequence,
+/// reusing `field` (the `Seq`'s own field) as the key passed to each
+/// element's [`Value::record`], since positional elements have no `Field`
+/// of their own.
+struct SerializeSeqField<'a> {
+    field: &'a Field,
+    seq: &'a dyn field::Seq,
+}
+
+impl Serialize for SerializeSeqField<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(None)?;
+        let mut state = Ok(());
+        self.seq.for_each(&mut |value| {
+            if state.is_ok() {
+                state = serialize_seq_element(&mut seq, self.field, value);
+            }
+        });
+        state?;
+        seq.end()
+    }
+}
+
+/// Serializes the entries of a recorded [`field::Map`] as a map, reusing
+/// `field` (the `Map`'s own field) as the key passed to each entry's
+/// [`Value::record`], since map entries have no `Field` of their own.
+struct SerializeMapField<'a> {
+    field: &'a Field,
+    map: &'a dyn field::Map,
+}
+
+impl Serialize for SerializeMapField<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+        let mut state = Ok(());
+        self.map.for_each(&mut |key, value| {
+            if state.is_ok() {
+                state = (|| {
+                    map.serialize_key(key)?;
+                    serialize_map_value(&mut map, self.field, value)
+                })();
+            }
+        });
+        state?;
+        map.end()
+    }
+}
+
+fn serialize_seq_element<S>(
+    serializer: &mut S,
+    field: &Field,
+    value: &dyn Value,
+) -> Result<(), S::Error>
+where
+    S: SerializeSeq,
+{
+    struct ElementVisitor<'a, S: SerializeSeq> {
+        serializer: &'a mut S,
+        state: Result<(), S::Error>,
+    }
+
+    impl<S: SerializeSeq> Visit for ElementVisitor<'_, S> {
+        fn record_bool(&mut self, _: &Field, value: bool) {
+            self.state = self.serializer.serialize_element(&value);
+        }
+
+        fn record_u64(&mut self, _: &Field, value: u64) {
+            self.state = self.serializer.serialize_element(&value);
+        }
+
+        fn record_i64(&mut self, _: &Field, value: i64) {
+            self.state = self.serializer.serialize_element(&value);
+        }
+
+        fn record_f64(&mut self, _: &Field, value: f64) {
+            self.state = self.serializer.serialize_element(&value);
+        }
+
+        fn record_str(&mut self, _: &Field, value: &str) {
+            self.state = self.serializer.serialize_element(&value);
+        }
+
+        fn record_debug(&mut self, _: &Field, value: &dyn fmt::Debug) {
+            self.state = self
+                .serializer
+                .serialize_element(&format_args!("{:?}", value));
+        }
+    }
+
+    let mut visitor = ElementVisitor {
+        serializer,
+        state: Ok(()),
+    };
+    value.record(field, &mut visitor);
+    visitor.state
+}
+
+fn serialize_map_value<S>(
+    serializer: &mut S,
+    field: &Field,
+    value: &dyn Value,
+) -> Result<(), S::Error>
+where
+    S: SerializeMap,
+{
+    struct EntryVisitor<'a, S: SerializeMap> {
+        serializer: &'a mut S,
+        state: Result<(), S::Error>,
+    }
+
+    impl<S: SerializeMap> Visit for EntryVisitor<'_, S> {
+        fn record_bool(&mut self, _: &Field, value: bool) {
+            self.state = self.serializer.serialize_value(&value);
+        }
+
+        fn record_u64(&mut self, _: &Field, value: u64) {
+            self.state = self.serializer.serialize_value(&value);
+        }
+
+        fn record_i64(&mut self, _: &Field, value: i64) {
+            self.state = self.serializer.serialize_value(&value);
+        }
+
+        fn record_f64(&mut self, _: &Field, value: f64) {
+            self.state = self.serializer.serialize_value(&value);
+        }
+
+        fn record_str(&mut self, _: &Field, value: &str) {
+            self.state = self.serializer.serialize_value(&value);
+        }
+
+        fn record_debug(&mut self, _: &Field, value: &dyn fmt::Debug) {
+            self.state = self
+                .serializer
+                .serialize_value(&format_args!("{:?}", value));
+        }
+    }
+
+    let mut visitor = EntryVisitor {
+        serializer,
+        state: Ok(()),
+    };
+    value.record(field, &mut visitor);
+    visitor.state
+}
+
 #[derive(Debug)]
 pub struct SerializeLevel<'a>(&'a Level);
 
@@ -259,6 +434,47 @@ impl Serialize for SerializeId<'_> {
     }
 }
 
+/// A stable identifier for a piece of [`Metadata`], derived from its
+/// target, name, file, and line.
+///
+/// Unlike [`tracing_core::callsite::Identifier`], which is derived from a
+/// callsite's `'static` address and is therefore only meaningful within the
+/// process that registered it, a `MetadataId` is a deterministic hash of the
+/// callsite's source location. This makes it stable across process
+/// restarts, so it can be used to correlate the same callsite's metadata
+/// across independent streaming sessions -- for example, letting a remote
+/// consumer recognize a callsite it has already indexed (via a
+/// [`SerializeMetadataIndexEntry`]) without needing to compare the full
+/// metadata every time.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize)]
+pub struct MetadataId(pub u64);
+
+impl MetadataId {
+    /// Computes the `MetadataId` for the given `metadata`.
+    pub fn of(metadata: &Metadata<'_>) -> Self {
+        // FNV-1a. This is used instead of `std`'s `RandomState`-seeded
+        // hasher because it must produce the same id for the same callsite
+        // on every run, including across processes, and it works without
+        // the `std` feature.
+        const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+        const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+        fn hash_bytes(hash: u64, bytes: &[u8]) -> u64 {
+            bytes.iter().fold(hash, |hash, &byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+            })
+        }
+
+        let mut hash = FNV_OFFSET_BASIS;
+        hash = hash_bytes(hash, metadata.target().as_bytes());
+        hash = hash_bytes(hash, metadata.name().as_bytes());
+        hash = hash_bytes(hash, metadata.file().unwrap_or_default().as_bytes());
+        hash = hash_bytes(hash, &metadata.line().unwrap_or(0).to_le_bytes());
+
+        MetadataId(hash)
+    }
+}
+
 #[derive(Debug)]
 pub struct SerializeMetadata<'a>(&'a Metadata<'a>);
 
@@ -267,7 +483,8 @@ impl Serialize for SerializeMetadata<'_> {
     where
         S: Serializer,
     {
-        let mut state = serializer.serialize_struct("Metadata", 9)?;
+        let mut state = serializer.serialize_struct("Metadata", 10)?;
+        state.serialize_field("metadata_id", &MetadataId::of(self.0))?;
         state.serialize_field("name", self.0.name())?;
         state.serialize_field("target", self.0.target())?;
         state.serialize_field("level", &SerializeLevel(self.0.level()))?;
@@ -281,6 +498,28 @@ impl Serialize for SerializeMetadata<'_> {
     }
 }
 
+/// A message pairing a [`MetadataId`] with the full [`SerializeMetadata`] it
+/// identifies.
+///
+/// A sender should emit one `SerializeMetadataIndexEntry` the first time it
+/// observes a given callsite, so that a remote consumer can build up an
+/// index of `MetadataId`s to full metadata and avoid depending on receiving
+/// (or re-parsing) the full metadata on every subsequent message.
+#[derive(Debug)]
+pub struct SerializeMetadataIndexEntry<'a>(pub &'a Metadata<'a>);
+
+impl Serialize for SerializeMetadataIndexEntry<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("MetadataIndexEntry", 2)?;
+        state.serialize_field("id", &MetadataId::of(self.0))?;
+        state.serialize_field("metadata", &SerializeMetadata(self.0))?;
+        state.end()
+    }
+}
+
 /// Implements `serde::Serialize` to write `Event` data to a serializer.
 #[derive(Debug)]
 pub struct SerializeEvent<'a>(&'a Event<'a>);
@@ -419,6 +658,22 @@ where
             self.state = self.serializer.serialize_entry(field.name(), &value)
         }
     }
+
+    fn record_seq(&mut self, field: &Field, _debug: &dyn fmt::Debug, seq: &dyn field::Seq) {
+        if self.state.is_ok() {
+            self.state = self
+                .serializer
+                .serialize_entry(field.name(), &SerializeSeqField { field, seq })
+        }
+    }
+
+    fn record_map(&mut self, field: &Field, _debug: &dyn fmt::Debug, map: &dyn field::Map) {
+        if self.state.is_ok() {
+            self.state = self
+                .serializer
+                .serialize_entry(field.name(), &SerializeMapField { field, map })
+        }
+    }
 }
 
 /// Implements `tracing_core::field::Visit` for some `serde::ser::SerializeStruct`.
@@ -471,6 +726,22 @@ where
             self.state = self.serializer.serialize_field(field.name(), &value)
         }
     }
+
+    fn record_seq(&mut self, field: &Field, _debug: &dyn fmt::Debug, seq: &dyn field::Seq) {
+        if self.state.is_ok() {
+            self.state = self
+                .serializer
+                .serialize_field(field.name(), &SerializeSeqField { field, seq })
+        }
+    }
+
+    fn record_map(&mut self, field: &Field, _debug: &dyn fmt::Debug, map: &dyn field::Map) {
+        if self.state.is_ok() {
+            self.state = self
+                .serializer
+                .serialize_field(field.name(), &SerializeMapField { field, map })
+        }
+    }
 }
 
 impl<S: SerializeStruct> SerdeStructVisitor<S> {
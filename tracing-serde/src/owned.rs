@@ -0,0 +1,141 @@
+//! Owned, `Deserialize`-able mirrors of the borrowed `tracing-core` types.
+//!
+//! [`SerializeMetadata`](crate::SerializeMetadata),
+//! [`SerializeEvent`](crate::SerializeEvent), and
+//! [`SerializeRecord`](crate::SerializeRecord) only implement `Serialize`:
+//! they borrow from a live `Metadata`/`Event`/`Record`, so there is nothing
+//! to deserialize *into*. This module adds owned counterparts --
+//! [`OwnedMetadata`], [`OwnedEvent`], and [`OwnedRecord`] -- that round-trip
+//! through any `serde` format, for consumers that just want the decoded data
+//! rather than a live [`Dispatch`](tracing_core::Dispatch) to replay it into.
+//!
+//! For reconstructing `'static` `Metadata` and re-dispatching decoded spans
+//! and events as if they had originated locally, see [`crate::replay`]
+//! instead.
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use tracing_core::{
+    field::{Field, Visit},
+    span::Record,
+    Event, Metadata,
+};
+
+use crate::indexed::OwnedValue;
+
+/// An owned, `Deserialize`-able mirror of [`Metadata`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OwnedMetadata {
+    /// The span or event's name.
+    pub name: String,
+    /// The span or event's target.
+    pub target: String,
+    /// The span or event's level, as a string (e.g. `"INFO"`).
+    pub level: String,
+    /// The module where the span or event occurred, if known.
+    pub module_path: Option<String>,
+    /// The source file where the span or event occurred, if known.
+    pub file: Option<String>,
+    /// The line number where the span or event occurred, if known.
+    pub line: Option<u32>,
+    /// The names of the span or event's fields, in declaration order.
+    pub fields: Vec<String>,
+    /// Whether this metadata describes a span.
+    pub is_span: bool,
+    /// Whether this metadata describes an event.
+    pub is_event: bool,
+}
+
+impl From<&Metadata<'_>> for OwnedMetadata {
+    fn from(metadata: &Metadata<'_>) -> Self {
+        Self {
+            name: metadata.name().to_string(),
+            target: metadata.target().to_string(),
+            level: metadata.level().to_string(),
+            module_path: metadata.module_path().map(str::to_string),
+            file: metadata.file().map(str::to_string),
+            line: metadata.line(),
+            fields: metadata
+                .fields()
+                .iter()
+                .map(|f| f.name().to_string())
+                .collect(),
+            is_span: metadata.is_span(),
+            is_event: metadata.is_event(),
+        }
+    }
+}
+
+/// An owned, `Deserialize`-able mirror of [`Event`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OwnedEvent {
+    /// The event's metadata.
+    pub metadata: OwnedMetadata,
+    /// The event's fields, in declaration order.
+    pub fields: Vec<(String, OwnedValue)>,
+}
+
+impl From<&Event<'_>> for OwnedEvent {
+    fn from(event: &Event<'_>) -> Self {
+        let mut visitor = OwnedValueVisitor::default();
+        event.record(&mut visitor);
+        Self {
+            metadata: OwnedMetadata::from(event.metadata()),
+            fields: visitor.0,
+        }
+    }
+}
+
+/// An owned, `Deserialize`-able mirror of [`Record`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OwnedRecord {
+    /// The recorded fields, in the order they were visited.
+    pub fields: Vec<(String, OwnedValue)>,
+}
+
+impl From<&Record<'_>> for OwnedRecord {
+    fn from(record: &Record<'_>) -> Self {
+        let mut visitor = OwnedValueVisitor::default();
+        record.record(&mut visitor);
+        Self { fields: visitor.0 }
+    }
+}
+
+/// Collects a `tracing_core::field::Visit` walk into owned `(name, value)`
+/// pairs.
+#[derive(Default)]
+struct OwnedValueVisitor(Vec<(String, OwnedValue)>);
+
+impl Visit for OwnedValueVisitor {
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0
+            .push((field.name().to_string(), OwnedValue::Bool(value)));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0
+            .push((field.name().to_string(), OwnedValue::I64(value)));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0
+            .push((field.name().to_string(), OwnedValue::U64(value)));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0
+            .push((field.name().to_string(), OwnedValue::F64(value)));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0
+            .push((field.name().to_string(), OwnedValue::Str(value.to_string())));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.push((
+            field.name().to_string(),
+            OwnedValue::Str(format!("{:?}", value)),
+        ));
+    }
+}
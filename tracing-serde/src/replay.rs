@@ -0,0 +1,295 @@
+//! Re-dispatching deserialized spans and events into a local [`Dispatch`].
+//!
+//! The rest of `tracing-serde` covers the *producing* half of shipping trace
+//! data off-process: turning live `tracing-core` types into something
+//! `serde` can serialize. This module is the consuming half. Given
+//! [`ReplaySpan`]s and [`ReplayEvent`]s decoded from whatever transport and
+//! format the two ends agreed on, [`Replayer`] reconstructs `'static`
+//! [`Metadata`] for each distinct callsite using
+//! [`tracing_core::callsite::dynamic`], remaps the span IDs assigned by the
+//! process that originally recorded them onto IDs minted by the local
+//! [`Dispatch`], and re-records each span and event as if it had originated
+//! locally.
+//!
+//! This makes it possible to build a collector process out of nothing but
+//! `tracing-core` and `tracing-serde`: spans and events are decoded off the
+//! wire, replayed into a [`Dispatch`] wrapping an ordinary
+//! [`Collect`](tracing_core::Collect) implementation (a `tracing-subscriber`
+//! `Registry`, for instance), and from that collector's perspective the
+//! replayed trace looks exactly like one recorded in-process.
+use std::{collections::HashMap, convert::TryFrom};
+
+use serde::{Deserialize, Serialize};
+use tracing_core::{
+    callsite::dynamic::{Interner, MetadataBuilder},
+    field::{Field, Value},
+    span::{Attributes, Id},
+    Dispatch, Event, Kind, Level, Metadata,
+};
+
+use crate::indexed::OwnedValue;
+
+/// The maximum number of fields a [`ReplaySpan`] or [`ReplayEvent`] may
+/// carry.
+///
+/// [`FieldSet::value_set`] only accepts fixed-size arrays -- its bound is a
+/// sealed trait implemented for `[(&Field, Option<&(dyn Value + '_)>); N]` -- so a
+/// runtime-length list of fields has to be dispatched to one of a fixed set
+/// of array sizes. [`Replayer`] panics if it is asked to replay a span or
+/// event with more fields than this.
+pub const MAX_FIELDS: usize = 32;
+
+/// A deserialized span, ready to be replayed by a [`Replayer`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReplaySpan {
+    /// This span's ID, as assigned by the process that recorded it.
+    ///
+    /// [`Replayer`] remaps this onto an ID minted by the local `Dispatch`; any
+    /// later [`ReplaySpan::parent`] or [`ReplayEvent::parent`] naming this
+    /// `id` is translated through that same mapping.
+    pub id: u64,
+    /// The ID of this span's parent, if any, using the same remote numbering
+    /// as `id`.
+    pub parent: Option<u64>,
+    /// Whether this span is explicitly a root, regardless of whichever span
+    /// happens to be entered on the replaying thread.
+    pub is_root: bool,
+    /// The span's name.
+    pub name: String,
+    /// The span's target.
+    pub target: String,
+    /// The span's level, as a string (e.g. `"INFO"`).
+    pub level: String,
+    /// The span's fields, in declaration order.
+    pub fields: Vec<(String, OwnedValue)>,
+}
+
+/// A deserialized event, ready to be replayed by a [`Replayer`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReplayEvent {
+    /// The ID of the span this event occurred in, if any, using the same
+    /// remote numbering as [`ReplaySpan::id`].
+    pub parent: Option<u64>,
+    /// The event's name.
+    pub name: String,
+    /// The event's target.
+    pub target: String,
+    /// The event's level, as a string (e.g. `"INFO"`).
+    pub level: String,
+    /// The event's fields, in declaration order.
+    pub fields: Vec<(String, OwnedValue)>,
+}
+
+fn level_from_str(level: &str) -> Level {
+    match level {
+        "ERROR" => Level::ERROR,
+        "WARN" => Level::WARN,
+        "INFO" => Level::INFO,
+        "DEBUG" => Level::DEBUG,
+        _ => Level::TRACE,
+    }
+}
+
+/// Builds a [`tracing_core::field::ValueSet`] out of `$fields`, binds it to
+/// `$values`, and evaluates `$body` with it in scope.
+///
+/// See [`MAX_FIELDS`] for why this has to dispatch to one of a fixed set of
+/// array sizes rather than accepting a slice directly. This has to be a
+/// macro rather than a function taking `impl FnOnce(&ValueSet<'_>)`: the
+/// elided lifetime in an `Fn`-family bound is always higher-ranked, which
+/// would force the `ValueSet` to be reusable for arbitrary lifetimes instead
+/// of the one its borrowed array actually has.
+fn build_array<'v, const N: usize>(
+    pairs: Vec<(&'v Field, Option<&'v (dyn Value + 'v)>)>,
+) -> [(&'v Field, Option<&'v (dyn Value + 'v)>); N] {
+    // `Vec<T>: Debug` isn't guaranteed for `T` containing a `dyn Value`, so
+    // `Result::unwrap` (which requires it) isn't usable here.
+    match <[(&'v Field, Option<&'v (dyn Value + 'v)>); N]>::try_from(pairs) {
+        Ok(array) => array,
+        Err(pairs) => panic!("expected {} fields, got {}", N, pairs.len()),
+    }
+}
+
+macro_rules! dispatch_by_len {
+    ($pairs:expr, $field_set:expr, |$values:ident| $body:expr, [$($n:literal),*]) => {
+        match $pairs.len() {
+            $($n => {
+                let array: [(&Field, Option<&(dyn Value + '_)>); $n] = build_array($pairs);
+                let $values = $field_set.value_set(&array);
+                $body
+            })*
+            n => panic!(
+                "tracing-serde: {} fields exceeds the maximum of {} supported by `Replayer` (see `replay::MAX_FIELDS`)",
+                n, MAX_FIELDS,
+            ),
+        }
+    };
+}
+
+macro_rules! with_value_set {
+    ($field_set:expr, $fields:expr, |$values:ident| $body:expr) => {{
+        let field_set = $field_set;
+        let owned: Vec<(Field, &dyn Value)> = $fields
+            .iter()
+            .map(|(name, value)| {
+                let field = field_set
+                    .field(name)
+                    .expect("interned field must be present in its own FieldSet");
+                (field, as_value(value))
+            })
+            .collect();
+        let pairs: Vec<(&Field, Option<&(dyn Value + '_)>)> = owned
+            .iter()
+            .map(|(field, value)| (field, Some(*value)))
+            .collect();
+
+        dispatch_by_len!(
+            pairs,
+            field_set,
+            |$values| $body,
+            [
+                0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22,
+                23, 24, 25, 26, 27, 28, 29, 30, 31, 32
+            ]
+        )
+    }};
+}
+
+fn as_value(value: &OwnedValue) -> &dyn Value {
+    match value {
+        OwnedValue::Bool(b) => b,
+        OwnedValue::I64(n) => n,
+        OwnedValue::U64(n) => n,
+        OwnedValue::F64(n) => n,
+        OwnedValue::Str(s) => s,
+    }
+}
+
+/// Re-dispatches a stream of [`ReplaySpan`]s and [`ReplayEvent`]s into a
+/// local [`Dispatch`].
+///
+/// A `Replayer` owns the [`Interner`] used to reconstruct `'static` metadata
+/// for the spans and events it's given, plus the table mapping the remote
+/// span IDs found in [`ReplaySpan::id`]/[`ReplaySpan::parent`]/
+/// [`ReplayEvent::parent`] onto the IDs the local `Dispatch` minted for them.
+/// It should be reused for the lifetime of the stream it's replaying, so that
+/// later spans and events can still find the local IDs of earlier ones.
+#[derive(Debug)]
+pub struct Replayer {
+    dispatch: Dispatch,
+    interner: Interner,
+    spans: HashMap<u64, Id>,
+}
+
+impl Replayer {
+    /// Returns a new `Replayer` that re-dispatches into `dispatch`.
+    pub fn new(dispatch: Dispatch) -> Self {
+        Self {
+            dispatch,
+            interner: Interner::new(),
+            spans: HashMap::new(),
+        }
+    }
+
+    fn metadata(
+        &self,
+        kind: Kind,
+        name: &str,
+        target: &str,
+        level: &str,
+        fields: &[(String, OwnedValue)],
+    ) -> &'static Metadata<'static> {
+        let builder = if kind == Kind::SPAN {
+            MetadataBuilder::span(name, level_from_str(level))
+        } else {
+            MetadataBuilder::event(name, level_from_str(level))
+        }
+        .target(target)
+        .fields(fields.iter().map(|(name, _)| name.as_str()));
+
+        self.interner.intern(builder).0.metadata()
+    }
+
+    /// Replays a decoded span, returning the [`Id`] the local `Dispatch`
+    /// assigned it.
+    ///
+    /// `span.id` is recorded alongside the returned `Id`, so that later spans
+    /// or events naming it as their `parent` are attached to the right local
+    /// span. If `span.parent` names a span this `Replayer` hasn't seen, the
+    /// new span is created as its own root rather than panicking, since a
+    /// lossy or out-of-order transport shouldn't bring down the collector.
+    pub fn new_span(&mut self, span: ReplaySpan) -> Id {
+        let metadata = self.metadata(
+            Kind::SPAN,
+            &span.name,
+            &span.target,
+            &span.level,
+            &span.fields,
+        );
+        let field_set = metadata.fields();
+        let parent = span.parent.and_then(|id| self.spans.get(&id).cloned());
+        let id = with_value_set!(field_set, &span.fields, |values| {
+            let attrs = if span.is_root {
+                Attributes::new_root(metadata, &values)
+            } else if let Some(parent) = parent {
+                Attributes::child_of(parent, metadata, &values)
+            } else {
+                Attributes::new(metadata, &values)
+            };
+            self.dispatch.new_span(&attrs)
+        });
+        self.spans.insert(span.id, id.clone());
+        id
+    }
+
+    /// Replays a decoded event.
+    ///
+    /// If `event.parent` names a span this `Replayer` hasn't seen, the event
+    /// is dispatched as if it had no parent, rather than panicking.
+    pub fn event(&self, event: ReplayEvent) {
+        let metadata = self.metadata(
+            Kind::EVENT,
+            &event.name,
+            &event.target,
+            &event.level,
+            &event.fields,
+        );
+        let field_set = metadata.fields();
+        let parent = event.parent.and_then(|id| self.spans.get(&id).cloned());
+        with_value_set!(field_set, &event.fields, |values| {
+            let evt = match parent {
+                Some(parent) => Event::new_child_of(parent, metadata, &values),
+                None => Event::new(metadata, &values),
+            };
+            self.dispatch.event(&evt);
+        });
+    }
+
+    /// Enters the local span mapped to the remote `id`, if this `Replayer`
+    /// has seen it.
+    pub fn enter(&self, id: u64) {
+        if let Some(id) = self.spans.get(&id) {
+            self.dispatch.enter(id);
+        }
+    }
+
+    /// Exits the local span mapped to the remote `id`, if this `Replayer` has
+    /// seen it.
+    pub fn exit(&self, id: u64) {
+        if let Some(id) = self.spans.get(&id) {
+            self.dispatch.exit(id);
+        }
+    }
+
+    /// Closes the local span mapped to the remote `id`, and forgets the
+    /// mapping.
+    ///
+    /// This should be called once the remote process reports that it has
+    /// dropped its own handle to the span, mirroring the remote span's
+    /// lifetime onto the local one.
+    pub fn close(&mut self, id: u64) {
+        if let Some(id) = self.spans.remove(&id) {
+            self.dispatch.try_close(id);
+        }
+    }
+}
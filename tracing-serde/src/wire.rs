@@ -0,0 +1,157 @@
+//! A compact, length-delimited binary encoding of events and metadata, for
+//! transports (embedded UARTs, IPC pipes) where JSON's overhead is
+//! unacceptable and framing has to be self-describing.
+//!
+//! This is built on [`postcard`]'s [COBS] framing, which needs only
+//! `liballoc`, not the full standard library, so it works on `no_std`
+//! targets with the `std` feature disabled. [`WireEvent`] and
+//! [`WireMetadata`] are minimal, allocation-light mirrors of [`Event`] and
+//! [`Metadata`] -- distinct from the [`owned`](crate::owned) types, which
+//! require `std` -- used as the wire format for [`encode_event`]/
+//! [`decode_event`] and [`encode_metadata`]/[`decode_metadata`].
+//!
+//! [COBS]: https://en.wikipedia.org/wiki/Consistent_Overhead_Byte_Stuffing
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use core::fmt;
+
+use postcard::Result;
+use serde::{Deserialize, Serialize};
+use tracing_core::{
+    field::{Field, Visit},
+    Event, Metadata,
+};
+
+/// An owned field value, as carried by a [`WireEvent`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum WireValue {
+    /// A boolean value.
+    Bool(bool),
+    /// A signed integer value.
+    I64(i64),
+    /// An unsigned integer value.
+    U64(u64),
+    /// A floating-point value.
+    F64(f64),
+    /// A string, or the `Debug`/`Display` representation of a value that
+    /// wasn't one of the other primitive kinds.
+    Str(String),
+}
+
+/// A minimal, wire-format mirror of [`Metadata`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WireMetadata {
+    /// The span or event's name.
+    pub name: String,
+    /// The span or event's target.
+    pub target: String,
+    /// The span or event's level, as a string (e.g. `"INFO"`).
+    pub level: String,
+    /// Whether this metadata describes a span, as opposed to an event.
+    pub is_span: bool,
+}
+
+impl From<&Metadata<'_>> for WireMetadata {
+    fn from(metadata: &Metadata<'_>) -> Self {
+        Self {
+            name: metadata.name().into(),
+            target: metadata.target().into(),
+            level: metadata.level().to_string(),
+            is_span: metadata.is_span(),
+        }
+    }
+}
+
+/// A minimal, wire-format mirror of [`Event`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WireEvent {
+    /// The event's metadata.
+    pub metadata: WireMetadata,
+    /// The event's fields, in declaration order.
+    pub fields: Vec<(String, WireValue)>,
+}
+
+impl From<&Event<'_>> for WireEvent {
+    fn from(event: &Event<'_>) -> Self {
+        let mut visitor = WireValueVisitor(Vec::new());
+        event.record(&mut visitor);
+        Self {
+            metadata: WireMetadata::from(event.metadata()),
+            fields: visitor.0,
+        }
+    }
+}
+
+struct WireValueVisitor(Vec<(String, WireValue)>);
+
+impl Visit for WireValueVisitor {
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.push((field.name().into(), WireValue::Bool(value)));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.push((field.name().into(), WireValue::I64(value)));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.push((field.name().into(), WireValue::U64(value)));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.push((field.name().into(), WireValue::F64(value)));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0
+            .push((field.name().into(), WireValue::Str(value.into())));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0
+            .push((field.name().into(), WireValue::Str(format!("{:?}", value))));
+    }
+}
+
+/// Encodes `event` into `buf` as a single COBS-framed, length-delimited
+/// postcard message, returning the used prefix of `buf`.
+///
+/// `buf` must be large enough to hold the encoded event, or this returns
+/// [`postcard::Error::SerializeBufferFull`].
+pub fn encode_event<'a>(event: &Event<'_>, buf: &'a mut [u8]) -> Result<&'a mut [u8]> {
+    postcard::to_slice_cobs(&WireEvent::from(event), buf)
+}
+
+/// Decodes a single COBS-framed postcard message produced by
+/// [`encode_event`] back into a [`WireEvent`].
+///
+/// `buf` is mutated in place to undo the COBS framing, per
+/// [`postcard::from_bytes_cobs`].
+pub fn decode_event(buf: &mut [u8]) -> Result<WireEvent> {
+    postcard::from_bytes_cobs(buf)
+}
+
+/// Encodes `metadata` into `buf` as a single COBS-framed, length-delimited
+/// postcard message, returning the used prefix of `buf`.
+///
+/// `buf` must be large enough to hold the encoded metadata, or this returns
+/// [`postcard::Error::SerializeBufferFull`].
+pub fn encode_metadata<'a>(metadata: &Metadata<'_>, buf: &'a mut [u8]) -> Result<&'a mut [u8]> {
+    postcard::to_slice_cobs(&WireMetadata::from(metadata), buf)
+}
+
+/// Decodes a single COBS-framed postcard message produced by
+/// [`encode_metadata`] back into a [`WireMetadata`].
+///
+/// `buf` is mutated in place to undo the COBS framing, per
+/// [`postcard::from_bytes_cobs`].
+pub fn decode_metadata(buf: &mut [u8]) -> Result<WireMetadata> {
+    postcard::from_bytes_cobs(buf)
+}
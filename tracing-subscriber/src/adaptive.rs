@@ -0,0 +1,263 @@
+//! A [`Subscribe`] that automates "turn on debug logging when things break".
+//!
+//! [`AdaptiveVerbosity`] watches for `ERROR`-level events and, once a
+//! target's error rate crosses a configured threshold within a sliding
+//! window, temporarily raises that target's verbosity (by default, to
+//! [`LevelFilter::DEBUG`]) for a fixed duration before reverting it. This is
+//! meant to sit alongside a [`reload`]-wrapped [`EnvFilter`], so the extra
+//! detail needed to diagnose a spike is captured automatically, without
+//! running at that verbosity all the time.
+//!
+//! # Examples
+//!
+//! ```
+//! use tracing_subscriber::adaptive::AdaptiveVerbosity;
+//! use tracing_subscriber::{reload, EnvFilter};
+//! use tracing_subscriber::prelude::*;
+//!
+//! let base = "my_app=info";
+//! let (filter, handle) = reload::Subscriber::new(EnvFilter::new(base));
+//! let adaptive = AdaptiveVerbosity::new(handle, base, 5);
+//!
+//! tracing_subscriber::registry()
+//!     .with(filter)
+//!     .with(adaptive)
+//!     .init();
+//! ```
+//!
+//! [`Subscribe`]: crate::Subscribe
+//! [`reload`]: crate::reload
+//! [`EnvFilter`]: crate::EnvFilter
+use crate::{
+    filter::{EnvFilter, LevelFilter},
+    reload,
+    subscribe::{Context, Subscribe},
+};
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+use tracing_core::{Collect, Event, Level};
+
+/// A [`Subscribe`] that reloads a [`reload`]-wrapped [`EnvFilter`], raising a
+/// target's verbosity for a while after its error rate spikes.
+///
+/// See the [module-level documentation](self) for details.
+pub struct AdaptiveVerbosity {
+    handle: reload::Handle<EnvFilter>,
+    base: Arc<str>,
+    error_threshold: u64,
+    window: Duration,
+    raise_for: Duration,
+    raise_level: LevelFilter,
+    targets: Arc<Mutex<HashMap<String, TargetState>>>,
+}
+
+#[derive(Clone, Copy)]
+struct TargetState {
+    window_start: Instant,
+    count: u64,
+    raised_until: Option<Instant>,
+}
+
+impl fmt::Debug for AdaptiveVerbosity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AdaptiveVerbosity")
+            .field("base", &self.base)
+            .field("error_threshold", &self.error_threshold)
+            .field("window", &self.window)
+            .field("raise_for", &self.raise_for)
+            .field("raise_level", &self.raise_level)
+            .finish()
+    }
+}
+
+impl AdaptiveVerbosity {
+    /// Returns a new `AdaptiveVerbosity` that reloads `handle` once a target
+    /// has logged at least `error_threshold` `ERROR`-level events within a
+    /// 10-second window, raising that target to [`LevelFilter::DEBUG`] for
+    /// 60 seconds before reverting it.
+    ///
+    /// `base` should be the same directives `handle`'s filter was
+    /// constructed with; every filter this reloads `handle` with is rebuilt
+    /// from `base` plus whichever targets are currently raised, so `base`
+    /// should be kept in sync if `handle` is also reloaded from elsewhere.
+    ///
+    /// Use [`window`](Self::window), [`raise_for`](Self::raise_for), and
+    /// [`raise_level`](Self::raise_level) to override the defaults.
+    pub fn new(
+        handle: reload::Handle<EnvFilter>,
+        base: impl Into<String>,
+        error_threshold: u64,
+    ) -> Self {
+        Self {
+            handle,
+            base: base.into().into(),
+            error_threshold,
+            window: Duration::from_secs(10),
+            raise_for: Duration::from_secs(60),
+            raise_level: LevelFilter::DEBUG,
+            targets: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Overrides the sliding window over which errors are counted towards
+    /// `error_threshold`. Defaults to 10 seconds.
+    pub fn window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Overrides how long an affected target's verbosity stays raised.
+    /// Defaults to 60 seconds.
+    pub fn raise_for(mut self, raise_for: Duration) -> Self {
+        self.raise_for = raise_for;
+        self
+    }
+
+    /// Overrides the level a target is raised to while its error rate is
+    /// elevated. Defaults to [`LevelFilter::DEBUG`].
+    pub fn raise_level(mut self, level: LevelFilter) -> Self {
+        self.raise_level = level;
+        self
+    }
+
+    /// Records an `ERROR`-level event for `target`, raising its verbosity if
+    /// this pushes it over `error_threshold` within the current window.
+    fn record_error(&self, target: &str) {
+        let now = Instant::now();
+        let should_raise = {
+            let mut targets = self.targets.lock().unwrap_or_else(|e| e.into_inner());
+            let state = targets.entry(target.to_string()).or_insert(TargetState {
+                window_start: now,
+                count: 0,
+                raised_until: None,
+            });
+
+            if now.duration_since(state.window_start) > self.window {
+                state.window_start = now;
+                state.count = 0;
+            }
+            state.count += 1;
+
+            let already_raised = matches!(state.raised_until, Some(until) if until > now);
+            if already_raised || state.count < self.error_threshold {
+                false
+            } else {
+                state.count = 0;
+                state.window_start = now;
+                state.raised_until = Some(now + self.raise_for);
+                true
+            }
+        };
+
+        if !should_raise {
+            return;
+        }
+
+        rebuild_and_reload(&self.handle, &self.base, self.raise_level, &self.targets);
+
+        let handle = self.handle.clone();
+        let base = self.base.clone();
+        let raise_level = self.raise_level;
+        let targets = self.targets.clone();
+        let raise_for = self.raise_for;
+        thread::spawn(move || {
+            thread::sleep(raise_for);
+            rebuild_and_reload(&handle, &base, raise_level, &targets);
+        });
+    }
+}
+
+/// Rebuilds `handle`'s filter as `base` plus a directive raising every
+/// currently-raised target to `raise_level`, and reloads it.
+fn rebuild_and_reload(
+    handle: &reload::Handle<EnvFilter>,
+    base: &str,
+    raise_level: LevelFilter,
+    targets: &Mutex<HashMap<String, TargetState>>,
+) {
+    let now = Instant::now();
+    let mut directives = base.to_string();
+    let targets = targets.lock().unwrap_or_else(|e| e.into_inner());
+    for (target, state) in targets.iter() {
+        if matches!(state.raised_until, Some(until) if until > now) {
+            if !directives.is_empty() {
+                directives.push(',');
+            }
+            directives.push_str(&format!("{}={}", target, raise_level));
+        }
+    }
+    let _ = handle.reload(EnvFilter::new(directives));
+}
+
+impl<C> Subscribe<C> for AdaptiveVerbosity
+where
+    C: Collect,
+{
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, C>) {
+        if *event.metadata().level() == Level::ERROR {
+            self.record_error(event.metadata().target());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn error_spike_raises_target() {
+        let (filter, handle) = reload::Subscriber::new(EnvFilter::new("my_target=info"));
+        let adaptive = AdaptiveVerbosity::new(handle.clone(), "my_target=info", 3);
+        let _guard = tracing::collect::set_default(crate::registry().with(filter).with(adaptive));
+
+        for _ in 0..3 {
+            tracing::error!(target: "my_target", "oh no");
+        }
+
+        let reloaded = handle.with_current(|f| f.to_string()).unwrap();
+        assert!(
+            reloaded.contains("my_target=debug"),
+            "expected target to be raised, got: {}",
+            reloaded
+        );
+    }
+
+    #[test]
+    fn error_below_threshold_does_not_raise() {
+        let (filter, handle) = reload::Subscriber::new(EnvFilter::new("my_target=info"));
+        let adaptive = AdaptiveVerbosity::new(handle.clone(), "my_target=info", 3);
+        let _guard = tracing::collect::set_default(crate::registry().with(filter).with(adaptive));
+
+        tracing::error!(target: "my_target", "oh no");
+
+        let reloaded = handle.with_current(|f| f.to_string()).unwrap();
+        assert!(!reloaded.contains("debug"));
+    }
+
+    #[test]
+    fn raise_reverts_after_duration() {
+        let (filter, handle) = reload::Subscriber::new(EnvFilter::new("my_target=info"));
+        let adaptive = AdaptiveVerbosity::new(handle.clone(), "my_target=info", 1)
+            .raise_for(Duration::from_millis(10));
+        let _guard = tracing::collect::set_default(crate::registry().with(filter).with(adaptive));
+
+        tracing::error!(target: "my_target", "oh no");
+
+        assert!(handle.with_current(|f| f.to_string()).unwrap().contains("debug"));
+
+        thread::sleep(Duration::from_millis(100));
+
+        let reloaded = handle.with_current(|f| f.to_string()).unwrap();
+        assert!(
+            !reloaded.contains("debug"),
+            "expected raise to have reverted, got: {}",
+            reloaded
+        );
+    }
+}
@@ -0,0 +1,388 @@
+//! A tiny HTTP endpoint for inspecting and updating an [`EnvFilter`] at
+//! runtime.
+//!
+//! Every production service that uses [`EnvFilter`] eventually grows its own
+//! bespoke glue for this: an endpoint an operator can hit to see what's
+//! currently enabled, change it without a restart, and check whether any
+//! layer has had to drop data. [`AdminServer`] is that glue, built on
+//! [`reload::Handle`] so it requires no dependencies beyond the standard
+//! library.
+//!
+//! This is intentionally minimal: a single worker thread accepts
+//! connections and speaks just enough HTTP/1.1 to serve three routes.
+//! It is not meant to replace a real metrics/control plane, just to cover
+//! the common case of a debug endpoint bound to a loopback or internal
+//! address.
+//!
+//! - `GET /filter` returns the current filter's directive string.
+//! - `PUT /filter` parses the request body as a directive string and, if
+//!   valid, replaces the current filter with it.
+//! - `GET /dropped` returns the current value of each counter registered
+//!   with [`AdminServer::with_counter`], one `name value` pair per line.
+//!   This is meant for counters like [`Offload::dropped_events`].
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use std::sync::{atomic::AtomicUsize, Arc};
+//! use tracing_subscriber::{admin::AdminServer, filter::EnvFilter, reload, prelude::*};
+//!
+//! let (filter, handle) = reload::Subscriber::new(EnvFilter::new("info"));
+//! tracing_subscriber::registry().with(filter).init();
+//!
+//! let dropped = Arc::new(AtomicUsize::new(0));
+//! let (server, _guard) =
+//!     AdminServer::bind("127.0.0.1:0", handle).expect("failed to bind admin endpoint");
+//! let _server = server.with_counter("my_exporter.dropped", dropped);
+//! ```
+//!
+//! [`Offload::dropped_events`]: crate::offload::Offload::dropped_events
+use crate::{filter::EnvFilter, reload};
+use std::{
+    fmt,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+/// How often the accept loop wakes up to check whether it's been asked to
+/// shut down.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// The maximum size of a request body this server will read, to bound how
+/// much memory a single `PUT /filter` request can consume.
+const MAX_BODY_LEN: usize = 64 * 1024;
+
+/// The maximum size of a single line (the request line, or one header) this
+/// server will buffer, so a connection that never sends a `\n` can't make
+/// [`BufRead::read_line`] grow its buffer without bound.
+const MAX_LINE_LEN: usize = 8 * 1024;
+
+/// The maximum total size of a request's headers, to bound how long a single
+/// connection can keep the worker thread busy reading them.
+const MAX_HEADERS_LEN: usize = 64 * 1024;
+
+/// How long a connection may go without making read progress before it's
+/// dropped, so a client that stops sending mid-request can't block the
+/// server's single worker thread indefinitely.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A tiny HTTP endpoint for inspecting and updating an [`EnvFilter`] at
+/// runtime.
+///
+/// See the [module-level documentation](self) for details.
+#[derive(Clone)]
+pub struct AdminServer {
+    local_addr: SocketAddr,
+    counters: Arc<Mutex<Vec<(String, Arc<AtomicUsize>)>>>,
+}
+
+impl fmt::Debug for AdminServer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AdminServer")
+            .field("local_addr", &self.local_addr)
+            .finish()
+    }
+}
+
+/// Stops an [`AdminServer`]'s worker thread when dropped.
+#[must_use]
+pub struct AdminServerGuard {
+    shutdown: Arc<AtomicBool>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl fmt::Debug for AdminServerGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AdminServerGuard").finish()
+    }
+}
+
+impl AdminServer {
+    /// Binds a new `AdminServer` to `addr`, serving the filter wrapped by
+    /// `handle`.
+    pub fn bind(
+        addr: impl ToSocketAddrs,
+        handle: reload::Handle<EnvFilter>,
+    ) -> io::Result<(Self, AdminServerGuard)> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        let local_addr = listener.local_addr()?;
+
+        let counters: Arc<Mutex<Vec<(String, Arc<AtomicUsize>)>>> = Arc::new(Mutex::new(Vec::new()));
+        let server = Self {
+            local_addr,
+            counters: counters.clone(),
+        };
+
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let worker_shutdown = shutdown.clone();
+        let worker_handle = handle;
+        let worker_counters = counters;
+        let join_handle = thread::Builder::new()
+            .name("tracing-subscriber-admin".into())
+            .spawn(move || {
+                serve(listener, &worker_shutdown, &worker_handle, &worker_counters)
+            })
+            .expect("failed to spawn `tracing-subscriber` admin worker thread");
+
+        Ok((
+            server,
+            AdminServerGuard {
+                shutdown,
+                handle: Some(join_handle),
+            },
+        ))
+    }
+
+    /// Registers a counter to be reported under `name` by `GET /dropped`.
+    ///
+    /// This consumes and returns `self` so it can be chained onto
+    /// [`AdminServer::bind`].
+    pub fn with_counter(self, name: impl Into<String>, counter: Arc<AtomicUsize>) -> Self {
+        self.counters
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push((name.into(), counter));
+        self
+    }
+
+    /// Returns the address this server is listening on.
+    ///
+    /// This is useful when binding to `"127.0.0.1:0"` in tests, to discover
+    /// which port the OS actually assigned.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
+
+fn serve(
+    listener: TcpListener,
+    shutdown: &AtomicBool,
+    handle: &reload::Handle<EnvFilter>,
+    counters: &Mutex<Vec<(String, Arc<AtomicUsize>)>>,
+) {
+    loop {
+        if shutdown.load(Ordering::Acquire) {
+            return;
+        }
+        match listener.accept() {
+            Ok((stream, _addr)) => {
+                let _ = stream.set_nonblocking(false);
+                if let Err(e) = handle_connection(stream, handle, counters) {
+                    // A single malformed or disconnected request shouldn't
+                    // bring down the whole endpoint.
+                    let _ = e;
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                thread::sleep(POLL_INTERVAL);
+            }
+            Err(_) => thread::sleep(POLL_INTERVAL),
+        }
+    }
+}
+
+fn handle_connection(
+    stream: TcpStream,
+    handle: &reload::Handle<EnvFilter>,
+    counters: &Mutex<Vec<(String, Arc<AtomicUsize>)>>,
+) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let request_line = read_bounded_line(&mut reader, MAX_LINE_LEN)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("").to_owned();
+    let path = parts.next().unwrap_or("").to_owned();
+
+    let mut content_length = 0usize;
+    let mut headers_len = 0usize;
+    loop {
+        let header = read_bounded_line(&mut reader, MAX_LINE_LEN)?;
+        if header.is_empty() {
+            break;
+        }
+        headers_len += header.len();
+        if headers_len > MAX_HEADERS_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "request headers too large",
+            ));
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(value) = header
+            .split_once(':')
+            .filter(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+            .map(|(_, value)| value.trim())
+        {
+            content_length = value.parse().unwrap_or(0);
+        }
+    }
+
+    let mut body = vec![0u8; content_length.min(MAX_BODY_LEN)];
+    reader.read_exact(&mut body)?;
+
+    let mut stream = stream;
+    match (method.as_str(), path.as_str()) {
+        ("GET", "/filter") => {
+            let current = handle
+                .with_current(|filter| filter.to_string())
+                .unwrap_or_default();
+            respond(&mut stream, 200, "OK", &current)
+        }
+        ("PUT", "/filter") => match std::str::from_utf8(&body) {
+            Ok(directives) => match EnvFilter::try_new(directives.trim()) {
+                Ok(filter) => match handle.reload(filter) {
+                    Ok(()) => respond(&mut stream, 200, "OK", "filter updated\n"),
+                    Err(e) => respond(&mut stream, 500, "Internal Server Error", &e.to_string()),
+                },
+                Err(e) => respond(&mut stream, 400, "Bad Request", &e.to_string()),
+            },
+            Err(_) => respond(&mut stream, 400, "Bad Request", "body is not valid UTF-8\n"),
+        },
+        ("GET", "/dropped") => {
+            let mut body = String::new();
+            for (name, counter) in counters.lock().unwrap_or_else(|e| e.into_inner()).iter() {
+                body.push_str(name);
+                body.push(' ');
+                body.push_str(&counter.load(Ordering::Relaxed).to_string());
+                body.push('\n');
+            }
+            respond(&mut stream, 200, "OK", &body)
+        }
+        _ => respond(&mut stream, 404, "Not Found", "not found\n"),
+    }
+}
+
+/// Reads a `\n`-terminated line from `reader`, refusing to buffer more than
+/// `limit` bytes.
+///
+/// Returns an empty string on EOF, matching `BufRead::read_line`'s
+/// zero-bytes-read convention. Errors with `InvalidData` if `limit` is
+/// reached before a newline is found.
+fn read_bounded_line(reader: &mut impl BufRead, limit: usize) -> io::Result<String> {
+    let mut line = String::new();
+    reader.take(limit as u64).read_line(&mut line)?;
+    if !line.ends_with('\n') && line.len() as u64 >= limit as u64 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "line exceeded maximum length",
+        ));
+    }
+    Ok(line)
+}
+
+fn respond(stream: &mut TcpStream, status: u16, reason: &str, body: &str) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    )
+}
+
+impl Drop for AdminServerGuard {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::Release);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    fn get(addr: SocketAddr, path: &str) -> (u16, String) {
+        use std::io::{Read, Write};
+        let mut stream = TcpStream::connect(addr).expect("connect");
+        write!(stream, "GET {} HTTP/1.1\r\n\r\n", path).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let status = response
+            .split_whitespace()
+            .nth(1)
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0);
+        let body = response.split("\r\n\r\n").nth(1).unwrap_or("").to_owned();
+        (status, body)
+    }
+
+    fn put(addr: SocketAddr, path: &str, body: &str) -> (u16, String) {
+        use std::io::{Read, Write};
+        let mut stream = TcpStream::connect(addr).expect("connect");
+        write!(
+            stream,
+            "PUT {} HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}",
+            path,
+            body.len(),
+            body
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let status = response
+            .split_whitespace()
+            .nth(1)
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0);
+        let resp_body = response.split("\r\n\r\n").nth(1).unwrap_or("").to_owned();
+        (status, resp_body)
+    }
+
+    #[test]
+    fn reports_and_updates_filter() {
+        let (filter, handle) = reload::Subscriber::new(EnvFilter::new("info"));
+        let _collect = tracing::collect::set_default(crate::registry().with(filter));
+
+        let (server, _guard) = AdminServer::bind("127.0.0.1:0", handle).expect("bind");
+        let addr = server.local_addr();
+
+        let (status, body) = get(addr, "/filter");
+        assert_eq!(status, 200);
+        assert_eq!(body.trim(), "info");
+
+        let (status, _) = put(addr, "/filter", "debug");
+        assert_eq!(status, 200);
+
+        let (status, body) = get(addr, "/filter");
+        assert_eq!(status, 200);
+        assert_eq!(body.trim(), "debug");
+    }
+
+    #[test]
+    fn reports_dropped_counters() {
+        let (_filter, handle) = reload::Subscriber::new(EnvFilter::new("info"));
+        let dropped = Arc::new(AtomicUsize::new(3));
+        let (server, _guard) = AdminServer::bind("127.0.0.1:0", handle).expect("bind");
+        let server = server.with_counter("my_exporter.dropped", dropped);
+        let addr = server.local_addr();
+
+        let (status, body) = get(addr, "/dropped");
+        assert_eq!(status, 200);
+        assert_eq!(body.trim(), "my_exporter.dropped 3");
+    }
+
+    #[test]
+    fn rejects_invalid_directive() {
+        let (_filter, handle) = reload::Subscriber::new(EnvFilter::new("info"));
+        let (server, _guard) = AdminServer::bind("127.0.0.1:0", handle).expect("bind");
+        let addr = server.local_addr();
+
+        let (status, _) = put(addr, "/filter", "not a valid directive [[[");
+        assert_eq!(status, 400);
+    }
+}
@@ -0,0 +1,386 @@
+//! A [`Subscribe`] for buffering the events recorded while a span --- and any
+//! spans entered beneath it --- are active, for retrieval on demand.
+//!
+//! This is the building block behind "attach the last N log lines to this
+//! error report" or "return the events logged while handling this request in
+//! a debug response header": add [`CaptureSubscriber`] to a collector once,
+//! then start and stop capturing per request with a [`CaptureHandle`].
+//!
+//! # Examples
+//!
+//! ```
+//! use tracing_subscriber::capture::{CaptureHandle, CaptureSubscriber};
+//! use tracing_subscriber::prelude::*;
+//!
+//! tracing_subscriber::registry()
+//!     .with(CaptureSubscriber::new(100))
+//!     .init();
+//!
+//! let request_span = tracing::info_span!("request", method = "GET");
+//! let _guard = request_span.enter();
+//!
+//! let handle = CaptureHandle::for_current_span().expect("just entered a span");
+//! tracing::info!("handling request");
+//! tracing::warn!("retrying downstream call");
+//!
+//! let events = handle.take();
+//! assert_eq!(events.len(), 2);
+//! ```
+use crate::{
+    registry::LookupSpan,
+    subscribe::{Context, Subscribe},
+};
+use std::{
+    any::TypeId,
+    collections::VecDeque,
+    fmt,
+    marker::PhantomData,
+    ptr::NonNull,
+    sync::Mutex,
+};
+use tracing_core::{dispatch, field::{Field, Visit}, span, Collect, Dispatch, Event, Level};
+
+/// The default number of events buffered per captured span before the
+/// oldest recorded event is discarded to bound memory use.
+pub const DEFAULT_CAPACITY: usize = 128;
+
+/// A single event buffered by a [`CaptureHandle`].
+#[derive(Debug, Clone)]
+pub struct CapturedEvent {
+    /// The event's level.
+    pub level: Level,
+    /// The event's target.
+    pub target: String,
+    /// The event's fields, formatted as `field=value` pairs separated by
+    /// spaces, in the order they were recorded.
+    pub fields: String,
+}
+
+impl CapturedEvent {
+    fn from_event(event: &Event<'_>) -> Self {
+        let mut fields = FieldsToString::default();
+        event.record(&mut fields);
+        Self {
+            level: *event.metadata().level(),
+            target: event.metadata().target().to_string(),
+            fields: fields.0,
+        }
+    }
+}
+
+#[derive(Default)]
+struct FieldsToString(String);
+
+impl Visit for FieldsToString {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if !self.0.is_empty() {
+            self.0.push(' ');
+        }
+        use std::fmt::Write;
+        let _ = write!(self.0, "{}={:?}", field.name(), value);
+    }
+}
+
+/// A [`Subscribe`] that lets [`CaptureHandle`]s buffer the events recorded
+/// while a span, and any spans entered beneath it, are active.
+///
+/// Adding a bare `CaptureSubscriber` to a collector does nothing on its own;
+/// call [`CaptureHandle::for_current_span`] (typically right after entering
+/// a request's root span) to start buffering, and [`CaptureHandle::take`] or
+/// [`CaptureHandle::discard`] at the end of the request to stop.
+pub struct CaptureSubscriber<C> {
+    capacity: usize,
+    get_context: WithContext,
+    _collector: PhantomData<fn(C)>,
+}
+
+/// "Remembers" the collector type a [`CaptureSubscriber`] was constructed
+/// for, so that a [`CaptureHandle`] can reach it back through a type-erased
+/// [`Dispatch`] without needing to name that type itself.
+pub(crate) struct WithContext {
+    capacity: usize,
+    start_fn: fn(&Dispatch, &span::Id, usize),
+    take_fn: fn(&Dispatch, &span::Id) -> Vec<CapturedEvent>,
+    discard_fn: fn(&Dispatch, &span::Id),
+}
+
+impl WithContext {
+    fn start(&self, dispatch: &Dispatch, id: &span::Id) {
+        (self.start_fn)(dispatch, id, self.capacity)
+    }
+
+    fn take(&self, dispatch: &Dispatch, id: &span::Id) -> Vec<CapturedEvent> {
+        (self.take_fn)(dispatch, id)
+    }
+
+    fn discard(&self, dispatch: &Dispatch, id: &span::Id) {
+        (self.discard_fn)(dispatch, id)
+    }
+}
+
+impl<C> CaptureSubscriber<C>
+where
+    C: Collect + for<'span> LookupSpan<'span>,
+{
+    /// Returns a new `CaptureSubscriber` that buffers up to `capacity`
+    /// events per captured span, discarding the oldest event once that
+    /// limit is reached.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            get_context: WithContext {
+                capacity,
+                start_fn: Self::start_capture,
+                take_fn: Self::take_events,
+                discard_fn: Self::discard_events,
+            },
+            _collector: PhantomData,
+        }
+    }
+
+    fn start_capture(dispatch: &Dispatch, id: &span::Id, capacity: usize) {
+        let collector = dispatch
+            .downcast_ref::<C>()
+            .expect("collector should downcast to expected type; this is a bug!");
+        let span = match collector.span(id) {
+            Some(span) => span,
+            None => return,
+        };
+        let mut extensions = span.extensions_mut();
+        if extensions.get_mut::<Capture>().is_none() {
+            extensions.insert(Capture::new(capacity));
+        }
+    }
+
+    fn take_events(dispatch: &Dispatch, id: &span::Id) -> Vec<CapturedEvent> {
+        let collector = dispatch
+            .downcast_ref::<C>()
+            .expect("collector should downcast to expected type; this is a bug!");
+        match collector.span(id) {
+            Some(span) => match span.extensions().get::<Capture>() {
+                Some(capture) => capture.take(),
+                None => Vec::new(),
+            },
+            None => Vec::new(),
+        }
+    }
+
+    fn discard_events(dispatch: &Dispatch, id: &span::Id) {
+        let collector = dispatch
+            .downcast_ref::<C>()
+            .expect("collector should downcast to expected type; this is a bug!");
+        if let Some(span) = collector.span(id) {
+            if let Some(capture) = span.extensions().get::<Capture>() {
+                capture.discard();
+            }
+        }
+    }
+}
+
+impl<C> Subscribe<C> for CaptureSubscriber<C>
+where
+    C: Collect + for<'span> LookupSpan<'span>,
+{
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, C>) {
+        let scope = match ctx.event_scope(event) {
+            Some(scope) => scope,
+            None => return,
+        };
+
+        let mut captured: Option<CapturedEvent> = None;
+        for span in scope {
+            let extensions = span.extensions();
+            if let Some(capture) = extensions.get::<Capture>() {
+                let captured = captured.get_or_insert_with(|| CapturedEvent::from_event(event));
+                capture.push(captured.clone());
+            }
+        }
+    }
+
+    unsafe fn downcast_raw(&self, id: TypeId) -> Option<NonNull<()>> {
+        match id {
+            id if id == TypeId::of::<Self>() => Some(NonNull::from(self).cast()),
+            id if id == TypeId::of::<WithContext>() => {
+                Some(NonNull::from(&self.get_context).cast())
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<C> fmt::Debug for CaptureSubscriber<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CaptureSubscriber")
+            .field("capacity", &self.capacity)
+            .finish()
+    }
+}
+
+/// The buffer of events for one captured span, stored in that span's
+/// [extensions].
+///
+/// [extensions]: crate::registry::SpanRef::extensions
+struct Capture {
+    capacity: usize,
+    events: Mutex<VecDeque<CapturedEvent>>,
+}
+
+impl Capture {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            events: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    fn push(&self, event: CapturedEvent) {
+        let mut events = match self.events.lock() {
+            Ok(events) => events,
+            Err(_) => return,
+        };
+        if events.len() >= self.capacity {
+            events.pop_front();
+        }
+        events.push_back(event);
+    }
+
+    fn take(&self) -> Vec<CapturedEvent> {
+        match self.events.lock() {
+            Ok(mut events) => std::mem::take(&mut *events).into(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    fn discard(&self) {
+        if let Ok(mut events) = self.events.lock() {
+            events.clear();
+        }
+    }
+}
+
+/// A handle to the events buffered for a captured span.
+///
+/// Obtained from [`CaptureHandle::for_current_span`], which starts buffering
+/// events for the current span if a [`CaptureSubscriber`] is installed.
+pub struct CaptureHandle {
+    dispatch: Dispatch,
+    id: span::Id,
+}
+
+impl CaptureHandle {
+    /// Starts capturing the events recorded while the current span --- and
+    /// any spans entered beneath it --- are active, and returns a handle to
+    /// retrieve them.
+    ///
+    /// Returns `None` if there is no current span, or if the current
+    /// collector does not have a [`CaptureSubscriber`] installed.
+    pub fn for_current_span() -> Option<Self> {
+        dispatch::get_default(|dispatch| {
+            let id = dispatch.current_span().id()?.clone();
+            let ctx = dispatch.downcast_ref::<WithContext>()?;
+            ctx.start(dispatch, &id);
+            Some(Self {
+                dispatch: dispatch.clone(),
+                id,
+            })
+        })
+    }
+
+    /// Removes and returns the events buffered for this span so far.
+    ///
+    /// Subsequent calls return only events recorded after this call.
+    pub fn take(&self) -> Vec<CapturedEvent> {
+        match self.dispatch.downcast_ref::<WithContext>() {
+            Some(ctx) => ctx.take(&self.dispatch, &self.id),
+            None => Vec::new(),
+        }
+    }
+
+    /// Discards the events buffered for this span so far, without returning
+    /// them.
+    pub fn discard(&self) {
+        if let Some(ctx) = self.dispatch.downcast_ref::<WithContext>() {
+            ctx.discard(&self.dispatch, &self.id)
+        }
+    }
+}
+
+impl fmt::Debug for CaptureHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CaptureHandle").field("id", &self.id).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn captures_events_within_the_span() {
+        tracing::collect::with_default(crate::registry().with(CaptureSubscriber::new(10)), || {
+            let span = tracing::info_span!("request");
+            let _guard = span.enter();
+
+            let handle = CaptureHandle::for_current_span().expect("span is current");
+            tracing::info!(status = 200, "handled request");
+            tracing::warn!("slow downstream call");
+
+            let events = handle.take();
+            assert_eq!(events.len(), 2);
+            assert!(events[0].fields.contains("status=200"));
+            assert_eq!(events[1].level, Level::WARN);
+
+            // `take` drains the buffer.
+            assert!(handle.take().is_empty());
+        });
+    }
+
+    #[test]
+    fn does_not_capture_events_outside_the_span() {
+        tracing::collect::with_default(crate::registry().with(CaptureSubscriber::new(10)), || {
+            tracing::info!("before capturing anything");
+
+            let span = tracing::info_span!("request");
+            let _guard = span.enter();
+            let handle = CaptureHandle::for_current_span().expect("span is current");
+
+            drop(_guard);
+            tracing::info!("after leaving the span");
+
+            assert!(handle.take().is_empty());
+        });
+    }
+
+    #[test]
+    fn oldest_events_are_dropped_once_capacity_is_reached() {
+        tracing::collect::with_default(crate::registry().with(CaptureSubscriber::new(2)), || {
+            let span = tracing::info_span!("request");
+            let _guard = span.enter();
+            let handle = CaptureHandle::for_current_span().expect("span is current");
+
+            tracing::info!(seq = 1, "first");
+            tracing::info!(seq = 2, "second");
+            tracing::info!(seq = 3, "third");
+
+            let events = handle.take();
+            assert_eq!(events.len(), 2);
+            assert!(events[0].fields.contains("seq=2"));
+            assert!(events[1].fields.contains("seq=3"));
+        });
+    }
+
+    #[test]
+    fn discard_clears_the_buffer_without_returning_it() {
+        tracing::collect::with_default(crate::registry().with(CaptureSubscriber::new(10)), || {
+            let span = tracing::info_span!("request");
+            let _guard = span.enter();
+            let handle = CaptureHandle::for_current_span().expect("span is current");
+
+            tracing::info!("this should be discarded");
+            handle.discard();
+
+            assert!(handle.take().is_empty());
+        });
+    }
+}
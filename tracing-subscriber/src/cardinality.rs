@@ -0,0 +1,263 @@
+//! A [`Subscribe`] that guards against field *cardinality explosions*.
+//!
+//! It's easy to accidentally instrument a hot path with a field whose value
+//! is effectively unique per call --- a request ID, a raw user input, a
+//! stack trace --- without realizing that a downstream metrics or tracing
+//! backend will try to build one time series or index entry per distinct
+//! value it sees. [`CardinalityGuard`] tracks an approximate count of
+//! distinct values seen for each field name (using a small [HyperLogLog]
+//! sketch, so memory use stays bounded regardless of how many distinct
+//! values actually occur) and calls back once a field crosses a configured
+//! threshold, so that offending fields can be logged, alerted on, or paired
+//! with a [`Subscribe`] that starts hashing that field's values before they
+//! reach anything that minds.
+//!
+//! [HyperLogLog]: https://en.wikipedia.org/wiki/HyperLogLog
+use crate::subscribe::{Context, Subscribe};
+use std::{
+    collections::HashMap,
+    fmt,
+    hash::{Hash, Hasher},
+    sync::atomic::{AtomicBool, Ordering},
+    sync::RwLock,
+};
+use tracing_core::{
+    field::{Field, Visit},
+    span, Collect, Event,
+};
+
+/// The number of registers used by each field's [`HyperLogLog`] sketch.
+///
+/// 256 registers keep the standard error around 6.5%, which is plenty for
+/// deciding whether a field has "a few" or "way too many" distinct values.
+const REGISTERS: usize = 256;
+
+/// A [`Subscribe`] that tracks the approximate number of distinct values
+/// recorded for each field name, and invokes a callback the first time a
+/// field's estimated cardinality crosses `threshold`.
+///
+/// # Examples
+///
+/// ```
+/// use tracing_subscriber::cardinality::CardinalityGuard;
+/// use tracing_subscriber::prelude::*;
+///
+/// let guard = CardinalityGuard::new(1_000).on_exceeded(|field, estimate| {
+///     eprintln!("field {:?} has ~{} distinct values, consider hashing it", field, estimate);
+/// });
+///
+/// tracing_subscriber::registry().with(guard).init();
+/// ```
+pub struct CardinalityGuard {
+    threshold: u64,
+    on_exceeded: Box<dyn Fn(&str, u64) + Send + Sync>,
+    fields: RwLock<HashMap<&'static str, FieldStats>>,
+}
+
+struct FieldStats {
+    sketch: HyperLogLog,
+    exceeded: AtomicBool,
+}
+
+impl fmt::Debug for CardinalityGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CardinalityGuard")
+            .field("threshold", &self.threshold)
+            .finish()
+    }
+}
+
+impl CardinalityGuard {
+    /// Returns a new `CardinalityGuard` that prints a warning to stderr when
+    /// a field's estimated cardinality exceeds `threshold`.
+    ///
+    /// Use [`on_exceeded`](Self::on_exceeded) to replace this with a callback
+    /// that reports to your own metrics or alerting system instead.
+    pub fn new(threshold: u64) -> Self {
+        Self {
+            threshold,
+            on_exceeded: Box::new(|field, estimate| {
+                eprintln!(
+                    "[tracing-subscriber] field {:?} has an estimated {} distinct values, \
+                     which exceeds the configured cardinality threshold",
+                    field, estimate
+                );
+            }),
+            fields: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Overrides the callback invoked the first time a field's estimated
+    /// cardinality crosses the configured threshold.
+    ///
+    /// The callback is invoked at most once per field name; it is passed the
+    /// field's name and its estimated distinct-value count at the moment the
+    /// threshold was crossed.
+    pub fn on_exceeded<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&str, u64) + Send + Sync + 'static,
+    {
+        self.on_exceeded = Box::new(f);
+        self
+    }
+
+    fn record(&self, field: &Field, hash: u64) {
+        if let Ok(fields) = self.fields.read() {
+            if let Some(stats) = fields.get(field.name()) {
+                stats.sketch.insert(hash);
+                self.check(field.name(), stats);
+                return;
+            }
+        }
+
+        // First time this field name has been seen; insert its sketch under
+        // the write lock, then record the value.
+        let mut fields = match self.fields.write() {
+            Ok(fields) => fields,
+            Err(_) => return,
+        };
+        let stats = fields.entry(field.name()).or_insert_with(|| FieldStats {
+            sketch: HyperLogLog::new(),
+            exceeded: AtomicBool::new(false),
+        });
+        stats.sketch.insert(hash);
+        self.check(field.name(), stats);
+    }
+
+    fn check(&self, name: &str, stats: &FieldStats) {
+        let estimate = stats.sketch.estimate();
+        if estimate >= self.threshold && !stats.exceeded.swap(true, Ordering::Relaxed) {
+            (self.on_exceeded)(name, estimate);
+        }
+    }
+}
+
+impl<C> Subscribe<C> for CardinalityGuard
+where
+    C: Collect,
+{
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, C>) {
+        let mut visitor = HashingVisitor { guard: self };
+        event.record(&mut visitor);
+    }
+
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, _id: &span::Id, _ctx: Context<'_, C>) {
+        let mut visitor = HashingVisitor { guard: self };
+        attrs.record(&mut visitor);
+    }
+}
+
+struct HashingVisitor<'a> {
+    guard: &'a CardinalityGuard,
+}
+
+impl Visit for HashingVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.guard.record(field, hash_debug(value));
+    }
+}
+
+fn hash_debug(value: &dyn fmt::Debug) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    format!("{:?}", value).hash(&mut hasher);
+    hasher.finish()
+}
+
+/// A small, fixed-size [HyperLogLog] sketch used to estimate the number of
+/// distinct 64-bit hashes inserted into it, using bounded memory.
+///
+/// [HyperLogLog]: https://en.wikipedia.org/wiki/HyperLogLog
+struct HyperLogLog {
+    registers: RwLock<[u8; REGISTERS]>,
+}
+
+impl HyperLogLog {
+    fn new() -> Self {
+        Self {
+            registers: RwLock::new([0u8; REGISTERS]),
+        }
+    }
+
+    fn insert(&self, hash: u64) {
+        // The low bits select which register to update; the remaining bits
+        // are used to count leading zeros, which is what makes this
+        // technique work: seeing a long run of leading zeros is exponentially
+        // unlikely, so the longest run seen is a signal of how many distinct
+        // values have been hashed into this register.
+        let index = (hash as usize) % REGISTERS;
+        let rank = ((hash >> 8) | (1 << 55)).trailing_zeros() as u8 + 1;
+        if let Ok(mut registers) = self.registers.write() {
+            if rank > registers[index] {
+                registers[index] = rank;
+            }
+        }
+    }
+
+    fn estimate(&self) -> u64 {
+        let registers = match self.registers.read() {
+            Ok(registers) => registers,
+            Err(_) => return 0,
+        };
+        let m = REGISTERS as f64;
+        // The standard HyperLogLog bias-correction constant for m = 256
+        // registers.
+        const ALPHA: f64 = 0.7213 / (1.0 + 1.079 / 256.0);
+
+        let sum: f64 = registers.iter().map(|&r| 2f64.powi(-(r as i32))).sum();
+        let raw_estimate = ALPHA * m * m / sum;
+
+        // The raw estimator above is badly biased for small cardinalities
+        // (it overshoots when most registers are still empty), so HyperLogLog
+        // falls back to linear counting in that regime instead.
+        let zero_registers = registers.iter().filter(|&&r| r == 0).count();
+        let estimate = if raw_estimate <= 2.5 * m && zero_registers != 0 {
+            m * (m / zero_registers as f64).ln()
+        } else {
+            raw_estimate
+        };
+        estimate.round() as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn low_cardinality_field_does_not_trigger() {
+        let exceeded = Arc::new(Mutex::new(Vec::new()));
+        let exceeded2 = exceeded.clone();
+        let guard = CardinalityGuard::new(10).on_exceeded(move |field, estimate| {
+            exceeded2.lock().unwrap().push((field.to_string(), estimate));
+        });
+
+        tracing::collect::with_default(crate::registry().with(guard), || {
+            for _ in 0..5 {
+                tracing::info!(status = "ok", "did a thing");
+            }
+        });
+
+        assert!(exceeded.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn high_cardinality_field_triggers_once() {
+        let exceeded = Arc::new(Mutex::new(Vec::new()));
+        let exceeded2 = exceeded.clone();
+        let guard = CardinalityGuard::new(10).on_exceeded(move |field, estimate| {
+            exceeded2.lock().unwrap().push((field.to_string(), estimate));
+        });
+
+        tracing::collect::with_default(crate::registry().with(guard), || {
+            for i in 0..500 {
+                tracing::info!(request_id = i, "handled request");
+            }
+        });
+
+        let exceeded = exceeded.lock().unwrap();
+        assert_eq!(exceeded.len(), 1);
+        assert_eq!(exceeded[0].0, "request_id");
+    }
+}
@@ -0,0 +1,222 @@
+//! A [`Subscribe`] that maintains a crash-safe, per-thread record of the
+//! currently entered spans, so a panic hook --- or a signal handler
+//! installed for `SIGSEGV`/`SIGABRT` --- can report which spans were active
+//! when the program went down.
+//!
+//! An ordinary panic hook can usually just walk the live [registry], the way
+//! the `panic_hook.rs` example in this repository does. That falls apart for
+//! a fatal crash: the registry's locks may be held (or mid-mutation) by
+//! whatever thread was running when the fault happened, so a handler running
+//! on that same thread can't safely take them without risking a deadlock or
+//! reentering a corrupted data structure.
+//!
+//! [`CrashContext`] sidesteps that by keeping a small, fixed-capacity, per-
+//! thread buffer of span names, updated on every enter/exit. Recording never
+//! allocates and never blocks, so [`with_current_thread_spans`] is safe to
+//! call from contexts where the registry itself is off-limits.
+//!
+//! This module only provides that buffer and a way to read it back; it does
+//! not install a signal handler itself, since doing so needs a dependency
+//! (such as the `libc` or `signal-hook` crates) this crate doesn't otherwise
+//! need. Hook it up with [`std::panic::set_hook`] for panics, or from your
+//! own `SIGSEGV`/`SIGABRT` handler for aborts.
+//!
+//! [registry]: crate::registry
+//!
+//! # Examples
+//!
+//! ```
+//! use tracing_subscriber::crash_context::{with_current_thread_spans, CrashContext};
+//! use tracing_subscriber::prelude::*;
+//!
+//! tracing_subscriber::registry()
+//!     .with(CrashContext::new())
+//!     .init();
+//!
+//! std::panic::set_hook(Box::new(|panic| {
+//!     with_current_thread_spans(|spans| {
+//!         eprintln!("panicked inside spans {:?}: {panic}", spans);
+//!     });
+//! }));
+//!
+//! tracing::info_span!("request", method = "GET").in_scope(|| {
+//!     with_current_thread_spans(|spans| assert_eq!(spans, &["request"]));
+//! });
+//! ```
+use crate::{
+    registry::LookupSpan,
+    subscribe::{Context, Subscribe},
+};
+use std::cell::Cell;
+use tracing_core::{span, Collect};
+
+/// The maximum number of nested spans recorded per thread.
+///
+/// Once a thread's span stack grows past this depth, spans beyond it are
+/// simply not recorded here --- the live registry's own tracking of the
+/// thread's spans is unaffected. This trades completeness for a fixed,
+/// allocation-free buffer that can be read back from a crash handler.
+const MAX_DEPTH: usize = 32;
+
+/// The maximum length, in bytes, of a single recorded span name. Names
+/// longer than this are truncated, for the same reason `MAX_DEPTH` is
+/// bounded: recording a span must never allocate.
+const MAX_NAME_LEN: usize = 47;
+
+/// A [`Subscribe`] that records each thread's currently entered spans in a
+/// fixed-size, allocation-free buffer, readable with
+/// [`with_current_thread_spans`].
+///
+/// See the [module-level documentation](self) for why this exists.
+#[derive(Debug, Default)]
+pub struct CrashContext {
+    _private: (),
+}
+
+impl CrashContext {
+    /// Returns a new `CrashContext` subscriber.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<C> Subscribe<C> for CrashContext
+where
+    C: Collect + for<'span> LookupSpan<'span>,
+{
+    fn on_enter(&self, id: &span::Id, ctx: Context<'_, C>) {
+        if let Some(span) = ctx.span(id) {
+            SPAN_STACK.with(|stack| stack.push(span.name()));
+        }
+    }
+
+    fn on_exit(&self, _id: &span::Id, _ctx: Context<'_, C>) {
+        SPAN_STACK.with(SpanStack::pop);
+    }
+}
+
+/// Calls `f` with the names of the spans currently entered on this thread,
+/// from outermost to innermost.
+///
+/// The slice is empty if no [`CrashContext`] subscriber is installed, or if
+/// no span is currently entered on this thread. Building and reading this
+/// slice never allocates, so this is safe to call from a panic hook or a
+/// `SIGSEGV`/`SIGABRT` handler.
+pub fn with_current_thread_spans<R>(f: impl FnOnce(&[&str]) -> R) -> R {
+    SPAN_STACK.with(|stack| stack.with_names(f))
+}
+
+/// One frame of a thread's recorded span stack: a span name, truncated to
+/// fit in a fixed-size buffer so recording it never allocates.
+#[derive(Clone, Copy)]
+struct Frame {
+    name: [u8; MAX_NAME_LEN],
+    len: u8,
+}
+
+impl Frame {
+    const EMPTY: Self = Self {
+        name: [0; MAX_NAME_LEN],
+        len: 0,
+    };
+
+    fn new(name: &str) -> Self {
+        let bytes = name.as_bytes();
+        let len = bytes.len().min(MAX_NAME_LEN);
+        let mut buf = [0u8; MAX_NAME_LEN];
+        buf[..len].copy_from_slice(&bytes[..len]);
+        Self {
+            name: buf,
+            len: len as u8,
+        }
+    }
+
+    fn as_str(&self) -> &str {
+        // `name` is always copied from a `&str` above, so this prefix of it
+        // is always valid UTF-8.
+        std::str::from_utf8(&self.name[..self.len as usize]).unwrap_or("")
+    }
+}
+
+/// A thread's recorded span stack.
+///
+/// Every field here is a [`Cell`] rather than a [`RefCell`](std::cell::RefCell)
+/// or a lock on purpose: a handler reading this back may be running on this
+/// same thread, interrupting whatever was last writing to it, so nothing
+/// here may ever panic or block on a borrow.
+struct SpanStack {
+    frames: [Cell<Frame>; MAX_DEPTH],
+    depth: Cell<usize>,
+}
+
+impl SpanStack {
+    const fn new() -> Self {
+        Self {
+            // `Cell<Frame>` isn't `Copy` (interior-mutability types never
+            // are), so the repeated element must be spelled as its own
+            // constant rather than `[Cell::new(Frame::EMPTY); MAX_DEPTH]`.
+            frames: [const { Cell::new(Frame::EMPTY) }; MAX_DEPTH],
+            depth: Cell::new(0),
+        }
+    }
+
+    fn push(&self, name: &str) {
+        let depth = self.depth.get();
+        if let Some(slot) = self.frames.get(depth) {
+            slot.set(Frame::new(name));
+            self.depth.set(depth + 1);
+        }
+    }
+
+    fn pop(&self) {
+        self.depth.set(self.depth.get().saturating_sub(1));
+    }
+
+    fn with_names<R>(&self, f: impl FnOnce(&[&str]) -> R) -> R {
+        let depth = self.depth.get().min(MAX_DEPTH);
+        let mut frames = [Frame::EMPTY; MAX_DEPTH];
+        for (slot, cell) in frames.iter_mut().zip(&self.frames) {
+            *slot = cell.get();
+        }
+        let mut names = [""; MAX_DEPTH];
+        for (name, frame) in names.iter_mut().zip(&frames) {
+            *name = frame.as_str();
+        }
+        f(&names[..depth])
+    }
+}
+
+thread_local! {
+    static SPAN_STACK: SpanStack = const { SpanStack::new() };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn records_nested_span_names() {
+        tracing::collect::with_default(crate::registry().with(CrashContext::new()), || {
+            with_current_thread_spans(|spans| assert_eq!(spans, &[] as &[&str]));
+
+            tracing::info_span!("outer").in_scope(|| {
+                with_current_thread_spans(|spans| assert_eq!(spans, &["outer"]));
+
+                tracing::info_span!("inner").in_scope(|| {
+                    with_current_thread_spans(|spans| assert_eq!(spans, &["outer", "inner"]));
+                });
+
+                with_current_thread_spans(|spans| assert_eq!(spans, &["outer"]));
+            });
+
+            with_current_thread_spans(|spans| assert_eq!(spans, &[] as &[&str]));
+        });
+    }
+
+    #[test]
+    fn long_names_are_truncated_not_allocated() {
+        let long_name = "x".repeat(MAX_NAME_LEN * 2);
+        assert_eq!(Frame::new(&long_name).as_str().len(), MAX_NAME_LEN);
+    }
+}
@@ -0,0 +1,259 @@
+//! Buffering events dispatched before the global default [collector] is
+//! installed, so that they can be replayed once one is.
+//!
+//! Code that runs during early program startup --- parsing configuration,
+//! handling command-line arguments --- often wants to emit diagnostic events,
+//! but frequently runs *before* the application has enough information (such
+//! as the parsed configuration) to construct its real [`Collect`]. Events
+//! recorded in that window are normally lost, since [`dispatch::get_default`]
+//! falls back to a no-op collector when none has been set.
+//!
+//! [`EarlyBuffer`] is a [`Collect`] that can be installed as a
+//! [scoped default][with_default] around that early code, recording every
+//! event it sees (in an approximate, best-effort form) into a bounded
+//! ring buffer. Once the real collector has been installed as the global
+//! default, calling [`EarlyBuffer::replay`] re-dispatches the buffered
+//! events through it.
+//!
+//! This is an opt-in mechanism: unless code is explicitly run inside
+//! [`with_default`] with an [`EarlyBuffer`], events are handled exactly as
+//! they would be otherwise.
+//!
+//! # Examples
+//!
+//! ```
+//! use tracing_subscriber::early::EarlyBuffer;
+//! use std::sync::Arc;
+//!
+//! let early = Arc::new(EarlyBuffer::new(64));
+//!
+//! tracing::dispatch::with_default(&tracing::Dispatch::new(early.clone()), || {
+//!     // Events emitted here, before the real collector exists, are
+//!     // captured rather than discarded.
+//!     tracing::info!("starting up");
+//! });
+//!
+//! tracing_subscriber::fmt().init();
+//!
+//! // Replay the buffered events into the now-installed collector.
+//! early.replay();
+//! ```
+//!
+//! [collector]: tracing_core::Collect
+//! [`Collect`]: tracing_core::Collect
+//! [`dispatch::get_default`]: tracing_core::dispatch::get_default
+//! [with_default]: tracing_core::dispatch::with_default
+//! [`with_default`]: tracing_core::dispatch::with_default
+use std::{
+    collections::VecDeque,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use tracing_core::{
+    callsite::Callsite,
+    dispatch, field,
+    span::{Attributes, Id, Record},
+    Collect, Event, Interest, Level, Metadata,
+};
+
+use crate::sync::RwLock;
+
+/// A [`Collect`] that buffers events recorded before the real, global
+/// default collector is installed.
+///
+/// See the [module-level documentation][self] for details.
+#[derive(Debug)]
+pub struct EarlyBuffer {
+    capacity: usize,
+    events: RwLock<VecDeque<Buffered>>,
+    dropped: AtomicUsize,
+}
+
+#[derive(Debug)]
+struct Buffered {
+    level: Level,
+    target: String,
+    message: String,
+}
+
+impl EarlyBuffer {
+    /// Returns a new `EarlyBuffer` that retains at most `capacity` events.
+    ///
+    /// Once `capacity` is exceeded, the oldest buffered event is dropped to
+    /// make room for the newest one; the number of events dropped this way
+    /// is available from [`EarlyBuffer::dropped_count`].
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            events: RwLock::new(VecDeque::with_capacity(capacity)),
+            dropped: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the number of buffered events that were dropped because the
+    /// buffer was at capacity.
+    pub fn dropped_count(&self) -> usize {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Re-dispatches every buffered event through the current
+    /// [default collector][dispatch::get_default], and clears the buffer.
+    ///
+    /// This should be called after the real collector has been installed
+    /// (for example, via [`set_global_default`]), so that the buffered
+    /// events are delivered to it.
+    ///
+    /// [`set_global_default`]: tracing_core::dispatch::set_global_default
+    pub fn replay(&self) {
+        let buffered = std::mem::take(&mut *self.events.write().unwrap_or_else(|e| e.into_inner()));
+        for event in buffered {
+            let (cs, fields) = level_callsite(event.level);
+            dispatch::get_default(|dispatch| {
+                let metadata = cs.metadata();
+                dispatch.event(&Event::new(
+                    metadata,
+                    &metadata.fields().value_set(&[
+                        (&fields.message, Some(&event.message as &dyn field::Value)),
+                        (&fields.target, Some(&event.target as &dyn field::Value)),
+                    ]),
+                ));
+            });
+        }
+    }
+}
+
+impl Collect for EarlyBuffer {
+    fn register_callsite(&self, _metadata: &'static Metadata<'static>) -> Interest {
+        Interest::always()
+    }
+
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _span: &Attributes<'_>) -> Id {
+        Id::from_u64(1)
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        // Only the formatted `Debug` representation of each field is kept;
+        // this buffer is a best-effort net for otherwise-lost early events,
+        // not a faithful structured replay.
+        let mut message = String::new();
+        let mut writer = MessageVisitor(&mut message);
+        event.record(&mut writer);
+
+        let buffered = Buffered {
+            level: *event.metadata().level(),
+            target: event.metadata().target().to_string(),
+            message,
+        };
+
+        let mut events = self.events.write().unwrap_or_else(|e| e.into_inner());
+        if events.len() >= self.capacity {
+            events.pop_front();
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+        events.push_back(buffered);
+    }
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+
+    fn current_span(&self) -> tracing_core::span::Current {
+        tracing_core::span::Current::none()
+    }
+}
+
+struct MessageVisitor<'a>(&'a mut String);
+
+impl field::Visit for MessageVisitor<'_> {
+    fn record_debug(&mut self, field: &field::Field, value: &dyn std::fmt::Debug) {
+        use std::fmt::Write;
+        if !self.0.is_empty() {
+            self.0.push(' ');
+        }
+        if field.name() == "message" {
+            let _ = write!(self.0, "{:?}", value);
+        } else {
+            let _ = write!(self.0, "{}={:?}", field.name(), value);
+        }
+    }
+}
+
+struct LevelFields {
+    message: field::Field,
+    target: field::Field,
+}
+
+static FIELD_NAMES: &[&str] = &["message", "early.target"];
+
+macro_rules! level_cs {
+    ($level:expr, $cs:ident, $meta:ident, $ty:ident) => {
+        struct $ty;
+        static $cs: $ty = $ty;
+        static $meta: Metadata<'static> = Metadata::new(
+            "buffered early event",
+            "tracing_subscriber::early",
+            $level,
+            None,
+            None,
+            None,
+            field::FieldSet::new(FIELD_NAMES, tracing_core::identify_callsite!(&$cs)),
+            tracing_core::metadata::Kind::EVENT,
+        );
+
+        impl Callsite for $ty {
+            fn set_interest(&self, _: Interest) {}
+            fn metadata(&self) -> &'static Metadata<'static> {
+                &$meta
+            }
+        }
+    };
+}
+
+level_cs!(Level::TRACE, TRACE_CS, TRACE_META, TraceCallsite);
+level_cs!(Level::DEBUG, DEBUG_CS, DEBUG_META, DebugCallsite);
+level_cs!(Level::INFO, INFO_CS, INFO_META, InfoCallsite);
+level_cs!(Level::WARN, WARN_CS, WARN_META, WarnCallsite);
+level_cs!(Level::ERROR, ERROR_CS, ERROR_META, ErrorCallsite);
+
+fn level_callsite(level: Level) -> (&'static dyn Callsite, LevelFields) {
+    let cs: &'static dyn Callsite = match level {
+        Level::TRACE => &TRACE_CS,
+        Level::DEBUG => &DEBUG_CS,
+        Level::INFO => &INFO_CS,
+        Level::WARN => &WARN_CS,
+        Level::ERROR => &ERROR_CS,
+    };
+    let fields = cs.metadata().fields();
+    let fields = LevelFields {
+        message: fields.field("message").unwrap(),
+        target: fields.field("early.target").unwrap(),
+    };
+    (cs, fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn buffers_and_replays() {
+        let early = Arc::new(EarlyBuffer::new(2));
+        tracing_core::dispatch::with_default(&tracing_core::Dispatch::new(early.clone()), || {
+            tracing::info!("one");
+            tracing::info!("two");
+            tracing::info!("three");
+        });
+
+        // Capacity is 2, so the oldest event should have been dropped.
+        assert_eq!(early.dropped_count(), 1);
+    }
+}
@@ -0,0 +1,269 @@
+//! `MakeVisitor` wrappers for redacting the values of fields whose names
+//! match a configured set of patterns, such as `password` or `*_token`.
+use super::{MakeVisitor, VisitFmt, VisitOutput};
+use tracing_core::field::{Field, Visit};
+
+use core::fmt;
+
+feature! {
+    #![feature = "alloc"]
+    use alloc::{
+        format,
+        string::{String, ToString},
+        vec::Vec,
+    };
+
+    /// A visitor wrapper that redacts the values of fields whose names match
+    /// a configured set of [patterns], replacing them with a placeholder (or
+    /// a caller-provided transformation, such as a hash) before passing them
+    /// on to the wrapped visitor.
+    ///
+    /// This is useful for scrubbing sensitive data --- passwords, tokens,
+    /// social security numbers, and the like --- from trace output, without
+    /// relying on every call site to remember not to log it.
+    ///
+    /// By default, a matching field's value is replaced with the literal
+    /// string `"[REDACTED]"`. Use [`with_redactor`] to customize this, e.g.
+    /// to hash the original value instead of discarding it outright.
+    ///
+    /// [patterns]: Redact::with_pattern
+    /// [`with_redactor`]: Redact::with_redactor
+    #[derive(Clone, Debug)]
+    pub struct Redact<V> {
+        inner: V,
+        patterns: Vec<Pattern>,
+        redact: fn(&Field, &str) -> String,
+    }
+
+    #[derive(Clone, Debug)]
+    enum Pattern {
+        Exact(String),
+        Prefix(String),
+        Suffix(String),
+    }
+
+    impl Pattern {
+        fn matches(&self, name: &str) -> bool {
+            match self {
+                Pattern::Exact(pattern) => name == pattern.as_str(),
+                Pattern::Prefix(prefix) => name.starts_with(prefix.as_str()),
+                Pattern::Suffix(suffix) => name.ends_with(suffix.as_str()),
+            }
+        }
+    }
+
+    fn default_redact(_: &Field, _: &str) -> String {
+        "[REDACTED]".to_string()
+    }
+
+    // === impl Redact ===
+
+    impl<V> Redact<V> {
+        /// Wraps `inner`, redacting the values of any fields whose names
+        /// match a pattern added with [`with_pattern`](Self::with_pattern).
+        pub fn new(inner: V) -> Self {
+            Self {
+                inner,
+                patterns: Vec::new(),
+                redact: default_redact,
+            }
+        }
+
+        /// Adds a pattern matching field names whose values should be
+        /// redacted.
+        ///
+        /// A pattern is either an exact field name (`"password"`), or begins
+        /// or ends with a single `*` wildcard (`"secret_*"`, `"*_token"`) to
+        /// match a prefix or suffix of the field name, respectively.
+        pub fn with_pattern(mut self, pattern: &str) -> Self {
+            let pattern = if let Some(suffix) = pattern.strip_prefix('*') {
+                Pattern::Suffix(suffix.to_string())
+            } else if let Some(prefix) = pattern.strip_suffix('*') {
+                Pattern::Prefix(prefix.to_string())
+            } else {
+                Pattern::Exact(pattern.to_string())
+            };
+            self.patterns.push(pattern);
+            self
+        }
+
+        /// Sets the function used to produce the replacement value for a
+        /// redacted field, given its original value formatted as a string.
+        ///
+        /// By default, redacted fields are replaced with the literal string
+        /// `"[REDACTED]"`, discarding the original value entirely; a
+        /// redactor can instead be used to, say, hash it.
+        pub fn with_redactor(mut self, redact: fn(&Field, &str) -> String) -> Self {
+            self.redact = redact;
+            self
+        }
+
+        fn is_sensitive(&self, field: &Field) -> bool {
+            self.patterns.iter().any(|pattern| pattern.matches(field.name()))
+        }
+
+        fn record_redacted(&mut self, field: &Field, raw: &str)
+        where
+            V: Visit,
+        {
+            let redacted = (self.redact)(field, raw);
+            self.inner.record_str(field, &redacted);
+        }
+    }
+
+    impl<T, V> MakeVisitor<T> for Redact<V>
+    where
+        V: MakeVisitor<T>,
+    {
+        type Visitor = Redact<V::Visitor>;
+
+        #[inline]
+        fn make_visitor(&self, target: T) -> Self::Visitor {
+            Redact {
+                inner: self.inner.make_visitor(target),
+                patterns: self.patterns.clone(),
+                redact: self.redact,
+            }
+        }
+    }
+
+    impl<V> Visit for Redact<V>
+    where
+        V: Visit,
+    {
+        fn record_f64(&mut self, field: &Field, value: f64) {
+            if self.is_sensitive(field) {
+                self.record_redacted(field, &value.to_string())
+            } else {
+                self.inner.record_f64(field, value)
+            }
+        }
+
+        fn record_i64(&mut self, field: &Field, value: i64) {
+            if self.is_sensitive(field) {
+                self.record_redacted(field, &value.to_string())
+            } else {
+                self.inner.record_i64(field, value)
+            }
+        }
+
+        fn record_u64(&mut self, field: &Field, value: u64) {
+            if self.is_sensitive(field) {
+                self.record_redacted(field, &value.to_string())
+            } else {
+                self.inner.record_u64(field, value)
+            }
+        }
+
+        fn record_bool(&mut self, field: &Field, value: bool) {
+            if self.is_sensitive(field) {
+                self.record_redacted(field, &value.to_string())
+            } else {
+                self.inner.record_bool(field, value)
+            }
+        }
+
+        fn record_str(&mut self, field: &Field, value: &str) {
+            if self.is_sensitive(field) {
+                self.record_redacted(field, value)
+            } else {
+                self.inner.record_str(field, value)
+            }
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            if self.is_sensitive(field) {
+                self.record_redacted(field, &format!("{:?}", value))
+            } else {
+                self.inner.record_debug(field, value)
+            }
+        }
+    }
+
+    impl<V, O> VisitOutput<O> for Redact<V>
+    where
+        V: VisitOutput<O>,
+    {
+        #[inline]
+        fn finish(self) -> O {
+            self.inner.finish()
+        }
+    }
+
+    impl<V> VisitFmt for Redact<V>
+    where
+        V: VisitFmt,
+    {
+        #[inline]
+        fn writer(&mut self) -> &mut dyn fmt::Write {
+            self.inner.writer()
+        }
+    }
+}
+
+feature! {
+    #![feature = "std"]
+    use super::VisitWrite;
+    use std::io;
+
+    impl<V> VisitWrite for Redact<V>
+    where
+        V: VisitWrite,
+    {
+        #[inline]
+        fn writer(&mut self) -> &mut dyn io::Write {
+            self.inner.writer()
+        }
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+    use crate::field::test_util::*;
+
+    #[test]
+    fn redacts_matching_exact_field() {
+        let mut s = String::new();
+        let visitor = DebugVisitor::new(&mut s);
+        let mut visitor = Redact::new(visitor).with_pattern("question");
+
+        TestAttrs1::with(|attrs| attrs.record(&mut visitor));
+        visitor.finish().unwrap();
+
+        assert_eq!(
+            s.as_str(),
+            "question=\"[REDACTED]\"tricky=truecan_you_do_it=true"
+        );
+    }
+
+    #[test]
+    fn redacts_matching_wildcard_field() {
+        let mut s = String::new();
+        let visitor = DebugVisitor::new(&mut s);
+        let mut visitor = Redact::new(visitor).with_pattern("*_do_it");
+
+        TestAttrs1::with(|attrs| attrs.record(&mut visitor));
+        visitor.finish().unwrap();
+
+        assert_eq!(
+            s.as_str(),
+            "question=\"life, the universe, and everything\"tricky=truecan_you_do_it=\"[REDACTED]\""
+        );
+    }
+
+    #[test]
+    fn leaves_non_matching_fields_alone() {
+        let mut s = String::new();
+        let visitor = DebugVisitor::new(&mut s);
+        let mut visitor = Redact::new(visitor).with_pattern("password");
+
+        TestAttrs1::with(|attrs| attrs.record(&mut visitor));
+        visitor.finish().unwrap();
+
+        assert_eq!(
+            s.as_str(),
+            "question=\"life, the universe, and everything\"tricky=truecan_you_do_it=true"
+        );
+    }
+}
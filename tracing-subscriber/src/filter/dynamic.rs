@@ -0,0 +1,210 @@
+//! A [filter] that wraps an arbitrary predicate, with an explicit handle for
+//! invalidating cached interest when the predicate's answer may have changed.
+//!
+//! See [`dynamic`] for details.
+//!
+//! [filter]: crate::subscribe#filtering-with-subscribers
+use crate::{
+    filter::LevelFilter,
+    subscribe::{Context, Subscribe},
+};
+use core::{
+    fmt,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use std::sync::Arc;
+use tracing_core::{callsite, Collect, Interest, Metadata};
+
+/// A filter implemented by a closure or function pointer that determines
+/// whether a given span or event is enabled, based on its [`Metadata`],
+/// paired with a [`DynamicFilterHandle`] that can be used to invalidate the
+/// filter's interest cache when the closure's answer to a previously-asked
+/// question may have changed.
+///
+/// Unlike [`FilterFn`], which assumes that its predicate's answer for a given
+/// [`Metadata`] never changes, a `DynamicFilter` is intended for predicates
+/// backed by external, mutable state --- such as a feature-flag system ---
+/// whose answers can change over time. Every time that state changes, call
+/// [`DynamicFilterHandle::invalidate`] to bump the filter's epoch; this
+/// rebuilds the global interest cache (via
+/// [`tracing_core::callsite::rebuild_interest_cache`]) so that spans and
+/// events which were previously cached as disabled are asked about again.
+///
+/// [`FilterFn`]: crate::filter::FilterFn
+/// [`Metadata`]: tracing_core::Metadata
+pub struct DynamicFilter<F = fn(&Metadata<'_>) -> bool> {
+    enabled: F,
+    max_level_hint: Option<LevelFilter>,
+    epoch: Arc<AtomicUsize>,
+}
+
+/// A handle for invalidating a [`DynamicFilter`]'s cached callsite interest.
+///
+/// Cloning a `DynamicFilterHandle` produces another handle that invalidates
+/// the *same* filter; this is how a `DynamicFilter` and its handle can be
+/// held independently (for example, the filter installed on a subscriber, and
+/// the handle stored wherever the backing feature-flag state is updated).
+#[derive(Clone, Debug)]
+pub struct DynamicFilterHandle {
+    epoch: Arc<AtomicUsize>,
+}
+
+/// Constructs a [`DynamicFilter`] from a function or closure that returns
+/// `true` if a span or event should be enabled, based on its [`Metadata`],
+/// along with a [`DynamicFilterHandle`] used to invalidate the filter's
+/// cached interest when the predicate's underlying state changes.
+///
+/// This is equivalent to calling [`DynamicFilter::new`].
+///
+/// # Examples
+///
+/// ```
+/// use tracing_subscriber::{filter, subscribe::{CollectExt, Subscribe}, util::SubscriberInitExt};
+/// use std::sync::atomic::{AtomicBool, Ordering};
+/// use std::sync::Arc;
+///
+/// let flag_enabled = Arc::new(AtomicBool::new(false));
+/// let flag = flag_enabled.clone();
+/// let (my_filter, handle) = filter::dynamic(move |_metadata| flag.load(Ordering::Relaxed));
+///
+/// tracing_subscriber::registry()
+///     .with(tracing_subscriber::fmt::subscriber().with_filter(my_filter))
+///     .init();
+///
+/// // The flag flips at runtime; tell the filter its cached decisions are stale.
+/// flag_enabled.store(true, Ordering::Relaxed);
+/// handle.invalidate();
+/// ```
+///
+/// [`Metadata`]: tracing_core::Metadata
+pub fn dynamic<F>(f: F) -> (DynamicFilter<F>, DynamicFilterHandle)
+where
+    F: Fn(&Metadata<'_>) -> bool,
+{
+    DynamicFilter::new(f)
+}
+
+// === impl DynamicFilter ===
+
+impl<F> DynamicFilter<F>
+where
+    F: Fn(&Metadata<'_>) -> bool,
+{
+    /// Constructs a [`DynamicFilter`] from a function or closure, returning
+    /// it along with a [`DynamicFilterHandle`] for invalidating its cached
+    /// interest.
+    ///
+    /// See [`dynamic`] for details.
+    pub fn new(enabled: F) -> (Self, DynamicFilterHandle) {
+        let epoch = Arc::new(AtomicUsize::new(0));
+        let filter = Self {
+            enabled,
+            max_level_hint: None,
+            epoch: epoch.clone(),
+        };
+        (filter, DynamicFilterHandle { epoch })
+    }
+
+    /// Sets the highest verbosity [`Level`] the filter function will enable.
+    ///
+    /// This behaves identically to [`FilterFn::with_max_level_hint`].
+    ///
+    /// [`Level`]: tracing_core::Level
+    /// [`FilterFn::with_max_level_hint`]: crate::filter::FilterFn::with_max_level_hint
+    pub fn with_max_level_hint(self, max_level_hint: impl Into<LevelFilter>) -> Self {
+        Self {
+            max_level_hint: Some(max_level_hint.into()),
+            ..self
+        }
+    }
+
+    #[inline]
+    fn is_enabled(&self, metadata: &Metadata<'_>) -> bool {
+        (self.enabled)(metadata)
+    }
+
+    fn is_callsite_enabled(&self, metadata: &'static Metadata<'static>) -> Interest {
+        if !self.is_below_max_level(metadata) {
+            return Interest::never();
+        }
+
+        // The predicate is backed by external, mutable state, so its answer
+        // for this callsite could change at any time. Never cache a
+        // definite `always`/`never` decision; instead, ask again on every
+        // occurrence, and rely on `DynamicFilterHandle::invalidate` to
+        // trigger a fresh `enabled` call sitewide when that state changes.
+        Interest::sometimes()
+    }
+
+    fn is_below_max_level(&self, metadata: &Metadata<'_>) -> bool {
+        self.max_level_hint
+            .as_ref()
+            .map(|hint| metadata.level() <= hint)
+            .unwrap_or(true)
+    }
+}
+
+impl<C, F> Subscribe<C> for DynamicFilter<F>
+where
+    F: Fn(&Metadata<'_>) -> bool + 'static,
+    C: Collect,
+{
+    fn enabled(&self, metadata: &Metadata<'_>, _: Context<'_, C>) -> bool {
+        self.is_enabled(metadata)
+    }
+
+    fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+        self.is_callsite_enabled(metadata)
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        self.max_level_hint
+    }
+}
+
+impl<F> fmt::Debug for DynamicFilter<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DynamicFilter")
+            .field("max_level_hint", &self.max_level_hint)
+            .field("epoch", &self.epoch.load(Ordering::Relaxed))
+            .finish()
+    }
+}
+
+feature! {
+    #![all(feature = "registry", feature = "std")]
+    use crate::subscribe::Filter;
+
+    impl<C, F> Filter<C> for DynamicFilter<F>
+    where
+        F: Fn(&Metadata<'_>) -> bool,
+    {
+        fn enabled(&self, metadata: &Metadata<'_>, _: &Context<'_, C>) -> bool {
+            self.is_enabled(metadata)
+        }
+
+        fn callsite_enabled(&self, metadata: &'static Metadata<'static>) -> Interest {
+            self.is_callsite_enabled(metadata)
+        }
+
+        fn max_level_hint(&self) -> Option<LevelFilter> {
+            self.max_level_hint
+        }
+    }
+}
+
+// === impl DynamicFilterHandle ===
+
+impl DynamicFilterHandle {
+    /// Invalidates the associated [`DynamicFilter`]'s cached callsite
+    /// interest, forcing every callsite to be asked about its interest
+    /// again.
+    ///
+    /// Call this whenever the external state backing the filter's predicate
+    /// changes, so that spans and events cached as disabled while the old
+    /// state was in effect are reevaluated under the new one.
+    pub fn invalidate(&self) {
+        self.epoch.fetch_add(1, Ordering::SeqCst);
+        callsite::rebuild_interest_cache();
+    }
+}
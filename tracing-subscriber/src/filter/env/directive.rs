@@ -40,6 +40,10 @@ impl Directive {
         self.in_span.is_some()
     }
 
+    pub(super) fn in_span_name(&self) -> Option<&str> {
+        self.in_span.as_deref()
+    }
+
     pub(super) fn has_fields(&self) -> bool {
         !self.fields.is_empty()
     }
@@ -151,8 +155,9 @@ impl Directive {
                 (
                     # field name
                     [[:word:]][[[:word:]]\.]*
-                    # value part (optional)
-                    (?:=[^,]+)?
+                    # value part (optional), introduced by a comparison
+                    # operator (`=`, `!=`, `<`, `<=`, `>`, or `>=`)
+                    (?:[=!<>][^,]+)?
                 )
                 # trailing comma or EOS
                 (?:,\s?|$)
@@ -826,6 +831,20 @@ mod test {
         assert_eq!(dirs[2].in_span, Some("baz".to_string()));
     }
 
+    #[test]
+    fn parse_directives_with_field_comparator() {
+        let dirs = parse_directives("crate1::mod1[foo{status>=500}]=error");
+        assert_eq!(dirs.len(), 1, "\nparsed: {:#?}", dirs);
+        assert_eq!(dirs[0].target, Some("crate1::mod1".to_string()));
+        assert_eq!(dirs[0].in_span, Some("foo".to_string()));
+        assert_eq!(dirs[0].fields.len(), 1);
+        assert_eq!(dirs[0].fields[0].name, "status");
+        assert_eq!(
+            dirs[0].fields[0].value,
+            Some(field::ValueMatch::U64(field::Comparator::Ge, 500))
+        );
+    }
+
     #[test]
     fn parse_directives_with_dash_in_target_name() {
         let dirs = parse_directives("target-name=info");
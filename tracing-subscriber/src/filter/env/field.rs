@@ -40,12 +40,12 @@ pub(crate) struct MatchVisitor<'a> {
 pub(crate) enum ValueMatch {
     /// Matches a specific `bool` value.
     Bool(bool),
-    /// Matches a specific `f64` value.
-    F64(f64),
-    /// Matches a specific `u64` value.
-    U64(u64),
-    /// Matches a specific `i64` value.
-    I64(i64),
+    /// Matches an `f64` value against `Comparator`.
+    F64(Comparator, f64),
+    /// Matches a `u64` value against `Comparator`.
+    U64(Comparator, u64),
+    /// Matches an `i64` value against `Comparator`.
+    I64(Comparator, i64),
     /// Matches any `NaN` `f64` value.
     NaN,
     /// Matches any field whose `fmt::Debug` output is equal to a fixed string.
@@ -55,6 +55,97 @@ pub(crate) enum ValueMatch {
     Pat(Box<MatchPattern>),
 }
 
+/// A comparison operator used to match a numeric field value against an
+/// expected value, e.g. `status>=500`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Comparator {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// Indicates that a comparison operator other than `=` was used with a field
+/// value that doesn't support ordering comparisons (a `bool`, or a value
+/// matched by `fmt::Debug` output or a regular expression).
+#[derive(Clone, Debug)]
+pub(crate) struct UnsupportedComparator {
+    comparator: Comparator,
+}
+
+/// Splits `s` at its first comparison operator (`=`, `!=`, `>`, `>=`, `<`,
+/// or `<=`), returning the field name, the operator, and the value on
+/// either side of it. Returns `None` if `s` contains no comparison
+/// operator, i.e. the directive matched a span by name only, with no field
+/// value (e.g. `my_target[span]=debug`, not
+/// `my_target[span{field=value}]=debug`).
+fn split_field_and_value(s: &str) -> Option<(&str, Comparator, &str)> {
+    let bytes = s.as_bytes();
+    for (i, &b) in bytes.iter().enumerate() {
+        let (comparator, len) = match b {
+            b'!' if bytes.get(i + 1) == Some(&b'=') => (Comparator::Ne, 2),
+            b'>' if bytes.get(i + 1) == Some(&b'=') => (Comparator::Ge, 2),
+            b'<' if bytes.get(i + 1) == Some(&b'=') => (Comparator::Le, 2),
+            b'=' => (Comparator::Eq, 1),
+            b'>' => (Comparator::Gt, 1),
+            b'<' => (Comparator::Lt, 1),
+            _ => continue,
+        };
+        return Some((&s[..i], comparator, &s[i + len..]));
+    }
+    None
+}
+
+impl Comparator {
+    /// Returns whether `ordering` satisfies this comparator.
+    fn matches_ordering(self, ordering: Ordering) -> bool {
+        match self {
+            Comparator::Eq => ordering == Ordering::Equal,
+            Comparator::Ne => ordering != Ordering::Equal,
+            Comparator::Lt => ordering == Ordering::Less,
+            Comparator::Le => ordering != Ordering::Greater,
+            Comparator::Gt => ordering == Ordering::Greater,
+            Comparator::Ge => ordering != Ordering::Less,
+        }
+    }
+
+    /// Returns an error unless `self` is `Comparator::Eq`, for value kinds
+    /// that can only be compared for equality.
+    fn eq_only(self) -> Result<(), UnsupportedComparator> {
+        match self {
+            Comparator::Eq => Ok(()),
+            comparator => Err(UnsupportedComparator { comparator }),
+        }
+    }
+}
+
+impl fmt::Display for Comparator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Comparator::Eq => "=",
+            Comparator::Ne => "!=",
+            Comparator::Lt => "<",
+            Comparator::Le => "<=",
+            Comparator::Gt => ">",
+            Comparator::Ge => ">=",
+        })
+    }
+}
+
+impl Error for UnsupportedComparator {}
+
+impl fmt::Display for UnsupportedComparator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "comparator `{}` may only be used with numeric field values",
+            self.comparator
+        )
+    }
+}
+
 impl Eq for ValueMatch {}
 
 impl PartialEq for ValueMatch {
@@ -62,14 +153,14 @@ impl PartialEq for ValueMatch {
         use ValueMatch::*;
         match (self, other) {
             (Bool(a), Bool(b)) => a.eq(b),
-            (F64(a), F64(b)) => {
+            (F64(cmp_a, a), F64(cmp_b, b)) => {
                 debug_assert!(!a.is_nan());
                 debug_assert!(!b.is_nan());
 
-                a.eq(b)
+                cmp_a.eq(cmp_b) && a.eq(b)
             }
-            (U64(a), U64(b)) => a.eq(b),
-            (I64(a), I64(b)) => a.eq(b),
+            (U64(cmp_a, a), U64(cmp_b, b)) => cmp_a.eq(cmp_b) && a.eq(b),
+            (I64(cmp_a, a), I64(cmp_b, b)) => cmp_a.eq(cmp_b) && a.eq(b),
             (NaN, NaN) => true,
             (Pat(a), Pat(b)) => a.eq(b),
             _ => false,
@@ -84,25 +175,30 @@ impl Ord for ValueMatch {
             (Bool(this), Bool(that)) => this.cmp(that),
             (Bool(_), _) => Ordering::Less,
 
-            (F64(this), F64(that)) => this
+            (F64(cmp_this, this), F64(cmp_that, that)) => this
                 .partial_cmp(that)
-                .expect("`ValueMatch::F64` may not contain `NaN` values"),
-            (F64(_), Bool(_)) => Ordering::Greater,
-            (F64(_), _) => Ordering::Less,
+                .expect("`ValueMatch::F64` may not contain `NaN` values")
+                .then_with(|| cmp_this.cmp(cmp_that)),
+            (F64(..), Bool(_)) => Ordering::Greater,
+            (F64(..), _) => Ordering::Less,
 
             (NaN, NaN) => Ordering::Equal,
-            (NaN, Bool(_)) | (NaN, F64(_)) => Ordering::Greater,
+            (NaN, Bool(_)) | (NaN, F64(..)) => Ordering::Greater,
             (NaN, _) => Ordering::Less,
 
-            (U64(this), U64(that)) => this.cmp(that),
-            (U64(_), Bool(_)) | (U64(_), F64(_)) | (U64(_), NaN) => Ordering::Greater,
-            (U64(_), _) => Ordering::Less,
+            (U64(cmp_this, this), U64(cmp_that, that)) => {
+                this.cmp(that).then_with(|| cmp_this.cmp(cmp_that))
+            }
+            (U64(..), Bool(_)) | (U64(..), F64(..)) | (U64(..), NaN) => Ordering::Greater,
+            (U64(..), _) => Ordering::Less,
 
-            (I64(this), I64(that)) => this.cmp(that),
-            (I64(_), Bool(_)) | (I64(_), F64(_)) | (I64(_), NaN) | (I64(_), U64(_)) => {
+            (I64(cmp_this, this), I64(cmp_that, that)) => {
+                this.cmp(that).then_with(|| cmp_this.cmp(cmp_that))
+            }
+            (I64(..), Bool(_)) | (I64(..), F64(..)) | (I64(..), NaN) | (I64(..), U64(..)) => {
                 Ordering::Greater
             }
-            (I64(_), _) => Ordering::Less,
+            (I64(..), _) => Ordering::Less,
 
             (Pat(this), Pat(that)) => this.cmp(that),
             (Pat(_), _) => Ordering::Greater,
@@ -158,22 +254,22 @@ impl Match {
     }
 
     pub(crate) fn parse(s: &str, regex: bool) -> Result<Self, Box<dyn Error + Send + Sync>> {
-        let mut parts = s.split('=');
-        let name = parts
-            .next()
-            .ok_or_else(|| BadName {
-                name: "".to_string(),
-            })?
+        let (name, value) = match split_field_and_value(s) {
+            Some((name, comparator, value)) => {
+                let value = if regex {
+                    ValueMatch::parse_regex(comparator, value)?
+                } else {
+                    ValueMatch::parse_non_regex(comparator, value)?
+                };
+                (name, Some(value))
+            }
             // TODO: validate field name
-            .to_string();
-        let value = parts
-            .next()
-            .map(|part| match regex {
-                true => ValueMatch::parse_regex(part),
-                false => Ok(ValueMatch::parse_non_regex(part)),
-            })
-            .transpose()?;
-        Ok(Match { name, value })
+            None => (s, None),
+        };
+        Ok(Match {
+            name: name.to_string(),
+            value,
+        })
     }
 }
 
@@ -181,7 +277,7 @@ impl fmt::Display for Match {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt::Display::fmt(&self.name, f)?;
         if let Some(ref value) = self.value {
-            write!(f, "={}", value)?;
+            fmt::Display::fmt(value, f)?;
         }
         Ok(())
     }
@@ -219,11 +315,11 @@ impl PartialOrd for Match {
 
 // === impl ValueMatch ===
 
-fn value_match_f64(v: f64) -> ValueMatch {
+fn value_match_f64(cmp: Comparator, v: f64) -> ValueMatch {
     if v.is_nan() {
         ValueMatch::NaN
     } else {
-        ValueMatch::F64(v)
+        ValueMatch::F64(cmp, v)
     }
 }
 
@@ -231,47 +327,66 @@ impl ValueMatch {
     /// Parse a `ValueMatch` that will match `fmt::Debug` fields using regular
     /// expressions.
     ///
-    /// This returns an error if the string didn't contain a valid `bool`,
-    /// `u64`, `i64`, or `f64` literal, and couldn't be parsed as a regular
-    /// expression.
-    fn parse_regex(s: &str) -> Result<Self, matchers::Error> {
-        s.parse::<bool>()
-            .map(ValueMatch::Bool)
-            .or_else(|_| s.parse::<u64>().map(ValueMatch::U64))
-            .or_else(|_| s.parse::<i64>().map(ValueMatch::I64))
-            .or_else(|_| s.parse::<f64>().map(value_match_f64))
-            .or_else(|_| {
-                s.parse::<MatchPattern>()
-                    .map(|p| ValueMatch::Pat(Box::new(p)))
-            })
+    /// This returns an error if `comparator` isn't `Comparator::Eq` and the
+    /// string didn't contain a valid `u64`, `i64`, or `f64` literal (since
+    /// only numeric values support ordering comparisons), or if the string
+    /// didn't contain a valid `bool`, `u64`, `i64`, or `f64` literal, and
+    /// couldn't be parsed as a regular expression.
+    fn parse_regex(comparator: Comparator, s: &str) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        if let Ok(value) = s.parse::<u64>() {
+            return Ok(ValueMatch::U64(comparator, value));
+        }
+        if let Ok(value) = s.parse::<i64>() {
+            return Ok(ValueMatch::I64(comparator, value));
+        }
+        if let Ok(value) = s.parse::<f64>() {
+            return Ok(value_match_f64(comparator, value));
+        }
+        comparator.eq_only()?;
+        if let Ok(value) = s.parse::<bool>() {
+            return Ok(ValueMatch::Bool(value));
+        }
+        Ok(ValueMatch::Pat(Box::new(s.parse::<MatchPattern>()?)))
     }
 
     /// Parse a `ValueMatch` that will match `fmt::Debug` against a fixed
     /// string.
     ///
-    /// This does *not* return an error, because any string that isn't a valid
-    /// `bool`, `u64`, `i64`, or `f64` literal is treated as expected
-    /// `fmt::Debug` output.
-    fn parse_non_regex(s: &str) -> Self {
-        s.parse::<bool>()
-            .map(ValueMatch::Bool)
-            .or_else(|_| s.parse::<u64>().map(ValueMatch::U64))
-            .or_else(|_| s.parse::<i64>().map(ValueMatch::I64))
-            .or_else(|_| s.parse::<f64>().map(value_match_f64))
-            .unwrap_or_else(|_| ValueMatch::Debug(MatchDebug::new(s)))
+    /// This returns an error only if `comparator` isn't `Comparator::Eq` and
+    /// the string didn't contain a valid `u64`, `i64`, or `f64` literal,
+    /// since a comparison operator other than `=` doesn't make sense when
+    /// matching `fmt::Debug` output.
+    fn parse_non_regex(
+        comparator: Comparator,
+        s: &str,
+    ) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        if let Ok(value) = s.parse::<u64>() {
+            return Ok(ValueMatch::U64(comparator, value));
+        }
+        if let Ok(value) = s.parse::<i64>() {
+            return Ok(ValueMatch::I64(comparator, value));
+        }
+        if let Ok(value) = s.parse::<f64>() {
+            return Ok(value_match_f64(comparator, value));
+        }
+        comparator.eq_only()?;
+        if let Ok(value) = s.parse::<bool>() {
+            return Ok(ValueMatch::Bool(value));
+        }
+        Ok(ValueMatch::Debug(MatchDebug::new(s)))
     }
 }
 
 impl fmt::Display for ValueMatch {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            ValueMatch::Bool(ref inner) => fmt::Display::fmt(inner, f),
-            ValueMatch::F64(ref inner) => fmt::Display::fmt(inner, f),
-            ValueMatch::NaN => fmt::Display::fmt(&f64::NAN, f),
-            ValueMatch::I64(ref inner) => fmt::Display::fmt(inner, f),
-            ValueMatch::U64(ref inner) => fmt::Display::fmt(inner, f),
-            ValueMatch::Debug(ref inner) => fmt::Display::fmt(inner, f),
-            ValueMatch::Pat(ref inner) => fmt::Display::fmt(inner, f),
+            ValueMatch::Bool(ref inner) => write!(f, "={}", inner),
+            ValueMatch::F64(ref cmp, ref inner) => write!(f, "{}{}", cmp, inner),
+            ValueMatch::NaN => write!(f, "={}", f64::NAN),
+            ValueMatch::I64(ref cmp, ref inner) => write!(f, "{}{}", cmp, inner),
+            ValueMatch::U64(ref cmp, ref inner) => write!(f, "{}{}", cmp, inner),
+            ValueMatch::Debug(ref inner) => write!(f, "={}", inner),
+            ValueMatch::Pat(ref inner) => write!(f, "={}", inner),
         }
     }
 }
@@ -506,8 +621,13 @@ impl Visit for MatchVisitor<'_> {
             Some((ValueMatch::NaN, ref matched)) if value.is_nan() => {
                 matched.store(true, Release);
             }
-            Some((ValueMatch::F64(ref e), ref matched))
-                if (value - *e).abs() < f64::EPSILON =>
+            Some((ValueMatch::F64(cmp, e), ref matched)) if cmp.matches_ordering(
+                if (value - *e).abs() < f64::EPSILON {
+                    Ordering::Equal
+                } else {
+                    value.partial_cmp(e).unwrap_or(Ordering::Greater)
+                },
+            ) =>
             {
                 matched.store(true, Release);
             }
@@ -519,10 +639,14 @@ impl Visit for MatchVisitor<'_> {
         use std::convert::TryInto;
 
         match self.inner.fields.get(field) {
-            Some((ValueMatch::I64(ref e), ref matched)) if value == *e => {
+            Some((ValueMatch::I64(cmp, e), ref matched)) if cmp.matches_ordering(value.cmp(e)) => {
                 matched.store(true, Release);
             }
-            Some((ValueMatch::U64(ref e), ref matched)) if Ok(value) == (*e).try_into() => {
+            Some((ValueMatch::U64(cmp, e), ref matched))
+                if TryInto::<i64>::try_into(*e)
+                    .map(|e| cmp.matches_ordering(value.cmp(&e)))
+                    .unwrap_or(false) =>
+            {
                 matched.store(true, Release);
             }
             _ => {}
@@ -531,7 +655,7 @@ impl Visit for MatchVisitor<'_> {
 
     fn record_u64(&mut self, field: &Field, value: u64) {
         match self.inner.fields.get(field) {
-            Some((ValueMatch::U64(ref e), ref matched)) if value == *e => {
+            Some((ValueMatch::U64(cmp, e), ref matched)) if cmp.matches_ordering(value.cmp(e)) => {
                 matched.store(true, Release);
             }
             _ => {}
@@ -623,4 +747,39 @@ mod tests {
         };
         assert!(!matcher.debug_matches(&my_struct))
     }
+
+    #[test]
+    fn parse_comparators() {
+        let ge = Match::parse("status>=500", true).unwrap();
+        assert_eq!(ge.name, "status");
+        assert_eq!(ge.value, Some(ValueMatch::U64(Comparator::Ge, 500)));
+
+        let lt = Match::parse("status<400", true).unwrap();
+        assert_eq!(lt.value, Some(ValueMatch::U64(Comparator::Lt, 400)));
+
+        let ne = Match::parse("status!=200", true).unwrap();
+        assert_eq!(ne.value, Some(ValueMatch::U64(Comparator::Ne, 200)));
+
+        let eq = Match::parse("status=200", true).unwrap();
+        assert_eq!(eq.value, Some(ValueMatch::U64(Comparator::Eq, 200)));
+    }
+
+    #[test]
+    fn ordering_comparator_on_non_numeric_value_is_an_error() {
+        assert!(Match::parse("name>=\"bob\"", true).is_err());
+        assert!(Match::parse("enabled>=true", true).is_err());
+    }
+
+    #[test]
+    fn comparator_matches_ordering() {
+        assert!(Comparator::Ge.matches_ordering(Ordering::Greater));
+        assert!(Comparator::Ge.matches_ordering(Ordering::Equal));
+        assert!(!Comparator::Ge.matches_ordering(Ordering::Less));
+
+        assert!(Comparator::Lt.matches_ordering(Ordering::Less));
+        assert!(!Comparator::Lt.matches_ordering(Ordering::Equal));
+
+        assert!(Comparator::Ne.matches_ordering(Ordering::Less));
+        assert!(!Comparator::Ne.matches_ordering(Ordering::Equal));
+    }
 }
@@ -21,7 +21,7 @@ use tracing_core::{
     callsite,
     collect::{Collect, Interest},
     field::Field,
-    span, Metadata,
+    span, Level, Metadata,
 };
 
 /// A [`Subscriber`] which filters spans and events based on a set of filter
@@ -65,6 +65,12 @@ use tracing_core::{
 /// - `value` matches on the value of a span's field. If a value is a numeric literal or a bool,
 ///    it will match _only_ on that value. Otherwise, this filter matches the
 ///    [`std::fmt::Debug`] output from the value.
+///
+///    Numeric field values may also be matched with a comparison operator other than `=`,
+///    to filter on a range of values rather than an exact one: `!=`, `<`, `<=`, `>`, or `>=`.
+///    For example, `[span{status>=500}]=debug` will only match spans with a `status` field
+///    whose value is at least `500`. Comparison operators other than `=` are not supported
+///    for non-numeric field values.
 /// - `level` sets a maximum verbosity level accepted by this directive.
 ///
 /// When a field value directive (`[{<FIELD NAME>=<FIELD_VALUE>}]=...`) matches a
@@ -607,6 +613,99 @@ impl EnvFilter {
         spans.contains_key(span)
     }
 
+    /// Explains why this `EnvFilter` would or would not enable the provided
+    /// `metadata`, without requiring trial-and-error edits to the filter
+    /// string.
+    ///
+    /// Unlike [`EnvFilter::enabled`], this only considers the directives this
+    /// filter was configured with --- it does not take the current span
+    /// scope into account, since a dynamic directive's effect on a given
+    /// event can depend on field values recorded on spans entered at the
+    /// time the event occurs. When a dynamic directive matches, the
+    /// explanation says so, but the actual enabled/disabled decision in that
+    /// case may differ at runtime.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tracing_subscriber::filter::EnvFilter;
+    ///
+    /// let filter = EnvFilter::new("my_crate=debug,other_crate=warn");
+    /// let explanation = filter.explain(tracing::Level::INFO, "other_crate::module", None);
+    /// assert!(!explanation.is_enabled());
+    /// println!("{}", explanation);
+    /// ```
+    pub fn explain(
+        &self,
+        level: Level,
+        target: &str,
+        name: Option<&str>,
+    ) -> Explanation {
+        // We only have a `&str` target/name to go on here (no callsite to
+        // build real `Metadata` from), so directive matching is done
+        // directly against `target`/`name` rather than via `Match::cares_about`.
+        if self.has_dynamics {
+            if let Some(directive) = self
+                .dynamics
+                .directives()
+                .find(|d| Self::directive_cares_about(d, target, name))
+            {
+                let enabled = directive.level >= level;
+                return Explanation {
+                    enabled,
+                    reason: format!(
+                        "{} by dynamic directive `{}`; dynamic directives may filter on \
+                         recorded field values, so the actual decision can differ once the \
+                         event occurs",
+                        if enabled { "enabled" } else { "disabled" },
+                        directive,
+                    ),
+                };
+            }
+        }
+
+        if let Some(directive) = self
+            .statics
+            .directives()
+            .find(|d| d.cares_about_target(target))
+        {
+            let enabled = directive.level >= level;
+            return Explanation {
+                enabled,
+                reason: format!(
+                    "{} by directive `{}`",
+                    if enabled { "enabled" } else { "disabled" },
+                    directive,
+                ),
+            };
+        }
+
+        Explanation {
+            enabled: false,
+            reason: format!("disabled: no configured directive matches target `{target}`"),
+        }
+    }
+
+    fn directive_cares_about(directive: &Directive, target: &str, name: Option<&str>) -> bool {
+        // A directive that filters on span field values can't be evaluated
+        // from just a target and name, since we don't have the field values
+        // recorded on the span; skip it rather than risk a false match.
+        if directive.has_fields() {
+            return false;
+        }
+        if let Some(ref dtarget) = directive.target {
+            if !target.starts_with(dtarget.as_str()) {
+                return false;
+            }
+        }
+        if let Some(dname) = directive.in_span_name() {
+            if name != Some(dname) {
+                return false;
+            }
+        }
+        true
+    }
+
     fn base_interest(&self) -> Interest {
         if self.has_dynamics {
             Interest::sometimes()
@@ -636,6 +735,32 @@ impl EnvFilter {
     }
 }
 
+/// The result of [`EnvFilter::explain`]: whether a piece of metadata would be
+/// enabled, along with a human-readable reason why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    enabled: bool,
+    reason: String,
+}
+
+impl Explanation {
+    /// Returns whether the explained metadata would be enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Returns the human-readable reason for the enabled/disabled decision.
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.reason)
+    }
+}
+
 impl<C: Collect> Subscribe<C> for EnvFilter {
     #[inline]
     fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
@@ -849,6 +974,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn explain_enabled_by_static_directive() {
+        let filter = EnvFilter::new("my_crate=debug,other_crate=warn");
+
+        let explanation = filter.explain(Level::DEBUG, "my_crate::module", None);
+        assert!(explanation.is_enabled());
+        assert!(explanation.reason().contains("my_crate"));
+    }
+
+    #[test]
+    fn explain_disabled_by_static_directive() {
+        let filter = EnvFilter::new("my_crate=debug,other_crate=warn");
+
+        let explanation = filter.explain(Level::INFO, "other_crate::module", None);
+        assert!(!explanation.is_enabled());
+        assert!(explanation.reason().contains("other_crate"));
+    }
+
+    #[test]
+    fn explain_no_matching_directive() {
+        let filter = EnvFilter::new("my_crate=debug");
+
+        let explanation = filter.explain(Level::ERROR, "unrelated_crate", None);
+        assert!(!explanation.is_enabled());
+        assert!(explanation.reason().contains("unrelated_crate"));
+    }
+
     #[test]
     fn callsite_enabled_no_span_directive() {
         let filter = EnvFilter::new("app=debug").with_collector(NoCollector);
@@ -952,6 +1104,16 @@ mod tests {
         assert_eq!(f1.dynamics, f2.dynamics);
     }
 
+    #[test]
+    fn roundtrip_with_comparator() {
+        let f1: EnvFilter = "[span1{status>=500}]=error,[span2{status!=200}]=debug"
+            .parse()
+            .unwrap();
+        let f2: EnvFilter = format!("{}", f1).parse().unwrap();
+        assert_eq!(f1.statics, f2.statics);
+        assert_eq!(f1.dynamics, f2.dynamics);
+    }
+
     #[test]
     fn size_of_filters() {
         fn print_sz(s: &str) {
@@ -0,0 +1,170 @@
+//! Span *importance*, a retention/sampling signal distinct from [`Level`].
+//!
+//! A span's [`Level`] describes how verbose it is, but says nothing about
+//! how important it is to keep around: a `TRACE`-level span deep inside a
+//! request handler might still be the one span you never want to drop, while
+//! plenty of routine `INFO` spans are safe to sample away. This module
+//! provides [`Importance`], a small, independent axis that spans can set via
+//! an ordinary `importance` field (e.g. `info_span!("checkout", importance =
+//! "critical")`), and [`ImportanceLayer`], a [`Subscribe`] that captures that
+//! field into a span [extension] so that other layers --- particularly
+//! sampling or retention filters --- can make decisions based on it.
+//!
+//! [extension]: crate::registry::Extensions
+//! [`Level`]: tracing_core::Level
+use crate::{
+    registry::LookupSpan,
+    subscribe::{Context, Subscribe},
+};
+use core::{fmt, str::FromStr};
+use tracing_core::{
+    field::{Field, Visit},
+    span::{Attributes, Id, Record},
+    Collect,
+};
+
+/// The name of the well-known field used to set a span's [`Importance`].
+pub const FIELD_NAME: &str = "importance";
+
+/// A span's importance for retention/sampling purposes, independent of its
+/// [`Level`].
+///
+/// Variants are ordered from least to most important, so that
+/// `Importance::Critical > Importance::Normal`.
+///
+/// [`Level`]: tracing_core::Level
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Importance {
+    /// Safe to discard first under load.
+    Low,
+    /// The default importance, for spans that don't set one explicitly.
+    #[default]
+    Normal,
+    /// Should be preferentially retained.
+    High,
+    /// Must always be retained (e.g. spans covering a paying customer's
+    /// checkout flow, or a security-relevant operation).
+    Critical,
+}
+
+impl fmt::Display for Importance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Importance::Low => "low",
+            Importance::Normal => "normal",
+            Importance::High => "high",
+            Importance::Critical => "critical",
+        })
+    }
+}
+
+/// An error returned when parsing an [`Importance`] from a string that
+/// doesn't name one of its variants.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ParseImportanceError(());
+
+impl fmt::Display for ParseImportanceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid importance level, expected one of: low, normal, high, critical")
+    }
+}
+
+impl std::error::Error for ParseImportanceError {}
+
+impl FromStr for Importance {
+    type Err = ParseImportanceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            s if s.eq_ignore_ascii_case("low") => Ok(Importance::Low),
+            s if s.eq_ignore_ascii_case("normal") => Ok(Importance::Normal),
+            s if s.eq_ignore_ascii_case("high") => Ok(Importance::High),
+            s if s.eq_ignore_ascii_case("critical") => Ok(Importance::Critical),
+            _ => Err(ParseImportanceError(())),
+        }
+    }
+}
+
+/// A [`Subscribe`] that captures the well-known `importance` field on new
+/// spans (and later updates to it via [`Span::record`]), storing the parsed
+/// [`Importance`] as a span extension.
+///
+/// Layers that come after this one in a stack can read a span's importance
+/// with `ctx.span(id).extensions().get::<Importance>()`, and use it for
+/// retention or sampling decisions independent of the span's [`Level`].
+///
+/// [`Span::record`]: tracing::Span::record
+/// [`Level`]: tracing_core::Level
+#[derive(Clone, Debug, Default)]
+pub struct ImportanceLayer {
+    _p: (),
+}
+
+/// Returns a new [`ImportanceLayer`].
+pub fn layer() -> ImportanceLayer {
+    ImportanceLayer::default()
+}
+
+struct ImportanceVisitor(Option<Importance>);
+
+impl Visit for ImportanceVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == FIELD_NAME {
+            self.0 = value.parse().ok();
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == FIELD_NAME && self.0.is_none() {
+            self.0 = format!("{:?}", value).trim_matches('"').parse().ok();
+        }
+    }
+}
+
+impl<C> Subscribe<C> for ImportanceLayer
+where
+    C: Collect + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, C>) {
+        let mut visitor = ImportanceVisitor(None);
+        attrs.record(&mut visitor);
+        if let Some(importance) = visitor.0 {
+            if let Some(span) = ctx.span(id) {
+                span.extensions_mut().insert(importance);
+            }
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, C>) {
+        let mut visitor = ImportanceVisitor(None);
+        values.record(&mut visitor);
+        if let Some(importance) = visitor.0 {
+            if let Some(span) = ctx.span(id) {
+                span.extensions_mut().insert(importance);
+            }
+        }
+    }
+}
+
+/// Returns whether `id`'s span, or any of its ancestors, has an
+/// [`Importance`] of at least `min`.
+///
+/// This can be used from a custom [`Filter`] or [`Subscribe`] to override an
+/// otherwise level-based enablement decision for spans and events that occur
+/// within an important span.
+///
+/// [`Filter`]: crate::subscribe::Filter
+pub fn is_at_least<C>(ctx: &Context<'_, C>, id: &Id, min: Importance) -> bool
+where
+    C: Collect + for<'a> LookupSpan<'a>,
+{
+    ctx.span(id)
+        .into_iter()
+        .flat_map(|span| span.scope())
+        .any(|span| {
+            span.extensions()
+                .get::<Importance>()
+                .map(|importance| *importance >= min)
+                .unwrap_or(false)
+        })
+}
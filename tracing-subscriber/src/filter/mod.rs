@@ -17,12 +17,44 @@ feature! {
     pub use self::env::*;
 }
 
+feature! {
+    #![feature = "std"]
+    mod dynamic;
+    pub use self::dynamic::*;
+}
+
 feature! {
     #![all(feature = "registry", feature = "std")]
     mod subscriber_filters;
     pub use self::subscriber_filters::*;
 }
 
+feature! {
+    #![all(feature = "registry", feature = "std", feature = "tracing")]
+    mod span_scope;
+    pub use self::span_scope::*;
+}
+
+feature! {
+    #![all(feature = "registry", feature = "std")]
+    pub mod importance;
+    pub use self::importance::{Importance, ImportanceLayer};
+}
+
+feature! {
+    #![all(feature = "registry", feature = "std")]
+    pub mod sampling;
+    pub use self::sampling::{is_sampled, SamplingLayer};
+}
+
+feature! {
+    #![feature = "std"]
+    mod rate_limit;
+    pub use self::rate_limit::RateLimit;
+    mod span_rate_limit;
+    pub use self::span_rate_limit::SpanRateLimit;
+}
+
 pub use self::filter_fn::*;
 #[cfg(not(feature = "registry"))]
 pub(crate) use self::has_psf_stubs::*;
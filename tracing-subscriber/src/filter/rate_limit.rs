@@ -0,0 +1,372 @@
+//! A [`Filter`] combinator that caps how many events a callsite may record
+//! within a window.
+//!
+//! [`Filter`]: crate::subscribe::Filter
+use crate::{filter::LevelFilter, subscribe::Context};
+use once_cell::sync::Lazy;
+use std::{
+    cell::Cell,
+    collections::HashMap,
+    fmt,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use tracing_core::{
+    callsite::{
+        dynamic::{Interner, MetadataBuilder},
+        Identifier,
+    },
+    collect::Interest,
+    field, Collect, Event, Metadata,
+};
+
+/// A [`Filter`] that caps how many events a single callsite may record
+/// within a sliding window, dropping the rest.
+///
+/// This is meant for noisy call sites --- a `warn!` in a hot retry loop, for
+/// instance --- that would otherwise flood the log with repetitions of
+/// essentially the same event. Once a window's budget for a callsite is
+/// exhausted, every further event from that callsite is suppressed until the
+/// window rolls over; when it does, and at least one event was suppressed
+/// during it, a single synthetic event reporting how many were dropped is
+/// recorded in its place.
+///
+/// Spans are never rate-limited by this filter, only events.
+///
+/// This type can be used for both [per-subscriber filtering][plf] (using its
+/// [`Filter`] implementation) and [global filtering][global] (using its
+/// [`Subscribe`] implementation).
+///
+/// [`Filter`]: crate::subscribe::Filter
+/// [`Subscribe`]: crate::subscribe::Subscribe
+/// [plf]: crate::subscribe#per-subscriber-filtering
+/// [global]: crate::subscribe#global-filtering
+///
+/// # Examples
+///
+/// ```
+/// use tracing_subscriber::{filter::RateLimit, prelude::*};
+///
+/// let fmt = tracing_subscriber::fmt::subscriber();
+///
+/// tracing_subscriber::registry()
+///     .with(fmt.with_filter(RateLimit::new(10)))
+///     .init();
+/// ```
+pub struct RateLimit {
+    max_events: u32,
+    window: Duration,
+    callsites: Mutex<HashMap<Identifier, Budget>>,
+}
+
+struct Budget {
+    metadata: &'static Metadata<'static>,
+    window_start: Instant,
+    count: u32,
+    suppressed: u32,
+}
+
+thread_local! {
+    // Set while this filter is recording its own "events suppressed"
+    // summary event, which is itself dispatched back through the whole
+    // collector (including this filter). Without this, the summary event
+    // could count against its own callsite's budget, or be suppressed.
+    static RECORDING_SUMMARY: Cell<bool> = const { Cell::new(false) };
+}
+
+impl RateLimit {
+    /// Returns a new `RateLimit` that allows at most `max_events` events per
+    /// callsite, per one-second window.
+    ///
+    /// Use [`per`](Self::per) to configure a different window.
+    pub fn new(max_events: u32) -> Self {
+        Self {
+            max_events,
+            window: Duration::from_secs(1),
+            callsites: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Overrides the window events are counted over. Defaults to one second.
+    pub fn per(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Registers a callsite's budget, if it's an event we haven't seen
+    /// before. Spans are left alone --- only events are rate-limited.
+    fn note_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+        if !metadata.is_event() {
+            return Interest::always();
+        }
+
+        let mut callsites = self.callsites.lock().unwrap_or_else(|e| e.into_inner());
+        callsites.entry(metadata.callsite()).or_insert_with(|| Budget {
+            metadata,
+            window_start: Instant::now(),
+            count: 0,
+            suppressed: 0,
+        });
+
+        // The budget changes over time, so we need `enabled` called for
+        // every event rather than having the result cached.
+        Interest::sometimes()
+    }
+
+    fn is_enabled<C: Collect>(&self, metadata: &Metadata<'_>, ctx: &Context<'_, C>) -> bool {
+        if !metadata.is_event() || RECORDING_SUMMARY.with(Cell::get) {
+            return true;
+        }
+
+        let now = Instant::now();
+        let (allowed, summary) = {
+            let mut callsites = self.callsites.lock().unwrap_or_else(|e| e.into_inner());
+            let budget = match callsites.get_mut(&metadata.callsite()) {
+                Some(budget) => budget,
+                // `register_callsite`/`callsite_enabled` should always have
+                // run before an event from this callsite reaches us; if it
+                // somehow hasn't, fail open rather than silently dropping
+                // events we have no budget tracked for.
+                None => return true,
+            };
+
+            let mut summary = None;
+            if now.duration_since(budget.window_start) > self.window {
+                if budget.suppressed > 0 {
+                    summary = Some((budget.metadata, budget.suppressed));
+                }
+                budget.window_start = now;
+                budget.count = 0;
+                budget.suppressed = 0;
+            }
+
+            budget.count += 1;
+            let allowed = budget.count <= self.max_events;
+            if !allowed {
+                budget.suppressed += 1;
+            }
+
+            (allowed, summary)
+        };
+
+        if let Some((metadata, suppressed)) = summary {
+            record_summary(metadata, suppressed, ctx);
+        }
+
+        allowed
+    }
+}
+
+impl fmt::Debug for RateLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RateLimit")
+            .field("max_events", &self.max_events)
+            .field("window", &self.window)
+            .finish()
+    }
+}
+
+/// Callsites for this module's synthetic "events suppressed" summary
+/// events, interned per distinct `(target, level)` pair.
+///
+/// A summary event's fields (`message`, `suppressed`) have nothing to do
+/// with the fields of the callsite it's summarizing, so it can't reuse that
+/// callsite's identifier: [`field::FieldSet`] documents that two `FieldSet`s
+/// sharing a callsite identifier must have identical fields, and violating
+/// that would hand out a summary event whose `metadata().fields()` lies
+/// about what fields it actually carries, to any consumer that trusts it
+/// (field-presence `EnvFilter` directives, schema-based exporters, and the
+/// like). Interning by `(target, level)` still gives every summary for a
+/// given callsite the same identity as each other, just not the same one as
+/// the callsite it's summarizing.
+static SUMMARY_CALLSITES: Lazy<Interner> = Lazy::new(Interner::new);
+
+/// Records a synthetic event, on a dedicated interned callsite (see
+/// [`SUMMARY_CALLSITES`]), reporting that `suppressed` events were dropped
+/// by the rate limiter during the window that just ended.
+fn record_summary<C: Collect>(
+    metadata: &'static Metadata<'static>,
+    suppressed: u32,
+    ctx: &Context<'_, C>,
+) {
+    static FIELD_NAMES: [&str; 2] = ["message", "suppressed"];
+
+    let builder = MetadataBuilder::event(metadata.name(), *metadata.level())
+        .target(metadata.target())
+        .fields(FIELD_NAMES);
+    let id = SUMMARY_CALLSITES.intern(builder);
+    let summary_meta = id.0.metadata();
+    let fields = summary_meta.fields();
+
+    let message = format!(
+        "rate limit: suppressed {} event(s) from this callsite",
+        suppressed
+    );
+    let message_field = fields.field("message").expect("field was just interned");
+    let suppressed_field = fields
+        .field("suppressed")
+        .expect("field was just interned");
+    let values: [(&field::Field, Option<&dyn field::Value>); 2] = [
+        (&message_field, Some(&message as &dyn field::Value)),
+        (&suppressed_field, Some(&suppressed as &dyn field::Value)),
+    ];
+    let value_set = fields.value_set(&values);
+    let event = Event::new(summary_meta, &value_set);
+
+    RECORDING_SUMMARY.with(|recording| recording.set(true));
+    ctx.event(&event);
+    RECORDING_SUMMARY.with(|recording| recording.set(false));
+}
+
+feature! {
+    #![feature = "std"]
+    use crate::subscribe::Subscribe;
+
+    impl<C> Subscribe<C> for RateLimit
+    where
+        C: Collect,
+    {
+        fn enabled(&self, metadata: &Metadata<'_>, ctx: Context<'_, C>) -> bool {
+            self.is_enabled(metadata, &ctx)
+        }
+
+        fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+            self.note_callsite(metadata)
+        }
+
+        fn max_level_hint(&self) -> Option<LevelFilter> {
+            None
+        }
+    }
+}
+
+feature! {
+    #![all(feature = "registry", feature = "std")]
+    use crate::subscribe::Filter;
+
+    impl<C> Filter<C> for RateLimit
+    where
+        C: Collect,
+    {
+        fn enabled(&self, metadata: &Metadata<'_>, cx: &Context<'_, C>) -> bool {
+            self.is_enabled(metadata, cx)
+        }
+
+        fn callsite_enabled(&self, metadata: &'static Metadata<'static>) -> Interest {
+            self.note_callsite(metadata)
+        }
+
+        fn max_level_hint(&self) -> Option<LevelFilter> {
+            None
+        }
+    }
+}
+
+#[cfg(all(test, feature = "registry"))]
+mod tests {
+    use super::*;
+    use crate::{prelude::*, subscribe::Subscribe};
+    use std::{
+        sync::{atomic::AtomicUsize, atomic::Ordering, Arc},
+        thread,
+    };
+
+    #[derive(Clone, Default)]
+    struct CountEvents(Arc<AtomicUsize>);
+
+    impl<C: Collect> Subscribe<C> for CountEvents {
+        fn on_event(&self, _event: &Event<'_>, _ctx: Context<'_, C>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn suppresses_events_over_budget() {
+        let count = CountEvents::default();
+        let _guard = tracing::collect::set_default(
+            crate::registry().with(count.clone().with_filter(RateLimit::new(2))),
+        );
+
+        for _ in 0..5 {
+            tracing::info!("noisy");
+        }
+
+        assert_eq!(count.0.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn emits_summary_once_window_rolls_over() {
+        let count = CountEvents::default();
+        let limiter = RateLimit::new(1).per(Duration::from_millis(10));
+        let _guard = tracing::collect::set_default(
+            crate::registry().with(count.clone().with_filter(limiter)),
+        );
+
+        tracing::info!("one");
+        tracing::info!("two"); // over budget, suppressed
+
+        thread::sleep(Duration::from_millis(50));
+        tracing::info!("three"); // new window; should also emit a summary for "two"
+
+        // "one", the suppressed-events summary, and "three".
+        assert_eq!(count.0.load(Ordering::Relaxed), 3);
+    }
+
+    #[derive(Clone, Default)]
+    struct RecordEvents(Arc<Mutex<Vec<(Identifier, Vec<&'static str>)>>>);
+
+    impl<C: Collect> Subscribe<C> for RecordEvents {
+        fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, C>) {
+            let meta = event.metadata();
+            let names = meta.fields().iter().map(|f| f.name()).collect();
+            self.0
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push((meta.callsite(), names));
+        }
+    }
+
+    // A single, reusable callsite: `tracing::info!` at two different
+    // locations would be two distinct callsites, which would defeat the
+    // point of comparing "real" events' callsite identity below.
+    fn noisy(n: u32) {
+        tracing::info!(n, "noisy");
+    }
+
+    #[test]
+    fn summary_event_has_its_own_callsite_and_fields() {
+        let events = RecordEvents::default();
+        let limiter = RateLimit::new(1).per(Duration::from_millis(10));
+        // `RateLimit` re-dispatches its summary event through its own
+        // `Context`, which only reaches subscribers added *before* it in the
+        // stack --- so it must be the outermost layer for `events` to see
+        // the summary too.
+        let _guard = tracing::collect::set_default(
+            crate::registry().with(events.clone()).with(limiter),
+        );
+
+        noisy(1);
+        noisy(2); // over budget, suppressed
+
+        thread::sleep(Duration::from_millis(50));
+        noisy(3); // new window; also emits a summary for the suppressed one
+
+        let recorded = events.0.lock().unwrap_or_else(|e| e.into_inner());
+        assert_eq!(recorded.len(), 3, "\"one\", the summary, and \"three\"");
+
+        let (real_callsite, real_fields) = &recorded[0];
+        let (summary_callsite, summary_fields) = &recorded[1];
+        let (real_callsite_again, real_fields_again) = &recorded[2];
+
+        // "one" and "three" share the real callsite, since they're the same
+        // `info!` call site --- the summary must not reuse it, since its
+        // fields don't match.
+        assert_eq!(real_callsite, real_callsite_again);
+        assert_eq!(real_fields, real_fields_again);
+        assert_ne!(
+            summary_callsite, real_callsite,
+            "the summary event must not reuse the real callsite's identity"
+        );
+        assert_eq!(summary_fields.as_slice(), ["message", "suppressed"]);
+    }
+}
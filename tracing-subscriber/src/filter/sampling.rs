@@ -0,0 +1,124 @@
+//! Head-based probabilistic sampling of traces.
+//!
+//! Dropping spans and events for some fraction of traces is a common way to
+//! control the volume of telemetry a service produces without losing entire
+//! traces piecemeal: once a root span is sampled out, every span and event
+//! nested inside it should go with it, rather than leaving a subtree with
+//! its root missing. Implementing that correctly on top of [`Subscribe`] and
+//! [`Filter`] requires remembering, for every span, which trace it belongs
+//! to and whether that trace was kept --- this module provides
+//! [`SamplingLayer`], which owns that bookkeeping, and [`is_sampled`], which
+//! other layers can call to read the decision back out.
+//!
+//! [`Filter`]: crate::subscribe::Filter
+use crate::{
+    registry::LookupSpan,
+    subscribe::{Context, Subscribe},
+};
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    sync::atomic::{AtomicU64, Ordering},
+};
+use tracing_core::{
+    span::{Attributes, Id},
+    Collect,
+};
+
+/// Whether a trace was selected for retention, as decided by a
+/// [`SamplingLayer`] for its root span and inherited by every span beneath
+/// it.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+struct Sampled(bool);
+
+/// A [`Subscribe`] that makes a head-based sampling decision for each root
+/// span (one with no parent), storing it as a span [extension] so the whole
+/// subtree --- the root span, its descendants, and any events recorded
+/// within them --- can be kept or dropped consistently.
+///
+/// The decision is made once, with probability `rate` of being kept, when a
+/// root span is created. Every descendant span copies its parent's decision
+/// rather than rolling again, so a trace is never sampled out partway
+/// through.
+///
+/// `SamplingLayer` only records the decision; on its own it doesn't drop
+/// anything. Pair it with [`is_sampled`] in a [`Filter`] (for example, via
+/// [`filter_fn`]) to actually suppress the spans and events of unsampled
+/// traces.
+///
+/// [extension]: crate::registry::Extensions
+/// [`Filter`]: crate::subscribe::Filter
+/// [`filter_fn`]: crate::filter::filter_fn()
+#[derive(Clone, Debug)]
+pub struct SamplingLayer {
+    rate: f64,
+}
+
+impl SamplingLayer {
+    /// Returns a new `SamplingLayer` that keeps each new trace with
+    /// probability `rate`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rate` is not in the range `0.0..=1.0`.
+    pub fn new(rate: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&rate),
+            "sampling rate must be between 0.0 and 1.0, got {}",
+            rate
+        );
+        Self { rate }
+    }
+
+    fn roll(&self) -> bool {
+        // A single coin flip per root span doesn't warrant pulling in a full
+        // RNG crate: hash an always-increasing counter with the standard
+        // library's randomly-seeded default hasher to get a stream of bits
+        // that's unpredictable enough for sampling, without the dependency.
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u64(n);
+        (hasher.finish() as f64 / u64::MAX as f64) < self.rate
+    }
+}
+
+impl<C> Subscribe<C> for SamplingLayer
+where
+    C: Collect + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, _attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, C>) {
+        let span = match ctx.span(id) {
+            Some(span) => span,
+            None => return,
+        };
+        let inherited = span
+            .parent()
+            .and_then(|parent| parent.extensions().get::<Sampled>().map(|sampled| sampled.0));
+        let sampled = inherited.unwrap_or_else(|| self.roll());
+        span.extensions_mut().insert(Sampled(sampled));
+    }
+}
+
+/// Returns whether `id`'s span was kept by a [`SamplingLayer`] somewhere in
+/// its ancestry.
+///
+/// If no [`SamplingLayer`] has recorded a decision for `id` or any of its
+/// ancestors --- for example, because no `SamplingLayer` is registered ---
+/// this returns `true`, so that sampling fails open rather than silently
+/// discarding everything.
+///
+/// This can be used from a custom [`Filter`] or [`Subscribe`] to suppress the
+/// spans and events belonging to traces a [`SamplingLayer`] sampled out.
+///
+/// [`Filter`]: crate::subscribe::Filter
+pub fn is_sampled<C>(ctx: &Context<'_, C>, id: &Id) -> bool
+where
+    C: Collect + for<'a> LookupSpan<'a>,
+{
+    ctx.span(id)
+        .into_iter()
+        .flat_map(|span| span.scope())
+        .find_map(|span| span.extensions().get::<Sampled>().map(|sampled| sampled.0))
+        .unwrap_or(true)
+}
@@ -0,0 +1,330 @@
+//! A [`Filter`] combinator that caps how many spans a callsite may create
+//! within a window.
+//!
+//! [`Filter`]: crate::subscribe::Filter
+use crate::{filter::LevelFilter, subscribe::Context};
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use tracing_core::{callsite::Identifier, collect::Interest, field, span, Collect, Event, Metadata};
+
+/// A [`Filter`] that caps how many spans a single callsite may create within
+/// a sliding window, replacing the rest with a disabled placeholder.
+///
+/// This is meant to protect the registry and downstream exporters from a
+/// pathological loop that creates spans at the same callsite millions of
+/// times a second --- for instance, a retry loop that enters a span on every
+/// attempt. Once a window's budget for a callsite is exhausted, every
+/// further span creation from that callsite is disabled until the window
+/// rolls over, which is cheap: a disabled span is never registered with the
+/// collector. When the window does roll over, and at least one span was
+/// suppressed during it, a single summary event reporting how many were
+/// dropped is recorded in its place.
+///
+/// Events are never rate-limited by this filter, only spans; see
+/// [`RateLimit`](super::RateLimit) for limiting events instead.
+///
+/// This type can be used for both [per-subscriber filtering][plf] (using its
+/// [`Filter`] implementation) and [global filtering][global] (using its
+/// [`Subscribe`] implementation).
+///
+/// [`Filter`]: crate::subscribe::Filter
+/// [`Subscribe`]: crate::subscribe::Subscribe
+/// [plf]: crate::subscribe#per-subscriber-filtering
+/// [global]: crate::subscribe#global-filtering
+///
+/// # Examples
+///
+/// ```
+/// use tracing_subscriber::{filter::SpanRateLimit, prelude::*};
+///
+/// let fmt = tracing_subscriber::fmt::subscriber();
+///
+/// tracing_subscriber::registry()
+///     .with(fmt.with_filter(SpanRateLimit::new(10)))
+///     .init();
+/// ```
+pub struct SpanRateLimit {
+    max_spans: u32,
+    window: Duration,
+    callsites: Mutex<HashMap<Identifier, Budget>>,
+}
+
+struct Budget {
+    window_start: Instant,
+    count: u32,
+    suppressed: u32,
+    /// Set when a window rolls over with at least one suppressed span, until
+    /// the next span from this callsite picks it up and reports it.
+    pending_summary: Option<u32>,
+}
+
+impl SpanRateLimit {
+    /// Returns a new `SpanRateLimit` that allows at most `max_spans` span
+    /// creations per callsite, per one-second window.
+    ///
+    /// Use [`per`](Self::per) to configure a different window.
+    pub fn new(max_spans: u32) -> Self {
+        Self {
+            max_spans,
+            window: Duration::from_secs(1),
+            callsites: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Overrides the window span creations are counted over. Defaults to one
+    /// second.
+    pub fn per(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Registers a callsite's budget, if it's a span we haven't seen before.
+    /// Events are left alone --- only spans are rate-limited.
+    fn note_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+        if !metadata.is_span() {
+            return Interest::always();
+        }
+
+        let mut callsites = self.callsites.lock().unwrap_or_else(|e| e.into_inner());
+        callsites.entry(metadata.callsite()).or_insert_with(|| Budget {
+            window_start: Instant::now(),
+            count: 0,
+            suppressed: 0,
+            pending_summary: None,
+        });
+
+        // The budget changes over time, so we need `enabled` called for
+        // every span rather than having the result cached.
+        Interest::sometimes()
+    }
+
+    fn is_enabled(&self, metadata: &Metadata<'_>) -> bool {
+        if !metadata.is_span() {
+            return true;
+        }
+
+        let now = Instant::now();
+        let mut callsites = self.callsites.lock().unwrap_or_else(|e| e.into_inner());
+        let budget = match callsites.get_mut(&metadata.callsite()) {
+            Some(budget) => budget,
+            // `register_callsite`/`callsite_enabled` should always have run
+            // before a span from this callsite reaches us; if it somehow
+            // hasn't, fail open rather than silently disabling spans we have
+            // no budget tracked for.
+            None => return true,
+        };
+
+        if now.duration_since(budget.window_start) > self.window {
+            if budget.suppressed > 0 {
+                budget.pending_summary = Some(budget.suppressed);
+            }
+            budget.window_start = now;
+            budget.count = 0;
+            budget.suppressed = 0;
+        }
+
+        budget.count += 1;
+        let allowed = budget.count <= self.max_spans;
+        if !allowed {
+            budget.suppressed += 1;
+        }
+
+        allowed
+    }
+
+    /// Dispatches a pending summary event for `attrs`'s callsite, if one is
+    /// waiting to be reported.
+    ///
+    /// This happens from `on_new_span` rather than from
+    /// [`is_enabled`](Self::is_enabled), since a span is always created even
+    /// when this filter disables it (see the notes on [`Filter::enabled`]);
+    /// `on_new_span` is the first point at which we're guaranteed to have a
+    /// [`Context`] to record the summary through.
+    ///
+    /// [`Filter::enabled`]: crate::subscribe::Filter::enabled
+    fn note_new_span<C: Collect>(&self, attrs: &span::Attributes<'_>, ctx: &Context<'_, C>) {
+        let metadata = attrs.metadata();
+        let pending = {
+            let mut callsites = self.callsites.lock().unwrap_or_else(|e| e.into_inner());
+            callsites
+                .get_mut(&metadata.callsite())
+                .and_then(|budget| budget.pending_summary.take())
+        };
+
+        if let Some(suppressed) = pending {
+            record_summary(metadata, suppressed, ctx);
+        }
+    }
+}
+
+impl fmt::Debug for SpanRateLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpanRateLimit")
+            .field("max_spans", &self.max_spans)
+            .field("window", &self.window)
+            .finish()
+    }
+}
+
+/// Records a synthetic event, reusing `metadata`'s callsite, reporting that
+/// `suppressed` span creations were disabled by the rate limiter during the
+/// window that just ended.
+fn record_summary<C: Collect>(
+    metadata: &'static Metadata<'static>,
+    suppressed: u32,
+    ctx: &Context<'_, C>,
+) {
+    static FIELD_NAMES: &[&str] = &["message", "suppressed"];
+
+    let fields = field::FieldSet::new(FIELD_NAMES, metadata.callsite());
+    let message = format!(
+        "rate limit: disabled {} span(s) from this callsite",
+        suppressed
+    );
+    let mut iter = fields.iter();
+    let message_field = iter.next().expect("message field must exist");
+    let suppressed_field = iter.next().expect("suppressed field must exist");
+    let values: [(&field::Field, Option<&dyn field::Value>); 2] = [
+        (&message_field, Some(&message as &dyn field::Value)),
+        (&suppressed_field, Some(&suppressed as &dyn field::Value)),
+    ];
+    let value_set = fields.value_set(&values);
+    let event = Event::new(metadata, &value_set);
+
+    ctx.event(&event);
+}
+
+feature! {
+    #![feature = "std"]
+    use crate::subscribe::Subscribe;
+
+    impl<C> Subscribe<C> for SpanRateLimit
+    where
+        C: Collect,
+    {
+        fn enabled(&self, metadata: &Metadata<'_>, _ctx: Context<'_, C>) -> bool {
+            self.is_enabled(metadata)
+        }
+
+        fn on_new_span(&self, attrs: &span::Attributes<'_>, _id: &span::Id, ctx: Context<'_, C>) {
+            self.note_new_span(attrs, &ctx);
+        }
+
+        fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+            self.note_callsite(metadata)
+        }
+
+        fn max_level_hint(&self) -> Option<LevelFilter> {
+            None
+        }
+    }
+}
+
+feature! {
+    #![all(feature = "registry", feature = "std")]
+    use crate::subscribe::Filter;
+
+    impl<C> Filter<C> for SpanRateLimit
+    where
+        C: Collect,
+    {
+        fn enabled(&self, metadata: &Metadata<'_>, _cx: &Context<'_, C>) -> bool {
+            self.is_enabled(metadata)
+        }
+
+        fn on_new_span(&self, attrs: &span::Attributes<'_>, _id: &span::Id, ctx: Context<'_, C>) {
+            self.note_new_span(attrs, &ctx);
+        }
+
+        fn callsite_enabled(&self, metadata: &'static Metadata<'static>) -> Interest {
+            self.note_callsite(metadata)
+        }
+
+        fn max_level_hint(&self) -> Option<LevelFilter> {
+            None
+        }
+    }
+}
+
+#[cfg(all(test, feature = "registry"))]
+mod tests {
+    use super::*;
+    use crate::{prelude::*, subscribe::Subscribe};
+    use std::{
+        sync::{atomic::AtomicUsize, atomic::Ordering, Arc},
+        thread,
+    };
+
+    #[derive(Clone, Default)]
+    struct CountEvents(Arc<AtomicUsize>);
+
+    impl<C: Collect> Subscribe<C> for CountEvents {
+        fn on_event(&self, _event: &Event<'_>, _ctx: Context<'_, C>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[derive(Clone, Default)]
+    struct CountSpans(Arc<AtomicUsize>);
+
+    impl<C: Collect> Subscribe<C> for CountSpans {
+        fn on_new_span(&self, _attrs: &span::Attributes<'_>, _id: &span::Id, _ctx: Context<'_, C>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn suppresses_spans_over_budget() {
+        let spans = CountSpans::default();
+        let _guard = tracing::collect::set_default(
+            crate::registry().with(spans.clone().with_filter(SpanRateLimit::new(2))),
+        );
+
+        for _ in 0..5 {
+            let _span = tracing::info_span!("hot_loop").entered();
+        }
+
+        assert_eq!(spans.0.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn does_not_rate_limit_events() {
+        let count = CountEvents::default();
+        let _guard = tracing::collect::set_default(
+            crate::registry().with(count.clone().with_filter(SpanRateLimit::new(1))),
+        );
+
+        for _ in 0..5 {
+            tracing::info!("not a span");
+        }
+
+        assert_eq!(count.0.load(Ordering::Relaxed), 5);
+    }
+
+    #[test]
+    fn emits_summary_once_window_rolls_over() {
+        let count = CountEvents::default();
+        let limiter = SpanRateLimit::new(1).per(Duration::from_millis(10));
+        let _guard = tracing::collect::set_default(
+            crate::registry().with(count.clone()).with(limiter),
+        );
+
+        for i in 0..3 {
+            if i == 2 {
+                // Let the window roll over before the third creation, which
+                // should also emit a summary event for the one suppressed
+                // above.
+                thread::sleep(Duration::from_millis(50));
+            }
+            // Every iteration hits the same callsite: the first is allowed,
+            // the second is over budget and suppressed.
+            tracing::info_span!("hot_loop").in_scope(|| {});
+        }
+
+        assert_eq!(count.0.load(Ordering::Relaxed), 1);
+    }
+}
@@ -0,0 +1,201 @@
+//! A [`Filter`] combinator that lets a span instance override the level
+//! required for spans and events within its subtree to be enabled.
+//!
+//! [`Filter`]: crate::subscribe::Filter
+use crate::{
+    filter::LevelFilter,
+    registry::LookupSpan,
+    subscribe::{Context, Filter},
+};
+use tracing_core::{collect::Interest, Collect, Metadata};
+
+/// A [`Filter`] that wraps an inner filter, but enables a span or event
+/// whenever it occurs within the subtree of a span whose level was
+/// overridden with [`set_span_level`], regardless of what the inner filter
+/// would otherwise decide.
+///
+/// This allows per-tenant or per-request verbosity to be controlled by
+/// application logic --- for example, a layer's `on_new_span` can inspect a
+/// span's fields and call [`set_span_level`] to loosen or tighten the level
+/// required for that span's children, while every span and event outside
+/// such a subtree is still governed by the wrapped filter.
+///
+/// Only the *nearest* enclosing override applies: if a span overrides the
+/// level and one of its children overrides it again, the child's override
+/// takes precedence for its own subtree.
+///
+/// [`Filter`]: crate::subscribe::Filter
+///
+/// # Examples
+///
+/// ```
+/// use tracing_subscriber::{filter::{LevelFilter, ScopedLevelFilter}, prelude::*};
+///
+/// let filter = ScopedLevelFilter::new(LevelFilter::INFO);
+/// tracing_subscriber::registry()
+///     .with(tracing_subscriber::fmt::subscriber().with_filter(filter))
+///     .init();
+///
+/// // Elsewhere, once a span identifying a particular tenant has been entered:
+/// let span = tracing::info_span!("request", tenant = "acme");
+/// tracing_subscriber::filter::set_span_level(&span, LevelFilter::TRACE);
+/// // Events inside `span`'s subtree are now enabled down to TRACE, while
+/// // everything else remains filtered at INFO.
+/// ```
+#[derive(Debug, Clone)]
+pub struct ScopedLevelFilter<F> {
+    inner: F,
+}
+
+/// The level override attached to a span's [extensions] by [`set_span_level`].
+///
+/// [extensions]: crate::registry::Extensions
+struct SpanLevelOverride(LevelFilter);
+
+impl<F> ScopedLevelFilter<F> {
+    /// Wraps `inner`, so that spans and events within an overridden span's
+    /// subtree bypass it, as described in the [type-level documentation](Self).
+    pub fn new(inner: F) -> Self {
+        Self { inner }
+    }
+}
+
+/// Overrides the level required for spans and events within `span`'s subtree
+/// to be enabled by a [`ScopedLevelFilter`] wrapping this span's collector.
+///
+/// The override is stored in `span`'s [extensions], so it only takes effect
+/// for a collector that supports [`LookupSpan`] (such as [`Registry`]) and
+/// has a [`ScopedLevelFilter`] installed somewhere in its stack of
+/// subscribers.
+///
+/// [extensions]: crate::registry::Extensions
+/// [`Registry`]: crate::registry::Registry
+pub fn set_span_level(span: &tracing::Span, level: impl Into<LevelFilter>) {
+    let level = level.into();
+    span.with_collector(|(id, dispatch)| {
+        if let Some(registry) = dispatch.downcast_ref::<crate::registry::Registry>() {
+            if let Some(span) = registry.span(id) {
+                span.extensions_mut().insert(SpanLevelOverride(level));
+            }
+        }
+    });
+}
+
+/// Returns the level override in effect for the current span's subtree, set
+/// by the nearest enclosing call to [`set_span_level`], if any.
+fn effective_level<S>(ctx: &Context<'_, S>) -> Option<LevelFilter>
+where
+    S: Collect + for<'lookup> LookupSpan<'lookup>,
+{
+    ctx.lookup_current()?
+        .scope()
+        .find_map(|span| span.extensions().get::<SpanLevelOverride>().map(|o| o.0))
+}
+
+impl<S, F> Filter<S> for ScopedLevelFilter<F>
+where
+    F: Filter<S>,
+    S: Collect + for<'lookup> LookupSpan<'lookup>,
+{
+    fn enabled(&self, meta: &Metadata<'_>, cx: &Context<'_, S>) -> bool {
+        // Always let spans through: a span may not gain its level override
+        // until after it's created (e.g. from fields recorded by an
+        // `on_new_span` hook further down the stack), and per-subscriber
+        // filtering remembers whether *this* filter enabled a span at
+        // creation time. If we rejected the span here, we could never
+        // discover an override attached to it later, which would also break
+        // `effective_level`'s walk up the span scope for every descendant.
+        // Events are unaffected: their one-shot `enabled` call always sees
+        // whatever overrides are in effect by the time they fire.
+        if meta.is_span() {
+            return true;
+        }
+        match effective_level(cx) {
+            Some(level) => level >= *meta.level(),
+            None => self.inner.enabled(meta, cx),
+        }
+    }
+
+    fn callsite_enabled(&self, meta: &'static Metadata<'static>) -> Interest {
+        // A span-local override could enable a callsite the inner filter
+        // would otherwise disable, and we have no way of knowing in advance
+        // whether some future span will install one, so we can never cache a
+        // blanket `always`/`never` answer: always ask again per-occurrence.
+        if self.inner.callsite_enabled(meta).is_never() {
+            return Interest::sometimes();
+        }
+        self.inner.callsite_enabled(meta)
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        // An override can always raise the effective level above whatever
+        // the inner filter would statically hint, so no hint is safe here.
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prelude::*, Registry};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+    use tracing_core::{Collect, Event};
+
+    #[derive(Clone, Default)]
+    struct CountEvents(Arc<AtomicUsize>);
+
+    impl<C: Collect> crate::Subscribe<C> for CountEvents {
+        fn on_event(&self, _event: &Event<'_>, _ctx: Context<'_, C>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn overridden_span_loosens_filtering() {
+        let events = CountEvents::default();
+        let filter = ScopedLevelFilter::new(LevelFilter::INFO);
+        let _guard = tracing::collect::set_default(
+            Registry::default().with(events.clone().with_filter(filter)),
+        );
+
+        tracing::debug!("before the span: filtered out");
+        assert_eq!(events.0.load(Ordering::Relaxed), 0);
+
+        let span = tracing::info_span!("request");
+        let _enter = span.enter();
+        set_span_level(&span, LevelFilter::DEBUG);
+        tracing::debug!("inside the span: enabled by the override");
+        assert_eq!(events.0.load(Ordering::Relaxed), 1);
+
+        drop(_enter);
+        tracing::debug!("after the span: filtered out again");
+        assert_eq!(events.0.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn nested_override_takes_precedence() {
+        let events = CountEvents::default();
+        let filter = ScopedLevelFilter::new(LevelFilter::ERROR);
+        let _guard = tracing::collect::set_default(
+            Registry::default().with(events.clone().with_filter(filter)),
+        );
+
+        let outer = tracing::info_span!("outer");
+        set_span_level(&outer, LevelFilter::INFO);
+        let _outer_enter = outer.enter();
+
+        let inner = tracing::info_span!("inner");
+        set_span_level(&inner, LevelFilter::TRACE);
+        let _inner_enter = inner.enter();
+
+        tracing::trace!("enabled by the inner span's override");
+        assert_eq!(events.0.load(Ordering::Relaxed), 1);
+
+        drop(_inner_enter);
+        tracing::trace!("back under the outer span's override: filtered out");
+        assert_eq!(events.0.load(Ordering::Relaxed), 1);
+    }
+}
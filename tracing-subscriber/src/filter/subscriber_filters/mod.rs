@@ -302,6 +302,33 @@ pub trait FilterExt<S>: subscribe::Filter<S> {
     ///     .init();
     /// ```
     ///
+    /// Enabling spans and events at `INFO` and above, as well as any event
+    /// with a `force_log` field, regardless of its value:
+    ///
+    /// ```
+    /// use tracing_subscriber::{
+    ///     filter::{filter_fn, LevelFilter, FilterExt},
+    ///     prelude::*,
+    /// };
+    ///
+    /// // Note that `Metadata` only describes a callsite's fields by name, not
+    /// // by value, so this enables any event with a `force_log` field, no
+    /// // matter what value is recorded for it.
+    /// let force_log = filter_fn(|meta| meta.fields().field("force_log").is_some());
+    ///
+    /// let filter = LevelFilter::INFO.or(force_log);
+    ///
+    /// tracing_subscriber::registry()
+    ///     .with(tracing_subscriber::fmt::subscriber().with_filter(filter))
+    ///     .init();
+    ///
+    /// // This event will *not* be enabled:
+    /// tracing::debug!("an uninteresting event");
+    ///
+    /// // This event *will* be enabled, since it has a `force_log` field:
+    /// tracing::debug!(force_log = true, "a forced debug event");
+    /// ```
+    ///
     /// [`Filter`]: crate::subscribe::Filter
     /// [`and`]: FilterExt::and
     fn or<B>(self, other: B) -> combinator::Or<Self, B, S>
@@ -4,9 +4,18 @@ use crate::{
     registry::{self, LookupSpan, SpanRef},
     subscribe::{self, Context},
 };
-use format::{FmtSpan, TimingDisplay};
+use format::{DurationPercentDisplay, FmtSpan, TimingDisplay};
 use std::{
-    any::TypeId, cell::RefCell, env, fmt, io, marker::PhantomData, ops::Deref, ptr::NonNull,
+    any::TypeId,
+    cell::RefCell,
+    env, fmt, io,
+    marker::PhantomData,
+    ops::Deref,
+    ptr::NonNull,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
     time::Instant,
 };
 use tracing_core::{
@@ -60,7 +69,6 @@ use tracing_core::{
 /// ```
 ///
 /// [`Subscriber`]: subscribe::Subscribe
-#[derive(Debug)]
 #[cfg_attr(docsrs, doc(cfg(all(feature = "fmt", feature = "std"))))]
 pub struct Subscriber<C, N = format::DefaultFields, E = format::Format, W = fn() -> io::Stdout> {
     make_writer: W,
@@ -69,6 +77,8 @@ pub struct Subscriber<C, N = format::DefaultFields, E = format::Format, W = fn()
     fmt_span: format::FmtSpanConfig,
     is_ansi: bool,
     log_internal_errors: bool,
+    on_write_error: Option<Arc<dyn Fn(io::Error) + Send + Sync>>,
+    write_error_count: Arc<AtomicUsize>,
     _inner: PhantomData<fn(C)>,
 }
 
@@ -79,6 +89,25 @@ impl<C> Subscriber<C> {
     }
 }
 
+impl<C, N, E, W> fmt::Debug for Subscriber<C, N, E, W>
+where
+    N: fmt::Debug,
+    E: fmt::Debug,
+    W: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Subscriber")
+            .field("make_writer", &self.make_writer)
+            .field("fmt_fields", &self.fmt_fields)
+            .field("fmt_event", &self.fmt_event)
+            .field("fmt_span", &self.fmt_span)
+            .field("is_ansi", &self.is_ansi)
+            .field("log_internal_errors", &self.log_internal_errors)
+            .field("write_error_count", &self.write_error_count)
+            .finish()
+    }
+}
+
 // This needs to be a separate impl block because they place different bounds on the type parameters.
 impl<C, N, E, W> Subscriber<C, N, E, W>
 where
@@ -105,6 +134,29 @@ where
     /// # use tracing_subscriber::Subscribe as _;
     /// # let _ = fmt_subscriber.with_collector(tracing_subscriber::registry::Registry::default());
     /// ```
+    /// Selecting a formatter at runtime, e.g. from a config flag, without
+    /// changing the subscriber's type: box the chosen [`FormatEvent`] and
+    /// pass the box, since `Box<dyn FormatEvent<C, N> + Send + Sync>` itself
+    /// implements [`FormatEvent`].
+    /// ```rust
+    /// use tracing_subscriber::fmt::{self, format::{DefaultFields, FormatEvent}};
+    ///
+    /// fn event_format(
+    ///     compact: bool,
+    /// ) -> Box<dyn FormatEvent<tracing_subscriber::Registry, DefaultFields> + Send + Sync> {
+    ///     if compact {
+    ///         Box::new(fmt::format().compact())
+    ///     } else {
+    ///         Box::new(fmt::format())
+    ///     }
+    /// }
+    ///
+    /// let fmt_subscriber = fmt::subscriber().event_format(event_format(true));
+    /// # // this is necessary for type inference.
+    /// # use tracing_subscriber::Subscribe as _;
+    /// # let _ = fmt_subscriber.with_collector(tracing_subscriber::registry::Registry::default());
+    /// ```
+    ///
     /// [`FormatEvent`]: format::FormatEvent
     /// [`Event`]: tracing::Event
     /// [`Writer`]: format::Writer
@@ -119,6 +171,8 @@ where
             make_writer: self.make_writer,
             is_ansi: self.is_ansi,
             log_internal_errors: self.log_internal_errors,
+            on_write_error: self.on_write_error,
+            write_error_count: self.write_error_count,
             _inner: self._inner,
         }
     }
@@ -149,6 +203,8 @@ where
             make_writer: self.make_writer,
             is_ansi: self.is_ansi,
             log_internal_errors: self.log_internal_errors,
+            on_write_error: self.on_write_error,
+            write_error_count: self.write_error_count,
             _inner: self._inner,
         }
     }
@@ -185,6 +241,8 @@ impl<C, N, E, W> Subscriber<C, N, E, W> {
             fmt_span: self.fmt_span,
             is_ansi: self.is_ansi,
             log_internal_errors: self.log_internal_errors,
+            on_write_error: self.on_write_error,
+            write_error_count: self.write_error_count,
             make_writer,
             _inner: self._inner,
         }
@@ -258,6 +316,8 @@ impl<C, N, E, W> Subscriber<C, N, E, W> {
         self.fmt_span = format::FmtSpanConfig {
             kind,
             fmt_timing: self.fmt_span.fmt_timing,
+            duration_percent: self.fmt_span.duration_percent,
+            duration_unit: self.fmt_span.duration_unit,
         }
     }
 
@@ -290,6 +350,8 @@ impl<C, N, E, W> Subscriber<C, N, E, W> {
             fmt_span: self.fmt_span,
             is_ansi: self.is_ansi,
             log_internal_errors: self.log_internal_errors,
+            on_write_error: self.on_write_error,
+            write_error_count: self.write_error_count,
             make_writer: TestWriter::default(),
             _inner: self._inner,
         }
@@ -357,6 +419,44 @@ impl<C, N, E, W> Subscriber<C, N, E, W> {
         }
     }
 
+    /// Sets a handler that is invoked whenever writing formatted output to
+    /// the [`MakeWriter`] fails.
+    ///
+    /// By default, write failures are silently counted (see
+    /// [`write_error_count`]) and, if [`log_internal_errors`] is enabled,
+    /// also printed to stderr. Setting a handler here replaces the stderr
+    /// fallback, allowing callers to implement their own policy — such as
+    /// writing to a fallback destination, or aborting the process — while
+    /// the error count returned by [`write_error_count`] is still updated
+    /// regardless of whether a handler is set.
+    ///
+    /// [`MakeWriter`]: super::writer::MakeWriter
+    /// [`write_error_count`]: Subscriber::write_error_count
+    /// [`log_internal_errors`]: Subscriber::log_internal_errors
+    pub fn on_write_error(
+        self,
+        on_write_error: impl Fn(io::Error) + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            on_write_error: Some(Arc::new(on_write_error)),
+            ..self
+        }
+    }
+
+    /// Returns the number of times this subscriber has failed to write
+    /// formatted output to its [`MakeWriter`].
+    ///
+    /// This count is updated regardless of whether [`log_internal_errors`]
+    /// or [`on_write_error`] is configured, providing a lightweight way to
+    /// monitor writer health without installing a handler.
+    ///
+    /// [`MakeWriter`]: super::writer::MakeWriter
+    /// [`log_internal_errors`]: Subscriber::log_internal_errors
+    /// [`on_write_error`]: Subscriber::on_write_error
+    pub fn write_error_count(&self) -> usize {
+        self.write_error_count.load(Ordering::Relaxed)
+    }
+
     /// Updates the [`MakeWriter`] by applying a function to the existing [`MakeWriter`].
     ///
     /// This sets the [`MakeWriter`] that the subscriber being built will use to write events.
@@ -386,6 +486,8 @@ impl<C, N, E, W> Subscriber<C, N, E, W> {
             fmt_span: self.fmt_span,
             is_ansi: self.is_ansi,
             log_internal_errors: self.log_internal_errors,
+            on_write_error: self.on_write_error,
+            write_error_count: self.write_error_count,
             make_writer: f(self.make_writer),
             _inner: self._inner,
         }
@@ -418,6 +520,8 @@ where
             make_writer: self.make_writer,
             is_ansi: self.is_ansi,
             log_internal_errors: self.log_internal_errors,
+            on_write_error: self.on_write_error,
+            write_error_count: self.write_error_count,
             _inner: self._inner,
         }
     }
@@ -431,6 +535,8 @@ where
             make_writer: self.make_writer,
             is_ansi: self.is_ansi,
             log_internal_errors: self.log_internal_errors,
+            on_write_error: self.on_write_error,
+            write_error_count: self.write_error_count,
             _inner: self._inner,
         }
     }
@@ -483,6 +589,50 @@ where
         }
     }
 
+    /// Sets whether the `time.busy` and `time.idle` fields on synthesized
+    /// span close events additionally include the span's busy time as a
+    /// percentage of its parent span's total (busy + idle) duration.
+    ///
+    /// This has no effect unless [`FmtSpan::CLOSE`] events are enabled via
+    /// [`with_span_events`], and the span has a parent with its own timing
+    /// data recorded (i.e. the parent was also entered at least once).
+    ///
+    /// By default, this is disabled.
+    ///
+    /// [`with_span_events`]: Subscriber::with_span_events
+    pub fn with_span_duration_percent(self, display_percent: bool) -> Self {
+        Subscriber {
+            fmt_span: self.fmt_span.with_duration_percent(display_percent),
+            ..self
+        }
+    }
+
+    /// Sets the unit that the `time.busy` and `time.idle` fields on
+    /// synthesized span close events are rendered in.
+    ///
+    /// By default, this is [`DurationUnit::Auto`], which scales each
+    /// duration to whichever of `ns`/`µs`/`ms`/`s` keeps it readable. For
+    /// basic latency logging where durations are parsed back out of the log
+    /// line (by a shell script, a log aggregator's field extractor, and so
+    /// on), a fixed unit is often easier to work with than one that changes
+    /// per line.
+    ///
+    /// This has no effect unless [`FmtSpan::CLOSE`] events are enabled via
+    /// [`with_span_events`].
+    ///
+    /// Note that there is no `time.poll_count` field: counting how many
+    /// times a span's future was polled requires cooperation from an async
+    /// runtime (as `tracing`'s `#[instrument]` macro has none on its own),
+    /// which is out of scope for this formatter.
+    ///
+    /// [`with_span_events`]: Subscriber::with_span_events
+    pub fn with_span_duration_unit(self, unit: format::DurationUnit) -> Self {
+        Subscriber {
+            fmt_span: self.fmt_span.with_duration_unit(unit),
+            ..self
+        }
+    }
+
     /// Sets whether or not an event's target is displayed.
     pub fn with_target(self, display_target: bool) -> Subscriber<C, N, format::Format<L, T>, W> {
         Subscriber {
@@ -523,6 +673,34 @@ where
         }
     }
 
+    /// Adds a static key-value pair that will be attached to every event
+    /// this subscriber formats.
+    ///
+    /// See [`format::Format::with_resource_field`] for details.
+    pub fn with_resource_field(
+        self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Subscriber<C, N, format::Format<L, T>, W> {
+        Subscriber {
+            fmt_event: self.fmt_event.with_resource_field(key, value),
+            ..self
+        }
+    }
+
+    /// Sets the maximum number of entered spans rendered as an event's span context.
+    ///
+    /// See [`format::Format::with_max_span_scope_depth`] for details.
+    pub fn with_max_span_scope_depth(
+        self,
+        max_depth: impl Into<Option<usize>>,
+    ) -> Subscriber<C, N, format::Format<L, T>, W> {
+        Subscriber {
+            fmt_event: self.fmt_event.with_max_span_scope_depth(max_depth),
+            ..self
+        }
+    }
+
     /// Sets whether or not the [thread ID] of the current thread is displayed
     /// when formatting events.
     ///
@@ -551,6 +729,17 @@ where
         }
     }
 
+    /// Sets whether or not a per-thread, monotonically increasing sequence
+    /// number is displayed when formatting events.
+    ///
+    /// See [`format::Format::with_seq`] for details.
+    pub fn with_seq(self, display_seq: bool) -> Subscriber<C, N, format::Format<L, T>, W> {
+        Subscriber {
+            fmt_event: self.fmt_event.with_seq(display_seq),
+            ..self
+        }
+    }
+
     /// Sets the subscriber being built to use a [less verbose formatter](format::Compact).
     pub fn compact(self) -> Subscriber<C, N, format::Format<format::Compact, T>, W>
     where
@@ -563,6 +752,8 @@ where
             make_writer: self.make_writer,
             is_ansi: self.is_ansi,
             log_internal_errors: self.log_internal_errors,
+            on_write_error: self.on_write_error,
+            write_error_count: self.write_error_count,
             _inner: self._inner,
         }
     }
@@ -578,6 +769,8 @@ where
             make_writer: self.make_writer,
             is_ansi: self.is_ansi,
             log_internal_errors: self.log_internal_errors,
+            on_write_error: self.on_write_error,
+            write_error_count: self.write_error_count,
             _inner: self._inner,
         }
     }
@@ -608,6 +801,76 @@ where
             // always disable ANSI escapes in JSON mode!
             is_ansi: false,
             log_internal_errors: self.log_internal_errors,
+            on_write_error: self.on_write_error,
+            write_error_count: self.write_error_count,
+            _inner: self._inner,
+        }
+    }
+
+    /// Sets the subscriber being built to use a [minimal, end-user-facing formatter](format::Human).
+    pub fn human(self) -> Subscriber<C, N, format::Format<format::Human, T>, W>
+    where
+        N: for<'writer> FormatFields<'writer> + 'static,
+    {
+        Subscriber {
+            fmt_event: self.fmt_event.human(),
+            fmt_fields: self.fmt_fields,
+            fmt_span: self.fmt_span,
+            make_writer: self.make_writer,
+            is_ansi: self.is_ansi,
+            log_internal_errors: self.log_internal_errors,
+            on_write_error: self.on_write_error,
+            write_error_count: self.write_error_count,
+            _inner: self._inner,
+        }
+    }
+
+    /// Sets the subscriber being built to use a [logfmt]-style formatter.
+    ///
+    /// See [`format::Logfmt`] for details.
+    ///
+    /// [logfmt]: format::Logfmt
+    pub fn logfmt(self) -> Subscriber<C, format::LogfmtFields, format::Format<format::Logfmt, T>, W> {
+        Subscriber {
+            fmt_event: self.fmt_event.logfmt(),
+            fmt_fields: format::LogfmtFields::new(),
+            fmt_span: self.fmt_span,
+            make_writer: self.make_writer,
+            // logfmt is a machine-readable format meant for ingestion by log
+            // pipelines, so disable ANSI escapes just as we do for JSON.
+            is_ansi: false,
+            log_internal_errors: self.log_internal_errors,
+            on_write_error: self.on_write_error,
+            write_error_count: self.write_error_count,
+            _inner: self._inner,
+        }
+    }
+
+    /// Sets the subscriber being built to use a hierarchical [`format::Tree`]
+    /// formatter.
+    ///
+    /// This also enables span open/close events (as if
+    /// [`with_span_events`](Self::with_span_events) had been called with
+    /// [`FmtSpan::NEW`](format::FmtSpan::NEW) `|`
+    /// [`FmtSpan::CLOSE`](format::FmtSpan::CLOSE)), since the tree formatter
+    /// draws its open/close markers from them.
+    ///
+    /// See [`format::Tree`] for details.
+    pub fn tree(self) -> Subscriber<C, N, format::Format<format::Tree, T>, W>
+    where
+        N: for<'writer> FormatFields<'writer> + 'static,
+    {
+        Subscriber {
+            fmt_event: self.fmt_event.tree(),
+            fmt_fields: self.fmt_fields,
+            fmt_span: self
+                .fmt_span
+                .with_kind(format::FmtSpan::NEW | format::FmtSpan::CLOSE),
+            make_writer: self.make_writer,
+            is_ansi: self.is_ansi,
+            log_internal_errors: self.log_internal_errors,
+            on_write_error: self.on_write_error,
+            write_error_count: self.write_error_count,
             _inner: self._inner,
         }
     }
@@ -659,6 +922,52 @@ impl<C, T, W> Subscriber<C, format::JsonFields, format::Format<format::Json, T>,
             ..self
         }
     }
+
+    /// Sets whether or not the formatter will merge the fields of every
+    /// currently entered span into the root object, instead of nesting them
+    /// under `span`/`spans`.
+    ///
+    /// See [`format::Json::flatten_span_fields`]
+    pub fn flatten_span_fields(
+        self,
+        flatten_span_fields: bool,
+    ) -> Subscriber<C, format::JsonFields, format::Format<format::Json, T>, W> {
+        Subscriber {
+            fmt_event: self.fmt_event.flatten_span_fields(flatten_span_fields),
+            fmt_fields: format::JsonFields::new(),
+            ..self
+        }
+    }
+
+    /// Sets the [`format::FieldCollisionPolicy`] used when flattening span
+    /// fields and two spans set a field with the same name.
+    ///
+    /// See [`format::Json::with_span_field_collision_policy`]
+    pub fn with_span_field_collision_policy(
+        self,
+        policy: format::FieldCollisionPolicy,
+    ) -> Subscriber<C, format::JsonFields, format::Format<format::Json, T>, W> {
+        Subscriber {
+            fmt_event: self.fmt_event.with_span_field_collision_policy(policy),
+            fmt_fields: format::JsonFields::new(),
+            ..self
+        }
+    }
+
+    /// Sets whether or not the formatter will include each span's id and
+    /// accumulated busy time (in nanoseconds) alongside its name.
+    ///
+    /// See [`format::Json::with_span_ids`]
+    pub fn with_span_ids(
+        self,
+        display_span_ids: bool,
+    ) -> Subscriber<C, format::JsonFields, format::Format<format::Json, T>, W> {
+        Subscriber {
+            fmt_event: self.fmt_event.with_span_ids(display_span_ids),
+            fmt_fields: format::JsonFields::new(),
+            ..self
+        }
+    }
 }
 
 impl<C, N, E, W> Subscriber<C, N, E, W> {
@@ -675,6 +984,8 @@ impl<C, N, E, W> Subscriber<C, N, E, W> {
             make_writer: self.make_writer,
             is_ansi: self.is_ansi,
             log_internal_errors: self.log_internal_errors,
+            on_write_error: self.on_write_error,
+            write_error_count: self.write_error_count,
             _inner: self._inner,
         }
     }
@@ -706,6 +1017,8 @@ impl<C, N, E, W> Subscriber<C, N, E, W> {
             make_writer: self.make_writer,
             is_ansi: self.is_ansi,
             log_internal_errors: self.log_internal_errors,
+            on_write_error: self.on_write_error,
+            write_error_count: self.write_error_count,
             _inner: self._inner,
         }
     }
@@ -724,6 +1037,8 @@ impl<C> Default for Subscriber<C> {
             make_writer: io::stdout,
             is_ansi: ansi,
             log_internal_errors: false,
+            on_write_error: None,
+            write_error_count: Arc::new(AtomicUsize::new(0)),
             _inner: PhantomData,
         }
     }
@@ -823,6 +1138,26 @@ macro_rules! with_event_from_span {
     };
 }
 
+impl<C, N, E, W> Subscriber<C, N, E, W> {
+    /// Handles a failure to write formatted output to the [`MakeWriter`],
+    /// incrementing [`write_error_count`](Subscriber::write_error_count) and
+    /// dispatching to the configured [`on_write_error`](Subscriber::on_write_error)
+    /// handler, falling back to an `eprintln!` if [`log_internal_errors`]
+    /// is enabled and no handler was set.
+    ///
+    /// [`log_internal_errors`]: Subscriber::log_internal_errors
+    fn handle_write_error(&self, error: io::Error, context: &str) {
+        self.write_error_count.fetch_add(1, Ordering::Relaxed);
+        match &self.on_write_error {
+            Some(handler) => handler(error),
+            None if self.log_internal_errors => {
+                eprintln!("[tracing-subscriber] {} Error: {}\n", context, error);
+            }
+            None => {}
+        }
+    }
+}
+
 impl<C, N, E, W> subscribe::Subscribe<C> for Subscriber<C, N, E, W>
 where
     C: Collect + for<'a> LookupSpan<'a>,
@@ -938,8 +1273,37 @@ where
                 } = *timing;
                 idle += (Instant::now() - last).as_nanos() as u64;
 
-                let t_idle = field::display(TimingDisplay(idle));
-                let t_busy = field::display(TimingDisplay(busy));
+                let t_idle = field::display(TimingDisplay(idle, self.fmt_span.duration_unit));
+                let t_busy = field::display(TimingDisplay(busy, self.fmt_span.duration_unit));
+
+                if self.fmt_span.duration_percent {
+                    let percent_busy = span.parent().and_then(|parent| {
+                        let parent_extensions = parent.extensions();
+                        let parent_timing = parent_extensions.get::<Timings>()?;
+                        let parent_total = (parent_timing.busy + parent_timing.idle) as f64;
+                        if parent_total <= 0.0 {
+                            return None;
+                        }
+                        Some(busy as f64 / parent_total * 100.0)
+                    });
+                    let t_busy_percent =
+                        field::display(DurationPercentDisplay(percent_busy));
+
+                    with_event_from_span!(
+                        id,
+                        span,
+                        "message" = "close",
+                        "time.busy" = t_busy,
+                        "time.busy_percent" = t_busy_percent,
+                        "time.idle" = t_idle,
+                        |event| {
+                            drop(extensions);
+                            drop(span);
+                            self.on_event(&event, ctx);
+                        }
+                    );
+                    return;
+                }
 
                 with_event_from_span!(
                     id,
@@ -995,10 +1359,11 @@ where
             {
                 let mut writer = self.make_writer.make_writer_for(event.metadata());
                 let res = io::Write::write_all(&mut writer, buf.as_bytes());
-                if self.log_internal_errors {
-                    if let Err(e) = res {
-                        eprintln!("[tracing-subscriber] Unable to write an event to the Writer for this Subscriber! Error: {}\n", e);
-                    }
+                if let Err(e) = res {
+                    self.handle_write_error(
+                        e,
+                        "Unable to write an event to the Writer for this Subscriber!",
+                    );
                 }
             } else if self.log_internal_errors {
                 let err_msg = format!("Unable to format the following event. Name: {}; Fields: {:?}\n",
@@ -1006,7 +1371,10 @@ where
                 let mut writer = self.make_writer.make_writer_for(event.metadata());
                 let res = io::Write::write_all(&mut writer, err_msg.as_bytes());
                 if let Err(e) = res {
-                    eprintln!("[tracing-subscriber] Unable to write an \"event formatting error\" to the Writer for this Subscriber! Error: {}\n", e);
+                    self.handle_write_error(
+                        e,
+                        "Unable to write an \"event formatting error\" to the Writer for this Subscriber!",
+                    );
                 }
             }
 
@@ -1221,7 +1589,7 @@ where
     }
 }
 
-struct Timings {
+pub(crate) struct Timings {
     idle: u64,
     busy: u64,
     last: Instant,
@@ -1235,6 +1603,12 @@ impl Timings {
             last: Instant::now(),
         }
     }
+
+    /// Returns the total time (in nanoseconds) this span has been entered,
+    /// measured so far.
+    pub(crate) fn busy_ns(&self) -> u64 {
+        self.busy
+    }
 }
 
 #[cfg(test)]
@@ -1303,7 +1677,9 @@ mod test {
 
     fn sanitize_timings(s: String) -> String {
         let re = Regex::new("time\\.(idle|busy)=([0-9.]+)[mµn]s").unwrap();
-        re.replace_all(s.as_str(), "timing").to_string()
+        let s = re.replace_all(s.as_str(), "timing").to_string();
+        let re = Regex::new("time\\.busy_percent=(([0-9.]+%)|n/a)").unwrap();
+        re.replace_all(s.as_str(), "timing_percent").to_string()
     }
 
     #[test]
@@ -1369,6 +1745,50 @@ mod test {
         assert_eq!("", actual.as_str());
     }
 
+    #[test]
+    fn on_write_error_is_invoked_and_counted() {
+        #[derive(Clone, Default)]
+        struct FailingMakeWriter;
+
+        struct FailingWriter;
+
+        impl io::Write for FailingWriter {
+            fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+                Err(io::Error::new(io::ErrorKind::Other, "always fails"))
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        impl<'a> crate::fmt::writer::MakeWriter<'a> for FailingMakeWriter {
+            type Writer = FailingWriter;
+
+            fn make_writer(&'a self) -> Self::Writer {
+                FailingWriter
+            }
+        }
+
+        let errors_seen = Arc::new(AtomicUsize::new(0));
+        let errors_seen2 = errors_seen.clone();
+
+        let subscriber = fmt::Subscriber::default()
+            .with_writer(FailingMakeWriter)
+            .on_write_error(move |_| {
+                errors_seen2.fetch_add(1, Ordering::Relaxed);
+            });
+
+        assert_eq!(subscriber.write_error_count(), 0);
+
+        let collector = subscriber.with_collector(Registry::default());
+        with_default(collector, || {
+            tracing::info!("this will fail to write");
+        });
+
+        assert_eq!(errors_seen.load(Ordering::Relaxed), 1);
+    }
+
     #[test]
     fn synthesize_span_none() {
         let make_writer = MockMakeWriter::default();
@@ -1433,6 +1853,32 @@ mod test {
         );
     }
 
+    #[test]
+    fn synthesize_span_close_with_duration_percent() {
+        let make_writer = MockMakeWriter::default();
+        let subscriber = crate::fmt::Collector::builder()
+            .with_writer(make_writer.clone())
+            .with_level(false)
+            .with_ansi(false)
+            .with_timer(MockTime)
+            .with_span_events(FmtSpan::CLOSE)
+            .with_span_duration_percent(true)
+            .finish();
+
+        with_default(subscriber, || {
+            let parent = tracing::info_span!("parent");
+            let _p = parent.enter();
+            let child = tracing::info_span!("child");
+            let _c = child.enter();
+        });
+        let actual = sanitize_timings(make_writer.get_string());
+        assert_eq!(
+            "fake time parent:child: tracing_subscriber::fmt::fmt_subscriber::test: close timing timing_percent timing\n\
+             fake time parent: tracing_subscriber::fmt::fmt_subscriber::test: close timing timing_percent timing\n",
+            actual.as_str()
+        );
+    }
+
     #[test]
     fn synthesize_span_close_no_timing() {
         let make_writer = MockMakeWriter::default();
@@ -1,11 +1,11 @@
-use super::{Format, FormatEvent, FormatFields, FormatTime, Writer};
+use super::{next_seq, Format, FormatEvent, FormatFields, FormatTime, Writer};
 use crate::{
     field::{RecordFields, VisitOutput},
     fmt::{
         fmt_subscriber::{FmtContext, FormattedFields},
         writer::WriteAdaptor,
     },
-    registry::LookupSpan,
+    registry::{LookupSpan, SpanRef},
 };
 use serde::ser::{SerializeMap, Serializer as _};
 use serde_json::Serializer;
@@ -65,15 +65,38 @@ use tracing_log::NormalizeEvent;
 ///   span
 /// - [`Json::with_span_list`] can be used to control logging of the span list
 ///   object.
+/// - [`Json::flatten_span_fields`] can be used to merge the fields of every
+///   entered span directly into the root object, instead of nesting them
+///   under `span`/`spans`. This is useful for log pipelines (such as
+///   Elasticsearch or Loki) that index flat keys more effectively than
+///   nested objects. [`Json::with_span_field_collision_policy`] controls
+///   what happens when two spans set a field with the same name.
 ///
-/// By default, event fields are not flattened, and both current span and span
-/// list are logged.
+/// By default, event fields are not flattened, span fields are not flattened,
+/// and both current span and span list are logged.
 ///
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct Json {
     pub(crate) flatten_event: bool,
     pub(crate) display_current_span: bool,
     pub(crate) display_span_list: bool,
+    pub(crate) flatten_span_fields: bool,
+    pub(crate) span_field_collision_policy: FieldCollisionPolicy,
+    pub(crate) display_span_ids: bool,
+}
+
+/// Determines how field name collisions are resolved when
+/// [flattening span fields](Json::flatten_span_fields) into the root object.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum FieldCollisionPolicy {
+    /// Fields set by a more deeply nested span overwrite fields of the same
+    /// name set by an ancestor span.
+    #[default]
+    Overwrite,
+    /// The value set by the outermost span with a given field name is kept;
+    /// fields of the same name set by descendant spans are discarded.
+    KeepFirst,
 }
 
 impl Json {
@@ -92,11 +115,50 @@ impl Json {
     pub fn with_span_list(&mut self, display_span_list: bool) {
         self.display_span_list = display_span_list;
     }
+
+    /// If set to `true`, the fields of every currently entered span (from
+    /// root to leaf) are merged directly into the root object, rather than
+    /// being nested under `span`/`spans` keys. When enabled, this takes
+    /// precedence over [`Json::with_current_span`] and [`Json::with_span_list`],
+    /// which no longer have any effect.
+    ///
+    /// Use [`Json::with_span_field_collision_policy`] to control what happens
+    /// when two spans set a field with the same name.
+    pub fn flatten_span_fields(&mut self, flatten_span_fields: bool) {
+        self.flatten_span_fields = flatten_span_fields;
+    }
+
+    /// Sets the [`FieldCollisionPolicy`] used when [flattening span
+    /// fields](Json::flatten_span_fields) and two spans set a field with the
+    /// same name. Defaults to [`FieldCollisionPolicy::Overwrite`].
+    pub fn with_span_field_collision_policy(&mut self, policy: FieldCollisionPolicy) {
+        self.span_field_collision_policy = policy;
+    }
+
+    /// If set to `true`, each span object in the [current span](Json::with_current_span)
+    /// and [span list](Json::with_span_list) additionally includes an `id`
+    /// field (the span's ID, stable for the lifetime of the span within this
+    /// process) and a `busy_ns` field (the total time, in nanoseconds, the
+    /// span has been entered so far).
+    ///
+    /// `busy_ns` is only populated when [span timing] is enabled (i.e. when
+    /// [`close`] events are configured via [`fmt::Subscriber::with_span_events`]);
+    /// otherwise it is reported as `0`.
+    ///
+    /// Disabled by default.
+    ///
+    /// [span timing]: crate::fmt::format::FmtSpan::CLOSE
+    /// [`close`]: crate::fmt::format::FmtSpan::CLOSE
+    /// [`fmt::Subscriber::with_span_events`]: crate::fmt::Subscriber::with_span_events
+    pub fn with_span_ids(&mut self, display_span_ids: bool) {
+        self.display_span_ids = display_span_ids;
+    }
 }
 
 struct SerializableContext<'a, 'b, Span, N>(
     &'b crate::subscribe::Context<'a, Span>,
     std::marker::PhantomData<N>,
+    bool,
 )
 where
     Span: Collect + for<'lookup> crate::registry::LookupSpan<'lookup>,
@@ -116,7 +178,7 @@ where
 
         if let Some(leaf_span) = self.0.lookup_current() {
             for span in leaf_span.scope().from_root() {
-                serializer.serialize_element(&SerializableSpan(&span, self.1))?;
+                serializer.serialize_element(&SerializableSpan(&span, self.1, self.2))?;
             }
         }
 
@@ -127,6 +189,7 @@ where
 struct SerializableSpan<'a, 'b, Span, N>(
     &'b crate::registry::SpanRef<'a, Span>,
     std::marker::PhantomData<N>,
+    bool,
 )
 where
     Span: for<'lookup> crate::registry::LookupSpan<'lookup>,
@@ -189,6 +252,16 @@ where
             Err(e) => serializer.serialize_entry("field_error", &format!("{}", e))?,
         };
         serializer.serialize_entry("name", self.0.metadata().name())?;
+
+        if self.2 {
+            serializer.serialize_entry("id", &self.0.id().into_u64())?;
+            let busy_ns = ext
+                .get::<crate::fmt::Timings>()
+                .map(|timings| timings.busy_ns())
+                .unwrap_or(0);
+            serializer.serialize_entry("busy_ns", &busy_ns)?;
+        }
+
         serializer.end()
     }
 }
@@ -231,6 +304,10 @@ where
                 serializer.serialize_entry("level", &meta.level().as_serde())?;
             }
 
+            if self.display_seq {
+                serializer.serialize_entry("seq", &next_seq())?;
+            }
+
             let format_field_marker: std::marker::PhantomData<N> = std::marker::PhantomData;
 
             let current_span = if self.format.display_current_span || self.format.display_span_list
@@ -269,19 +346,49 @@ where
                 }
             }
 
-            if self.format.display_current_span {
+            if !self.resource_fields.is_empty() {
+                let resource: BTreeMap<_, _> = self
+                    .resource_fields
+                    .iter()
+                    .map(|(key, value)| (key, value))
+                    .collect();
+                serializer.serialize_entry("resource", &resource)?;
+            }
+
+            if self.format.flatten_span_fields {
                 if let Some(ref span) = current_span {
-                    serializer
-                        .serialize_entry("span", &SerializableSpan(span, format_field_marker))
-                        .unwrap_or(());
+                    for (key, value) in
+                        merge_span_fields::<_, N>(span, self.format.span_field_collision_policy)
+                    {
+                        serializer.serialize_entry(&key, &value)?;
+                    }
+                }
+            } else {
+                if self.format.display_current_span {
+                    if let Some(ref span) = current_span {
+                        serializer
+                            .serialize_entry(
+                                "span",
+                                &SerializableSpan(
+                                    span,
+                                    format_field_marker,
+                                    self.format.display_span_ids,
+                                ),
+                            )
+                            .unwrap_or(());
+                    }
                 }
-            }
 
-            if self.format.display_span_list && current_span.is_some() {
-                serializer.serialize_entry(
-                    "spans",
-                    &SerializableContext(&ctx.ctx, format_field_marker),
-                )?;
+                if self.format.display_span_list && current_span.is_some() {
+                    serializer.serialize_entry(
+                        "spans",
+                        &SerializableContext(
+                            &ctx.ctx,
+                            format_field_marker,
+                            self.format.display_span_ids,
+                        ),
+                    )?;
+                }
             }
 
             if self.display_thread_name {
@@ -318,10 +425,47 @@ impl Default for Json {
             flatten_event: false,
             display_current_span: true,
             display_span_list: true,
+            flatten_span_fields: false,
+            span_field_collision_policy: FieldCollisionPolicy::Overwrite,
+            display_span_ids: false,
         }
     }
 }
 
+/// Merges the fields of every span in `leaf`'s scope (from root to leaf) into
+/// a single map, resolving name collisions according to `policy`.
+fn merge_span_fields<C, N>(
+    leaf: &SpanRef<'_, C>,
+    policy: FieldCollisionPolicy,
+) -> BTreeMap<String, serde_json::Value>
+where
+    C: for<'lookup> LookupSpan<'lookup>,
+    N: for<'writer> FormatFields<'writer> + 'static,
+{
+    let mut merged = BTreeMap::new();
+    for span in leaf.scope().from_root() {
+        let ext = span.extensions();
+        let data = ext
+            .get::<FormattedFields<N>>()
+            .expect("Unable to find FormattedFields in extensions; this is a bug");
+
+        if let Ok(serde_json::Value::Object(fields)) = serde_json::from_str::<serde_json::Value>(data)
+        {
+            for (key, value) in fields {
+                match policy {
+                    FieldCollisionPolicy::Overwrite => {
+                        merged.insert(key, value);
+                    }
+                    FieldCollisionPolicy::KeepFirst => {
+                        merged.entry(key).or_insert(value);
+                    }
+                }
+            }
+        }
+    }
+    merged
+}
+
 /// The JSON [`FormatFields`] implementation.
 ///
 #[derive(Debug)]
@@ -457,6 +601,44 @@ impl crate::field::VisitOutput<fmt::Result> for JsonVisitor<'_> {
     }
 }
 
+/// Records a single element of a [`field::Seq`] or [`field::Map`] as a
+/// `serde_json::Value`, reusing the element's own `Field` as the key passed
+/// to [`field::Value::record`] since positional/map elements have no `Field`
+/// of their own.
+fn json_value_of(field: &Field, value: &dyn field::Value) -> serde_json::Value {
+    struct JsonValueVisitor(Option<serde_json::Value>);
+
+    impl field::Visit for JsonValueVisitor {
+        fn record_f64(&mut self, _: &Field, value: f64) {
+            self.0 = Some(serde_json::Value::from(value));
+        }
+
+        fn record_i64(&mut self, _: &Field, value: i64) {
+            self.0 = Some(serde_json::Value::from(value));
+        }
+
+        fn record_u64(&mut self, _: &Field, value: u64) {
+            self.0 = Some(serde_json::Value::from(value));
+        }
+
+        fn record_bool(&mut self, _: &Field, value: bool) {
+            self.0 = Some(serde_json::Value::from(value));
+        }
+
+        fn record_str(&mut self, _: &Field, value: &str) {
+            self.0 = Some(serde_json::Value::from(value));
+        }
+
+        fn record_debug(&mut self, _: &Field, value: &dyn fmt::Debug) {
+            self.0 = Some(serde_json::Value::from(format!("{:?}", value)));
+        }
+    }
+
+    let mut visitor = JsonValueVisitor(None);
+    value.record(field, &mut visitor);
+    visitor.0.unwrap_or(serde_json::Value::Null)
+}
+
 impl field::Visit for JsonVisitor<'_> {
     /// Visit a double precision floating point value.
     fn record_f64(&mut self, field: &Field, value: f64) {
@@ -493,6 +675,42 @@ impl field::Visit for JsonVisitor<'_> {
             .insert(field.name(), serde_json::Value::from(value));
     }
 
+    /// Visit a sequence of values, recording it as a real JSON array rather
+    /// than a `Debug`-formatted string.
+    fn record_seq(&mut self, field: &Field, _debug: &dyn fmt::Debug, seq: &dyn field::Seq) {
+        let mut elements = Vec::new();
+        seq.for_each(&mut |value| elements.push(json_value_of(field, value)));
+        self.values
+            .insert(field.name(), serde_json::Value::Array(elements));
+    }
+
+    /// Visit a map of string keys to values, recording it as a real JSON
+    /// object rather than a `Debug`-formatted string.
+    fn record_map(&mut self, field: &Field, _debug: &dyn fmt::Debug, map: &dyn field::Map) {
+        let mut entries = serde_json::Map::new();
+        map.for_each(&mut |key, value| {
+            entries.insert(key.to_string(), json_value_of(field, value));
+        });
+        self.values
+            .insert(field.name(), serde_json::Value::Object(entries));
+    }
+
+    /// Visit a `Duration`, recording it as a number of seconds.
+    fn record_duration(&mut self, field: &Field, value: std::time::Duration) {
+        self.values
+            .insert(field.name(), serde_json::Value::from(value.as_secs_f64()));
+    }
+
+    /// Visit a `SystemTime`, recording it as a number of seconds since the Unix epoch.
+    fn record_system_time(&mut self, field: &Field, value: std::time::SystemTime) {
+        let secs = value
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs_f64())
+            .unwrap_or_else(|e| -e.duration().as_secs_f64());
+        self.values
+            .insert(field.name(), serde_json::Value::from(secs));
+    }
+
     fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
         match field.name() {
             // Skip fields that are actually log metadata that have already been handled
@@ -595,6 +813,16 @@ mod test {
         });
     }
 
+    #[test]
+    fn json_multiline_message_is_escaped() {
+        let expected =
+        "{\"timestamp\":\"fake time\",\"level\":\"INFO\",\"target\":\"tracing_subscriber::fmt::format::json::test\",\"fields\":{\"message\":\"line one\\nline two\"}}\n";
+        let collector = collector().flatten_event(false);
+        test_json(expected, collector, || {
+            tracing::info!("line one\nline two");
+        });
+    }
+
     #[test]
     fn json_flattened_event() {
         let expected =
@@ -663,6 +891,49 @@ mod test {
         });
     }
 
+    #[test]
+    fn json_flatten_span_fields() {
+        let expected =
+        "{\"timestamp\":\"fake time\",\"level\":\"INFO\",\"fields\":{\"message\":\"some json test\"},\"target\":\"tracing_subscriber::fmt::format::json::test\",\"answer\":43,\"number\":4}\n";
+        let collector = collector()
+            .flatten_event(false)
+            .flatten_span_fields(true);
+        test_json(expected, collector, || {
+            let span = tracing::span!(tracing::Level::INFO, "json_span", answer = 42, number = 3);
+            let _guard = span.enter();
+            let span = tracing::span!(
+                tracing::Level::INFO,
+                "nested_json_span",
+                answer = 43,
+                number = 4
+            );
+            let _guard = span.enter();
+            tracing::info!("some json test");
+        });
+    }
+
+    #[test]
+    fn json_flatten_span_fields_keep_first() {
+        let expected =
+        "{\"timestamp\":\"fake time\",\"level\":\"INFO\",\"fields\":{\"message\":\"some json test\"},\"target\":\"tracing_subscriber::fmt::format::json::test\",\"answer\":42,\"number\":3}\n";
+        let collector = collector()
+            .flatten_event(false)
+            .flatten_span_fields(true)
+            .with_span_field_collision_policy(FieldCollisionPolicy::KeepFirst);
+        test_json(expected, collector, || {
+            let span = tracing::span!(tracing::Level::INFO, "json_span", answer = 42, number = 3);
+            let _guard = span.enter();
+            let span = tracing::span!(
+                tracing::Level::INFO,
+                "nested_json_span",
+                answer = 99,
+                number = 4
+            );
+            let _guard = span.enter();
+            tracing::info!("some json test");
+        });
+    }
+
     #[test]
     fn json_no_span() {
         let expected =
@@ -676,6 +947,98 @@ mod test {
         });
     }
 
+    #[test]
+    fn json_seq_and_map_are_structured() {
+        let buffer = MockMakeWriter::default();
+        let subscriber = crate::fmt().json().with_writer(buffer.clone()).finish();
+
+        with_default(subscriber, || {
+            tracing::info!(
+                numbers = tracing::field::seq(&[1, 2, 3]),
+                pairs = tracing::field::map(&[("a", 1), ("b", 2)][..]),
+                "an event with structured fields"
+            );
+        });
+
+        let json = parse_as_json(&buffer);
+        assert_eq!(json["fields"]["numbers"], serde_json::json!([1, 2, 3]));
+        assert_eq!(
+            json["fields"]["pairs"],
+            serde_json::json!({"a": 1, "b": 2})
+        );
+    }
+
+    #[test]
+    fn json_with_span_ids() {
+        let buffer = MockMakeWriter::default();
+        let subscriber = crate::fmt()
+            .json()
+            .with_current_span(true)
+            .with_span_ids(true)
+            .with_writer(buffer.clone())
+            .finish();
+
+        with_default(subscriber, || {
+            let span = tracing::info_span!("json_span", answer = 42);
+            let _guard = span.enter();
+            tracing::info!("some json test");
+        });
+
+        let json = parse_as_json(&buffer);
+        assert!(json["span"]["id"].is_u64());
+        assert_eq!(json["span"]["busy_ns"], serde_json::json!(0));
+    }
+
+    #[test]
+    fn json_without_span_ids_by_default() {
+        let buffer = MockMakeWriter::default();
+        let subscriber = crate::fmt()
+            .json()
+            .with_current_span(true)
+            .with_writer(buffer.clone())
+            .finish();
+
+        with_default(subscriber, || {
+            let span = tracing::info_span!("json_span", answer = 42);
+            let _guard = span.enter();
+            tracing::info!("some json test");
+        });
+
+        let json = parse_as_json(&buffer);
+        assert!(json["span"].get("id").is_none());
+        assert!(json["span"].get("busy_ns").is_none());
+    }
+
+    #[test]
+    fn json_with_resource_fields() {
+        let buffer = MockMakeWriter::default();
+        let subscriber = crate::fmt()
+            .json()
+            .with_resource_field("service.name", "my_service")
+            .with_writer(buffer.clone())
+            .finish();
+
+        with_default(subscriber, || {
+            tracing::info!("some json test");
+        });
+
+        let json = parse_as_json(&buffer);
+        assert_eq!(json["resource"]["service.name"], "my_service");
+    }
+
+    #[test]
+    fn json_without_resource_fields_by_default() {
+        let buffer = MockMakeWriter::default();
+        let subscriber = crate::fmt().json().with_writer(buffer.clone()).finish();
+
+        with_default(subscriber, || {
+            tracing::info!("some json test");
+        });
+
+        let json = parse_as_json(&buffer);
+        assert!(json.get("resource").is_none());
+    }
+
     #[test]
     fn record_works() {
         // This test reproduces issue #707, where using `Span::record` causes
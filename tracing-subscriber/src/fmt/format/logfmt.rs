@@ -0,0 +1,408 @@
+//! A [logfmt]-style `key=value` event formatter.
+//!
+//! [logfmt]: https://brandur.org/logfmt
+use super::{next_seq, Format, FormatEvent, FormatFields, FormatTime, Writer};
+use crate::{
+    field::{MakeVisitor, VisitFmt, VisitOutput},
+    fmt::fmt_subscriber::{FmtContext, FormattedFields},
+    registry::LookupSpan,
+    registry::Scope,
+};
+use std::fmt;
+use tracing_core::{field, field::Field, Collect, Event};
+
+#[cfg(feature = "tracing-log")]
+use tracing_log::NormalizeEvent;
+
+/// Marker for [`Format`] that indicates that [logfmt]-style output should be
+/// used.
+///
+/// `logfmt` represents every piece of information --- the timestamp, level,
+/// target, message, and every field, from both the event and its containing
+/// spans --- as a `key=value` pair on a single line. Values containing
+/// whitespace, `=`, `"`, or control characters are quoted and escaped; all
+/// other values are left bare. This is the format produced by Heroku's
+/// router and consumed natively by log pipelines such as Grafana Loki, which
+/// makes it a convenient choice when [`Json`](super::Json) is more
+/// structure than a consumer needs.
+///
+/// # Example Output
+///
+/// <pre><font color="#4E9A06"><b>:;</b></font> <font color="#4E9A06">cargo</font> run --example fmt-logfmt
+/// <font color="#4E9A06"><b>    Finished</b></font> dev [unoptimized + debuginfo] target(s) in 0.08s
+/// <font color="#4E9A06"><b>     Running</b></font> `target/debug/examples/fmt-logfmt`
+/// time=2022-02-15T18:47:10.821315Z level=info target=fmt_logfmt msg="preparing to shave yaks" number_of_yaks=3
+/// time=2022-02-15T18:47:10.821422Z level=info target=fmt_logfmt::yak_shave msg="shaving yaks" yaks=3
+/// time=2022-02-15T18:47:10.821546Z level=warn target=fmt_logfmt::yak_shave msg="could not locate yak" yaks=3 yak=3
+/// time=2022-02-15T18:47:10.822041Z level=error target=fmt_logfmt::yak_shave msg="failed to shave yak" yak=3 error="missing yak"
+/// </pre>
+///
+/// [logfmt]: https://brandur.org/logfmt
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Logfmt;
+
+impl<C, N, T> FormatEvent<C, N> for Format<Logfmt, T>
+where
+    C: Collect + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+    T: FormatTime,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, C, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        #[cfg(feature = "tracing-log")]
+        let normalized_meta = event.normalized_metadata();
+        #[cfg(feature = "tracing-log")]
+        let meta = normalized_meta.as_ref().unwrap_or_else(|| event.metadata());
+        #[cfg(not(feature = "tracing-log"))]
+        let meta = event.metadata();
+
+        let mut has_fields = false;
+
+        if self.display_timestamp {
+            let mut timestamp = String::new();
+            if self
+                .timer
+                .format_time(&mut Writer::new(&mut timestamp))
+                .is_err()
+            {
+                timestamp.push_str("<unknown time>");
+            }
+            write_pair(&mut writer, "time", &timestamp)?;
+            has_fields = true;
+        }
+
+        if self.display_level {
+            if has_fields {
+                writer.write_char(' ')?;
+            }
+            write!(writer, "level={}", meta.level().as_str().to_ascii_lowercase())?;
+            has_fields = true;
+        }
+
+        if self.display_target {
+            if has_fields {
+                writer.write_char(' ')?;
+            }
+            write_pair(&mut writer, "target", meta.target())?;
+            has_fields = true;
+        }
+
+        if self.display_thread_name {
+            let current_thread = std::thread::current();
+            if let Some(name) = current_thread.name() {
+                if has_fields {
+                    writer.write_char(' ')?;
+                }
+                write_pair(&mut writer, "thread_name", name)?;
+                has_fields = true;
+            }
+        }
+
+        if self.display_thread_id {
+            if has_fields {
+                writer.write_char(' ')?;
+            }
+            write!(writer, "thread_id={:?}", std::thread::current().id())?;
+            has_fields = true;
+        }
+
+        if self.display_seq {
+            if has_fields {
+                writer.write_char(' ')?;
+            }
+            write!(writer, "seq={}", next_seq())?;
+            has_fields = true;
+        }
+
+        if self.display_filename {
+            if let Some(filename) = meta.file() {
+                if has_fields {
+                    writer.write_char(' ')?;
+                }
+                write_pair(&mut writer, "filename", filename)?;
+                has_fields = true;
+            }
+        }
+
+        if self.display_line_number {
+            if let Some(line_number) = meta.line() {
+                if has_fields {
+                    writer.write_char(' ')?;
+                }
+                write!(writer, "line_number={}", line_number)?;
+                has_fields = true;
+            }
+        }
+
+        if has_fields {
+            writer.write_char(' ')?;
+        }
+        let mut visitor = LogfmtVisitor::new(writer.by_ref(), true);
+        event.record(&mut visitor);
+        visitor.finish()?;
+
+        for span in ctx.event_scope().into_iter().flat_map(Scope::from_root) {
+            let ext = span.extensions();
+            if let Some(fields) = ext.get::<FormattedFields<N>>() {
+                if !fields.is_empty() {
+                    write!(writer, " {}", fields)?;
+                }
+            }
+        }
+
+        self.format_resource_fields(&mut writer)?;
+
+        writeln!(writer)
+    }
+}
+
+/// Returns `true` if `value` must be quoted (and have its contents escaped)
+/// to be represented as a single logfmt value: when it's empty, or contains
+/// whitespace, `=`, `"`, `\`, or a control character.
+fn needs_quoting(value: &str) -> bool {
+    value.is_empty()
+        || value
+            .bytes()
+            .any(|b| matches!(b, b' ' | b'=' | b'"' | b'\\') || b.is_ascii_control())
+}
+
+/// Writes `value` to `writer`, quoting and escaping it if necessary so that
+/// it parses back as a single logfmt value.
+fn write_value(writer: &mut Writer<'_>, value: &str) -> fmt::Result {
+    if !needs_quoting(value) {
+        return writer.write_str(value);
+    }
+
+    writer.write_char('"')?;
+    for c in value.chars() {
+        match c {
+            '"' => writer.write_str("\\\"")?,
+            '\\' => writer.write_str("\\\\")?,
+            '\n' => writer.write_str("\\n")?,
+            '\r' => writer.write_str("\\r")?,
+            '\t' => writer.write_str("\\t")?,
+            c if c.is_control() => write!(writer, "\\u{{{:x}}}", c as u32)?,
+            c => writer.write_char(c)?,
+        }
+    }
+    writer.write_char('"')
+}
+
+/// Writes a single `key=value` logfmt pair to `writer`.
+fn write_pair(writer: &mut Writer<'_>, key: &str, value: &str) -> fmt::Result {
+    writer.write_str(key)?;
+    writer.write_char('=')?;
+    write_value(writer, value)
+}
+
+/// The `logfmt` [`FormatFields`] implementation.
+///
+/// This formats a set of fields as space-separated `key=value` pairs, quoting
+/// and escaping values as needed; see [`Logfmt`] for details.
+#[derive(Debug)]
+pub struct LogfmtFields {
+    // reserve the ability to add fields to this without causing a breaking
+    // change in the future.
+    _private: (),
+}
+
+impl LogfmtFields {
+    /// Returns a new logfmt [`FormatFields`] implementation.
+    pub fn new() -> Self {
+        Self { _private: () }
+    }
+}
+
+impl Default for LogfmtFields {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> MakeVisitor<Writer<'a>> for LogfmtFields {
+    type Visitor = LogfmtVisitor<'a>;
+
+    #[inline]
+    fn make_visitor(&self, target: Writer<'a>) -> Self::Visitor {
+        LogfmtVisitor::new(target, true)
+    }
+}
+
+/// The [visitor] produced by [`LogfmtFields`]'s [`MakeVisitor`] implementation.
+///
+/// [visitor]: crate::field::Visit
+/// [`MakeVisitor`]: crate::field::MakeVisitor
+#[derive(Debug)]
+pub struct LogfmtVisitor<'a> {
+    writer: Writer<'a>,
+    is_empty: bool,
+    result: fmt::Result,
+}
+
+impl<'a> LogfmtVisitor<'a> {
+    /// Returns a new logfmt visitor that formats to the provided `writer`.
+    ///
+    /// # Arguments
+    /// - `writer`: the writer to format to.
+    /// - `is_empty`: whether or not any fields have been previously written to
+    ///   that writer.
+    pub fn new(writer: Writer<'a>, is_empty: bool) -> Self {
+        Self {
+            writer,
+            is_empty,
+            result: Ok(()),
+        }
+    }
+
+    fn maybe_pad(&mut self) {
+        if self.is_empty {
+            self.is_empty = false;
+        } else {
+            self.result = self.writer.write_char(' ');
+        }
+    }
+
+    fn write_pair(&mut self, name: &str, value: &str) {
+        if self.result.is_err() {
+            return;
+        }
+        self.maybe_pad();
+        if self.result.is_err() {
+            return;
+        }
+        self.result = write_pair(&mut self.writer, name, value);
+    }
+
+    /// Renames the `message` field to `msg`, matching the convention used by
+    /// most logfmt-emitting tools.
+    fn name_of(field: &Field) -> &str {
+        if field.name() == "message" {
+            "msg"
+        } else {
+            field.name()
+        }
+    }
+}
+
+impl VisitFmt for LogfmtVisitor<'_> {
+    fn writer(&mut self) -> &mut dyn fmt::Write {
+        &mut self.writer
+    }
+}
+
+impl VisitOutput<fmt::Result> for LogfmtVisitor<'_> {
+    fn finish(self) -> fmt::Result {
+        self.result
+    }
+}
+
+impl field::Visit for LogfmtVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.write_pair(Self::name_of(field), value);
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if self.result.is_err() {
+            return;
+        }
+
+        // Skip fields that are actually log metadata that have already been handled.
+        #[cfg(feature = "tracing-log")]
+        if field.name().starts_with("log.") {
+            return;
+        }
+
+        self.write_pair(Self::name_of(field), &format!("{:?}", value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.write_pair(Self::name_of(field), &value.to_string());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.write_pair(Self::name_of(field), &value.to_string());
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.write_pair(Self::name_of(field), &value.to_string());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.write_pair(Self::name_of(field), if value { "true" } else { "false" });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fmt::{test::MockMakeWriter, CollectorBuilder};
+    use tracing::collect::with_default;
+
+    struct MockTime;
+    impl FormatTime for MockTime {
+        fn format_time(&self, w: &mut Writer<'_>) -> fmt::Result {
+            write!(w, "fake time")
+        }
+    }
+
+    fn test_logfmt(expected: &str, producer: impl FnOnce()) {
+        let make_writer = MockMakeWriter::default();
+        let collector = CollectorBuilder::default()
+            .logfmt()
+            .with_writer(make_writer.clone())
+            .with_timer(MockTime)
+            .finish();
+
+        with_default(collector, producer);
+
+        let buf = make_writer.buf();
+        let actual = std::str::from_utf8(&buf[..]).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn fields_and_message() {
+        test_logfmt(
+            "time=\"fake time\" level=info target=tracing_subscriber::fmt::format::logfmt::test msg=\"some message\" answer=42\n",
+            || {
+                tracing::info!(answer = 42, "some message");
+            },
+        );
+    }
+
+    #[test]
+    fn quotes_values_with_spaces() {
+        test_logfmt(
+            "time=\"fake time\" level=info target=tracing_subscriber::fmt::format::logfmt::test msg=hello name=\"a b\"\n",
+            || {
+                tracing::info!(name = "a b", "hello");
+            },
+        );
+    }
+
+    #[test]
+    fn escapes_quotes_and_backslashes() {
+        test_logfmt(
+            "time=\"fake time\" level=info target=tracing_subscriber::fmt::format::logfmt::test msg=hello path=\"a\\\\\\\"b\"\n",
+            || {
+                tracing::info!(path = "a\\\"b", "hello");
+            },
+        );
+    }
+
+    #[test]
+    fn span_fields_are_appended() {
+        test_logfmt(
+            "time=\"fake time\" level=info target=tracing_subscriber::fmt::format::logfmt::test msg=\"in span\" answer=42\n",
+            || {
+                let span = tracing::info_span!("my_span", answer = 42);
+                let _guard = span.enter();
+                tracing::info!("in span");
+            },
+        );
+    }
+}
@@ -28,15 +28,29 @@
 //!   for production use with systems where structured logs are consumed as JSON
 //!   by analysis and viewing tools. The JSON output is not optimized for human
 //!   readability. See [here](Json#example-output) for sample output.
-use super::time::{FormatTime, SystemTime};
+//!
+//! * [`Human`]: A minimal formatter intended for CLI tools whose primary
+//!   audience is an end user rather than an operator. It shows a symbol in
+//!   place of the level name, a compact time-of-day timestamp, and omits span
+//!   context by default. See [here](Human#example-output) for sample output.
+//!
+//! * [`Logfmt`]: Outputs `key=value` pairs, one event per line, in the
+//!   format popularized by Heroku and consumed natively by log pipelines
+//!   such as Grafana Loki. See [here](Logfmt#example-output) for sample
+//!   output.
+//!
+//! * [`Tree`]: Renders events indented under the span they occurred in,
+//!   with open/close markers and per-span timing, similar to the
+//!   third-party `tracing-tree` crate. Intended for local development. See
+//!   [here](Tree#example-output) for sample output.
+use super::time::{ClockTime, FormatTime, SystemTime};
 use crate::{
     field::{MakeOutput, MakeVisitor, RecordFields, VisitFmt, VisitOutput},
     fmt::fmt_subscriber::{FmtContext, FormattedFields},
     registry::LookupSpan,
-    registry::Scope,
 };
 
-use std::{fmt, marker::PhantomData};
+use std::{cell::Cell, fmt, marker::PhantomData};
 use tracing_core::{
     field::{self, Field, Visit},
     span, Collect, Event, Level,
@@ -60,6 +74,12 @@ mod pretty;
 #[cfg_attr(docsrs, doc(cfg(feature = "ansi")))]
 pub use pretty::*;
 
+mod logfmt;
+pub use logfmt::*;
+
+mod tree;
+pub use tree::*;
+
 use fmt::{Debug, Display};
 
 /// A type that can format a tracing [`Event`] to a [`Writer`].
@@ -223,6 +243,25 @@ where
         (*self)(ctx, writer, event)
     }
 }
+
+// A boxed `FormatEvent` trait object can itself be used as an event
+// formatter, so that the output format (e.g. text vs. JSON) can be selected
+// at runtime without changing the type of the subscriber it's installed on.
+impl<C, N> FormatEvent<C, N> for Box<dyn FormatEvent<C, N> + Send + Sync>
+where
+    C: Collect + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, C, N>,
+        writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        self.as_ref().format_event(ctx, writer, event)
+    }
+}
+
 /// A type that can format a [set of fields] to a [`Writer`].
 ///
 /// `FormatFields` is primarily used in the context of [`fmt::Subscriber`]. Each
@@ -283,6 +322,33 @@ pub fn json() -> Format<Json> {
     format().json()
 }
 
+/// Returns the default configuration for a minimal, end-user-facing [event formatter].
+///
+/// This uses a compact `HH:MM:SS` timestamp in place of [`Format`]'s default
+/// full date-time, and does not display the event's target or thread
+/// information, since these are rarely meaningful to an end user.
+///
+/// [event formatter]: FormatEvent
+pub fn human() -> Format<Human, ClockTime> {
+    format().with_timer(ClockTime).human()
+}
+
+/// Returns the default configuration for a [logfmt]-style [event formatter].
+///
+/// [logfmt]: Logfmt
+/// [event formatter]: FormatEvent
+pub fn logfmt() -> Format<Logfmt> {
+    format().logfmt()
+}
+
+/// Returns the default configuration for a hierarchical [`Tree`] [event
+/// formatter].
+///
+/// [event formatter]: FormatEvent
+pub fn tree() -> Format<Tree> {
+    format().tree()
+}
+
 /// Returns a [`FormatFields`] implementation that formats fields using the
 /// provided function or closure.
 ///
@@ -391,6 +457,25 @@ pub struct Compact;
 #[derive(Default, Debug, Copy, Clone, Eq, PartialEq)]
 pub struct Full;
 
+/// Marker for [`Format`] that indicates that a minimal, end-user-facing log format should be
+/// used.
+///
+/// This formatter is intended for CLI tools whose output is read by the person running them,
+/// rather than for operators grepping production logs. It replaces the level name with a short
+/// symbol, uses a bare `HH:MM:SS` timestamp instead of a full date-time, and does not display
+/// span context, since that's rarely meaningful to an end user.
+///
+/// # Example Output
+///
+/// <pre><font color="#AAAAAA">09:40:14 </font><font color="#4E9A06">✔</font> preparing to shave yaks <i>number_of_yaks</i><font color="#AAAAAA">=3</font>
+/// <font color="#AAAAAA">09:40:14 </font><font color="#75507B">·</font> hello! I&apos;m gonna shave a yak <i>excitement</i><font color="#AAAAAA">=&quot;yay!&quot;</font>
+/// <font color="#AAAAAA">09:40:14 </font><font color="#C4A000">⚠</font> could not locate yak
+/// <font color="#AAAAAA">09:40:14 </font><font color="#CC0000">✘</font> failed to shave yak <i>yak</i><font color="#AAAAAA">=3 </font><i>error</i><font color="#AAAAAA">=missing yak</font>
+/// <font color="#AAAAAA">09:40:14 </font><font color="#4E9A06">✔</font> yak shaving completed <i>all_yaks_shaved</i><font color="#AAAAAA">=false</font>
+/// </pre>
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Human;
+
 /// A pre-configured event formatter.
 ///
 /// You will usually want to use this as the `FormatEvent` for a `FmtSubscriber`.
@@ -411,8 +496,11 @@ pub struct Format<F = Full, T = SystemTime> {
     pub(crate) display_level: bool,
     pub(crate) display_thread_id: bool,
     pub(crate) display_thread_name: bool,
+    pub(crate) display_seq: bool,
     pub(crate) display_filename: bool,
     pub(crate) display_line_number: bool,
+    pub(crate) resource_fields: Vec<(String, String)>,
+    pub(crate) max_span_scope_depth: Option<usize>,
 }
 
 // === impl Writer ===
@@ -601,8 +689,11 @@ impl Default for Format<Full, SystemTime> {
             display_level: true,
             display_thread_id: false,
             display_thread_name: false,
+            display_seq: false,
             display_filename: false,
             display_line_number: false,
+            resource_fields: Vec::new(),
+            max_span_scope_depth: None,
         }
     }
 }
@@ -621,8 +712,11 @@ impl<F, T> Format<F, T> {
             display_level: self.display_level,
             display_thread_id: self.display_thread_id,
             display_thread_name: self.display_thread_name,
+            display_seq: self.display_seq,
             display_filename: self.display_filename,
             display_line_number: self.display_line_number,
+            resource_fields: self.resource_fields,
+            max_span_scope_depth: self.max_span_scope_depth,
         }
     }
 
@@ -660,8 +754,11 @@ impl<F, T> Format<F, T> {
             display_level: self.display_level,
             display_thread_id: self.display_thread_id,
             display_thread_name: self.display_thread_name,
+            display_seq: self.display_seq,
             display_filename: true,
             display_line_number: true,
+            resource_fields: self.resource_fields,
+            max_span_scope_depth: self.max_span_scope_depth,
         }
     }
 
@@ -691,8 +788,86 @@ impl<F, T> Format<F, T> {
             display_level: self.display_level,
             display_thread_id: self.display_thread_id,
             display_thread_name: self.display_thread_name,
+            display_seq: self.display_seq,
+            display_filename: self.display_filename,
+            display_line_number: self.display_line_number,
+            resource_fields: self.resource_fields,
+            max_span_scope_depth: self.max_span_scope_depth,
+        }
+    }
+
+    /// Use a minimal, end-user-facing output format.
+    ///
+    /// See [`Human`].
+    pub fn human(self) -> Format<Human, T> {
+        Format {
+            format: Human,
+            timer: self.timer,
+            ansi: self.ansi,
+            display_target: false,
+            display_timestamp: self.display_timestamp,
+            display_level: self.display_level,
+            display_thread_id: false,
+            display_thread_name: false,
+            display_seq: false,
+            display_filename: self.display_filename,
+            display_line_number: self.display_line_number,
+            resource_fields: self.resource_fields,
+            max_span_scope_depth: self.max_span_scope_depth,
+        }
+    }
+
+    /// Use the [logfmt]-style `key=value` output format.
+    ///
+    /// See [`Logfmt`].
+    ///
+    /// [logfmt]: Logfmt
+    pub fn logfmt(self) -> Format<Logfmt, T> {
+        Format {
+            format: Logfmt,
+            timer: self.timer,
+            ansi: self.ansi,
+            display_target: self.display_target,
+            display_timestamp: self.display_timestamp,
+            display_level: self.display_level,
+            display_thread_id: self.display_thread_id,
+            display_thread_name: self.display_thread_name,
+            display_seq: self.display_seq,
+            display_filename: self.display_filename,
+            display_line_number: self.display_line_number,
+            resource_fields: self.resource_fields,
+            max_span_scope_depth: self.max_span_scope_depth,
+        }
+    }
+
+    /// Use the hierarchical [`Tree`] output format.
+    ///
+    /// Note that this only changes the event formatter; span open/close
+    /// markers additionally require [`with_span_events`] to be configured
+    /// with at least [`FmtSpan::NEW`] and [`FmtSpan::CLOSE`]. The
+    /// [`fmt::Subscriber::tree`]/[`fmt::CollectorBuilder::tree`] builder
+    /// methods do this for you.
+    ///
+    /// See [`Tree`] for details.
+    ///
+    /// [`with_span_events`]: crate::fmt::Subscriber::with_span_events
+    /// [`fmt::Subscriber::tree`]: crate::fmt::Subscriber::tree
+    /// [`fmt::CollectorBuilder::tree`]: crate::fmt::CollectorBuilder::tree
+    pub fn tree(self) -> Format<Tree, T> {
+        Format {
+            format: Tree::default(),
+            timer: self.timer,
+            ansi: self.ansi,
+            display_target: self.display_target,
+            display_timestamp: self.display_timestamp,
+            display_level: self.display_level,
+            display_thread_id: self.display_thread_id,
+            display_thread_name: self.display_thread_name,
+            display_seq: self.display_seq,
             display_filename: self.display_filename,
             display_line_number: self.display_line_number,
+            resource_fields: self.resource_fields,
+            max_span_scope_depth: self.max_span_scope_depth,
         }
     }
 
@@ -720,8 +895,11 @@ impl<F, T> Format<F, T> {
             display_level: self.display_level,
             display_thread_id: self.display_thread_id,
             display_thread_name: self.display_thread_name,
+            display_seq: self.display_seq,
             display_filename: self.display_filename,
             display_line_number: self.display_line_number,
+            resource_fields: self.resource_fields,
+            max_span_scope_depth: self.max_span_scope_depth,
         }
     }
 
@@ -736,8 +914,11 @@ impl<F, T> Format<F, T> {
             display_level: self.display_level,
             display_thread_id: self.display_thread_id,
             display_thread_name: self.display_thread_name,
+            display_seq: self.display_seq,
             display_filename: self.display_filename,
             display_line_number: self.display_line_number,
+            resource_fields: self.resource_fields,
+            max_span_scope_depth: self.max_span_scope_depth,
         }
     }
 
@@ -787,6 +968,20 @@ impl<F, T> Format<F, T> {
         }
     }
 
+    /// Sets whether or not a per-thread, monotonically increasing sequence
+    /// number is displayed when formatting events.
+    ///
+    /// The sequence number starts at 0 for each thread's first event and has
+    /// no meaning across threads, but within a thread it's useful for
+    /// noticing gaps left by dropped lines, and for ordering events whose
+    /// timestamps happen to be identical.
+    pub fn with_seq(self, display_seq: bool) -> Format<F, T> {
+        Format {
+            display_seq,
+            ..self
+        }
+    }
+
     /// Sets whether or not an event's [source code file path][file] is
     /// displayed.
     ///
@@ -819,6 +1014,58 @@ impl<F, T> Format<F, T> {
             .with_file(display_location)
     }
 
+    /// Adds a static key-value pair (such as `service.name` or
+    /// `deployment.env`) that will be attached to every event this formatter
+    /// writes.
+    ///
+    /// This is useful for attributes that describe the process emitting the
+    /// trace data, rather than the event itself, and which would otherwise
+    /// have to be added to every span and event by hand.
+    ///
+    /// Calling this multiple times adds multiple resource fields; fields are
+    /// written in the order they were added.
+    pub fn with_resource_field(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.resource_fields.push((key.into(), value.into()));
+        self
+    }
+
+    /// Sets the maximum number of entered spans whose names (and, for the [`Full`] formatter,
+    /// fields) are rendered as an event's span context.
+    ///
+    /// By default, this is unset, and the full span scope --- from the root span down to the
+    /// event's immediate parent --- is always rendered. For deeply nested spans, walking and
+    /// formatting the entire scope on every event adds up; setting a limit bounds that cost to a
+    /// fixed number of spans, regardless of how deep the scope actually is.
+    ///
+    /// When the scope is deeper than `max_depth`, the innermost `max_depth` spans are rendered
+    /// (closest to the event, since those are usually the most relevant), and the elided older
+    /// ancestors are summarized as a single `...` marker.
+    pub fn with_max_span_scope_depth(self, max_depth: impl Into<Option<usize>>) -> Self {
+        Format {
+            max_span_scope_depth: max_depth.into(),
+            ..self
+        }
+    }
+
+    /// Given the total depth of an event's span scope, returns how many of the outermost
+    /// (closest-to-root) spans should be elided to respect [`Format::with_max_span_scope_depth`].
+    fn scope_skip(&self, total_depth: usize) -> usize {
+        self.max_span_scope_depth
+            .map(|max| total_depth.saturating_sub(max))
+            .unwrap_or(0)
+    }
+
+    /// Writes this formatter's configured [resource fields] to `writer`,
+    /// preceding each with a space.
+    ///
+    /// [resource fields]: Format::with_resource_field
+    fn format_resource_fields(&self, writer: &mut Writer<'_>) -> fmt::Result {
+        for (key, value) in &self.resource_fields {
+            write!(writer, " {}={}", key, value)?;
+        }
+        Ok(())
+    }
+
     fn format_level(&self, level: Level, writer: &mut Writer<'_>) -> fmt::Result
     where
         F: LevelNames,
@@ -918,6 +1165,44 @@ impl<T> Format<Json, T> {
         self.format.with_span_list(display_span_list);
         self
     }
+
+    /// Sets whether or not the formatter will merge the fields of every
+    /// currently entered span into the root object, instead of nesting them
+    /// under `span`/`spans`.
+    ///
+    /// See [`Json::flatten_span_fields`].
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub fn flatten_span_fields(mut self, flatten_span_fields: bool) -> Format<Json, T> {
+        self.format.flatten_span_fields(flatten_span_fields);
+        self
+    }
+
+    /// Sets the [`FieldCollisionPolicy`] used when [flattening span
+    /// fields](Format::flatten_span_fields) and two spans set a field with
+    /// the same name.
+    ///
+    /// See [`Json::with_span_field_collision_policy`].
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub fn with_span_field_collision_policy(
+        mut self,
+        policy: FieldCollisionPolicy,
+    ) -> Format<Json, T> {
+        self.format.with_span_field_collision_policy(policy);
+        self
+    }
+
+    /// Sets whether or not the formatter will include each span's id and
+    /// accumulated busy time (in nanoseconds) alongside its name.
+    ///
+    /// See [`Json::with_span_ids`].
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub fn with_span_ids(mut self, display_span_ids: bool) -> Format<Json, T> {
+        self.format.with_span_ids(display_span_ids);
+        self
+    }
 }
 
 impl<C, N, T> FormatEvent<C, N> for Format<Full, T>
@@ -968,6 +1253,10 @@ where
             write!(writer, "{:0>2?} ", std::thread::current().id())?;
         }
 
+        if self.display_seq {
+            write!(writer, "seq={} ", next_seq())?;
+        }
+
         let dimmed = writer.dimmed();
 
         if let Some(scope) = ctx.event_scope() {
@@ -975,7 +1264,14 @@ where
 
             let mut seen = false;
 
-            for span in scope.from_root() {
+            let spans: Vec<_> = scope.from_root().collect();
+            let skip = self.scope_skip(spans.len());
+            if skip > 0 {
+                write!(writer, "{}{}", dimmed.paint("..."), dimmed.paint(":"))?;
+                seen = true;
+            }
+
+            for span in spans.into_iter().skip(skip) {
                 write!(writer, "{}", bold.paint(span.metadata().name()))?;
                 seen = true;
 
@@ -1031,6 +1327,7 @@ where
         }
 
         ctx.format_fields(writer.by_ref(), event)?;
+        self.format_resource_fields(&mut writer)?;
         writeln!(writer)
     }
 }
@@ -1075,6 +1372,10 @@ where
             write!(writer, "{:0>2?} ", std::thread::current().id())?;
         }
 
+        if self.display_seq {
+            write!(writer, "seq={} ", next_seq())?;
+        }
+
         let dimmed = writer.dimmed();
         if self.display_target {
             write!(
@@ -1106,15 +1407,67 @@ where
 
         ctx.format_fields(writer.by_ref(), event)?;
 
-        for span in ctx.event_scope().into_iter().flat_map(Scope::from_root) {
-            let exts = span.extensions();
-            if let Some(fields) = exts.get::<FormattedFields<N>>() {
-                if !fields.is_empty() {
-                    write!(writer, " {}", dimmed.paint(&fields.fields))?;
+        if let Some(scope) = ctx.event_scope() {
+            let spans: Vec<_> = scope.from_root().collect();
+            let skip = self.scope_skip(spans.len());
+            for span in spans.into_iter().skip(skip) {
+                let exts = span.extensions();
+                if let Some(fields) = exts.get::<FormattedFields<N>>() {
+                    if !fields.is_empty() {
+                        write!(writer, " {}", dimmed.paint(&fields.fields))?;
+                    }
                 }
             }
         }
 
+        self.format_resource_fields(&mut writer)?;
+        writeln!(writer)
+    }
+}
+
+impl<C, N, T> FormatEvent<C, N> for Format<Human, T>
+where
+    C: Collect + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+    T: FormatTime,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, C, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        #[cfg(feature = "tracing-log")]
+        let normalized_meta = event.normalized_metadata();
+        #[cfg(feature = "tracing-log")]
+        let meta = normalized_meta.as_ref().unwrap_or_else(|| event.metadata());
+        #[cfg(not(feature = "tracing-log"))]
+        let meta = event.metadata();
+
+        if let Some(ansi) = self.ansi {
+            writer = writer.with_ansi(ansi);
+        }
+
+        self.format_timestamp(&mut writer)?;
+        self.format_level(*meta.level(), &mut writer)?;
+
+        // Span context is intentionally elided: a person reading a CLI tool's
+        // output rarely cares which span an event was emitted from, only what
+        // happened. `Format::with_target`/`with_filename`/`with_line_number`
+        // remain available for anyone who wants that context back.
+        let dimmed = writer.dimmed();
+        if self.display_target {
+            write!(
+                writer,
+                "{}{} ",
+                dimmed.paint(meta.target()),
+                dimmed.paint(":")
+            )?;
+        }
+
+        ctx.format_fields(writer.by_ref(), event)?;
+        self.format_resource_fields(&mut writer)?;
+
         writeln!(writer)
     }
 }
@@ -1136,9 +1489,10 @@ where
 ///
 #[derive(Debug)]
 pub struct DefaultFields {
-    // reserve the ability to add fields to this without causing a breaking
-    // change in the future.
-    _private: (),
+    max_field_length: Option<usize>,
+    max_fields: Option<usize>,
+    continuation_marker: Option<&'static str>,
+    preserve_indent: bool,
 }
 
 /// The [visitor] produced by [`DefaultFields`]'s [`MakeVisitor`] implementation.
@@ -1150,13 +1504,79 @@ pub struct DefaultVisitor<'a> {
     writer: Writer<'a>,
     is_empty: bool,
     result: fmt::Result,
+    max_field_length: Option<usize>,
+    max_fields: Option<usize>,
+    fields_recorded: usize,
+    continuation_marker: Option<&'static str>,
+    preserve_indent: bool,
 }
 
 impl DefaultFields {
     /// Returns a new default [`FormatFields`] implementation.
     ///
     pub fn new() -> Self {
-        Self { _private: () }
+        Self {
+            max_field_length: None,
+            max_fields: None,
+            continuation_marker: None,
+            preserve_indent: false,
+        }
+    }
+
+    /// Sets the maximum length, in bytes, of a single field's formatted value.
+    ///
+    /// Fields whose `Debug` (or `Display`, for string fields) output would exceed this length are
+    /// truncated, with a trailing `...` marker appended in their place. This bounds the cost of
+    /// formatting a single field whose value is very large, or whose `Debug` implementation
+    /// recurses arbitrarily deep, to roughly `max_len` bytes of work rather than however much the
+    /// value would otherwise produce.
+    ///
+    /// By default, there is no limit.
+    pub fn with_max_field_length(mut self, max_len: impl Into<Option<usize>>) -> Self {
+        self.max_field_length = max_len.into();
+        self
+    }
+
+    /// Sets the maximum number of fields recorded for a single span or event.
+    ///
+    /// Once the limit is reached, remaining fields are dropped and a trailing `...` marker is
+    /// written in their place. This bounds the cost of processing a single span or event with an
+    /// unusually large number of fields.
+    ///
+    /// By default, there is no limit.
+    pub fn with_max_fields(mut self, max_fields: impl Into<Option<usize>>) -> Self {
+        self.max_fields = max_fields.into();
+        self
+    }
+
+    /// Sets a marker with which to prefix continuation lines when a field's formatted value
+    /// contains embedded newlines.
+    ///
+    /// Without this, a value spanning multiple lines --- a stack trace, a formatted SQL query ---
+    /// splits a single logical log line across several physical lines, with no indication that
+    /// they all belong to the same record. With a marker set, each line after the first is
+    /// written on its own line prefixed with `marker`, so the record stays unambiguous and
+    /// `grep`-able even though it spans more than one physical line.
+    ///
+    /// By default, no marker is set, and embedded newlines are passed through unchanged.
+    pub fn with_line_continuation(mut self, marker: impl Into<Option<&'static str>>) -> Self {
+        self.continuation_marker = marker.into();
+        self
+    }
+
+    /// Sets whether continuation lines preserve the original indentation of the value being
+    /// formatted, rather than having it stripped.
+    ///
+    /// This has no effect unless [`with_line_continuation`] has also been used to set a
+    /// continuation marker.
+    ///
+    /// By default, this is `false`, and leading whitespace is stripped from each continuation
+    /// line.
+    ///
+    /// [`with_line_continuation`]: DefaultFields::with_line_continuation
+    pub fn with_continuation_indent(mut self, preserve_indent: bool) -> Self {
+        self.preserve_indent = preserve_indent;
+        self
     }
 }
 
@@ -1172,6 +1592,10 @@ impl<'a> MakeVisitor<Writer<'a>> for DefaultFields {
     #[inline]
     fn make_visitor(&self, target: Writer<'a>) -> Self::Visitor {
         DefaultVisitor::new(target, true)
+            .with_max_field_length(self.max_field_length)
+            .with_max_fields(self.max_fields)
+            .with_line_continuation(self.continuation_marker)
+            .with_continuation_indent(self.preserve_indent)
     }
 }
 
@@ -1189,6 +1613,47 @@ impl<'a> DefaultVisitor<'a> {
             writer,
             is_empty,
             result: Ok(()),
+            max_field_length: None,
+            max_fields: None,
+            fields_recorded: 0,
+            continuation_marker: None,
+            preserve_indent: false,
+        }
+    }
+
+    /// Sets the maximum length, in bytes, of a single field's formatted value.
+    ///
+    /// See [`DefaultFields::with_max_field_length`] for details.
+    pub fn with_max_field_length(self, max_len: impl Into<Option<usize>>) -> Self {
+        Self {
+            max_field_length: max_len.into(),
+            ..self
+        }
+    }
+
+    /// Sets the maximum number of fields recorded for a single span or event.
+    ///
+    /// See [`DefaultFields::with_max_fields`] for details.
+    pub fn with_max_fields(self, max_fields: impl Into<Option<usize>>) -> Self {
+        Self {
+            max_fields: max_fields.into(),
+            ..self
+        }
+    }
+
+    /// See [`DefaultFields::with_line_continuation`] for details.
+    pub fn with_line_continuation(self, marker: impl Into<Option<&'static str>>) -> Self {
+        Self {
+            continuation_marker: marker.into(),
+            ..self
+        }
+    }
+
+    /// See [`DefaultFields::with_continuation_indent`] for details.
+    pub fn with_continuation_indent(self, preserve_indent: bool) -> Self {
+        Self {
+            preserve_indent,
+            ..self
         }
     }
 
@@ -1199,6 +1664,48 @@ impl<'a> DefaultVisitor<'a> {
             self.result = write!(self.writer, " ");
         }
     }
+
+    /// Writes `value`'s `Debug` output, truncating it (with a trailing `...` marker) if it would
+    /// exceed `self.max_field_length`, and rewriting any embedded newlines into continuation
+    /// lines if `self.continuation_marker` is set.
+    fn write_capped_debug(&mut self, value: &dyn fmt::Debug) -> fmt::Result {
+        match self.continuation_marker {
+            Some(marker) => {
+                let mut writer =
+                    ContinuationWriter::new(&mut self.writer, marker, self.preserve_indent);
+                Self::write_debug_to(&mut writer, self.max_field_length, value)
+            }
+            None => Self::write_debug_to(&mut self.writer, self.max_field_length, value),
+        }
+    }
+
+    /// Writes `value`'s `Debug` output to `writer`, truncating it (with a trailing `...` marker)
+    /// if it would exceed `max_len`.
+    fn write_debug_to(
+        writer: &mut dyn fmt::Write,
+        max_len: Option<usize>,
+        value: &dyn fmt::Debug,
+    ) -> fmt::Result {
+        let max_len = match max_len {
+            Some(max_len) => max_len,
+            None => return write!(writer, "{:?}", value),
+        };
+
+        let truncated = {
+            let mut capped = CappedWriter::new(writer, max_len);
+            match fmt::Write::write_fmt(&mut capped, format_args!("{:?}", value)) {
+                Ok(()) => false,
+                Err(_) if capped.truncated => true,
+                Err(e) => return Err(e),
+            }
+        };
+
+        if truncated {
+            writer.write_str("...")
+        } else {
+            Ok(())
+        }
+    }
 }
 
 impl field::Visit for DefaultVisitor<'_> {
@@ -1247,25 +1754,38 @@ impl field::Visit for DefaultVisitor<'_> {
             return;
         }
 
+        if let Some(max_fields) = self.max_fields {
+            if self.fields_recorded > max_fields {
+                return;
+            }
+            if self.fields_recorded == max_fields {
+                self.fields_recorded += 1;
+                self.maybe_pad();
+                self.result = write!(self.writer, "...");
+                return;
+            }
+            self.fields_recorded += 1;
+        }
+
         // emit separating spaces if needed
         self.maybe_pad();
 
         self.result = match name {
-            "message" => write!(self.writer, "{:?}", value),
+            "message" => self.write_capped_debug(value),
             name if name.starts_with("r#") => write!(
                 self.writer,
-                "{}{}{:?}",
+                "{}{}",
                 self.writer.italic().paint(&name[2..]),
-                self.writer.dimmed().paint("="),
-                value
-            ),
+                self.writer.dimmed().paint("=")
+            )
+            .and_then(|_| self.write_capped_debug(value)),
             name => write!(
                 self.writer,
-                "{}{}{:?}",
+                "{}{}",
                 self.writer.italic().paint(name),
-                self.writer.dimmed().paint("="),
-                value
-            ),
+                self.writer.dimmed().paint("=")
+            )
+            .and_then(|_| self.write_capped_debug(value)),
         };
     }
 }
@@ -1282,6 +1802,90 @@ impl crate::field::VisitFmt for DefaultVisitor<'_> {
     }
 }
 
+/// A [`fmt::Write`] adaptor that forwards at most `remaining` bytes to an inner writer, then
+/// starts returning [`fmt::Error`] — the standard mechanism by which a `fmt::Write`
+/// implementation signals a `Debug`/`Display` formatting call to stop early.
+///
+/// This bounds the cost of formatting a single field to roughly `remaining` bytes of work, even
+/// if the value's `Debug` implementation would otherwise produce arbitrarily large (or deeply
+/// recursive) output.
+struct CappedWriter<'a> {
+    inner: &'a mut dyn fmt::Write,
+    remaining: usize,
+    truncated: bool,
+}
+
+impl<'a> CappedWriter<'a> {
+    fn new(inner: &'a mut dyn fmt::Write, max_len: usize) -> Self {
+        Self {
+            inner,
+            remaining: max_len,
+            truncated: false,
+        }
+    }
+}
+
+impl fmt::Write for CappedWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.truncated {
+            return Err(fmt::Error);
+        }
+
+        if s.len() <= self.remaining {
+            self.remaining -= s.len();
+            return self.inner.write_str(s);
+        }
+
+        // write as much of `s` as fits, on a char boundary, then report truncation.
+        let mut end = self.remaining;
+        while end > 0 && !s.is_char_boundary(end) {
+            end -= 1;
+        }
+        self.inner.write_str(&s[..end])?;
+        self.remaining = 0;
+        self.truncated = true;
+        Err(fmt::Error)
+    }
+}
+
+/// A `fmt::Write` adaptor that rewrites embedded newlines into continuation lines, each prefixed
+/// with `marker`, so that a value spanning multiple lines doesn't split a single logical log
+/// record across several physical lines.
+struct ContinuationWriter<'a> {
+    inner: &'a mut dyn fmt::Write,
+    marker: &'static str,
+    preserve_indent: bool,
+}
+
+impl<'a> ContinuationWriter<'a> {
+    fn new(inner: &'a mut dyn fmt::Write, marker: &'static str, preserve_indent: bool) -> Self {
+        Self {
+            inner,
+            marker,
+            preserve_indent,
+        }
+    }
+}
+
+impl fmt::Write for ContinuationWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut lines = s.split('\n');
+        if let Some(first) = lines.next() {
+            self.inner.write_str(first)?;
+        }
+        for line in lines {
+            self.inner.write_str("\n")?;
+            self.inner.write_str(self.marker)?;
+            if self.preserve_indent {
+                self.inner.write_str(line)?;
+            } else {
+                self.inner.write_str(line.trim_start())?;
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Renders an error into a list of sources, *including* the error
 struct ErrorSourceList<'a>(&'a (dyn std::error::Error + 'static));
 
@@ -1319,6 +1923,22 @@ impl Style {
     }
 }
 
+// Returns a per-thread, monotonically increasing sequence number, starting
+// at 0 for each thread's first event. It has no meaning across threads: two
+// events on different threads may report the same `seq`. Within a thread,
+// though, it's useful for noticing gaps left by dropped lines and for
+// ordering events whose timestamps happen to be identical.
+fn next_seq() -> u64 {
+    thread_local! {
+        static SEQ: Cell<u64> = const { Cell::new(0) };
+    }
+    SEQ.with(|seq| {
+        let next = seq.get();
+        seq.set(next.wrapping_add(1));
+        next
+    })
+}
+
 struct FmtThreadName<'a> {
     name: &'a str,
 }
@@ -1410,6 +2030,20 @@ impl LevelNames for Compact {
     const WARN_STR: &'static str = "!";
     const ERROR_STR: &'static str = "X";
 }
+impl LevelNames for Human {
+    const TRACE_STR: &'static str = "·";
+    const DEBUG_STR: &'static str = "‣";
+    const INFO_STR: &'static str = "✔";
+    const WARN_STR: &'static str = "⚠";
+    const ERROR_STR: &'static str = "✘";
+}
+impl LevelNames for Tree {
+    const TRACE_STR: &'static str = "TRACE";
+    const DEBUG_STR: &'static str = "DEBUG";
+    const INFO_STR: &'static str = " INFO";
+    const WARN_STR: &'static str = " WARN";
+    const ERROR_STR: &'static str = "ERROR";
+}
 
 struct FmtLevel<F: ?Sized> {
     level: Level,
@@ -1593,6 +2227,8 @@ impl Debug for FmtSpan {
 pub(super) struct FmtSpanConfig {
     pub(super) kind: FmtSpan,
     pub(super) fmt_timing: bool,
+    pub(super) duration_percent: bool,
+    pub(super) duration_unit: DurationUnit,
 }
 
 impl FmtSpanConfig {
@@ -1600,12 +2236,28 @@ impl FmtSpanConfig {
         Self {
             kind: self.kind,
             fmt_timing: false,
+            duration_percent: self.duration_percent,
+            duration_unit: self.duration_unit,
         }
     }
     pub(super) fn with_kind(self, kind: FmtSpan) -> Self {
         Self {
             kind,
             fmt_timing: self.fmt_timing,
+            duration_percent: self.duration_percent,
+            duration_unit: self.duration_unit,
+        }
+    }
+    pub(super) fn with_duration_percent(self, duration_percent: bool) -> Self {
+        Self {
+            duration_percent,
+            ..self
+        }
+    }
+    pub(super) fn with_duration_unit(self, duration_unit: DurationUnit) -> Self {
+        Self {
+            duration_unit,
+            ..self
         }
     }
     pub(super) fn trace_new(&self) -> bool {
@@ -1633,25 +2285,69 @@ impl Default for FmtSpanConfig {
         Self {
             kind: FmtSpan::NONE,
             fmt_timing: true,
+            duration_percent: false,
+            duration_unit: DurationUnit::Auto,
         }
     }
 }
 
-pub(super) struct TimingDisplay(pub(super) u64);
+/// The unit that `time.busy`/`time.idle` fields on synthesized span close
+/// events are rendered in.
+///
+/// See [`with_span_duration_unit`] for details.
+///
+/// [`with_span_duration_unit`]: super::Subscriber::with_span_duration_unit
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum DurationUnit {
+    /// Automatically scale to whichever of `ns`, `µs`, `ms`, or `s` keeps
+    /// the rendered value readable. This is the default.
+    Auto,
+    /// Always render as whole nanoseconds, e.g. `1234ns`.
+    Nanos,
+    /// Always render as fractional microseconds, e.g. `1.23µs`.
+    Micros,
+    /// Always render as fractional milliseconds, e.g. `1.23ms`.
+    Millis,
+    /// Always render as fractional seconds, e.g. `1.23s`.
+    Seconds,
+}
+
+pub(super) struct TimingDisplay(pub(super) u64, pub(super) DurationUnit);
 impl Display for TimingDisplay {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut t = self.0 as f64;
-        for unit in ["ns", "µs", "ms", "s"].iter() {
-            if t < 10.0 {
-                return write!(f, "{:.2}{}", t, unit);
-            } else if t < 100.0 {
-                return write!(f, "{:.1}{}", t, unit);
-            } else if t < 1000.0 {
-                return write!(f, "{:.0}{}", t, unit);
+        match self.1 {
+            DurationUnit::Auto => {
+                let mut t = self.0 as f64;
+                for unit in ["ns", "µs", "ms", "s"].iter() {
+                    if t < 10.0 {
+                        return write!(f, "{:.2}{}", t, unit);
+                    } else if t < 100.0 {
+                        return write!(f, "{:.1}{}", t, unit);
+                    } else if t < 1000.0 {
+                        return write!(f, "{:.0}{}", t, unit);
+                    }
+                    t /= 1000.0;
+                }
+                write!(f, "{:.0}s", t * 1000.0)
             }
-            t /= 1000.0;
+            DurationUnit::Nanos => write!(f, "{}ns", self.0),
+            DurationUnit::Micros => write!(f, "{:.2}µs", self.0 as f64 / 1_000.0),
+            DurationUnit::Millis => write!(f, "{:.2}ms", self.0 as f64 / 1_000_000.0),
+            DurationUnit::Seconds => write!(f, "{:.2}s", self.0 as f64 / 1_000_000_000.0),
+        }
+    }
+}
+
+/// Displays a span's busy time as a percentage of its parent's total
+/// duration, if one was computed; otherwise, displays nothing.
+pub(super) struct DurationPercentDisplay(pub(super) Option<f64>);
+impl Display for DurationPercentDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(percent) => write!(f, "{:.1}%", percent),
+            None => write!(f, "n/a"),
         }
-        write!(f, "{:.0}s", t * 1000.0)
     }
 }
 
@@ -1664,7 +2360,7 @@ pub(super) mod test {
         dispatch::{set_default, Dispatch},
     };
 
-    use super::{FmtSpan, TimingDisplay, Writer};
+    use super::{DefaultFields, DurationUnit, FmtSpan, TimingDisplay, Writer};
     use regex::Regex;
     use std::fmt;
     use std::path::Path;
@@ -1740,6 +2436,59 @@ pub(super) mod test {
         assert_info_hello(subscriber, make_writer, expected);
     }
 
+    #[test]
+    fn with_resource_field() {
+        let make_writer = MockMakeWriter::default();
+        let subscriber = crate::fmt::Collector::builder()
+            .with_writer(make_writer.clone())
+            .with_ansi(false)
+            .with_timer(MockTime)
+            .with_resource_field("service.name", "my_service")
+            .with_resource_field("service.version", "1.0.0");
+        let expected = "fake time  INFO tracing_subscriber::fmt::format::test: hello service.name=my_service service.version=1.0.0\n";
+
+        assert_info_hello(subscriber, make_writer, expected);
+    }
+
+    #[test]
+    fn without_resource_fields_by_default() {
+        let make_writer = MockMakeWriter::default();
+        let subscriber = crate::fmt::Collector::builder()
+            .with_writer(make_writer.clone())
+            .with_ansi(false)
+            .with_timer(MockTime);
+        let expected = "fake time  INFO tracing_subscriber::fmt::format::test: hello\n";
+
+        assert_info_hello(subscriber, make_writer, expected);
+    }
+
+    #[test]
+    fn human_format() {
+        let make_writer = MockMakeWriter::default();
+        let subscriber = crate::fmt::Collector::builder()
+            .with_writer(make_writer.clone())
+            .with_ansi(false)
+            .with_timer(MockTime)
+            .human();
+        let expected = "fake time ✔ hello\n";
+
+        assert_info_hello(subscriber, make_writer, expected);
+    }
+
+    #[test]
+    fn human_format_with_target() {
+        let make_writer = MockMakeWriter::default();
+        let subscriber = crate::fmt::Collector::builder()
+            .with_writer(make_writer.clone())
+            .with_ansi(false)
+            .with_timer(MockTime)
+            .human()
+            .with_target(true);
+        let expected = "fake time ✔ tracing_subscriber::fmt::format::test: hello\n";
+
+        assert_info_hello(subscriber, make_writer, expected);
+    }
+
     #[test]
     fn with_line_number_and_file_name() {
         let make_writer = MockMakeWriter::default();
@@ -1814,6 +2563,19 @@ pub(super) mod test {
         assert_info_hello_ignore_numeric(subscriber, make_writer, expected);
     }
 
+    #[test]
+    fn with_seq() {
+        let make_writer = MockMakeWriter::default();
+        let subscriber = crate::fmt::Collector::builder()
+            .with_writer(make_writer.clone())
+            .with_seq(true)
+            .with_ansi(false)
+            .with_timer(MockTime);
+        let expected = "fake time  INFO seq=NUMERIC tracing_subscriber::fmt::format::test: hello\n";
+
+        assert_info_hello_ignore_numeric(subscriber, make_writer, expected);
+    }
+
     #[test]
     fn pretty_default() {
         let make_writer = MockMakeWriter::default();
@@ -1879,6 +2641,108 @@ pub(super) mod test {
         );
     }
 
+    #[test]
+    fn with_max_span_scope_depth() {
+        let make_writer = MockMakeWriter::default();
+        let collector = crate::fmt::Collector::builder()
+            .with_writer(make_writer.clone())
+            .with_level(false)
+            .with_ansi(false)
+            .with_timer(MockTime)
+            .with_max_span_scope_depth(1)
+            .finish();
+
+        with_default(collector, || {
+            let span1 = tracing::info_span!("span1");
+            let span2 = tracing::info_span!(parent: &span1, "span2");
+            tracing::info!(parent: &span2, "hello");
+        });
+        assert_eq!(
+            "fake time ...:span2: tracing_subscriber::fmt::format::test: hello\n",
+            make_writer.get_string()
+        );
+    }
+
+    #[test]
+    fn with_max_fields() {
+        let make_writer = MockMakeWriter::default();
+        let subscriber = crate::fmt::Collector::builder()
+            .with_writer(make_writer.clone())
+            .with_ansi(false)
+            .with_timer(MockTime)
+            .fmt_fields(DefaultFields::new().with_max_fields(1))
+            .finish();
+
+        with_default(subscriber, || {
+            tracing::info!(a = 1, b = 2, "hello");
+        });
+        assert_eq!(
+            "fake time  INFO tracing_subscriber::fmt::format::test: hello ...\n",
+            make_writer.get_string()
+        );
+    }
+
+    #[test]
+    fn with_max_field_length() {
+        let make_writer = MockMakeWriter::default();
+        let subscriber = crate::fmt::Collector::builder()
+            .with_writer(make_writer.clone())
+            .with_ansi(false)
+            .with_timer(MockTime)
+            .fmt_fields(DefaultFields::new().with_max_field_length(3))
+            .finish();
+
+        with_default(subscriber, || {
+            tracing::info!(value = ?"a very long value", "hello");
+        });
+        assert_eq!(
+            "fake time  INFO tracing_subscriber::fmt::format::test: hel... value=\"a ...\n",
+            make_writer.get_string()
+        );
+    }
+
+    #[test]
+    fn with_line_continuation() {
+        let make_writer = MockMakeWriter::default();
+        let subscriber = crate::fmt::Collector::builder()
+            .with_writer(make_writer.clone())
+            .with_ansi(false)
+            .with_timer(MockTime)
+            .fmt_fields(DefaultFields::new().with_line_continuation("| "))
+            .finish();
+
+        with_default(subscriber, || {
+            tracing::info!("line one\n    line two\nline three");
+        });
+        assert_eq!(
+            "fake time  INFO tracing_subscriber::fmt::format::test: line one\n| line two\n| line three\n",
+            make_writer.get_string()
+        );
+    }
+
+    #[test]
+    fn with_line_continuation_preserves_indent() {
+        let make_writer = MockMakeWriter::default();
+        let subscriber = crate::fmt::Collector::builder()
+            .with_writer(make_writer.clone())
+            .with_ansi(false)
+            .with_timer(MockTime)
+            .fmt_fields(
+                DefaultFields::new()
+                    .with_line_continuation("| ")
+                    .with_continuation_indent(true),
+            )
+            .finish();
+
+        with_default(subscriber, || {
+            tracing::info!("line one\n    line two");
+        });
+        assert_eq!(
+            "fake time  INFO tracing_subscriber::fmt::format::test: line one\n|     line two\n",
+            make_writer.get_string()
+        );
+    }
+
     #[test]
     fn overridden_parents_in_scope() {
         let make_writer = MockMakeWriter::default();
@@ -1912,7 +2776,7 @@ pub(super) mod test {
     #[test]
     fn format_nanos() {
         fn fmt(t: u64) -> String {
-            TimingDisplay(t).to_string()
+            TimingDisplay(t, DurationUnit::Auto).to_string()
         }
 
         assert_eq!(fmt(1), "1.00ns");
@@ -1930,6 +2794,23 @@ pub(super) mod test {
         assert_eq!(fmt(1234567890123), "1235s");
     }
 
+    #[test]
+    fn format_nanos_with_fixed_unit() {
+        assert_eq!(TimingDisplay(1_500, DurationUnit::Nanos).to_string(), "1500ns");
+        assert_eq!(
+            TimingDisplay(1_500, DurationUnit::Micros).to_string(),
+            "1.50µs"
+        );
+        assert_eq!(
+            TimingDisplay(1_500_000, DurationUnit::Millis).to_string(),
+            "1.50ms"
+        );
+        assert_eq!(
+            TimingDisplay(1_500_000_000, DurationUnit::Seconds).to_string(),
+            "1.50s"
+        );
+    }
+
     #[test]
     fn fmt_span_combinations() {
         let f = FmtSpan::NONE;
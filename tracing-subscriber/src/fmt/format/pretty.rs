@@ -247,7 +247,7 @@ where
         } else {
             Style::new()
         };
-        let thread = self.display_thread_name || self.display_thread_id;
+        let thread = self.display_thread_name || self.display_thread_id || self.display_seq;
 
         if let (Some(file), true, true) = (
             meta.file(),
@@ -264,6 +264,10 @@ where
             write!(writer, "    ")?;
         };
 
+        for (key, value) in &self.resource_fields {
+            writeln!(writer, "    {} {}={}", dimmed.paint("with"), key, value)?;
+        }
+
         if thread {
             write!(writer, "{} ", dimmed.paint("on"))?;
             let thread = std::thread::current();
@@ -278,6 +282,12 @@ where
             if self.display_thread_id {
                 write!(writer, "{:?}", thread.id())?;
             }
+            if self.display_seq {
+                if self.display_thread_name || self.display_thread_id {
+                    writer.write_char(' ')?;
+                }
+                write!(writer, "seq={}", next_seq())?;
+            }
             writer.write_char('\n')?;
         }
 
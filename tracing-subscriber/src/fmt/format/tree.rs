@@ -0,0 +1,208 @@
+use super::{Format, FormatEvent, FormatFields, FormatTime, Writer};
+use crate::{
+    fmt::fmt_subscriber::{FmtContext, FormattedFields},
+    registry::LookupSpan,
+};
+use std::fmt;
+use tracing_core::{field::Field, field::Visit, Collect, Event};
+
+/// A hierarchical, human-readable formatter that renders events indented
+/// under the span they occurred in, with markers showing where each span was
+/// entered and closed.
+///
+/// Unlike the other formatters, `Tree` depends on span open/close events
+/// being enabled: use [`with_span_events`] (or the `tree` convenience method
+/// on [`fmt::Subscriber`]/[`fmt::CollectorBuilder`], which does this for
+/// you) with at least [`FmtSpan::NEW`](super::FmtSpan::NEW) and
+/// [`FmtSpan::CLOSE`](super::FmtSpan::CLOSE) set, or no span boundaries
+/// will be drawn.
+///
+/// This is similar in spirit to the third-party `tracing-tree` crate, and is
+/// intended for local development, where seeing the shape of nested spans at
+/// a glance is more useful than a flat stream of lines.
+///
+/// [`with_span_events`]: crate::fmt::Subscriber::with_span_events
+/// [`fmt::Subscriber`]: crate::fmt::Subscriber
+/// [`fmt::CollectorBuilder`]: crate::fmt::CollectorBuilder
+///
+/// # Example Output
+///
+/// ```text
+/// 2022-02-15T18:44:24.535324Z  INFO shaving_yaks{yaks=3}
+/// 2022-02-15T18:44:24.535403Z  INFO   shaving yaks
+/// 2022-02-15T18:44:24.535670Z  WARN   could not locate yak, yak=3
+/// 2022-02-15T18:44:24.535720Z  INFO shaving_yaks{yaks=3}: close time.busy=125µs time.idle=5.00µs
+/// ```
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct Tree {
+    _private: (),
+}
+
+impl<C, N, T> FormatEvent<C, N> for Format<Tree, T>
+where
+    C: Collect + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+    T: FormatTime,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, C, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let meta = event.metadata();
+        let depth = ctx.event_scope().into_iter().flatten().count();
+
+        self.format_timestamp(&mut writer)?;
+        self.format_level(*meta.level(), &mut writer)?;
+
+        if meta.is_span() {
+            let mut lifecycle = LifecycleVisitor::default();
+            event.record(&mut lifecycle);
+
+            // The synthetic event's parent is the span it was generated
+            // from, so the innermost entry in its scope is that span itself
+            // --- even for a "new" event, which fires before the span has
+            // been entered, so `ctx.lookup_current()` wouldn't find it yet.
+            let span = match lifecycle.kind {
+                LifecycleKind::New | LifecycleKind::Close => {
+                    ctx.event_scope().and_then(|mut scope| scope.next())
+                }
+                _ => None,
+            };
+
+            match (lifecycle.kind, span) {
+                (LifecycleKind::New, Some(span)) => {
+                    write_indent(&mut writer, depth.saturating_sub(1))?;
+                    write!(writer, "{}", span.name())?;
+                    let exts = span.extensions();
+                    if let Some(fields) = exts.get::<FormattedFields<N>>() {
+                        if !fields.is_empty() {
+                            write!(writer, "{{{}}}", fields)?;
+                        }
+                    }
+                    writeln!(writer)
+                }
+                (LifecycleKind::Close, Some(span)) => {
+                    write_indent(&mut writer, depth.saturating_sub(1))?;
+                    write!(writer, "{}: close", span.name())?;
+                    if let Some(busy) = &lifecycle.busy {
+                        write!(writer, " time.busy={}", busy)?;
+                    }
+                    if let Some(idle) = &lifecycle.idle {
+                        write!(writer, " time.idle={}", idle)?;
+                    }
+                    writeln!(writer)
+                }
+                // `enter`/`exit` events aren't rendered by this formatter;
+                // they only matter when timing without open/close markers.
+                _ => Ok(()),
+            }
+        } else {
+            write_indent(&mut writer, depth)?;
+            ctx.format_fields(writer.by_ref(), event)?;
+            self.format_resource_fields(&mut writer)?;
+            writeln!(writer)
+        }
+    }
+}
+
+/// Writes two spaces of indentation per level of span nesting.
+fn write_indent(writer: &mut Writer<'_>, depth: usize) -> fmt::Result {
+    for _ in 0..depth {
+        writer.write_str("  ")?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+enum LifecycleKind {
+    #[default]
+    Other,
+    New,
+    Close,
+}
+
+/// Picks out the `message` (`"new"`/`"close"`/...) and `time.busy`/`time.idle`
+/// fields recorded on the synthetic events emitted when span events are
+/// enabled.
+#[derive(Debug, Default)]
+struct LifecycleVisitor {
+    kind: LifecycleKind,
+    busy: Option<String>,
+    idle: Option<String>,
+}
+
+impl Visit for LifecycleVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.kind = match value {
+                "new" => LifecycleKind::New,
+                "close" => LifecycleKind::Close,
+                _ => LifecycleKind::Other,
+            };
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        match field.name() {
+            "time.busy" => self.busy = Some(format!("{:?}", value)),
+            "time.idle" => self.idle = Some(format!("{:?}", value)),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fmt::{test::MockMakeWriter, CollectorBuilder};
+    use tracing::collect::with_default;
+
+    struct MockTime;
+    impl FormatTime for MockTime {
+        fn format_time(&self, w: &mut Writer<'_>) -> fmt::Result {
+            write!(w, "fake time")
+        }
+    }
+
+    fn test_tree(producer: impl FnOnce()) -> String {
+        let make_writer = MockMakeWriter::default();
+        let collector = CollectorBuilder::default()
+            .tree()
+            .with_writer(make_writer.clone())
+            .with_timer(MockTime)
+            .with_ansi(false)
+            .finish();
+
+        with_default(collector, producer);
+
+        make_writer.get_string()
+    }
+
+    #[test]
+    fn spans_are_opened_and_closed() {
+        let actual = test_tree(|| {
+            tracing::info_span!("my_span", answer = 42).in_scope(|| {
+                tracing::info!("inside the span");
+            });
+        });
+        let mut lines = actual.lines();
+
+        assert_eq!(lines.next(), Some("fake time  INFO my_span{answer=42}"));
+        assert_eq!(lines.next(), Some("fake time  INFO   inside the span"));
+        let close = lines.next().expect("a close line should be emitted");
+        assert!(close.starts_with("fake time  INFO my_span: close time.busy="));
+        assert!(close.contains("time.idle="));
+        assert_eq!(lines.next(), None);
+    }
+
+    #[test]
+    fn events_outside_any_span_are_not_indented() {
+        let actual = test_tree(|| {
+            tracing::info!("no span here");
+        });
+
+        assert_eq!(actual, "fake time  INFO no span here\n");
+    }
+}
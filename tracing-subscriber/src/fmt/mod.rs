@@ -191,7 +191,7 @@
 //! [`Collect`]: https://docs.rs/tracing/latest/tracing/trait.Collect.html
 //! [`tracing`]: https://crates.io/crates/tracing
 //! [`fmt::format`]: mod@crate::fmt::format
-use std::{any::TypeId, error::Error, io, ptr::NonNull};
+use std::{any::TypeId, error::Error, fmt, io, ptr::NonNull};
 use tracing_core::{collect::Interest, span, Event, Metadata};
 
 mod fmt_subscriber;
@@ -202,6 +202,7 @@ pub mod time;
 #[cfg_attr(docsrs, doc(cfg(all(feature = "fmt", feature = "std"))))]
 pub mod writer;
 pub use fmt_subscriber::{FmtContext, FormattedFields, Subscriber};
+pub(crate) use fmt_subscriber::Timings;
 
 use crate::subscribe::Subscribe as _;
 use crate::{
@@ -332,6 +333,63 @@ pub fn subscriber<C>() -> Subscriber<C> {
     Subscriber::default()
 }
 
+/// Combines two [subscriber]s --- typically two [`fmt::Subscriber`]s, each
+/// with its own format, [`MakeWriter`], and [`Filter`] --- into one, for
+/// drop-in use with [`CollectExt::with`].
+///
+/// This is a named, documented shorthand for [`SubscribeExt::and_then`]: it
+/// exists so that driving several differently formatted, differently
+/// filtered outputs doesn't require rediscovering that `and_then` (rather
+/// than a bespoke multiplexer type) is how this crate composes that. Each
+/// branch still formats and writes independently --- there's no shared
+/// cache between formatters that render events differently --- but callers
+/// no longer need to build and name the intermediate [`Layered`] type
+/// themselves.
+///
+/// # Examples
+///
+/// Compact, colored output to stderr at `INFO` and above, and newline-
+/// delimited JSON to a file at `DEBUG` and above:
+///
+/// ```rust
+/// use tracing_subscriber::filter::LevelFilter;
+/// use tracing_subscriber::prelude::*;
+///
+/// # fn docs() -> std::io::Result<()> {
+/// let debug_log = std::fs::File::create("debug.jsonl")?;
+///
+/// tracing_subscriber::registry()
+///     .with(tracing_subscriber::fmt::fanout(
+///         tracing_subscriber::fmt::subscriber()
+///             .compact()
+///             .with_filter(LevelFilter::INFO),
+///         tracing_subscriber::fmt::subscriber()
+///             .json()
+///             .with_writer(debug_log)
+///             .with_filter(LevelFilter::DEBUG),
+///     ))
+///     .init();
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [subscriber]: Subscribe
+/// [`fmt::Subscriber`]: Subscriber
+/// [`MakeWriter`]: writer::MakeWriter
+/// [`Filter`]: crate::subscribe::Filter
+/// [`CollectExt::with`]: crate::subscribe::CollectExt::with
+/// [`SubscribeExt::and_then`]: crate::subscribe::SubscribeExt::and_then
+/// [`Layered`]: crate::subscribe::Layered
+#[cfg_attr(docsrs, doc(cfg(all(feature = "fmt", feature = "std"))))]
+pub fn fanout<A, B, C>(a: A, b: B) -> subscribe::Layered<B, A, C>
+where
+    A: subscribe::Subscribe<C>,
+    B: subscribe::Subscribe<C>,
+    C: tracing_core::Collect,
+{
+    a.and_then(b)
+}
+
 impl Collector {
     /// The maximum [verbosity level] that is enabled by a `Collector` by
     /// default.
@@ -453,6 +511,14 @@ where
     fn span_data(&'a self, id: &span::Id) -> Option<Self::Data> {
         self.inner.span_data(id)
     }
+
+    fn dispatch_extensions(&'a self) -> crate::registry::Extensions<'a> {
+        self.inner.dispatch_extensions()
+    }
+
+    fn dispatch_extensions_mut(&'a self) -> crate::registry::ExtensionsMut<'a> {
+        self.inner.dispatch_extensions_mut()
+    }
 }
 
 // ===== impl CollectorBuilder =====
@@ -515,6 +581,65 @@ where
     }
 }
 
+/// Errors that can occur while assembling and installing a [format
+/// collector](Collector) from its fallible pieces, such as
+/// [`try_with_env_filter`] and [`try_init`].
+///
+/// This aggregates the different ways that configuring and installing a
+/// collector can fail into one type, so that a binary composing several
+/// fallible steps --- parsing an [`EnvFilter`], setting up a [`MakeWriter`]
+/// that requires a guard, installing the collector as the global default,
+/// and so on --- can propagate all of them with `?` instead of inventing a
+/// bespoke error enum for each builder it touches.
+///
+/// [`try_with_env_filter`]: CollectorBuilder::try_with_env_filter()
+/// [`try_init`]: CollectorBuilder::try_init()
+/// [`EnvFilter`]: super::filter::EnvFilter
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum InitError {
+    /// The directives passed to [`try_with_env_filter`] could not be parsed
+    /// as an [`EnvFilter`].
+    ///
+    /// [`try_with_env_filter`]: CollectorBuilder::try_with_env_filter()
+    /// [`EnvFilter`]: super::filter::EnvFilter
+    #[cfg(feature = "env-filter")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "env-filter")))]
+    Filter(crate::filter::ParseError),
+
+    /// Installing the collector as the global default failed, likely
+    /// because one was already installed by another call to [`try_init`].
+    ///
+    /// [`try_init`]: CollectorBuilder::try_init()
+    SetGlobalDefault(Box<dyn Error + Send + Sync + 'static>),
+}
+
+impl fmt::Display for InitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(feature = "env-filter")]
+            InitError::Filter(e) => write!(f, "invalid env filter directives: {}", e),
+            InitError::SetGlobalDefault(e) => write!(f, "failed to install collector: {}", e),
+        }
+    }
+}
+
+impl Error for InitError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            #[cfg(feature = "env-filter")]
+            InitError::Filter(e) => Some(e),
+            InitError::SetGlobalDefault(e) => Some(e.as_ref()),
+        }
+    }
+}
+
+impl From<Box<dyn Error + Send + Sync + 'static>> for InitError {
+    fn from(e: Box<dyn Error + Send + Sync + 'static>) -> Self {
+        InitError::SetGlobalDefault(e)
+    }
+}
+
 impl<N, E, F, W> From<CollectorBuilder<N, E, F, W>> for tracing_core::Dispatch
 where
     N: for<'writer> FormatFields<'writer> + 'static,
@@ -610,6 +735,29 @@ where
         }
     }
 
+    /// Sets whether the `time.busy` and `time.idle` fields on synthesized
+    /// span close events additionally include the span's busy time as a
+    /// percentage of its parent span's total (busy + idle) duration.
+    ///
+    /// See [`Subscriber::with_span_duration_percent`] for details.
+    pub fn with_span_duration_percent(self, display_percent: bool) -> Self {
+        CollectorBuilder {
+            inner: self.inner.with_span_duration_percent(display_percent),
+            ..self
+        }
+    }
+
+    /// Sets the unit that the `time.busy` and `time.idle` fields on
+    /// synthesized span close events are rendered in.
+    ///
+    /// See [`Subscriber::with_span_duration_unit`] for details.
+    pub fn with_span_duration_unit(self, unit: format::DurationUnit) -> Self {
+        CollectorBuilder {
+            inner: self.inner.with_span_duration_unit(unit),
+            ..self
+        }
+    }
+
     /// Sets whether or not the formatter emits ANSI terminal escape codes
     /// for colors and other text formatting.
     ///
@@ -653,6 +801,23 @@ where
         }
     }
 
+    /// Sets a handler that is invoked whenever writing formatted output to
+    /// the [`MakeWriter`] fails.
+    ///
+    /// See [`fmt::Subscriber::on_write_error`] for details.
+    ///
+    /// [`MakeWriter`]: super::writer::MakeWriter
+    /// [`fmt::Subscriber::on_write_error`]: super::Subscriber::on_write_error
+    pub fn on_write_error(
+        self,
+        on_write_error: impl Fn(io::Error) + Send + Sync + 'static,
+    ) -> CollectorBuilder<N, format::Format<L, T>, F, W> {
+        CollectorBuilder {
+            inner: self.inner.on_write_error(on_write_error),
+            ..self
+        }
+    }
+
     /// Sets whether or not an event's target is displayed.
     pub fn with_target(
         self,
@@ -703,6 +868,34 @@ where
         }
     }
 
+    /// Adds a static key-value pair that will be attached to every event
+    /// the collector being built formats.
+    ///
+    /// See [`format::Format::with_resource_field`] for details.
+    pub fn with_resource_field(
+        self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> CollectorBuilder<N, format::Format<L, T>, F, W> {
+        CollectorBuilder {
+            inner: self.inner.with_resource_field(key, value),
+            ..self
+        }
+    }
+
+    /// Sets the maximum number of entered spans rendered as an event's span context.
+    ///
+    /// See [`format::Format::with_max_span_scope_depth`] for details.
+    pub fn with_max_span_scope_depth(
+        self,
+        max_depth: impl Into<Option<usize>>,
+    ) -> CollectorBuilder<N, format::Format<L, T>, F, W> {
+        CollectorBuilder {
+            inner: self.inner.with_max_span_scope_depth(max_depth),
+            ..self
+        }
+    }
+
     /// Sets whether or not the [name] of the current thread is displayed
     /// when formatting events.
     ///
@@ -731,6 +924,20 @@ where
         }
     }
 
+    /// Sets whether or not a per-thread, monotonically increasing sequence
+    /// number is displayed when formatting events.
+    ///
+    /// See [`format::Format::with_seq`] for details.
+    pub fn with_seq(
+        self,
+        display_seq: bool,
+    ) -> CollectorBuilder<N, format::Format<L, T>, F, W> {
+        CollectorBuilder {
+            inner: self.inner.with_seq(display_seq),
+            ..self
+        }
+    }
+
     /// Sets the collector being built to use a less verbose formatter.
     ///
     /// See [`format::Compact`] for details.
@@ -770,6 +977,51 @@ where
             inner: self.inner.json(),
         }
     }
+
+    /// Sets the collector being built to use a [minimal, end-user-facing formatter](format::Human).
+    pub fn human(self) -> CollectorBuilder<N, format::Format<format::Human, T>, F, W>
+    where
+        N: for<'writer> FormatFields<'writer> + 'static,
+    {
+        CollectorBuilder {
+            filter: self.filter,
+            inner: self.inner.human(),
+        }
+    }
+
+    /// Sets the collector being built to use a [logfmt]-style formatter.
+    ///
+    /// See [`format::Logfmt`] for details.
+    ///
+    /// [logfmt]: format::Logfmt
+    pub fn logfmt(
+        self,
+    ) -> CollectorBuilder<format::LogfmtFields, format::Format<format::Logfmt, T>, F, W> {
+        CollectorBuilder {
+            filter: self.filter,
+            inner: self.inner.logfmt(),
+        }
+    }
+
+    /// Sets the collector being built to use a hierarchical [`format::Tree`]
+    /// formatter.
+    ///
+    /// This also enables span open/close events (as if
+    /// [`with_span_events`](Self::with_span_events) had been called with
+    /// [`FmtSpan::NEW`](format::FmtSpan::NEW) `|`
+    /// [`FmtSpan::CLOSE`](format::FmtSpan::CLOSE)), since the tree formatter
+    /// draws its open/close markers from them.
+    ///
+    /// See [`format::Tree`] for details.
+    pub fn tree(self) -> CollectorBuilder<N, format::Format<format::Tree, T>, F, W>
+    where
+        N: for<'writer> FormatFields<'writer> + 'static,
+    {
+        CollectorBuilder {
+            filter: self.filter,
+            inner: self.inner.tree(),
+        }
+    }
 }
 
 #[cfg(feature = "json")]
@@ -815,6 +1067,51 @@ impl<T, F, W> CollectorBuilder<format::JsonFields, format::Format<format::Json,
             inner: self.inner.with_span_list(display_span_list),
         }
     }
+
+    /// Sets whether or not the JSON subscriber being built will merge the
+    /// fields of every currently entered span into the root object, instead
+    /// of nesting them under `span`/`spans`.
+    ///
+    /// See [`format::Json::flatten_span_fields`] for details.
+    pub fn flatten_span_fields(
+        self,
+        flatten_span_fields: bool,
+    ) -> CollectorBuilder<format::JsonFields, format::Format<format::Json, T>, F, W> {
+        CollectorBuilder {
+            filter: self.filter,
+            inner: self.inner.flatten_span_fields(flatten_span_fields),
+        }
+    }
+
+    /// Sets the [`format::FieldCollisionPolicy`] used when [flattening span
+    /// fields](CollectorBuilder::flatten_span_fields) and two spans set a
+    /// field with the same name.
+    ///
+    /// See [`format::Json::with_span_field_collision_policy`] for details.
+    pub fn with_span_field_collision_policy(
+        self,
+        policy: format::FieldCollisionPolicy,
+    ) -> CollectorBuilder<format::JsonFields, format::Format<format::Json, T>, F, W> {
+        CollectorBuilder {
+            filter: self.filter,
+            inner: self.inner.with_span_field_collision_policy(policy),
+        }
+    }
+
+    /// Sets whether or not the JSON subscriber being built will include each
+    /// span's id and accumulated busy time (in nanoseconds) alongside its
+    /// name.
+    ///
+    /// See [`format::Json::with_span_ids`] for details.
+    pub fn with_span_ids(
+        self,
+        display_span_ids: bool,
+    ) -> CollectorBuilder<format::JsonFields, format::Format<format::Json, T>, F, W> {
+        CollectorBuilder {
+            filter: self.filter,
+            inner: self.inner.with_span_ids(display_span_ids),
+        }
+    }
 }
 
 impl<N, E, F, W> CollectorBuilder<N, E, reload::Subscriber<F>, W>
@@ -923,6 +1220,47 @@ impl<N, E, F, W> CollectorBuilder<N, E, F, W> {
         }
     }
 
+    /// Tries to parse `filter` as a set of [`EnvFilter`] directives, using it
+    /// to determine if a span or event is enabled.
+    ///
+    /// Unlike [`with_env_filter`], which silently ignores directives it
+    /// can't parse, this returns an [`InitError`] if `filter` is invalid.
+    /// This is useful for binaries that build up a collector from several
+    /// fallible pieces --- an env filter, a file appender, and so on --- and
+    /// want to surface whichever one failed to their caller, rather than
+    /// panicking or logging a warning that may go unnoticed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tracing_subscriber::fmt::InitError;
+    ///
+    /// fn init_subscriber(filter: &str) -> Result<(), InitError> {
+    ///     tracing_subscriber::fmt()
+    ///         .try_with_env_filter(filter)?
+    ///         .try_init()?;
+    ///
+    ///     Ok(())
+    /// }
+    ///
+    /// assert!(init_subscriber("info,my_crate=debug").is_ok());
+    /// ```
+    ///
+    /// [`EnvFilter`]: super::filter::EnvFilter
+    /// [`with_env_filter`]: CollectorBuilder::with_env_filter()
+    #[cfg(feature = "env-filter")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "env-filter")))]
+    pub fn try_with_env_filter(
+        self,
+        filter: impl AsRef<str>,
+    ) -> Result<CollectorBuilder<N, E, crate::EnvFilter, W>, InitError>
+    where
+        Formatter<N, E, W>: tracing_core::Collect + 'static,
+    {
+        let filter = crate::EnvFilter::try_new(filter).map_err(InitError::Filter)?;
+        Ok(self.with_env_filter(filter))
+    }
+
     /// Sets the maximum [verbosity level] that will be enabled by the
     /// collector.
     ///
@@ -1027,6 +1365,29 @@ impl<N, E, F, W> CollectorBuilder<N, E, F, W> {
     ///     .finish();
     /// ```
     ///
+    /// Selecting a formatter at runtime, e.g. from a config flag, without
+    /// changing the collector's type: box the chosen [`FormatEvent`] and pass
+    /// the box, since `Box<dyn FormatEvent<Registry, N> + Send + Sync>`
+    /// itself implements [`FormatEvent`].
+    ///
+    /// ```rust
+    /// use tracing_subscriber::{fmt::format::{DefaultFields, FormatEvent}, registry::Registry};
+    ///
+    /// fn event_format(
+    ///     compact: bool,
+    /// ) -> Box<dyn FormatEvent<Registry, DefaultFields> + Send + Sync> {
+    ///     if compact {
+    ///         Box::new(tracing_subscriber::fmt::format().compact())
+    ///     } else {
+    ///         Box::new(tracing_subscriber::fmt::format())
+    ///     }
+    /// }
+    ///
+    /// let subscriber = tracing_subscriber::fmt()
+    ///     .event_format(event_format(true))
+    ///     .finish();
+    /// ```
+    ///
     /// [`Writer`]: struct@self::format::Writer
     pub fn event_format<E2>(self, fmt_event: E2) -> CollectorBuilder<N, E2, F, W>
     where
@@ -1326,6 +1687,10 @@ mod test {
         let f = format::Format::default().compact();
         let subscriber = Collector::builder().event_format(f).finish();
         let _dispatch = Dispatch::new(subscriber);
+
+        let f = format::Format::default().human();
+        let subscriber = Collector::builder().event_format(f).finish();
+        let _dispatch = Dispatch::new(subscriber);
     }
 
     #[test]
@@ -1350,4 +1715,21 @@ mod test {
         let subscriber = Collector::new();
         assert_lookup_span(subscriber)
     }
+
+    #[test]
+    #[cfg(feature = "env-filter")]
+    fn try_with_env_filter_rejects_invalid_directives() {
+        let err = Collector::builder()
+            .try_with_env_filter("this is not a valid directive")
+            .unwrap_err();
+        assert!(matches!(err, super::InitError::Filter(_)));
+    }
+
+    #[test]
+    #[cfg(feature = "env-filter")]
+    fn try_with_env_filter_accepts_valid_directives() {
+        Collector::builder()
+            .try_with_env_filter("info,my_crate=debug")
+            .expect("valid directives should be accepted");
+    }
 }
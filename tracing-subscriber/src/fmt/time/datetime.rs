@@ -219,6 +219,20 @@ pub(crate) struct DateTime {
     nanos: u32,
 }
 
+impl DateTime {
+    pub(crate) fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    pub(crate) fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    pub(crate) fn second(&self) -> u8 {
+        self.second
+    }
+}
+
 impl fmt::Display for DateTime {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if self.year > 9999 {
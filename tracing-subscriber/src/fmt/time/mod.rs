@@ -64,6 +64,21 @@ pub fn time() -> SystemTime {
     SystemTime
 }
 
+/// Returns a new `ClockTime` timestamp provider.
+///
+/// This can then be configured further to determine how timestamps should be
+/// configured.
+///
+/// This is equivalent to calling
+/// ```rust
+/// # fn timer() -> tracing_subscriber::fmt::time::ClockTime {
+/// tracing_subscriber::fmt::time::ClockTime::default()
+/// # }
+/// ```
+pub fn clock() -> ClockTime {
+    ClockTime
+}
+
 /// Returns a new `Uptime` timestamp provider.
 ///
 /// With this timer, timestamps will be formatted with the amount of time
@@ -107,6 +122,21 @@ impl FormatTime for fn(&mut Writer<'_>) -> fmt::Result {
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
 pub struct SystemTime;
 
+/// Retrieve and print the current wall-clock time as a bare `HH:MM:SS`, with no date component.
+///
+/// This is intended for short-lived, interactive sessions, where the date is already obvious
+/// from context and the sub-second precision printed by [`SystemTime`] is more noise than it's
+/// worth.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub struct ClockTime;
+
+impl FormatTime for ClockTime {
+    fn format_time(&self, w: &mut Writer<'_>) -> fmt::Result {
+        let now = datetime::DateTime::from(std::time::SystemTime::now());
+        write!(w, "{:02}:{:02}:{:02}", now.hour(), now.minute(), now.second())
+    }
+}
+
 /// Retrieve and print the relative elapsed wall-clock time since an epoch.
 ///
 /// The `Default` implementation for `Uptime` makes the epoch the current time.
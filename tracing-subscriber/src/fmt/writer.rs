@@ -728,6 +728,185 @@ impl<'a> MakeWriter<'a> for BoxMakeWriter {
     }
 }
 
+/// A [`MakeWriter`] that routes to one of several registered [`MakeWriter`]s
+/// based on the target prefix (and, optionally, the verbosity level) of the
+/// span or event being recorded, falling back to a default [`MakeWriter`] if
+/// no registered route matches.
+///
+/// This is useful for services that must keep different classes of log data
+/// --- for example, audit logs and application logs --- in separate files,
+/// declared up front rather than built up out of nested
+/// [`with_filter`](MakeWriterExt::with_filter) calls. Unlike nesting
+/// [`with_max_level`](MakeWriterExt::with_max_level) inside a route's
+/// [`MakeWriter`] directly, a route added with
+/// [`route_with_max_level`](ByTargetBuilder::route_with_max_level) falls back
+/// to the next matching route (or the default) when its level doesn't match,
+/// rather than silently discarding the record.
+///
+/// Constructed with [`ByTarget::builder`].
+///
+/// # Examples
+///
+/// ```
+/// use tracing_subscriber::fmt::writer::BoxMakeWriter;
+///
+/// # fn docs() -> std::io::Result<()> {
+/// let make_writer = tracing_subscriber::fmt::writer::ByTarget::builder()
+///     .route("audit", BoxMakeWriter::new(std::fs::File::create("audit.jsonl")?))
+///     .route("access", BoxMakeWriter::new(std::fs::File::create("access.jsonl")?))
+///     .default(BoxMakeWriter::new(std::fs::File::create("app.jsonl")?))
+///     .build();
+///
+/// tracing_subscriber::fmt().json().with_writer(make_writer).init();
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Routing by level and target together, e.g. sending errors to stderr while
+/// still keeping `audit`-target events in their own file regardless of
+/// level:
+///
+/// ```
+/// use tracing_subscriber::fmt::writer::BoxMakeWriter;
+/// use tracing_core::Level;
+///
+/// # fn docs() -> std::io::Result<()> {
+/// let make_writer = tracing_subscriber::fmt::writer::ByTarget::builder()
+///     .route("audit", BoxMakeWriter::new(std::fs::File::create("audit.jsonl")?))
+///     .route_with_max_level("", Level::ERROR, BoxMakeWriter::new(std::io::stderr))
+///     .default(BoxMakeWriter::new(std::io::stdout))
+///     .build();
+///
+/// tracing_subscriber::fmt().with_writer(make_writer).init();
+/// # Ok(())
+/// # }
+/// ```
+pub struct ByTarget {
+    routes: Vec<(String, Option<tracing_core::Level>, BoxMakeWriter)>,
+    default: BoxMakeWriter,
+}
+
+/// Constructs a [`ByTarget`] writer. See [`ByTarget::builder`].
+#[derive(Debug)]
+pub struct ByTargetBuilder {
+    routes: Vec<(String, Option<tracing_core::Level>, BoxMakeWriter)>,
+    default: Option<BoxMakeWriter>,
+}
+
+impl ByTarget {
+    /// Returns a new [`ByTargetBuilder`] for constructing a `ByTarget`
+    /// writer.
+    pub fn builder() -> ByTargetBuilder {
+        ByTargetBuilder {
+            routes: Vec::new(),
+            default: None,
+        }
+    }
+}
+
+impl ByTargetBuilder {
+    /// Routes spans and events whose target starts with `prefix` to
+    /// `make_writer`, regardless of level.
+    ///
+    /// If more than one registered route matches, the longest prefix wins.
+    pub fn route<M>(mut self, prefix: impl Into<String>, make_writer: M) -> Self
+    where
+        M: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+    {
+        self.routes
+            .push((prefix.into(), None, BoxMakeWriter::new(make_writer)));
+        self
+    }
+
+    /// Routes spans and events whose target starts with `prefix` *and* whose
+    /// level is at or more severe than `level` to `make_writer`.
+    ///
+    /// If no registered route matches a given span or event --- either
+    /// because its target doesn't match any prefix, or because its level is
+    /// more verbose than every matching route's `level` --- it falls through
+    /// to the next matching route, or to the [`default`](Self::default)
+    /// writer, the same as an unmatched target does. Pass an empty string as
+    /// `prefix` to route purely by level, matching every target.
+    ///
+    /// If more than one registered route matches, the longest prefix wins;
+    /// ties between a [`route`](Self::route) and a `route_with_max_level`
+    /// registered with the same prefix are broken in registration order.
+    pub fn route_with_max_level<M>(
+        mut self,
+        prefix: impl Into<String>,
+        level: tracing_core::Level,
+        make_writer: M,
+    ) -> Self
+    where
+        M: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+    {
+        self.routes
+            .push((prefix.into(), Some(level), BoxMakeWriter::new(make_writer)));
+        self
+    }
+
+    /// Sets the [`MakeWriter`] used for spans and events whose target
+    /// doesn't match any registered prefix.
+    ///
+    /// If this is never called, unmatched spans and events are written to
+    /// [`std::io::stdout`].
+    pub fn default<M>(mut self, make_writer: M) -> Self
+    where
+        M: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+    {
+        self.default = Some(BoxMakeWriter::new(make_writer));
+        self
+    }
+
+    /// Consumes the builder, returning the constructed [`ByTarget`] writer.
+    pub fn build(self) -> ByTarget {
+        ByTarget {
+            routes: self.routes,
+            default: self
+                .default
+                .unwrap_or_else(|| BoxMakeWriter::new(std::io::stdout)),
+        }
+    }
+}
+
+impl fmt::Debug for ByTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ByTarget")
+            .field(
+                "routes",
+                &self
+                    .routes
+                    .iter()
+                    .map(|(prefix, level, _)| (prefix, level))
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl<'a> MakeWriter<'a> for ByTarget {
+    type Writer = Box<dyn Write + 'a>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.default.make_writer()
+    }
+
+    fn make_writer_for(&'a self, meta: &Metadata<'_>) -> Self::Writer {
+        let best_match = self
+            .routes
+            .iter()
+            .filter(|(prefix, level, _)| {
+                meta.target().starts_with(prefix.as_str())
+                    && level.map_or(true, |level| meta.level() <= &level)
+            })
+            .max_by_key(|(prefix, _, _)| prefix.len());
+        match best_match {
+            Some((_, _, make_writer)) => make_writer.make_writer_for(meta),
+            None => self.default.make_writer_for(meta),
+        }
+    }
+}
+
 struct Boxed<M>(M);
 
 impl<'a, M> MakeWriter<'a> for Boxed<M>
@@ -1266,6 +1445,51 @@ mod test {
         has_lines(&err_buf, &all_lines[4..]);
     }
 
+    #[test]
+    fn by_target_routes_by_level_and_target() {
+        let audit_buf = Arc::new(Mutex::new(Vec::new()));
+        let audit = MockMakeWriter::new(audit_buf.clone());
+
+        let err_buf = Arc::new(Mutex::new(Vec::new()));
+        let err = MockMakeWriter::new(err_buf.clone());
+
+        let default_buf = Arc::new(Mutex::new(Vec::new()));
+        let default = MockMakeWriter::new(default_buf.clone());
+
+        let make_writer = ByTarget::builder()
+            .route("audit", audit)
+            .route_with_max_level("", Level::ERROR, err)
+            .default(default)
+            .build();
+
+        let c = {
+            #[cfg(feature = "ansi")]
+            let f = Format::default().without_time().with_ansi(false);
+            #[cfg(not(feature = "ansi"))]
+            let f = Format::default().without_time();
+            Collector::builder()
+                .event_format(f)
+                .with_writer(make_writer)
+                .with_max_level(Level::TRACE)
+                .finish()
+        };
+
+        let _s = tracing::collect::set_default(c);
+
+        tracing::event!(target: "audit", Level::INFO, "audit info");
+        tracing::event!(target: "audit", Level::ERROR, "audit error");
+        error!("app error");
+        info!("app info");
+
+        let audit_actual = String::from_utf8(audit_buf.try_lock().unwrap().to_vec()).unwrap();
+        assert_eq!(
+            audit_actual,
+            " INFO audit: audit info\nERROR audit: audit error\n"
+        );
+        has_lines(&err_buf, &[(Level::ERROR, "app error")]);
+        has_lines(&default_buf, &[(Level::INFO, "app info")]);
+    }
+
     #[test]
     fn combinators_or_else() {
         let some_buf = Arc::new(Mutex::new(Vec::new()));
@@ -0,0 +1,258 @@
+//! A [`Subscribe`] that watches for spans that stay open far longer than
+//! expected, and periodically logs a `WARN` diagnostic about each one.
+//!
+//! A span that's never closed --- a forgotten [`EnteredSpan`], a guard held
+//! in a struct that outlives the request it was created for --- doesn't
+//! panic or fail loudly. It just keeps showing up as an ever-growing
+//! ancestor in every subsequent span's context, quietly skewing timing and
+//! making traces harder to read. [`LeakDetector`] catches this class of bug
+//! in a long-running service by tracking when each span was created and,
+//! on a background thread, periodically checking for spans that have
+//! outlived a configured threshold.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::time::Duration;
+//! use tracing_subscriber::leak_detector::LeakDetector;
+//! use tracing_subscriber::prelude::*;
+//!
+//! let (leak_detector, _guard) = LeakDetector::new(Duration::from_secs(60));
+//!
+//! tracing_subscriber::registry()
+//!     .with(leak_detector)
+//!     .init();
+//! ```
+//!
+//! [`Subscribe`]: crate::Subscribe
+//! [`EnteredSpan`]: tracing::span::EnteredSpan
+use crate::{
+    registry::LookupSpan,
+    subscribe::{Context, Subscribe},
+};
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+use tracing_core::{span, Collect};
+
+/// How often, by default, the background thread checks for spans that have
+/// outlived [`LeakDetector`]'s threshold.
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How often the background thread wakes up to check whether it's been
+/// asked to shut down, regardless of `poll_interval`. This keeps
+/// [`LeakDetectorGuard`]'s drop from blocking for the whole, potentially
+/// long, `poll_interval` while the thread is asleep.
+const SHUTDOWN_CHECK_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A [`Subscribe`] that periodically emits a `WARN` event for every
+/// currently open span older than a configured threshold.
+///
+/// See the [module-level documentation](self) for details.
+pub struct LeakDetector {
+    spans: Arc<Mutex<HashMap<span::Id, SpanInfo>>>,
+    threshold: Duration,
+}
+
+impl fmt::Debug for LeakDetector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LeakDetector")
+            .field("threshold", &self.threshold)
+            .finish()
+    }
+}
+
+/// What's recorded about each currently open span, used to report it if it
+/// outlives [`LeakDetector`]'s threshold.
+struct SpanInfo {
+    name: &'static str,
+    file: Option<&'static str>,
+    line: Option<u32>,
+    created_at: Instant,
+}
+
+/// Stops a [`LeakDetector`]'s background thread when dropped.
+#[must_use]
+pub struct LeakDetectorGuard {
+    shutdown: Arc<AtomicBool>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl fmt::Debug for LeakDetectorGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LeakDetectorGuard").finish()
+    }
+}
+
+impl LeakDetector {
+    /// Returns a new `LeakDetector` that warns about any span still open
+    /// after `threshold` has elapsed since it was created, checking every
+    /// [`DEFAULT_POLL_INTERVAL`] (30 seconds). Use
+    /// [`with_poll_interval`](Self::with_poll_interval) to override how
+    /// often it checks.
+    ///
+    /// The returned [`LeakDetectorGuard`] must be kept alive for as long as
+    /// the background thread should keep running; dropping it stops the
+    /// thread.
+    pub fn new(threshold: Duration) -> (Self, LeakDetectorGuard) {
+        Self::with_poll_interval(threshold, DEFAULT_POLL_INTERVAL)
+    }
+
+    /// Returns a new `LeakDetector` that warns about any span still open
+    /// after `threshold` has elapsed since it was created, checking every
+    /// `poll_interval`.
+    pub fn with_poll_interval(
+        threshold: Duration,
+        poll_interval: Duration,
+    ) -> (Self, LeakDetectorGuard) {
+        let spans: Arc<Mutex<HashMap<span::Id, SpanInfo>>> = Arc::new(Mutex::new(HashMap::new()));
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let worker_spans = spans.clone();
+        let worker_shutdown = shutdown.clone();
+        let handle = thread::Builder::new()
+            .name("tracing-subscriber-leak-detector".into())
+            .spawn(move || scan(&worker_spans, threshold, poll_interval, &worker_shutdown))
+            .expect("failed to spawn `tracing-subscriber` leak detector thread");
+
+        (
+            Self { spans, threshold },
+            LeakDetectorGuard {
+                shutdown,
+                handle: Some(handle),
+            },
+        )
+    }
+}
+
+fn scan(
+    spans: &Mutex<HashMap<span::Id, SpanInfo>>,
+    threshold: Duration,
+    poll_interval: Duration,
+    shutdown: &AtomicBool,
+) {
+    loop {
+        let mut slept = Duration::ZERO;
+        while slept < poll_interval {
+            if shutdown.load(Ordering::Acquire) {
+                return;
+            }
+            let remaining = poll_interval - slept;
+            let step = SHUTDOWN_CHECK_INTERVAL.min(remaining);
+            thread::sleep(step);
+            slept += step;
+        }
+
+        if shutdown.load(Ordering::Acquire) {
+            return;
+        }
+        check_once(spans, threshold);
+    }
+}
+
+/// Warns about every span in `spans` that's been open for at least
+/// `threshold`, as of now.
+fn check_once(spans: &Mutex<HashMap<span::Id, SpanInfo>>, threshold: Duration) {
+    let now = Instant::now();
+    for info in spans.lock().unwrap_or_else(|e| e.into_inner()).values() {
+        let age = now.saturating_duration_since(info.created_at);
+        if age < threshold {
+            continue;
+        }
+        tracing::warn!(
+            span_name = info.name,
+            span_age_secs = age.as_secs_f64(),
+            span_file = info.file.unwrap_or("<unknown>"),
+            span_line = info.line.unwrap_or(0),
+            "span has been open for longer than the configured leak detection threshold"
+        );
+    }
+}
+
+impl<C> Subscribe<C> for LeakDetector
+where
+    C: Collect + for<'span> LookupSpan<'span>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, _ctx: Context<'_, C>) {
+        let metadata = attrs.metadata();
+        self.spans.lock().unwrap_or_else(|e| e.into_inner()).insert(
+            id.clone(),
+            SpanInfo {
+                name: metadata.name(),
+                file: metadata.file(),
+                line: metadata.line(),
+                created_at: Instant::now(),
+            },
+        );
+    }
+
+    fn on_close(&self, id: span::Id, _ctx: Context<'_, C>) {
+        self.spans.lock().unwrap_or_else(|e| e.into_inner()).remove(&id);
+    }
+}
+
+impl Drop for LeakDetectorGuard {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::Release);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn tracks_and_forgets_spans() {
+        let (leak_detector, _guard) =
+            LeakDetector::with_poll_interval(Duration::from_secs(3600), Duration::from_secs(3600));
+        let spans = leak_detector.spans.clone();
+
+        tracing::collect::with_default(crate::registry().with(leak_detector), || {
+            let span = tracing::info_span!("request");
+            let id = span.id().expect("span was not disabled");
+
+            assert!(spans.lock().unwrap().contains_key(&id));
+
+            drop(span);
+
+            assert!(!spans.lock().unwrap().contains_key(&id));
+        });
+    }
+
+    #[test]
+    fn warns_about_spans_older_than_threshold() {
+        // Drive `check_once` directly rather than through the background
+        // thread: `tracing`'s default dispatcher is thread-local, so an
+        // event emitted from another thread wouldn't reach the mock
+        // subscriber installed by `with_default` below.
+        let spans: Mutex<HashMap<span::Id, SpanInfo>> = Mutex::new(HashMap::new());
+        spans.lock().unwrap().insert(
+            span::Id::from_u64(1),
+            SpanInfo {
+                name: "request",
+                file: Some("src/lib.rs"),
+                line: Some(42),
+                created_at: Instant::now(),
+            },
+        );
+
+        tracing::collect::with_default(crate::registry(), || {
+            thread::sleep(Duration::from_millis(10));
+            check_once(&spans, Duration::from_millis(1));
+        });
+
+        // `check_once` only reports on spans, it doesn't forget them.
+        assert!(spans.lock().unwrap().contains_key(&span::Id::from_u64(1)));
+    }
+}
@@ -188,6 +188,9 @@ pub mod util;
 
 feature! {
     #![feature = "std"]
+    pub mod cardinality;
+    pub mod early;
+    pub mod offload;
     pub mod reload;
     pub(crate) mod sync;
 }
@@ -202,6 +205,25 @@ feature! {
 feature! {
     #![all(feature = "env-filter", feature = "std")]
     pub use filter::EnvFilter;
+    pub mod adaptive;
+}
+
+feature! {
+    #![feature = "admin"]
+    pub mod admin;
+}
+
+feature! {
+    #![all(feature = "registry", feature = "std")]
+    pub mod capture;
+    pub mod crash_context;
+    pub mod timing;
+}
+
+feature! {
+    #![all(feature = "registry", feature = "std", feature = "tracing")]
+    pub mod lifecycle;
+    pub mod leak_detector;
 }
 
 pub use subscribe::Subscribe;
@@ -0,0 +1,225 @@
+//! A [`Subscribe`] that runs user-registered callbacks when a span closes.
+//!
+//! Request-handling code often wants to colocate "when this request is done,
+//! do X" logic with the point where the request's span is created, rather
+//! than threading a completion hook through every function that might
+//! eventually finish the request. [`SpanExt::on_close`] lets a [`Span`] take
+//! a callback at creation time; [`CloseCallbacks`] runs it once, with access
+//! to that span's [extensions], when the span finally closes.
+//!
+//! [`Span`]: tracing::Span
+//! [extensions]: crate::registry::SpanRef::extensions
+//!
+//! # Examples
+//!
+//! ```
+//! use tracing_subscriber::lifecycle::{CloseCallbacks, SpanExt};
+//! use tracing_subscriber::prelude::*;
+//!
+//! tracing_subscriber::registry()
+//!     .with(CloseCallbacks::new())
+//!     .init();
+//!
+//! let span = tracing::info_span!("request");
+//! span.on_close(|_extensions| {
+//!     println!("request finished");
+//! });
+//!
+//! drop(span);
+//! ```
+use crate::{
+    registry::{ExtensionsMut, LookupSpan},
+    subscribe::{Context, Subscribe},
+};
+use std::{any::TypeId, fmt, marker::PhantomData, ptr::NonNull};
+use tracing_core::{span, Collect, Dispatch};
+
+type Callback = Box<dyn FnOnce(&mut ExtensionsMut<'_>) + Send + Sync>;
+
+/// A [`Subscribe`] that invokes callbacks registered with [`SpanExt::on_close`]
+/// once their span closes.
+///
+/// Adding a bare `CloseCallbacks` to a collector does nothing on its own;
+/// call [`SpanExt::on_close`] on a [`Span`](tracing::Span) to register a
+/// callback to run when that span closes.
+pub struct CloseCallbacks<C> {
+    get_context: WithContext,
+    _collector: PhantomData<fn(C)>,
+}
+
+/// "Remembers" the collector type a [`CloseCallbacks`] was constructed for,
+/// so that [`SpanExt::on_close`] can reach it back through a type-erased
+/// [`Dispatch`] without needing to name that type itself.
+pub(crate) struct WithContext {
+    register_fn: fn(&Dispatch, &span::Id, Callback),
+}
+
+impl WithContext {
+    fn register(&self, dispatch: &Dispatch, id: &span::Id, callback: Callback) {
+        (self.register_fn)(dispatch, id, callback)
+    }
+}
+
+impl<C> CloseCallbacks<C>
+where
+    C: Collect + for<'span> LookupSpan<'span>,
+{
+    /// Returns a new `CloseCallbacks` subscriber.
+    pub fn new() -> Self {
+        Self {
+            get_context: WithContext {
+                register_fn: Self::register_callback,
+            },
+            _collector: PhantomData,
+        }
+    }
+
+    fn register_callback(dispatch: &Dispatch, id: &span::Id, callback: Callback) {
+        let collector = dispatch
+            .downcast_ref::<C>()
+            .expect("collector should downcast to expected type; this is a bug!");
+        let span = match collector.span(id) {
+            Some(span) => span,
+            None => return,
+        };
+        let mut extensions = span.extensions_mut();
+        match extensions.get_mut::<Callbacks>() {
+            Some(callbacks) => callbacks.0.push(callback),
+            None => extensions.insert(Callbacks(vec![callback])),
+        }
+    }
+}
+
+impl<C> Default for CloseCallbacks<C>
+where
+    C: Collect + for<'span> LookupSpan<'span>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C> Subscribe<C> for CloseCallbacks<C>
+where
+    C: Collect + for<'span> LookupSpan<'span>,
+{
+    fn on_close(&self, id: span::Id, ctx: Context<'_, C>) {
+        let span = match ctx.span(&id) {
+            Some(span) => span,
+            None => return,
+        };
+        let callbacks = span.extensions_mut().remove::<Callbacks>();
+        if let Some(callbacks) = callbacks {
+            let mut extensions = span.extensions_mut();
+            for callback in callbacks.0 {
+                callback(&mut extensions);
+            }
+        }
+    }
+
+    unsafe fn downcast_raw(&self, id: TypeId) -> Option<NonNull<()>> {
+        match id {
+            id if id == TypeId::of::<Self>() => Some(NonNull::from(self).cast()),
+            id if id == TypeId::of::<WithContext>() => {
+                Some(NonNull::from(&self.get_context).cast())
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<C> fmt::Debug for CloseCallbacks<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CloseCallbacks").finish()
+    }
+}
+
+/// The callbacks registered for one span, stored in that span's
+/// [extensions].
+///
+/// [extensions]: crate::registry::SpanRef::extensions
+struct Callbacks(Vec<Callback>);
+
+/// Extends [`Span`](tracing::Span) with the ability to register a callback
+/// that runs when the span closes.
+pub trait SpanExt {
+    /// Registers `callback` to run, with mutable access to this span's
+    /// [extensions], when this span closes.
+    ///
+    /// Does nothing if this span is disabled, or if the current collector
+    /// does not have a [`CloseCallbacks`] subscriber installed.
+    ///
+    /// [extensions]: crate::registry::SpanRef::extensions
+    fn on_close<F>(&self, callback: F)
+    where
+        F: FnOnce(&mut ExtensionsMut<'_>) + Send + Sync + 'static;
+}
+
+impl SpanExt for tracing::Span {
+    fn on_close<F>(&self, callback: F)
+    where
+        F: FnOnce(&mut ExtensionsMut<'_>) + Send + Sync + 'static,
+    {
+        self.with_collector(|(id, dispatch)| {
+            if let Some(ctx) = dispatch.downcast_ref::<WithContext>() {
+                ctx.register(dispatch, id, Box::new(callback));
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use std::sync::{Arc, Mutex};
+
+    struct Finished;
+
+    #[test]
+    fn callback_runs_when_span_closes() {
+        let ran = Arc::new(Mutex::new(false));
+        let ran2 = ran.clone();
+
+        tracing::collect::with_default(crate::registry().with(CloseCallbacks::new()), || {
+            let span = tracing::info_span!("request");
+            span.on_close(move |extensions| {
+                extensions.insert(Finished);
+                *ran2.lock().unwrap() = true;
+            });
+
+            assert!(!*ran.lock().unwrap(), "callback must not run before close");
+            drop(span);
+        });
+
+        assert!(*ran.lock().unwrap(), "callback should run once the span closes");
+    }
+
+    #[test]
+    fn multiple_callbacks_all_run() {
+        let count = Arc::new(Mutex::new(0));
+
+        tracing::collect::with_default(crate::registry().with(CloseCallbacks::new()), || {
+            let span = tracing::info_span!("request");
+            for _ in 0..3 {
+                let count = count.clone();
+                span.on_close(move |_extensions| {
+                    *count.lock().unwrap() += 1;
+                });
+            }
+            drop(span);
+        });
+
+        assert_eq!(*count.lock().unwrap(), 3);
+    }
+
+    #[test]
+    fn does_nothing_without_the_subscriber() {
+        tracing::collect::with_default(crate::registry(), || {
+            let span = tracing::info_span!("request");
+            // Should not panic even though no `CloseCallbacks` is installed.
+            span.on_close(|_extensions| panic!("should never run"));
+            drop(span);
+        });
+    }
+}
@@ -0,0 +1,424 @@
+//! A [`Subscribe`] that moves expensive per-event work off of the dispatch
+//! path onto a dedicated worker thread.
+//!
+//! [`Subscribe::on_event`] implementations run inline, on whichever thread
+//! recorded the event. For most subscribers that's fine, but an exporter
+//! that serializes events and ships them over the network can end up
+//! stalling that thread for long enough to matter -- which is especially
+//! costly if the thread belongs to an async executor, since blocking it
+//! also blocks every other task scheduled onto it.
+//!
+//! [`Offload`] splits such a subscriber's work in two: a `capture` step that
+//! runs on the dispatch path and must not block, turning a borrowed
+//! [`Event`] into an owned, `'static` snapshot; and a [`Sink::export`] step
+//! that runs later, on a dedicated worker thread, and is allowed to block.
+//!
+//! If an event records a `ttl_ms` field, that's treated as a deadline for
+//! the export step: once the worker thread has fallen behind far enough
+//! that `ttl_ms` milliseconds have passed since the event was captured,
+//! [`Offload`] drops it rather than exporting it late, and counts it in
+//! [`Offload::expired_events`]. This keeps a backed-up exporter from
+//! spending its recovery time shipping stale telemetry instead of current
+//! data -- events without a `ttl_ms` field never expire this way.
+//!
+//! # Examples
+//!
+//! ```
+//! use tracing_subscriber::offload::{Offload, Sink};
+//! use tracing_subscriber::prelude::*;
+//!
+//! struct LineSink;
+//!
+//! impl Sink for LineSink {
+//!     // An owned, 'static snapshot of whatever the sink needs, captured
+//!     // before the borrowed `Event` goes out of scope.
+//!     type Captured = String;
+//!
+//!     fn export(&mut self, line: String) {
+//!         // Pretend this is an expensive, blocking network call.
+//!         println!("exporting: {}", line);
+//!     }
+//! }
+//!
+//! let (offload, _guard) = Offload::new(
+//!     |event: &tracing::Event<'_>| format!("{:?}", event.metadata().name()),
+//!     LineSink,
+//! );
+//! tracing_subscriber::registry().with(offload).init();
+//!
+//! tracing::info!("hello from the dispatch path");
+//! ```
+//!
+//! [`Subscribe`]: crate::subscribe::Subscribe
+//! [`Subscribe::on_event`]: crate::subscribe::Subscribe::on_event
+use crate::subscribe::{Context, Subscribe};
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+use tracing_core::{field::Field, field::Visit, Collect, Event};
+
+/// The default number of captured events buffered between the dispatch path
+/// and the worker thread.
+///
+/// If the worker thread falls behind and the buffer fills up, [`Offload`]
+/// drops further events rather than blocking the dispatch path; see
+/// [`Offload::dropped_events`].
+pub const DEFAULT_CAPACITY: usize = 1024;
+
+/// The part of exporting an event that is allowed to block.
+///
+/// This runs on [`Offload`]'s dedicated worker thread, not on the dispatch
+/// path, so -- unlike the `capture` closure passed to [`Offload::new`] --
+/// it's allowed to block for as long as it needs to: for instance, to
+/// serialize the captured event and send it over the network.
+pub trait Sink: Send + 'static {
+    /// An owned snapshot of an event, captured on the dispatch path.
+    type Captured: Send + 'static;
+
+    /// Processes a value previously produced by the `capture` closure
+    /// passed to [`Offload::new`].
+    ///
+    /// This runs on `Offload`'s worker thread, and may block.
+    fn export(&mut self, captured: Self::Captured);
+}
+
+enum Msg<T> {
+    Captured {
+        value: T,
+        /// When this capture should be given up on rather than exported, if
+        /// the event that produced it recorded a `ttl_ms` field.
+        expires_at: Option<Instant>,
+    },
+    Shutdown,
+}
+
+/// The reserved name of the field an event can record to give its capture a
+/// time-to-live, in milliseconds. See the [module-level documentation](self)
+/// for details.
+const TTL_FIELD: &str = "ttl_ms";
+
+/// A [`Visit`] that looks for a `ttl_ms` field among an event's fields,
+/// ignoring everything else.
+#[derive(Default)]
+struct TtlVisitor {
+    ttl_ms: Option<u64>,
+}
+
+impl Visit for TtlVisitor {
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        if field.name() == TTL_FIELD {
+            self.ttl_ms = Some(value);
+        }
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        if field.name() == TTL_FIELD {
+            self.ttl_ms = Some(value.max(0) as u64);
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn fmt::Debug) {}
+}
+
+/// A [`Subscribe`] that captures events on the dispatch path and exports
+/// them on a dedicated worker thread.
+///
+/// See the [module-level documentation](self) for details.
+pub struct Offload<S: Sink> {
+    capture: Arc<dyn Fn(&Event<'_>) -> S::Captured + Send + Sync>,
+    tx: mpsc::SyncSender<Msg<S::Captured>>,
+    dropped: Arc<AtomicUsize>,
+    expired: Arc<AtomicUsize>,
+}
+
+/// Flushes an [`Offload`]'s worker thread when dropped.
+///
+/// This should be held for as long as the [`Offload`] it was returned
+/// alongside is in use -- typically in a binding in `main` -- so that events
+/// captured just before the program exits still get exported, the same way
+/// `tracing-appender`'s `WorkerGuard` does for its non-blocking writer.
+#[must_use]
+pub struct OffloadGuard {
+    handle: Option<thread::JoinHandle<()>>,
+    tx: Option<Box<dyn FnOnce() + Send>>,
+}
+
+impl<S: Sink> Offload<S> {
+    /// Returns a new `Offload`, along with a guard that must be held for it
+    /// to flush on shutdown.
+    ///
+    /// `capture` runs inline on the dispatch path and must produce an
+    /// owned, `'static` snapshot of whatever `sink` needs from an event, as
+    /// cheaply as possible. `sink` then runs entirely on a dedicated worker
+    /// thread, processing each snapshot as it arrives.
+    ///
+    /// This is equivalent to
+    /// `Offload::with_capacity(capture, sink, DEFAULT_CAPACITY)`.
+    pub fn new(
+        capture: impl Fn(&Event<'_>) -> S::Captured + Send + Sync + 'static,
+        sink: S,
+    ) -> (Self, OffloadGuard) {
+        Self::with_capacity(capture, sink, DEFAULT_CAPACITY)
+    }
+
+    /// Returns a new `Offload`, buffering up to `capacity` captured events
+    /// between the dispatch path and the worker thread.
+    ///
+    /// See [`Offload::new`] for details on `capture` and `sink`.
+    pub fn with_capacity(
+        capture: impl Fn(&Event<'_>) -> S::Captured + Send + Sync + 'static,
+        mut sink: S,
+        capacity: usize,
+    ) -> (Self, OffloadGuard) {
+        let (tx, rx) = mpsc::sync_channel::<Msg<S::Captured>>(capacity);
+        let expired = Arc::new(AtomicUsize::new(0));
+        let worker_expired = expired.clone();
+        let handle = thread::Builder::new()
+            .name("tracing-subscriber-offload".into())
+            .spawn(move || loop {
+                match rx.recv() {
+                    Ok(Msg::Captured { value, expires_at }) => {
+                        if expires_at.map_or(false, |at| Instant::now() > at) {
+                            worker_expired.fetch_add(1, Ordering::Relaxed);
+                        } else {
+                            sink.export(value);
+                        }
+                    }
+                    Ok(Msg::Shutdown) | Err(_) => return,
+                }
+            })
+            .expect("failed to spawn `tracing-subscriber` offload worker thread");
+
+        let shutdown_tx = tx.clone();
+        (
+            Self {
+                capture: Arc::new(capture),
+                tx,
+                dropped: Arc::new(AtomicUsize::new(0)),
+                expired,
+            },
+            OffloadGuard {
+                handle: Some(handle),
+                tx: Some(Box::new(move || {
+                    let _ = shutdown_tx.send(Msg::Shutdown);
+                })),
+            },
+        )
+    }
+
+    /// Returns the number of events dropped because the worker thread
+    /// couldn't keep up and the internal buffer was full.
+    pub fn dropped_events(&self) -> usize {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of events dropped because their `ttl_ms` field
+    /// expired before the worker thread got to exporting them.
+    ///
+    /// See the [module-level documentation](self) for details on `ttl_ms`.
+    pub fn expired_events(&self) -> usize {
+        self.expired.load(Ordering::Relaxed)
+    }
+}
+
+impl<C, S> Subscribe<C> for Offload<S>
+where
+    C: Collect,
+    S: Sink,
+{
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, C>) {
+        let mut ttl = TtlVisitor::default();
+        event.record(&mut ttl);
+        let expires_at = ttl.ttl_ms.map(|ttl_ms| Instant::now() + Duration::from_millis(ttl_ms));
+
+        let value = (self.capture)(event);
+        if self
+            .tx
+            .try_send(Msg::Captured { value, expires_at })
+            .is_err()
+        {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+impl<S: Sink> fmt::Debug for Offload<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Offload")
+            .field("dropped_events", &self.dropped_events())
+            .field("expired_events", &self.expired_events())
+            .finish()
+    }
+}
+
+impl fmt::Debug for OffloadGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OffloadGuard").finish()
+    }
+}
+
+impl Drop for OffloadGuard {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.tx.take() {
+            shutdown();
+        }
+        if let Some(handle) = self.handle.take() {
+            if handle.join().is_err() {
+                eprintln!("tracing-subscriber offload worker thread panicked");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn exports_captured_events() {
+        let exported = Arc::new(Mutex::new(Vec::new()));
+
+        struct VecSink(Arc<Mutex<Vec<String>>>);
+        impl Sink for VecSink {
+            type Captured = String;
+            fn export(&mut self, captured: String) {
+                self.0.lock().unwrap().push(captured);
+            }
+        }
+
+        let (offload, guard) = Offload::new(
+            |event: &Event<'_>| event.metadata().name().to_string(),
+            VecSink(exported.clone()),
+        );
+
+        tracing::collect::with_default(crate::registry().with(offload), || {
+            tracing::info!("first");
+            tracing::info!("second");
+        });
+
+        // Dropping the guard blocks until the worker thread has drained and
+        // exported every event sent before the shutdown signal.
+        drop(guard);
+
+        let exported = exported.lock().unwrap();
+        assert_eq!(exported.len(), 2);
+    }
+
+    #[test]
+    fn drops_events_past_capacity() {
+        struct BlockingSink(mpsc::Receiver<()>);
+        impl Sink for BlockingSink {
+            type Captured = ();
+            fn export(&mut self, (): ()) {
+                // Block the worker thread until the test releases it, so
+                // that events pile up in the channel.
+                let _ = self.0.recv();
+            }
+        }
+
+        let (release_tx, release_rx) = mpsc::channel();
+        let (offload, guard) =
+            Offload::with_capacity(|_event: &Event<'_>| (), BlockingSink(release_rx), 1);
+        let dropped = offload.dropped.clone();
+
+        tracing::collect::with_default(crate::registry().with(offload), || {
+            for _ in 0..8 {
+                tracing::info!("event");
+            }
+        });
+
+        assert!(dropped.load(Ordering::Relaxed) > 0);
+
+        // Unblock the worker thread so the guard's drop doesn't hang.
+        for _ in 0..8 {
+            let _ = release_tx.send(());
+        }
+        drop(guard);
+    }
+
+    #[test]
+    fn drops_expired_captures_instead_of_exporting_them() {
+        let exported = Arc::new(Mutex::new(Vec::new()));
+
+        struct VecSink(Arc<Mutex<Vec<String>>>);
+        impl Sink for VecSink {
+            type Captured = String;
+            fn export(&mut self, captured: String) {
+                self.0.lock().unwrap().push(captured);
+            }
+        }
+
+        // Blocks the worker thread in its very first `export` call until
+        // released, so later captures pile up in the channel behind it long
+        // enough for their TTLs to lapse before the worker gets to them.
+        struct GatedSink {
+            started: mpsc::SyncSender<()>,
+            release: Option<mpsc::Receiver<()>>,
+            inner: VecSink,
+        }
+        impl Sink for GatedSink {
+            type Captured = String;
+            fn export(&mut self, captured: String) {
+                if let Some(release) = self.release.take() {
+                    let _ = self.started.send(());
+                    let _ = release.recv();
+                }
+                self.inner.export(captured);
+            }
+        }
+
+        let (started_tx, started_rx) = mpsc::sync_channel::<()>(1);
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        let (offload, guard) = Offload::new(
+            |event: &Event<'_>| {
+                struct MessageVisitor(String);
+                impl Visit for MessageVisitor {
+                    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+                        if field.name() == "message" {
+                            self.0 = format!("{:?}", value);
+                        }
+                    }
+                }
+                let mut visitor = MessageVisitor(String::new());
+                event.record(&mut visitor);
+                visitor.0
+            },
+            GatedSink {
+                started: started_tx,
+                release: Some(release_rx),
+                inner: VecSink(exported.clone()),
+            },
+        );
+
+        let expired = offload.expired.clone();
+
+        tracing::collect::with_default(crate::registry().with(offload), || {
+            tracing::info!("filler");
+            // Wait for the worker thread to be blocked inside `filler`'s
+            // export, so `stale` below is guaranteed to sit unprocessed in
+            // the channel while its TTL lapses.
+            started_rx.recv().unwrap();
+
+            tracing::info!(ttl_ms = 1u64, "stale");
+            thread::sleep(Duration::from_millis(50));
+            // This one has no `ttl_ms` field, so it's exported no matter
+            // how long it waited.
+            tracing::info!("fresh");
+        });
+
+        let _ = release_tx.send(());
+        drop(guard);
+
+        let exported = exported.lock().unwrap();
+        assert_eq!(&*exported, &["filler".to_string(), "fresh".to_string()]);
+        assert_eq!(expired.load(Ordering::Relaxed), 1);
+    }
+}
@@ -14,3 +14,8 @@ feature! {
     #![all(feature = "fmt", feature = "std")]
     pub use crate::fmt::writer::MakeWriterExt as _;
 }
+
+feature! {
+    #![all(feature = "registry", feature = "std", feature = "tracing")]
+    pub use crate::lifecycle::SpanExt as _;
+}
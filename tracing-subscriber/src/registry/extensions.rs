@@ -87,7 +87,13 @@ impl<'a> ExtensionsMut<'a> {
     ///
     /// [subscriber]: crate::subscribe::Subscribe
     pub fn insert<T: Send + Sync + 'static>(&mut self, val: T) {
-        assert!(self.replace(val).is_none())
+        assert!(
+            self.replace(val).is_none(),
+            "tried to insert an extension of type `{}`, but one was already present \
+             (if this is expected, use `replace` instead; if two unrelated subscribers \
+             both store this type, wrap it in `Namespaced` to give each a distinct key)",
+            std::any::type_name::<T>()
+        )
     }
 
     /// Replaces an existing `T` into this extensions.
@@ -110,6 +116,78 @@ impl<'a> ExtensionsMut<'a> {
     }
 }
 
+/// A namespaced key for storing a type `T` in a span's [`Extensions`],
+/// scoped to the marker type `N`.
+///
+/// [`ExtensionsMut::insert`] keys extensions by the `TypeId` of the stored
+/// value's own type, so two independently developed [subscriber]s that
+/// happen to store the exact same type -- for example, both defining their
+/// own private `Timings` struct in a shared dependency -- would otherwise
+/// clobber each other's data, since the second `insert` panics (or, if
+/// [`replace`] is used, silently overwrites the first).
+///
+/// Wrapping the stored value as `Namespaced<N, T>`, where `N` is a
+/// subscriber-specific marker type (typically a private zero-sized type
+/// unique to the subscriber's own crate), gives the extension a distinct
+/// `TypeId` per `N`, so two subscribers using distinct markers never
+/// collide, even when they store the same underlying `T`.
+///
+/// # Examples
+///
+/// ```
+/// use tracing_subscriber::registry::{Namespaced, LookupSpan};
+///
+/// // A marker type private to this subscriber, used only to namespace its
+/// // extensions.
+/// struct MyLayerMarker;
+///
+/// struct Timings {
+///     busy: u64,
+/// }
+///
+/// fn record_timings<'a>(span: &tracing_subscriber::registry::SpanRef<'a, impl LookupSpan<'a>>) {
+///     span.extensions_mut()
+///         .insert(Namespaced::<MyLayerMarker, _>::new(Timings { busy: 0 }));
+/// }
+/// ```
+///
+/// [subscriber]: crate::subscribe::Subscribe
+/// [`replace`]: ExtensionsMut::replace
+#[derive(Debug)]
+pub struct Namespaced<N, T> {
+    value: T,
+    _marker: std::marker::PhantomData<fn(N)>,
+}
+
+impl<N, T> Namespaced<N, T> {
+    /// Wraps `value`, namespacing it to `N`.
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Consumes the wrapper, returning the namespaced value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<N, T> std::ops::Deref for Namespaced<N, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<N, T> std::ops::DerefMut for Namespaced<N, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
 /// A type map of span extensions.
 ///
 /// [ExtensionsInner] is used by [Data] to store and
@@ -221,6 +299,26 @@ mod tests {
         assert_eq!(extensions.get(), Some(&MyType(10)));
     }
 
+    #[test]
+    fn namespaced_avoids_collisions_between_layers() {
+        struct LayerA;
+        struct LayerB;
+
+        let mut extensions = ExtensionsInner::new();
+
+        extensions.insert(Namespaced::<LayerA, _>::new(MyType(1)));
+        extensions.insert(Namespaced::<LayerB, _>::new(MyType(2)));
+
+        assert_eq!(
+            extensions.get::<Namespaced<LayerA, MyType>>().map(|ns| &**ns),
+            Some(&MyType(1))
+        );
+        assert_eq!(
+            extensions.get::<Namespaced<LayerB, MyType>>().map(|ns| &**ns),
+            Some(&MyType(2))
+        );
+    }
+
     #[test]
     fn clear_retains_capacity() {
         let mut extensions = ExtensionsInner::new();
@@ -66,7 +66,7 @@ feature! {
     #![feature = "std"]
     /// A module containing a type map of span extensions.
     mod extensions;
-    pub use extensions::{Extensions, ExtensionsMut};
+    pub use extensions::{Extensions, ExtensionsMut, Namespaced};
 
 }
 
@@ -156,6 +156,39 @@ pub trait LookupSpan<'a> {
             std::any::type_name::<Self>()
         )
     }
+
+    /// Returns an immutable reference to the dispatcher-scoped extensions
+    /// for this collector.
+    ///
+    /// Unlike a span's [extensions], which live only as long as that span,
+    /// dispatcher-scoped extensions live as long as the collector itself.
+    /// This allows [`Subscribe`]s to publish and consume shared state (for
+    /// example, a sampling subscriber publishing its decisions for an export
+    /// subscriber to consume) without resorting to global statics.
+    ///
+    /// [extensions]: SpanData::extensions
+    /// [`Subscribe`]: crate::subscribe::Subscribe
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    fn dispatch_extensions(&'a self) -> Extensions<'a> {
+        panic!(
+            "{} does not currently support dispatcher-scoped extensions",
+            std::any::type_name::<Self>()
+        )
+    }
+
+    /// Returns a mutable reference to the dispatcher-scoped extensions for
+    /// this collector.
+    ///
+    /// See [`dispatch_extensions`](Self::dispatch_extensions) for details.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    fn dispatch_extensions_mut(&'a self) -> ExtensionsMut<'a> {
+        panic!(
+            "{} does not currently support dispatcher-scoped extensions",
+            std::any::type_name::<Self>()
+        )
+    }
 }
 
 /// A stored representation of data associated with a span.
@@ -169,6 +202,25 @@ pub trait SpanData<'a> {
     /// Returns a reference to the ID
     fn parent(&self) -> Option<&Id>;
 
+    /// Returns the total number of handles that have ever referred to this
+    /// span, including the one created when the span itself was recorded.
+    ///
+    /// Unlike the span's current reference count, this number never
+    /// decreases: it only grows each time the span is cloned. Once the span
+    /// has closed (i.e. during the `on_close` callback, after which this
+    /// data is no longer accessible), this is the final, total count of
+    /// handles that ever existed for the span, which can be useful to
+    /// leak-detection layers trying to identify code paths that hold spans
+    /// open longer than expected.
+    ///
+    /// ## Default Implementation
+    ///
+    /// By default, this method returns 0. [`LookupSpan`] implementations
+    /// that do not track clone counts may use this default implementation.
+    fn clone_count(&self) -> usize {
+        0
+    }
+
     /// Returns a reference to this span's `Extensions`.
     ///
     /// The extensions may be used by `Subscriber`s to store additional data
@@ -421,6 +473,14 @@ where
         self.data.metadata().name()
     }
 
+    /// Returns the total number of handles that have ever referred to this
+    /// span.
+    ///
+    /// See [`SpanData::clone_count`] for details.
+    pub fn clone_count(&self) -> usize {
+        self.data.clone_count()
+    }
+
     /// Returns a list of [fields] defined by the span.
     ///
     /// [fields]: tracing_core::field
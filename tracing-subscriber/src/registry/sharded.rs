@@ -16,6 +16,7 @@ use std::{
 };
 use tracing_core::{
     dispatch::{self, Dispatch},
+    error_hook::{report_error, InternalError},
     span::{self, Current, Id},
     Collect, Event, Interest, Metadata,
 };
@@ -93,6 +94,12 @@ pub struct Registry {
     spans: Pool<DataInner>,
     current_spans: ThreadLocal<RefCell<SpanStack>>,
     next_filter_id: u8,
+    // A typemap of data that isn't associated with any particular span, but
+    // rather with the registry as a whole. Unlike a span's `extensions`, this
+    // outlives every span, so it's suited to state that one `Subscribe`
+    // publishes for others to consume across the lifetime of the dispatcher,
+    // rather than the lifetime of a span.
+    dispatch_extensions: RwLock<ExtensionsInner>,
 }
 
 /// Span data stored in a [`Registry`].
@@ -125,6 +132,10 @@ struct DataInner {
     metadata: &'static Metadata<'static>,
     parent: Option<Id>,
     ref_count: AtomicUsize,
+    // The total number of handles ever issued for this span, for the
+    // lifetime of this `DataInner` slot (reset to 0 when the slot is
+    // reused for a new span). See `SpanData::clone_count`.
+    clone_count: AtomicUsize,
     // The span's `Extensions` typemap. Allocations for the `HashMap` backing
     // this are pooled and reused in place.
     pub(crate) extensions: RwLock<ExtensionsInner>,
@@ -138,6 +149,7 @@ impl Default for Registry {
             spans: Pool::new(),
             current_spans: ThreadLocal::new(),
             next_filter_id: 0,
+            dispatch_extensions: RwLock::new(ExtensionsInner::new()),
         }
     }
 }
@@ -263,6 +275,10 @@ impl Collect for Registry {
                 let refs = data.ref_count.get_mut();
                 debug_assert_eq!(*refs, 0);
                 *refs = 1;
+
+                let clones = data.clone_count.get_mut();
+                debug_assert_eq!(*clones, 0);
+                *clones = 1;
             })
             .expect("Unable to allocate another span");
         idx_to_id(id)
@@ -320,6 +336,7 @@ impl Collect for Registry {
             "tried to clone a span ({:?}) that already closed",
             id
         );
+        span.clone_count.fetch_add(1, Ordering::Relaxed);
         id.clone()
     }
 
@@ -366,8 +383,11 @@ impl<'a> LookupSpan<'a> for Registry {
     type Data = Data<'a>;
 
     fn span_data(&'a self, id: &Id) -> Option<Self::Data> {
-        let inner = self.get(id)?;
-        Some(Data { inner })
+        let inner = self.get(id);
+        if inner.is_none() {
+            report_error(InternalError::SpanNotFound { id: id.into_u64() });
+        }
+        Some(Data { inner: inner? })
     }
 
     fn register_filter(&mut self) -> FilterId {
@@ -375,6 +395,14 @@ impl<'a> LookupSpan<'a> for Registry {
         self.next_filter_id += 1;
         id
     }
+
+    fn dispatch_extensions(&'a self) -> Extensions<'a> {
+        Extensions::new(self.dispatch_extensions.read().expect("lock poisoned"))
+    }
+
+    fn dispatch_extensions_mut(&'a self) -> ExtensionsMut<'a> {
+        ExtensionsMut::new(self.dispatch_extensions.write().expect("lock poisoned"))
+    }
 }
 
 // === impl CloseGuard ===
@@ -425,6 +453,10 @@ impl<'a> SpanData<'a> for Data<'a> {
         self.inner.parent.as_ref()
     }
 
+    fn clone_count(&self) -> usize {
+        self.inner.clone_count.load(Ordering::Acquire)
+    }
+
     fn extensions(&self) -> Extensions<'_> {
         Extensions::new(self.inner.extensions.read().expect("Mutex poisoned"))
     }
@@ -481,6 +513,7 @@ impl Default for DataInner {
             metadata: &NULL_METADATA,
             parent: None,
             ref_count: AtomicUsize::new(0),
+            clone_count: AtomicUsize::new(0),
             extensions: RwLock::new(ExtensionsInner::new()),
         }
     }
@@ -523,6 +556,7 @@ impl Clear for DataInner {
             .clear();
 
         self.filter_map = FilterMap::new();
+        *self.clone_count.get_mut() = 0;
     }
 }
 
@@ -562,6 +596,43 @@ mod tests {
         });
     }
 
+    struct CloneCountSubscriber {
+        counts: Arc<Mutex<Vec<usize>>>,
+    }
+
+    impl<C> Subscribe<C> for CloneCountSubscriber
+    where
+        C: Collect + for<'a> LookupSpan<'a>,
+    {
+        fn on_close(&self, id: Id, ctx: Context<'_, C>) {
+            let span = ctx.span(&id).expect("span must still exist in on_close");
+            self.counts.lock().unwrap().push(span.clone_count());
+        }
+    }
+
+    #[test]
+    fn clone_count_reflects_total_handles_issued() {
+        let counts = Arc::new(Mutex::new(Vec::new()));
+        let subscriber = CloneCountSubscriber {
+            counts: counts.clone(),
+        }
+        .with_collector(Registry::default());
+
+        with_default(subscriber, || {
+            let span = tracing::debug_span!("span");
+            let clone1 = span.clone();
+            let clone2 = span.clone();
+            drop(clone1);
+            drop(clone2);
+            drop(span);
+        });
+
+        // the span itself, plus the two explicit clones: 3 handles were ever
+        // issued for this span, even though only one was ever live at a time
+        // after the drops above.
+        assert_eq!(&*counts.lock().unwrap(), &[3]);
+    }
+
     #[test]
     fn multiple_subscribers_can_access_closed_span() {
         let subscriber = AssertionSubscriber
@@ -898,4 +969,40 @@ mod tests {
             state.assert_closed_in_order(["child", "parent", "grandparent"]);
         });
     }
+
+    #[test]
+    fn dispatch_extensions_are_shared_across_subscribers() {
+        struct Publisher;
+        impl<C> Subscribe<C> for Publisher
+        where
+            C: Collect + for<'a> LookupSpan<'a>,
+        {
+            fn on_new_span(&self, _: &Attributes<'_>, _: &Id, ctx: Context<'_, C>) {
+                ctx.dispatch_ext_mut().unwrap().insert(42u32);
+            }
+        }
+
+        struct Consumer {
+            seen: Arc<Mutex<Option<u32>>>,
+        }
+        impl<C> Subscribe<C> for Consumer
+        where
+            C: Collect + for<'a> LookupSpan<'a>,
+        {
+            fn on_new_span(&self, _: &Attributes<'_>, _: &Id, ctx: Context<'_, C>) {
+                *self.seen.lock().unwrap() = ctx.dispatch_ext().unwrap().get::<u32>().copied();
+            }
+        }
+
+        let seen = Arc::new(Mutex::new(None));
+        let subscriber = Publisher
+            .and_then(Consumer { seen: seen.clone() })
+            .with_collector(Registry::default());
+
+        with_default(subscriber, || {
+            let _span = tracing::info_span!("span");
+        });
+
+        assert_eq!(*seen.lock().unwrap(), Some(42));
+    }
 }
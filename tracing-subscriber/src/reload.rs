@@ -63,6 +63,8 @@ pub struct Error {
 enum ErrorKind {
     CollectorGone,
     Poisoned,
+    #[cfg(all(feature = "env-filter", feature = "std"))]
+    Directive(crate::filter::ParseError),
 }
 
 // ===== impl Collect =====
@@ -76,6 +78,15 @@ where
         try_lock!(self.inner.read()).on_register_dispatch(collector);
     }
 
+    #[inline]
+    fn on_subscribe(&mut self, collector: &mut C) {
+        // This must be forwarded so that a wrapped `Filtered` subscriber (or
+        // anything else relying on `on_subscribe`, such as per-subscriber
+        // filtering) still gets to register itself with `collector` --
+        // otherwise, filtering silently breaks once it's wrapped here.
+        try_lock!(self.inner.write()).on_subscribe(collector);
+    }
+
     #[inline]
     fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
         try_lock!(self.inner.read(), else return Interest::sometimes()).register_callsite(metadata)
@@ -312,6 +323,125 @@ impl<T> Handle<T> {
     }
 }
 
+feature! {
+    #![all(feature = "env-filter", feature = "std")]
+    use crate::filter::{EnvFilter, ParseError};
+
+    impl Handle<EnvFilter> {
+        /// Parses and adds a single filtering directive to the wrapped
+        /// [`EnvFilter`], without affecting any of the filter's other
+        /// directives.
+        ///
+        /// This is a shorthand for parsing `directive` and passing it to
+        /// [`EnvFilter::add_directive`] inside a call to [`Handle::modify`],
+        /// so that callers (such as an admin/debug endpoint) don't need to
+        /// reconstruct the filter's entire directive string just to tweak a
+        /// single target's verbosity.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// # use tracing_subscriber::{filter::EnvFilter, reload, Registry, prelude::*};
+        /// # fn main() {
+        /// let filter = EnvFilter::new("info");
+        /// let (filter, handle) = reload::Subscriber::new(filter);
+        /// tracing_subscriber::registry().with(filter).init();
+        ///
+        /// handle.add_directive("my_crate=trace").expect("valid directive");
+        /// # }
+        /// ```
+        pub fn add_directive(&self, directive: impl AsRef<str>) -> Result<(), Error> {
+            let directive = directive
+                .as_ref()
+                .parse()
+                .map_err(Error::invalid_directive)?;
+            self.modify(|filter| {
+                *filter = std::mem::take(filter).add_directive(directive);
+            })
+        }
+
+        /// Removes every directive in the wrapped [`EnvFilter`] that targets
+        /// `target`, leaving the filter's other directives untouched.
+        ///
+        /// `target` is matched against each directive's target exactly, the
+        /// same way a target is written in a filter directive (for example,
+        /// `"hyper"` or `"my_crate::module"`). Directives with no target
+        /// (such as a bare default level) are never removed.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// # use tracing_subscriber::{filter::EnvFilter, reload, Registry, prelude::*};
+        /// # fn main() {
+        /// let filter = EnvFilter::new("info,hyper=debug");
+        /// let (filter, handle) = reload::Subscriber::new(filter);
+        /// tracing_subscriber::registry().with(filter).init();
+        ///
+        /// handle.remove_target("hyper").expect("subscriber exists");
+        /// # }
+        /// ```
+        pub fn remove_target(&self, target: impl AsRef<str>) -> Result<(), Error> {
+            let target = target.as_ref();
+            self.modify(|filter| {
+                let retained = split_directives(&filter.to_string())
+                    .filter(|directive| directive_target(directive) != Some(target))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                *filter = EnvFilter::new(retained);
+            })
+        }
+    }
+
+    impl Error {
+        fn invalid_directive(e: ParseError) -> Self {
+            Self {
+                kind: ErrorKind::Directive(e),
+            }
+        }
+    }
+
+    /// Splits a comma-separated list of filter directives into its individual
+    /// directives, without splitting on commas nested inside a directive's
+    /// `[span{field=value}]` portion.
+    fn split_directives(directives: &str) -> impl Iterator<Item = &str> {
+        let mut depth = 0usize;
+        let mut start = 0;
+        let mut parts = Vec::new();
+        for (i, c) in directives.char_indices() {
+            match c {
+                '[' | '{' => depth += 1,
+                ']' | '}' => depth = depth.saturating_sub(1),
+                ',' if depth == 0 => {
+                    parts.push(&directives[start..i]);
+                    start = i + 1;
+                }
+                _ => {}
+            }
+        }
+        parts.push(&directives[start..]);
+        parts.into_iter().filter(|s| !s.is_empty())
+    }
+
+    /// Returns the target portion of a single filter directive, or `None` if
+    /// the directive has no target (e.g. a bare default level).
+    fn directive_target(directive: &str) -> Option<&str> {
+        let mut depth = 0usize;
+        for (i, c) in directive.char_indices() {
+            match c {
+                '[' | '{' => depth += 1,
+                ']' | '}' => depth = depth.saturating_sub(1),
+                '=' if depth == 0 => {
+                    let head = &directive[..i];
+                    let target = &head[..head.find('[').unwrap_or(head.len())];
+                    return if target.is_empty() { None } else { Some(target) };
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+}
+
 impl<T> Clone for Handle<T> {
     fn clone(&self) -> Self {
         Handle {
@@ -344,12 +474,94 @@ impl Error {
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let msg = match self.kind {
-            ErrorKind::CollectorGone => "subscriber no longer exists",
-            ErrorKind::Poisoned => "lock poisoned",
-        };
-        f.pad(msg)
+        match self.kind {
+            ErrorKind::CollectorGone => f.pad("subscriber no longer exists"),
+            ErrorKind::Poisoned => f.pad("lock poisoned"),
+            #[cfg(all(feature = "env-filter", feature = "std"))]
+            ErrorKind::Directive(ref e) => write!(f, "invalid filter directive: {}", e),
+        }
     }
 }
 
-impl error::Error for Error {}
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self.kind {
+            #[cfg(all(feature = "env-filter", feature = "std"))]
+            ErrorKind::Directive(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "env-filter", feature = "std"))]
+mod test {
+    use super::*;
+    use crate::filter::EnvFilter;
+
+    #[test]
+    fn add_directive() {
+        let (_filter, handle) = Subscriber::new(EnvFilter::new("info"));
+
+        handle.add_directive("my_crate=trace").unwrap();
+
+        handle
+            .with_current(|filter| assert_eq!(filter.to_string(), "my_crate=trace,info"))
+            .unwrap();
+    }
+
+    #[test]
+    fn add_directive_rejects_invalid_syntax() {
+        let (_filter, handle) = Subscriber::new(EnvFilter::new("info"));
+
+        let err = handle.add_directive("#nonsense").unwrap_err();
+        assert!(err.to_string().contains("invalid filter directive"));
+    }
+
+    #[test]
+    fn remove_target() {
+        let (_filter, handle) = Subscriber::new(EnvFilter::new("info,hyper=debug,my_crate=trace"));
+
+        handle.remove_target("hyper").unwrap();
+
+        handle
+            .with_current(|filter| {
+                let rendered = filter.to_string();
+                assert!(!rendered.contains("hyper"));
+                assert!(rendered.contains("my_crate=trace"));
+                assert!(rendered.contains("info"));
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn remove_target_leaves_bare_level_alone() {
+        let (_filter, handle) = Subscriber::new(EnvFilter::new("info"));
+
+        handle.remove_target("info").unwrap();
+
+        handle
+            .with_current(|filter| assert_eq!(filter.to_string(), "info"))
+            .unwrap();
+    }
+
+    #[test]
+    fn split_directives_ignores_nested_commas() {
+        let directives: Vec<_> =
+            split_directives("info,my_crate[span{a=1,b=2}]=trace,hyper=debug").collect();
+        assert_eq!(
+            directives,
+            vec!["info", "my_crate[span{a=1,b=2}]=trace", "hyper=debug"]
+        );
+    }
+
+    #[test]
+    fn directive_target_parses_each_form() {
+        assert_eq!(directive_target("info"), None);
+        assert_eq!(directive_target("hyper=debug"), Some("hyper"));
+        assert_eq!(
+            directive_target("my_crate[span{a=1}]=trace"),
+            Some("my_crate")
+        );
+        assert_eq!(directive_target("[span{a=1}]=trace"), None);
+    }
+}
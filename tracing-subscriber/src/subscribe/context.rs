@@ -193,6 +193,47 @@ where
         Some(span.metadata())
     }
 
+    /// Returns the dispatcher-scoped extensions for the wrapped collector,
+    /// allowing [`Subscribe`]s to share state that isn't tied to any
+    /// particular span.
+    ///
+    /// For example, a sampling subscriber might [`insert`] its decisions
+    /// here for a downstream export subscriber to [`get`] later, without
+    /// either subscriber needing a global static.
+    ///
+    /// <div class="example-wrap" style="display:inline-block">
+    /// <pre class="ignore" style="white-space:normal;font:inherit;">
+    /// <strong>Note</strong>: This requires the wrapped collector to implement the
+    /// <a href="../registry/trait.LookupSpan.html"><code>LookupSpan</code></a> trait.
+    /// See the documentation on <a href="./struct.Context.html"><code>Context</code>'s
+    /// declaration</a> for details.
+    /// </pre></div>
+    ///
+    /// [`Subscribe`]: crate::subscribe::Subscribe
+    /// [`insert`]: registry::ExtensionsMut::insert
+    /// [`get`]: registry::Extensions::get
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn dispatch_ext(&self) -> Option<registry::Extensions<'_>>
+    where
+        C: for<'lookup> LookupSpan<'lookup>,
+    {
+        Some(self.subscriber?.dispatch_extensions())
+    }
+
+    /// Returns the mutable dispatcher-scoped extensions for the wrapped
+    /// collector.
+    ///
+    /// See [`dispatch_ext`](Self::dispatch_ext) for details.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn dispatch_ext_mut(&self) -> Option<registry::ExtensionsMut<'_>>
+    where
+        C: for<'lookup> LookupSpan<'lookup>,
+    {
+        Some(self.subscriber?.dispatch_extensions_mut())
+    }
+
     /// Returns [stored data] for the span with the given `id`, if it exists.
     ///
     /// If this returns `None`, then no span exists for that ID (either it has
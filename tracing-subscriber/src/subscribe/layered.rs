@@ -398,6 +398,16 @@ where
     fn register_filter(&mut self) -> FilterId {
         self.inner.register_filter()
     }
+
+    #[cfg(feature = "std")]
+    fn dispatch_extensions(&'a self) -> crate::registry::Extensions<'a> {
+        self.inner.dispatch_extensions()
+    }
+
+    #[cfg(feature = "std")]
+    fn dispatch_extensions_mut(&'a self) -> crate::registry::ExtensionsMut<'a> {
+        self.inner.dispatch_extensions_mut()
+    }
 }
 
 impl<S, C> Layered<S, C>
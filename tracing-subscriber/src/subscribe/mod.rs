@@ -417,6 +417,17 @@
 //! [`Interest::never()`] from its [`register_callsite`] method, filter
 //! evaluation will short-circuit and the span or event will be disabled.
 //!
+//! Because evaluation is short-circuiting and follows composition order,
+//! that order determines how much filtering work is actually done: a
+//! `Subscribe` composed with [`with`] before another one is asked
+//! [`enabled`] first, and if it returns `false`, no later subscriber (and
+//! not the wrapped [`Collect`]) is consulted at all. For the best
+//! throughput, compose your cheapest filters --- for example, a level
+//! filter that only inspects [`Metadata`] --- before more expensive ones,
+//! rather than relying on `Subscribe` composition order to be arbitrary.
+//!
+//! [`with`]: CollectExt::with
+//!
 //! ### Enabling Interest
 //!
 //! Whenever an tracing event (or span) is emitted, it goes through a number of
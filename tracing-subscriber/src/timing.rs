@@ -0,0 +1,187 @@
+//! A [`Subscribe`] that records how long each span spends busy (entered) and
+//! idle (created but not currently entered), for retrieval through
+//! [`SpanRef`].
+//!
+//! The `fmt` subscriber already tracks this internally to print its
+//! `time.busy`/`time.idle` fields, but that bookkeeping is private to
+//! `fmt`. [`SpanTiming`] lifts the same idea out into a small, opt-in layer
+//! so other subscribers --- `tracing-journald`, `tracing-flame`, or your
+//! own --- can read the durations back off [`SpanRef::busy`] and
+//! [`SpanRef::idle`] instead of reimplementing the timer bookkeeping
+//! themselves.
+//!
+//! # Examples
+//!
+//! ```
+//! use tracing_subscriber::prelude::*;
+//! use tracing_subscriber::registry::LookupSpan;
+//! use tracing_subscriber::timing::SpanTiming;
+//!
+//! tracing_subscriber::registry()
+//!     .with(SpanTiming::new())
+//!     .init();
+//!
+//! let span = tracing::info_span!("request");
+//! let _guard = span.enter();
+//! // ... do some work ...
+//! drop(_guard);
+//!
+//! tracing::dispatch::get_default(|dispatch| {
+//!     let id = span.id().expect("span was not disabled");
+//!     let collector = dispatch
+//!         .downcast_ref::<tracing_subscriber::Registry>()
+//!         .expect("the collector is a `Registry`");
+//!     let span = collector.span(&id).expect("span must exist");
+//!     assert!(span.busy().is_some());
+//! });
+//! ```
+use crate::{
+    registry::{LookupSpan, SpanRef},
+    subscribe::{Context, Subscribe},
+};
+use std::time::{Duration, Instant};
+use tracing_core::{span, Collect};
+
+/// A [`Subscribe`] that records each span's busy/idle timing in its
+/// [extensions], so it can be read back with [`SpanRef::busy`] and
+/// [`SpanRef::idle`].
+///
+/// Adding a bare `SpanTiming` to a collector is enough --- unlike
+/// [`CaptureSubscriber`](crate::capture::CaptureSubscriber), no handle is
+/// needed, since the recorded durations are read directly off [`SpanRef`].
+///
+/// [extensions]: crate::registry::SpanRef::extensions
+#[derive(Debug, Default)]
+pub struct SpanTiming {
+    _private: (),
+}
+
+impl SpanTiming {
+    /// Returns a new `SpanTiming` subscriber.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// The busy/idle bookkeeping recorded for one span, stored in that span's
+/// extensions.
+struct Timing {
+    idle: Duration,
+    busy: Duration,
+    last: Instant,
+}
+
+impl Timing {
+    fn new() -> Self {
+        Self {
+            idle: Duration::ZERO,
+            busy: Duration::ZERO,
+            last: Instant::now(),
+        }
+    }
+}
+
+impl<C> Subscribe<C> for SpanTiming
+where
+    C: Collect + for<'span> LookupSpan<'span>,
+{
+    fn on_new_span(&self, _attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, C>) {
+        let span = ctx.span(id).expect("span must exist, this is a bug");
+        let mut extensions = span.extensions_mut();
+        if extensions.get_mut::<Timing>().is_none() {
+            extensions.insert(Timing::new());
+        }
+    }
+
+    fn on_enter(&self, id: &span::Id, ctx: Context<'_, C>) {
+        let span = ctx.span(id).expect("span must exist, this is a bug");
+        let mut extensions = span.extensions_mut();
+        if let Some(timing) = extensions.get_mut::<Timing>() {
+            let now = Instant::now();
+            timing.idle += now.saturating_duration_since(timing.last);
+            timing.last = now;
+        }
+    }
+
+    fn on_exit(&self, id: &span::Id, ctx: Context<'_, C>) {
+        let span = ctx.span(id).expect("span must exist, this is a bug");
+        let mut extensions = span.extensions_mut();
+        if let Some(timing) = extensions.get_mut::<Timing>() {
+            let now = Instant::now();
+            timing.busy += now.saturating_duration_since(timing.last);
+            timing.last = now;
+        }
+    }
+}
+
+impl<'a, R> SpanRef<'a, R>
+where
+    R: LookupSpan<'a>,
+{
+    /// Returns the total time this span has spent entered, measured so far,
+    /// if a [`SpanTiming`] subscriber is installed and recording it.
+    ///
+    /// Returns `None` if no `SpanTiming` subscriber is recording this span.
+    pub fn busy(&self) -> Option<Duration> {
+        self.extensions().get::<Timing>().map(|timing| timing.busy)
+    }
+
+    /// Returns the total time this span has existed but not been entered,
+    /// measured so far, if a [`SpanTiming`] subscriber is installed and
+    /// recording it.
+    ///
+    /// Returns `None` if no `SpanTiming` subscriber is recording this span.
+    pub fn idle(&self) -> Option<Duration> {
+        self.extensions().get::<Timing>().map(|timing| timing.idle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn records_busy_and_idle_time() {
+        tracing::collect::with_default(crate::registry().with(SpanTiming::new()), || {
+            let span = tracing::info_span!("request");
+            let id = span.id().expect("span was not disabled");
+
+            tracing::dispatch::get_default(|dispatch| {
+                let collector = dispatch
+                    .downcast_ref::<crate::Registry>()
+                    .expect("collector should be a `Registry`");
+                let span_ref = collector.span(&id).expect("span must exist");
+                assert_eq!(span_ref.busy(), Some(Duration::ZERO));
+                assert_eq!(span_ref.idle(), Some(Duration::ZERO));
+            });
+
+            span.in_scope(|| std::thread::sleep(Duration::from_millis(1)));
+
+            tracing::dispatch::get_default(|dispatch| {
+                let collector = dispatch
+                    .downcast_ref::<crate::Registry>()
+                    .expect("collector should be a `Registry`");
+                let span_ref = collector.span(&id).expect("span must exist");
+                assert!(span_ref.busy().unwrap() > Duration::ZERO);
+            });
+        });
+    }
+
+    #[test]
+    fn absent_without_a_span_timing_subscriber() {
+        tracing::collect::with_default(crate::registry(), || {
+            let span = tracing::info_span!("request");
+            let id = span.id().expect("span was not disabled");
+
+            tracing::dispatch::get_default(|dispatch| {
+                let collector = dispatch
+                    .downcast_ref::<crate::Registry>()
+                    .expect("collector should be a `Registry`");
+                let span_ref = collector.span(&id).expect("span must exist");
+                assert_eq!(span_ref.busy(), None);
+                assert_eq!(span_ref.idle(), None);
+            });
+        });
+    }
+}
@@ -112,6 +112,41 @@ fn reload_handle() {
     })
 }
 
+/// Reloadable subscribers must forward `on_subscribe` to whatever they wrap,
+/// or a [`Filtered`] subscriber placed inside one will never be assigned a
+/// real `FilterId` and will fall back to acting as if every span and event
+/// passed its filter --- see https://github.com/tokio-rs/tracing/issues/1629.
+///
+/// [`Filtered`]: tracing_subscriber::filter::Filtered
+#[test]
+fn reload_of_filtered_subscriber_respects_filter() {
+    static EVENTS: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Clone, Default)]
+    struct CountEvents;
+
+    impl<S: Collect> tracing_subscriber::Subscribe<S> for CountEvents {
+        fn on_event(&self, _event: &Event<'_>, _cx: subscribe::Context<'_, S>) {
+            EVENTS.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let filtered = CountEvents.with_filter(tracing_subscriber::filter::filter_fn(|_| false));
+    let (filtered, _handle) = Subscriber::new(filtered);
+
+    let dispatcher =
+        tracing_core::dispatch::Dispatch::new(tracing_subscriber::registry().with(filtered));
+
+    tracing_core::dispatch::with_default(&dispatcher, || {
+        event();
+        assert_eq!(
+            EVENTS.load(Ordering::SeqCst),
+            0,
+            "the wrapped filter denied this event, so it should never reach the subscriber"
+        );
+    })
+}
+
 fn reload_filter() {
     static FILTER1_CALLS: AtomicUsize = AtomicUsize::new(0);
     static FILTER2_CALLS: AtomicUsize = AtomicUsize::new(0);
@@ -62,3 +62,45 @@ where
         }
     }
 }
+
+/// Wraps `future` so that it is always polled with the [`tracing::Dispatch`]
+/// that was the default on the calling thread when this function was called,
+/// no matter which thread the future is later polled on.
+///
+/// This lets a test's dispatcher follow a future onto a spawned task without
+/// relying on `tracing`'s *global* default dispatcher: since the dispatcher
+/// is carried by the future itself, it works the same way whether the task
+/// ends up running on a `#[tokio::test(flavor = "multi_thread")]` worker
+/// thread, an `async-std` task, or any other executor, and it can't leak
+/// into unrelated tests sharing the same worker.
+///
+/// [`tracing::Dispatch`]: tracing::Dispatch
+pub fn with_current_dispatch<F>(future: F) -> WithDispatch<F>
+where
+    F: std::future::Future + Unpin,
+{
+    WithDispatch {
+        dispatch: tracing::dispatch::get_default(|dispatch| dispatch.clone()),
+        future,
+    }
+}
+
+#[allow(missing_docs)]
+pub struct WithDispatch<F> {
+    dispatch: tracing::Dispatch,
+    future: F,
+}
+
+impl<F> std::future::Future for WithDispatch<F>
+where
+    F: std::future::Future + Unpin,
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let dispatch = this.dispatch.clone();
+        let future = Pin::new(&mut this.future);
+        tracing::dispatch::with_default(&dispatch, || future.poll(cx))
+    }
+}
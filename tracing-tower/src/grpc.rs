@@ -0,0 +1,93 @@
+//! Presets for instrumenting gRPC (tonic-compatible) request/response
+//! services.
+//!
+//! gRPC requests and responses are ordinary [`http::Request`]s and
+//! [`http::Response`]s under the hood (a gRPC method call is a `POST` to a
+//! path of the form `/package.Service/Method`, and the RPC's outcome is
+//! reported via the `grpc-status` trailer). The functions in this module
+//! build on that to create spans and record fields following the
+//! semantic conventions used by OpenTelemetry's RPC instrumentation
+//! (`rpc.system`, `rpc.service`, `rpc.method`), so that this crate's
+//! `http`-based middleware (see [`request_span`](crate::request_span) and
+//! [`service_span`](crate::service_span)) can be used to instrument
+//! tonic-compatible services without depending on `tonic` directly.
+use tracing::field::Empty;
+
+/// The name recorded for the `rpc.system` field on spans created by this
+/// module.
+pub const RPC_SYSTEM: &str = "grpc";
+
+/// Creates a span for an incoming or outgoing gRPC request, recording
+/// `rpc.system`, `rpc.service`, and `rpc.method` from the request's URI
+/// path (of the form `/package.Service/Method`).
+///
+/// If the request carries a `traceparent` header (as defined by the [W3C
+/// Trace Context] specification), its value is recorded on the span as
+/// `trace_parent`, so that layers responsible for context propagation (such
+/// as `tracing-opentelemetry`) can pick the parent context up from there.
+///
+/// The returned span has an empty `grpc.status_code` field, to be filled in
+/// once a response is available via [`record_grpc_status`].
+///
+/// [W3C Trace Context]: https://www.w3.org/TR/trace-context/
+#[inline]
+pub fn grpc_request<A>(req: &http::Request<A>) -> tracing::Span {
+    let (service, method) = service_and_method(req.uri().path());
+    let trace_parent = req
+        .headers()
+        .get("traceparent")
+        .and_then(|value| value.to_str().ok());
+
+    tracing::span!(
+        tracing::Level::INFO,
+        "grpc_request",
+        "rpc.system" = RPC_SYSTEM,
+        "rpc.service" = service,
+        "rpc.method" = method,
+        trace_parent = trace_parent.unwrap_or_default(),
+        "grpc.status_code" = Empty,
+    )
+}
+
+/// Records the outcome of a gRPC call on `span`, reading the `grpc-status`
+/// trailer or header from `res`.
+///
+/// This should be called once the response (and, for streaming responses,
+/// its trailers) is available. If no `grpc-status` is present, the span is
+/// left unchanged.
+pub fn record_grpc_status<A>(span: &tracing::Span, res: &http::Response<A>) {
+    if let Some(status) = res
+        .headers()
+        .get("grpc-status")
+        .and_then(|value| value.to_str().ok())
+    {
+        span.record("grpc.status_code", status);
+    }
+}
+
+/// Splits a gRPC request path of the form `/package.Service/Method` into
+/// its service and method components.
+///
+/// If `path` doesn't match that shape, both are returned as empty strings.
+fn service_and_method(path: &str) -> (&str, &str) {
+    let path = path.strip_prefix('/').unwrap_or(path);
+    match path.split_once('/') {
+        Some((service, method)) => (service, method),
+        None => ("", ""),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_service_and_method() {
+        assert_eq!(
+            service_and_method("/package.MyService/DoThing"),
+            ("package.MyService", "DoThing")
+        );
+        assert_eq!(service_and_method("/"), ("", ""));
+        assert_eq!(service_and_method("not-a-grpc-path"), ("", ""));
+    }
+}
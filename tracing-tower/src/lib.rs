@@ -38,6 +38,10 @@ pub mod service_span;
 #[cfg_attr(docsrs, doc(cfg(feature = "http")))]
 pub mod http;
 
+#[cfg(feature = "http")]
+#[cfg_attr(docsrs, doc(cfg(feature = "http")))]
+pub mod grpc;
+
 pub type InstrumentedService<S, R> = service_span::Service<request_span::Service<S, R>>;
 
 pub trait InstrumentableService<Request>
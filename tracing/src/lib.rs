@@ -884,6 +884,10 @@
 //!   applications which intend to collect traces and logs separately; if an
 //!   adapter is used to convert `log` records into `tracing` events, this will
 //!   cause duplicate events to occur.
+//! * `log-kv`: When emitting `log` records via the `log`/`log-always` bridge,
+//!   attach the current span's name and ID as `log` key-values (using `log`'s
+//!   `kv` feature), so that `log`-only consumers can still recover some span
+//!   context from records that would otherwise carry none.
 //! * `attributes`: Includes support for the `#[instrument]` attribute.
 //!   This is on by default, but does bring in the `syn` crate as a dependency,
 //!   which may add to the compile time of crates that do not already use it.
@@ -979,6 +983,9 @@ pub use tracing_core::{
     metadata,
 };
 pub use tracing_core::{event, Level, Metadata};
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use tracing_core::error_hook::{set_error_hook, InternalError, SetErrorHookError};
 
 #[doc(inline)]
 pub use self::span::Span;
@@ -986,9 +993,15 @@ pub use self::span::Span;
 #[cfg_attr(docsrs, doc(cfg(feature = "attributes")))]
 #[doc(inline)]
 pub use tracing_attributes::instrument;
+#[cfg(feature = "attributes")]
+#[cfg_attr(docsrs, doc(cfg(feature = "attributes")))]
+#[doc(inline)]
+pub use tracing_attributes::instrument_drop;
 
 #[macro_use]
 mod macros;
+#[cfg(feature = "std")]
+mod sampling;
 
 pub mod collect;
 pub mod dispatch;
@@ -1001,6 +1014,8 @@ pub mod span;
 #[doc(hidden)]
 pub mod __macro_support {
     pub use crate::callsite::{Callsite, Registration};
+    #[cfg(feature = "std")]
+    pub use crate::sampling::{sample, SampleGuard};
     use crate::{collect::Interest, Metadata};
     use core::fmt;
     use core::sync::atomic::{AtomicU8, Ordering};
@@ -1143,7 +1158,32 @@ pub mod __macro_support {
             crate::Span::none()
         }
 
-        #[cfg(feature = "log")]
+        #[cfg(all(feature = "log", not(feature = "log-kv")))]
+        pub fn log(
+            &self,
+            logger: &'static dyn log::Log,
+            log_meta: log::Metadata<'_>,
+            values: &tracing_core::field::ValueSet<'_>,
+        ) {
+            let meta = self.metadata();
+            logger.log(
+                &crate::log::Record::builder()
+                    .file(meta.file())
+                    .module_path(meta.module_path())
+                    .line(meta.line())
+                    .metadata(log_meta)
+                    .args(format_args!(
+                        "{}",
+                        crate::log::LogValueSet {
+                            values,
+                            is_first: true
+                        }
+                    ))
+                    .build(),
+            );
+        }
+
+        #[cfg(feature = "log-kv")]
         pub fn log(
             &self,
             logger: &'static dyn log::Log,
@@ -1151,12 +1191,14 @@ pub mod __macro_support {
             values: &tracing_core::field::ValueSet<'_>,
         ) {
             let meta = self.metadata();
+            let span = crate::log::SpanKeyValues::current();
             logger.log(
                 &crate::log::Record::builder()
                     .file(meta.file())
                     .module_path(meta.module_path())
                     .line(meta.line())
                     .metadata(log_meta)
+                    .key_values(&span)
                     .args(format_args!(
                         "{}",
                         crate::log::LogValueSet {
@@ -1254,6 +1296,42 @@ pub mod log {
             visit.result
         }
     }
+
+    /// The name and ID of the current span, forwarded as `log` key-values so
+    /// that `log`-only consumers can still recover span context from records
+    /// emitted by the `log` bridge.
+    #[cfg(feature = "log-kv")]
+    pub(crate) struct SpanKeyValues {
+        name: Option<&'static str>,
+        id: Option<u64>,
+    }
+
+    #[cfg(feature = "log-kv")]
+    impl SpanKeyValues {
+        pub(crate) fn current() -> Self {
+            let span = crate::Span::current();
+            Self {
+                name: span.metadata().map(|meta| meta.name()),
+                id: span.id().map(|id| id.into_u64()),
+            }
+        }
+    }
+
+    #[cfg(feature = "log-kv")]
+    impl log::kv::Source for SpanKeyValues {
+        fn visit<'kvs>(
+            &'kvs self,
+            visitor: &mut dyn log::kv::VisitSource<'kvs>,
+        ) -> Result<(), log::kv::Error> {
+            if let Some(name) = self.name {
+                visitor.visit_pair(log::kv::Key::from("span.name"), log::kv::Value::from(name))?;
+            }
+            if let Some(id) = self.id {
+                visitor.visit_pair(log::kv::Key::from("span.id"), log::kv::Value::from(id))?;
+            }
+            Ok(())
+        }
+    }
 }
 
 mod sealed {
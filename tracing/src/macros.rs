@@ -131,6 +131,122 @@ macro_rules! span {
     };
 }
 
+/// Constructs a span with a [`Level`] that is only known at runtime.
+///
+/// The [`Level`] argument to [`span!`] must be a constant expression, for the same reason as
+/// [`dyn_event!`]'s: it's baked into the static callsite the macro generates. `dyn_span!` accepts
+/// a runtime `Level` by matching over it and forwarding to `span!` with the matched constant,
+/// registering a callsite for every level.
+///
+/// See [the top-level documentation][lib] for details on the syntax accepted by
+/// this macro.
+///
+/// [lib]: crate#using-the-macros
+/// [`Level`]: super::Level
+///
+/// # Examples
+///
+/// ```rust
+/// # use tracing::{dyn_span, Level};
+/// # fn main() {
+/// let level = Level::INFO;
+/// let span = dyn_span!(level, "my span");
+/// let _enter = span.enter();
+/// # }
+/// ```
+#[macro_export]
+macro_rules! dyn_span {
+    (target: $target:expr, parent: $parent:expr, $lvl:expr, $name:expr) => {
+        $crate::dyn_span!(target: $target, parent: $parent, $lvl, $name,)
+    };
+    (target: $target:expr, parent: $parent:expr, $lvl:expr, $name:expr, $($fields:tt)*) => {
+        match $lvl {
+            $crate::Level::TRACE => $crate::span!(target: $target, parent: $parent, $crate::Level::TRACE, $name, $($fields)*),
+            $crate::Level::DEBUG => $crate::span!(target: $target, parent: $parent, $crate::Level::DEBUG, $name, $($fields)*),
+            $crate::Level::INFO => $crate::span!(target: $target, parent: $parent, $crate::Level::INFO, $name, $($fields)*),
+            $crate::Level::WARN => $crate::span!(target: $target, parent: $parent, $crate::Level::WARN, $name, $($fields)*),
+            $crate::Level::ERROR => $crate::span!(target: $target, parent: $parent, $crate::Level::ERROR, $name, $($fields)*),
+        }
+    };
+    (target: $target:expr, $lvl:expr, $name:expr) => {
+        $crate::dyn_span!(target: $target, $lvl, $name,)
+    };
+    (target: $target:expr, $lvl:expr, $name:expr, $($fields:tt)*) => {
+        match $lvl {
+            $crate::Level::TRACE => $crate::span!(target: $target, $crate::Level::TRACE, $name, $($fields)*),
+            $crate::Level::DEBUG => $crate::span!(target: $target, $crate::Level::DEBUG, $name, $($fields)*),
+            $crate::Level::INFO => $crate::span!(target: $target, $crate::Level::INFO, $name, $($fields)*),
+            $crate::Level::WARN => $crate::span!(target: $target, $crate::Level::WARN, $name, $($fields)*),
+            $crate::Level::ERROR => $crate::span!(target: $target, $crate::Level::ERROR, $name, $($fields)*),
+        }
+    };
+    (parent: $parent:expr, $lvl:expr, $name:expr) => {
+        $crate::dyn_span!(parent: $parent, $lvl, $name,)
+    };
+    (parent: $parent:expr, $lvl:expr, $name:expr, $($fields:tt)*) => {
+        match $lvl {
+            $crate::Level::TRACE => $crate::span!(parent: $parent, $crate::Level::TRACE, $name, $($fields)*),
+            $crate::Level::DEBUG => $crate::span!(parent: $parent, $crate::Level::DEBUG, $name, $($fields)*),
+            $crate::Level::INFO => $crate::span!(parent: $parent, $crate::Level::INFO, $name, $($fields)*),
+            $crate::Level::WARN => $crate::span!(parent: $parent, $crate::Level::WARN, $name, $($fields)*),
+            $crate::Level::ERROR => $crate::span!(parent: $parent, $crate::Level::ERROR, $name, $($fields)*),
+        }
+    };
+    ($lvl:expr, $name:expr, $($fields:tt)*) => {
+        match $lvl {
+            $crate::Level::TRACE => $crate::span!($crate::Level::TRACE, $name, $($fields)*),
+            $crate::Level::DEBUG => $crate::span!($crate::Level::DEBUG, $name, $($fields)*),
+            $crate::Level::INFO => $crate::span!($crate::Level::INFO, $name, $($fields)*),
+            $crate::Level::WARN => $crate::span!($crate::Level::WARN, $name, $($fields)*),
+            $crate::Level::ERROR => $crate::span!($crate::Level::ERROR, $name, $($fields)*),
+        }
+    };
+    ($lvl:expr, $name:expr) => {
+        $crate::dyn_span!($lvl, $name,)
+    };
+}
+
+/// Executes a block, attaching `$fields` to every event emitted from within it.
+///
+/// `tracing` doesn't have a way to inject fields into an event's callsite from
+/// outside of it, so this is implemented by entering a hidden, fixed-name
+/// [`span!`] for the duration of `$body`: any layer that renders a span's
+/// fields alongside its events (such as
+/// [`tracing_subscriber::fmt`](https://docs.rs/tracing-subscriber/latest/tracing_subscriber/fmt/index.html))
+/// will show `$fields` on every event nested inside, without the caller
+/// having to repeat them at each individual `event!`/`info!`/etc. callsite.
+/// Unlike a span you'd construct with [`span!`] directly, the hidden span
+/// here isn't meant to represent a unit of work with its own duration; it
+/// exists only to carry `$fields`, so it's always recorded at
+/// [`Level::TRACE`] under a fixed name rather than one the caller chooses.
+///
+/// As with any span, if `$body` contains `.await` points, the fields will
+/// only be attached to events emitted while the span is entered; wrap the
+/// future in [`Instrument::in_current_span`] to carry them across `.await`.
+///
+/// [`Level::TRACE`]: super::Level::TRACE
+/// [`Instrument::in_current_span`]: crate::Instrument::in_current_span
+///
+/// # Examples
+///
+/// ```rust
+/// # use tracing::info;
+/// # fn tenant_id() -> u64 { 1 }
+/// let id = tenant_id();
+/// tracing::with_fields!({ tenant = id }, {
+///     info!("handling request");
+///     info!("request handled");
+/// });
+/// ```
+#[macro_export]
+macro_rules! with_fields {
+    ({ $($fields:tt)* }, $body:block) => {{
+        let __with_fields_span = $crate::span!($crate::Level::TRACE, "with_fields", $($fields)*);
+        let _with_fields_enter = __with_fields_span.enter();
+        $body
+    }};
+}
+
 /// Constructs a span at the trace level.
 ///
 /// [Fields] and [attributes] are set using the same syntax as the [`span!`]
@@ -1016,6 +1132,71 @@ macro_rules! event {
     );
 }
 
+/// Constructs an event with a [`Level`] that is only known at runtime.
+///
+/// The [`Level`] argument to [`event!`] must be a constant expression, since it's baked into
+/// the static callsite that the macro generates --- so a `Level` computed at runtime (for
+/// example, one parsed from configuration or forwarded from another logging framework) can't be
+/// passed to `event!` directly. `dyn_event!` accepts such a runtime `Level` by matching over it
+/// and forwarding to `event!` with the matched constant, registering a callsite for every level.
+///
+/// See [the top-level documentation][lib] for details on the syntax accepted by
+/// this macro.
+///
+/// [lib]: crate#using-the-macros
+/// [`Level`]: super::Level
+///
+/// # Examples
+///
+/// ```rust
+/// # use tracing::{dyn_event, Level};
+/// # fn main() {
+/// let level = Level::INFO;
+/// dyn_event!(level, "hello");
+/// dyn_event!(target: "my_crate", level, "hello");
+/// dyn_event!(target: "my_crate", parent: None, level, "hello");
+/// # }
+/// ```
+#[macro_export]
+macro_rules! dyn_event {
+    (target: $target:expr, parent: $parent:expr, $lvl:expr, $($arg:tt)+) => {
+        match $lvl {
+            $crate::Level::TRACE => $crate::event!(target: $target, parent: $parent, $crate::Level::TRACE, $($arg)+),
+            $crate::Level::DEBUG => $crate::event!(target: $target, parent: $parent, $crate::Level::DEBUG, $($arg)+),
+            $crate::Level::INFO => $crate::event!(target: $target, parent: $parent, $crate::Level::INFO, $($arg)+),
+            $crate::Level::WARN => $crate::event!(target: $target, parent: $parent, $crate::Level::WARN, $($arg)+),
+            $crate::Level::ERROR => $crate::event!(target: $target, parent: $parent, $crate::Level::ERROR, $($arg)+),
+        }
+    };
+    (target: $target:expr, $lvl:expr, $($arg:tt)+) => {
+        match $lvl {
+            $crate::Level::TRACE => $crate::event!(target: $target, $crate::Level::TRACE, $($arg)+),
+            $crate::Level::DEBUG => $crate::event!(target: $target, $crate::Level::DEBUG, $($arg)+),
+            $crate::Level::INFO => $crate::event!(target: $target, $crate::Level::INFO, $($arg)+),
+            $crate::Level::WARN => $crate::event!(target: $target, $crate::Level::WARN, $($arg)+),
+            $crate::Level::ERROR => $crate::event!(target: $target, $crate::Level::ERROR, $($arg)+),
+        }
+    };
+    (parent: $parent:expr, $lvl:expr, $($arg:tt)+) => {
+        match $lvl {
+            $crate::Level::TRACE => $crate::event!(parent: $parent, $crate::Level::TRACE, $($arg)+),
+            $crate::Level::DEBUG => $crate::event!(parent: $parent, $crate::Level::DEBUG, $($arg)+),
+            $crate::Level::INFO => $crate::event!(parent: $parent, $crate::Level::INFO, $($arg)+),
+            $crate::Level::WARN => $crate::event!(parent: $parent, $crate::Level::WARN, $($arg)+),
+            $crate::Level::ERROR => $crate::event!(parent: $parent, $crate::Level::ERROR, $($arg)+),
+        }
+    };
+    ($lvl:expr, $($arg:tt)+) => {
+        match $lvl {
+            $crate::Level::TRACE => $crate::event!($crate::Level::TRACE, $($arg)+),
+            $crate::Level::DEBUG => $crate::event!($crate::Level::DEBUG, $($arg)+),
+            $crate::Level::INFO => $crate::event!($crate::Level::INFO, $($arg)+),
+            $crate::Level::WARN => $crate::event!($crate::Level::WARN, $($arg)+),
+            $crate::Level::ERROR => $crate::event!($crate::Level::ERROR, $($arg)+),
+        }
+    };
+}
+
 /// Tests whether an event with the specified level and target would be enabled.
 ///
 /// This is similar to [`enabled!`], but queries the current collector specifically for
@@ -2669,6 +2850,26 @@ macro_rules! error {
     );
 }
 
+/// Stamps `$meta` with the name/version of the crate being compiled, if the
+/// `crate-origin` feature is enabled; otherwise, expands to `$meta` unchanged.
+#[cfg(feature = "crate-origin")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __with_crate_origin {
+    ($meta:expr) => {
+        $meta.with_crate_origin(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
+    };
+}
+
+#[cfg(not(feature = "crate-origin"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __with_crate_origin {
+    ($meta:expr) => {
+        $meta
+    };
+}
+
 /// Constructs a new static callsite for a span or event.
 #[doc(hidden)]
 #[macro_export]
@@ -2705,14 +2906,14 @@ macro_rules! callsite {
     ) => {{
         use $crate::__macro_support::{MacroCallsite, Registration};
         static __META: $crate::Metadata<'static> = {
-            $crate::metadata! {
+            $crate::__with_crate_origin!($crate::metadata! {
                 name: $name,
                 target: $target,
                 level: $lvl,
                 fields: $crate::fieldset!( $($fields)* ),
                 callsite: &__CALLSITE,
                 kind: $kind,
-            }
+            })
         };
         static REG: Registration = Registration::new(&__CALLSITE);
         static __CALLSITE: MacroCallsite = MacroCallsite::new(&__META, &REG);
@@ -2757,14 +2958,14 @@ macro_rules! callsite2 {
     ) => {{
         use $crate::__macro_support::{MacroCallsite, Registration};
         static __META: $crate::Metadata<'static> = {
-            $crate::metadata! {
+            $crate::__with_crate_origin!($crate::metadata! {
                 name: $name,
                 target: $target,
                 level: $lvl,
                 fields: $crate::fieldset!( $($fields)* ),
                 callsite: &__CALLSITE,
                 kind: $kind,
-            }
+            })
         };
         static REG: Registration = Registration::new(&__CALLSITE);
 
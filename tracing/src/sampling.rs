@@ -0,0 +1,115 @@
+//! Support for `#[instrument(sample = ...)]`, used by the `tracing-attributes`
+//! macro to decide whether an instrumented function's span should be built at
+//! all.
+//!
+//! Unlike head-based sampling implemented as a [`Subscribe`] (which still
+//! pays for constructing the span and recording its fields before the
+//! decision can be attached to it), this makes the decision *before* the span
+//! or its fields are built, so an unsampled call to an `#[instrument]`d
+//! function costs one coin flip rather than a span and a set of recorded
+//! arguments. This makes it cheap enough to leave on for very hot functions.
+//!
+//! [`Subscribe`]: https://docs.rs/tracing-subscriber/latest/tracing_subscriber/subscribe/trait.Subscribe.html
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread::{self, ThreadId};
+
+/// The sampling decision made by the innermost `#[instrument(sample =
+/// ...)]`d function currently executing on each thread, if any.
+///
+/// Nested instrumented calls consult this before rolling their own decision,
+/// so a sampled-out call doesn't produce a subtree with some spans present
+/// and others missing.
+///
+/// This is keyed explicitly by [`ThreadId`] rather than stored in a
+/// `thread_local!`, so that a [`SampleGuard`] can restore *its* thread's
+/// entry no matter which OS thread ends up running its `Drop` impl --- see
+/// `SampleGuard::drop` for why that distinction matters for `async fn`s. A
+/// plain `Vec` (rather than a `HashMap`) is enough, since the number of
+/// threads with a sampling decision in flight at once is small, and `Vec::new`
+/// is a `const fn`, unlike `HashMap::new`.
+static CURRENT_DECISIONS: Mutex<Vec<(ThreadId, bool)>> = Mutex::new(Vec::new());
+
+/// Restores the previous sampling decision when dropped.
+///
+/// Held for the duration of an `#[instrument(sample = ...)]`d function's
+/// body, so the decision it made (or inherited) is visible to any
+/// `#[instrument(sample = ...)]`d functions it calls, without leaking into
+/// whatever called it.
+///
+/// For an `async fn`, this guard is held across the function's `.await`
+/// points, which means a multi-threaded executor can resume the function
+/// (and thus drop this guard) on a different OS thread than the one it was
+/// created on. The guard records the [`ThreadId`] `sample()` was called on
+/// and always restores *that* thread's decision in [`CURRENT_DECISIONS`],
+/// regardless of which thread actually runs `drop` --- so the origin
+/// thread's decision is never left stuck, and the thread that happens to
+/// drop this guard never has its own, unrelated decision clobbered.
+#[must_use = "holding this guard is what makes the sampling decision visible to child calls; if it is dropped immediately, the decision is not inherited"]
+#[derive(Debug)]
+pub struct SampleGuard {
+    previous: Option<bool>,
+    thread: ThreadId,
+}
+
+impl Drop for SampleGuard {
+    fn drop(&mut self) {
+        let mut decisions = CURRENT_DECISIONS.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = decisions.iter().position(|(thread, _)| *thread == self.thread);
+        match (entry, self.previous) {
+            (Some(index), Some(previous)) => decisions[index].1 = previous,
+            (Some(index), None) => {
+                decisions.swap_remove(index);
+            }
+            (None, Some(previous)) => decisions.push((self.thread, previous)),
+            (None, None) => {}
+        }
+    }
+}
+
+/// Returns whether an `#[instrument(sample = rate)]`d call should be kept,
+/// along with a guard that makes the decision visible to nested
+/// `#[instrument(sample = ...)]`d calls for as long as it's held.
+///
+/// If a decision is already in scope on this thread --- because this call is
+/// nested inside another sampled function --- it's reused rather than rolling
+/// again, so a sampled-out call can't have sampled-in descendants or vice
+/// versa.
+///
+/// # Panics
+///
+/// Panics if `rate` is not in the range `0.0..=1.0`.
+pub fn sample(rate: f64) -> (bool, SampleGuard) {
+    assert!(
+        (0.0..=1.0).contains(&rate),
+        "sampling rate must be between 0.0 and 1.0, got {}",
+        rate
+    );
+    let thread = thread::current().id();
+    let mut decisions = CURRENT_DECISIONS.lock().unwrap_or_else(|e| e.into_inner());
+    let entry = decisions.iter_mut().find(|(t, _)| *t == thread);
+    let previous = entry.as_ref().map(|(_, sampled)| *sampled);
+    let sampled = previous.unwrap_or_else(|| roll(rate));
+    match entry {
+        Some((_, current)) => *current = sampled,
+        None => decisions.push((thread, sampled)),
+    }
+    drop(decisions);
+    (sampled, SampleGuard { previous, thread })
+}
+
+/// A single sampling coin flip.
+///
+/// A coin flip per call doesn't warrant pulling in a full RNG crate: hash an
+/// always-increasing counter with the standard library's randomly-seeded
+/// default hasher to get a stream of bits that's unpredictable enough for
+/// sampling, without the dependency.
+fn roll(rate: f64) -> bool {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(n);
+    (hasher.finish() as f64 / u64::MAX as f64) < rate
+}
@@ -1211,6 +1211,55 @@ impl Span {
         self
     }
 
+    /// Records a value for a field that has already been resolved to a
+    /// [`Field`] handle, bypassing the by-name lookup that [`record`] performs.
+    ///
+    /// [`record`] accepts any type implementing [`AsField`], including plain
+    /// field names, but looking a field up by name is an iterative search
+    /// that performs string comparisons. If the same span's fields are
+    /// recorded repeatedly (for example, in a hot loop), looking the
+    /// [`Field`] up once and reusing the handle avoids repeating that search
+    /// on every call.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tracing::field;
+    ///
+    /// # fn do_something() -> usize { 42 }
+    /// let span = tracing::info_span!("my_span", count = field::Empty);
+    /// let _e = span.enter();
+    ///
+    /// // Resolve the `Field` handle once...
+    /// if let Some(count_field) = span.metadata().and_then(|m| m.fields().field("count")) {
+    ///     for i in 0..10 {
+    ///         // ...and reuse it on every iteration, rather than looking
+    ///         // `"count"` up by name each time.
+    ///         span.record_typed(&count_field, do_something() + i);
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// [`record`]: Span::record
+    /// [`Field`]: field::Field
+    /// [`AsField`]: field::AsField
+    pub fn record_typed<V>(&self, field: &field::Field, value: V) -> &Self
+    where
+        V: field::Value,
+    {
+        if let Some(meta) = self.meta {
+            if field.callsite() == meta.callsite() {
+                self.record_all(
+                    &meta
+                        .fields()
+                        .value_set(&[(field, Some(&value as &dyn field::Value))]),
+                );
+            }
+        }
+
+        self
+    }
+
     /// Records all the fields in the provided `ValueSet`.
     pub fn record_all(&self, values: &field::ValueSet<'_>) -> &Self {
         let record = Record::new(values);
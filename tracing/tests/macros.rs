@@ -6,8 +6,8 @@ extern crate tracing;
 extern crate wasm_bindgen_test;
 
 use tracing::{
-    callsite, debug, debug_span, enabled, error, error_span, event, event_enabled, info, info_span,
-    span, span_enabled, trace, trace_span, warn, warn_span, Level,
+    callsite, debug, debug_span, dyn_event, dyn_span, enabled, error, error_span, event,
+    event_enabled, info, info_span, span, span_enabled, trace, trace_span, warn, warn_span, Level,
 };
 
 /// A type that implements `Display` and `Debug`, but not `Value`.
@@ -44,6 +44,24 @@ fn span() {
     span!(Level::DEBUG, "bar",);
 }
 
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[test]
+fn dyn_span() {
+    let level = Level::DEBUG;
+    dyn_span!(target: "foo_events", parent: ::std::option::Option::None, level, "foo", bar.baz = ?2, quux = %3, quuux = 4);
+    dyn_span!(target: "foo_events", level, "foo", bar.baz = 2, quux = 3);
+    dyn_span!(target: "foo_events", level, "foo", bar.baz = 2, quux = 4,);
+    dyn_span!(target: "foo_events", level, "foo");
+    dyn_span!(target: "foo_events", level, "bar",);
+    dyn_span!(parent: ::std::option::Option::None, level, "foo", bar.baz = 2, quux = 3);
+    dyn_span!(level, "foo", bar.baz = 2, quux = 3);
+    dyn_span!(level, "foo", bar.baz = 2, quux = 4,);
+    dyn_span!(level, "foo", bar.baz = ?2);
+    dyn_span!(level, "foo", bar.baz = %2);
+    dyn_span!(level, "foo");
+    dyn_span!(level, "bar",);
+}
+
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
 #[test]
 fn trace_span() {
@@ -454,6 +472,32 @@ fn event() {
     event!(Level::DEBUG, foo);
 }
 
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[test]
+fn dyn_event() {
+    let level = Level::DEBUG;
+    dyn_event!(level, foo = ?3, bar.baz = %2, quux = false);
+    dyn_event!(level, foo = 3, bar.baz = 2, quux = false);
+    dyn_event!(level, "foo");
+    dyn_event!(level, "foo: {}", 3);
+    dyn_event!(target: "foo_events", level, foo = 3, bar.baz = 2, quux = false);
+    dyn_event!(target: "foo_events", level, "foo");
+    dyn_event!(parent: ::std::option::Option::None, level, foo = ?3, bar.baz = %2, quux = false);
+    dyn_event!(parent: ::std::option::Option::None, level, "foo");
+    dyn_event!(
+        target: "foo_events",
+        parent: ::std::option::Option::None,
+        level,
+        foo = 3,
+        bar.baz = 2,
+        quux = false
+    );
+    let foo = 1;
+    dyn_event!(level, ?foo);
+    dyn_event!(level, %foo);
+    dyn_event!(level, foo);
+}
+
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
 #[test]
 fn enabled() {
@@ -916,3 +916,28 @@ fn keyword_ident_in_field_name_span_macro() {
     });
     handle.assert_finished();
 }
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[test]
+fn with_fields_attaches_fields_to_a_hidden_span_around_the_body() {
+    let (collector, handle) = collector::mock()
+        .new_span(
+            expect::span()
+                .named("with_fields")
+                .with_fields(expect::field("tenant").with_value(&"acme").only()),
+        )
+        .enter(expect::span().named("with_fields"))
+        .event(expect::event())
+        .exit(expect::span().named("with_fields"))
+        .drop_span(expect::span().named("with_fields"))
+        .only()
+        .run_with_handle();
+
+    with_default(collector, || {
+        tracing::with_fields!({ tenant = "acme" }, {
+            tracing::event!(Level::DEBUG, {}, "handling request");
+        });
+    });
+
+    handle.assert_finished();
+}